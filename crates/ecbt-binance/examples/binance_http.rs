@@ -17,6 +17,7 @@ async fn main() -> Result<()> {
     let param = BinanceParameters {
         environment: Sandbox,
         credentials: Some(credentials),
+        ..Default::default()
     };
     let binance = Binance::new(param).await?;
     let request = GetPriceTickerRequest {
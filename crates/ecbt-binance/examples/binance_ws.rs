@@ -18,10 +18,12 @@ async fn main() -> Result<()> {
     let param = BinanceParameters {
         environment: Sandbox,
         credentials: Some(credentials),
+        ..Default::default()
     };
     let binance_ws = BinanceWebsocket::new(param).await?;
     let symbol = MarketPair(Currency::ETH, Currency::USDT).to_string();
-    binance_ws
+    // The subscription lasts as long as its handle.
+    let _handle = binance_ws
         .subscribe(BinanceSubscription::Ticker(symbol), call_back)
         .await?;
 
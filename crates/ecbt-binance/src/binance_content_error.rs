@@ -1,3 +1,5 @@
+use ecbt_exchange::errors::{match_error_message, EcbtError, ErrorConstructor};
+use ecbt_exchange::transport::HttpResponse;
 use serde::Deserialize;
 use serde::Serialize;
 use serde_json::Value;
@@ -5,6 +7,39 @@ use std::collections::HashMap;
 use std::fmt;
 use thiserror::Error;
 
+/// Binance error codes that map to a single typed error.
+const ERROR_CODES: &[(i16, ErrorConstructor)] = &[
+    (-1021, EcbtError::TimestampOutsideRecvWindow),
+    (-1022, EcbtError::AuthenticationRejected),
+    (-2008, EcbtError::AuthenticationRejected),
+    (-2011, EcbtError::OrderNotFound),
+    (-2013, EcbtError::OrderNotFound),
+    (-2014, EcbtError::AuthenticationRejected),
+    (-2015, EcbtError::AuthenticationRejected),
+    (-2018, EcbtError::InsufficientBalance),
+    (-2019, EcbtError::InsufficientBalance),
+];
+
+/// Messages of the generic Binance rejection codes (-1013, -2010, -2021, ...) that tell
+/// what was wrong with the order.
+const ERROR_MESSAGES: &[(&str, ErrorConstructor)] = &[
+    ("insufficient balance", EcbtError::InsufficientBalance),
+    (
+        "would immediately match and take",
+        EcbtError::PostOnlyWouldTake,
+    ),
+    ("market is closed", EcbtError::MarketClosed),
+    ("duplicate order", EcbtError::DuplicateOrder),
+    ("unknown order", EcbtError::OrderNotFound),
+    ("order does not exist", EcbtError::OrderNotFound),
+    ("PERCENT_PRICE", EcbtError::InvalidPrice),
+    ("PRICE_FILTER", EcbtError::InvalidPrice),
+    ("invalid price", EcbtError::InvalidPrice),
+    ("LOT_SIZE", EcbtError::InvalidQuantity),
+    ("NOTIONAL", EcbtError::InvalidQuantity),
+    ("invalid quantity", EcbtError::InvalidQuantity),
+];
+
 /// This struct represents a ecbt-binance content error
 #[derive(Serialize, Deserialize, Debug, Error)]
 pub struct BinanceContentError {
@@ -15,6 +50,25 @@ pub struct BinanceContentError {
     extra: HashMap<String, Value>,
 }
 
+impl BinanceContentError {
+    /// Maps the error to a typed [`EcbtError`] carrying the code, endpoint and status of
+    /// `response`.
+    pub fn into_error(self, response: &HttpResponse) -> EcbtError {
+        let context = response.error_context(self.msg).with_code(self.code.into());
+        match self.code {
+            // Too many requests or orders.
+            -1003 | -1015 => EcbtError::RateLimited {
+                retry_after: response.retry_after(),
+                context: Some(context),
+            },
+            code => match ERROR_CODES.iter().find(|(known, _)| *known == code) {
+                Some((_, error)) => error(context),
+                None => match_error_message(ERROR_MESSAGES, context),
+            },
+        }
+    }
+}
+
 impl fmt::Display for BinanceContentError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "error code: {} msg: {}", self.code, self.msg)
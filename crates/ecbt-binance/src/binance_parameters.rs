@@ -1,11 +1,30 @@
-use super::BinanceCredentials;
+use super::shared::Result;
+use super::{BinanceCredentials, BinanceRequestWeigher, BinanceStreamSharding, Transport};
 use ecbt_exchange::exchange::Environment;
+use ecbt_exchange::info::OrderValidation;
+use ecbt_exchange::rate_limit::{RateLimitMode, RateLimitedTransport, RateLimiter};
+use ecbt_exchange::retry::{RetryPolicy, RetryingTransport};
+use ecbt_exchange::stream::ReconnectPolicy;
+use ecbt_exchange::transport::{http_transport_or_default, HttpTransport};
+use std::sync::Arc;
 
 /// This struct represents the type of environment that will be used and receives a boolean and the credentials as parameters.
 #[derive(Default, Clone, Debug)]
 pub struct BinanceParameters {
     pub environment: Environment,
     pub credentials: Option<BinanceCredentials>,
+    /// The HTTP transport used for REST calls, defaults to a `ReqwestTransport`.
+    pub http_transport: Option<Arc<dyn HttpTransport>>,
+    /// How websocket streams reconnect when dropped, streams end with the connection if unset.
+    pub reconnect_policy: Option<ReconnectPolicy>,
+    /// Whether REST requests over the Binance limits wait or fail, they are not limited if unset.
+    pub rate_limit: Option<RateLimitMode>,
+    /// How orders are checked against the symbol filters before being sent.
+    pub order_validation: OrderValidation,
+    /// How failed REST requests are retried, nothing is retried if unset.
+    pub retry_policy: Option<RetryPolicy>,
+    /// How websocket subscriptions are spread over combined stream connections.
+    pub stream_sharding: BinanceStreamSharding,
 }
 
 impl BinanceParameters {
@@ -24,4 +43,71 @@ impl BinanceParameters {
             ..Default::default()
         }
     }
+
+    /// Set the HTTP transport.
+    pub fn with_http_transport(mut self, http_transport: Arc<dyn HttpTransport>) -> Self {
+        self.http_transport = Some(http_transport);
+        self
+    }
+
+    /// Set the websocket reconnect policy.
+    pub fn with_reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = Some(reconnect_policy);
+        self
+    }
+
+    /// Set how requests over the rate limits are handled.
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitMode) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Set how orders are checked against the symbol filters.
+    pub fn with_order_validation(mut self, order_validation: OrderValidation) -> Self {
+        self.order_validation = order_validation;
+        self
+    }
+
+    /// Set how failed requests are retried.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Set how websocket subscriptions are spread over connections.
+    pub fn with_stream_sharding(mut self, stream_sharding: BinanceStreamSharding) -> Self {
+        self.stream_sharding = stream_sharding;
+        self
+    }
+
+    /// Builds the REST transport of these parameters, limited by the returned rate limiter
+    /// and retrying failed requests as configured.
+    pub(crate) fn transport(&self) -> Result<(Transport, Option<Arc<RateLimiter>>)> {
+        let mut http = http_transport_or_default(self.http_transport.clone())?;
+        let rate_limiter = self
+            .rate_limit
+            .map(|mode| Arc::new(RateLimiter::new(BinanceRequestWeigher::rules(), mode)));
+        if let Some(rate_limiter) = &rate_limiter {
+            http = Arc::new(RateLimitedTransport::new(
+                http,
+                rate_limiter.clone(),
+                Arc::new(BinanceRequestWeigher),
+            )) as Arc<dyn HttpTransport>;
+        }
+        if let Some(retry_policy) = &self.retry_policy {
+            http = Arc::new(RetryingTransport::new(http, retry_policy.clone()))
+                as Arc<dyn HttpTransport>;
+        }
+        let sandbox = self.environment == Environment::Sandbox;
+        let transport = match &self.credentials {
+            Some(credentials) => Transport::with_credential(
+                http,
+                &credentials.api_key,
+                &credentials.api_secret,
+                sandbox,
+            )?,
+            None => Transport::new(http, sandbox)?,
+        };
+        Ok((transport, rate_limiter))
+    }
 }
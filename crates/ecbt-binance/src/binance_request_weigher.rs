@@ -0,0 +1,131 @@
+use ecbt_exchange::rate_limit::{
+    RateLimitKind, RateLimitRule, RateLimitUsage, RequestCost, RequestPriority, RequestWeigher,
+};
+use ecbt_exchange::transport::{HttpRequest, HttpResponse};
+use reqwest::Method;
+use std::time::Duration;
+
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-";
+const ORDER_COUNT_HEADER: &str = "x-mbx-order-count-";
+
+/// The request weights of the Binance spot API
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinanceRequestWeigher;
+
+impl BinanceRequestWeigher {
+    /// The limits Binance enforces until the ones published in `exchangeInfo` are known.
+    pub fn rules() -> Vec<RateLimitRule> {
+        vec![
+            RateLimitRule::new(RateLimitKind::Weight, Duration::from_secs(60), 6000),
+            RateLimitRule::new(RateLimitKind::Orders, Duration::from_secs(10), 100),
+            RateLimitRule::new(RateLimitKind::Orders, Duration::from_secs(86400), 200000),
+            RateLimitRule::new(RateLimitKind::Requests, Duration::from_secs(300), 61000),
+        ]
+    }
+
+    fn weight(method: &Method, path: &str, request: &HttpRequest) -> u32 {
+        let param = |name: &str| {
+            request
+                .url
+                .query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.into_owned())
+        };
+        let by_symbol = |with_symbol, without_symbol| {
+            if param("symbol").is_some() {
+                with_symbol
+            } else {
+                without_symbol
+            }
+        };
+
+        match (method, path) {
+            (&Method::GET, "/api/v3/depth") => {
+                match param("limit").and_then(|limit| limit.parse().ok()) {
+                    Some(0..=100) | None => 5,
+                    Some(101..=500) => 25,
+                    Some(501..=1000) => 50,
+                    Some(_) => 250,
+                }
+            }
+            (&Method::GET, "/api/v3/openOrders") => by_symbol(6, 80),
+            (&Method::GET, "/api/v3/ticker/24hr") => by_symbol(2, 80),
+            (&Method::GET, "/api/v3/ticker/price") => by_symbol(2, 4),
+            (&Method::GET, "/api/v3/ticker/bookTicker") => by_symbol(2, 4),
+            (&Method::GET, "/api/v3/order") | (&Method::GET, "/api/v3/orderList") => 4,
+            (&Method::GET, "/api/v3/openOrderList") => 6,
+            (&Method::GET, "/api/v3/trades") | (&Method::GET, "/api/v3/historicalTrades") => 25,
+            (&Method::GET, "/api/v3/exchangeInfo")
+            | (&Method::GET, "/api/v3/account")
+            | (&Method::GET, "/api/v3/allOrders")
+            | (&Method::GET, "/api/v3/myTrades") => 20,
+            (&Method::GET, "/api/v3/klines")
+            | (&Method::GET, "/api/v3/aggTrades")
+            | (&Method::GET, "/api/v3/avgPrice") => 2,
+            (_, "/api/v3/userDataStream") => 2,
+            _ => 1,
+        }
+    }
+}
+
+impl RequestWeigher for BinanceRequestWeigher {
+    fn cost(&self, request: &HttpRequest) -> RequestCost {
+        let method = &request.method;
+        let path = request.url.path();
+        let order_action = *method != Method::GET
+            && (path.starts_with("/api/v3/order") || path == "/api/v3/openOrders");
+        let orders = match (method, path) {
+            (&Method::POST, "/api/v3/order") | (&Method::POST, "/api/v3/order/cancelReplace") => 1,
+            (&Method::POST, "/api/v3/order/oco") => 2,
+            _ => 0,
+        };
+
+        RequestCost::new()
+            .with_weight(Self::weight(method, path, request))
+            .with_orders(orders)
+            .with_private(request.url.query_pairs().any(|(key, _)| key == "signature"))
+            .with_priority(if order_action {
+                RequestPriority::High
+            } else {
+                RequestPriority::Normal
+            })
+    }
+
+    /// Reads the `X-MBX-USED-WEIGHT-(interval)` and `X-MBX-ORDER-COUNT-(interval)` headers.
+    fn usage(&self, response: &HttpResponse) -> Vec<RateLimitUsage> {
+        response
+            .headers
+            .iter()
+            .filter_map(|(name, value)| {
+                let name = name.as_str();
+                let (kind, interval) = if let Some(interval) = name.strip_prefix(USED_WEIGHT_HEADER)
+                {
+                    (RateLimitKind::Weight, interval)
+                } else {
+                    (
+                        RateLimitKind::Orders,
+                        name.strip_prefix(ORDER_COUNT_HEADER)?,
+                    )
+                };
+                Some(RateLimitUsage {
+                    kind,
+                    interval: parse_interval(interval)?,
+                    used: value.to_str().ok()?.parse().ok()?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Parses the interval suffix of the usage headers, e.g. `1m` or `10s`.
+fn parse_interval(interval: &str) -> Option<Duration> {
+    let (count, unit) = interval.split_at(interval.len().checked_sub(1)?);
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "d" => 86400,
+        _ => return None,
+    };
+    Some(Duration::from_secs(count.parse::<u64>().ok()? * seconds))
+}
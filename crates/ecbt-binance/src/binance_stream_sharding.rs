@@ -0,0 +1,65 @@
+/// This struct represents how the subscriptions of a stream are spread over combined stream
+/// connections, so a connection never exceeds the Binance stream cap or a safe URL length.
+///
+/// Every shard carries its streams in its URL, so opening one sends no message and stays clear
+/// of the inbound message limit. Only rebalancing the streams of a dropped shard onto the
+/// others sends a single `SUBSCRIBE` request per receiving shard.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BinanceStreamSharding {
+    /// Streams carried by a connection, Binance accepts at most 1024.
+    pub max_streams_per_connection: usize,
+    /// Length of a connection URL, with its `?streams=` query.
+    pub max_url_length: usize,
+}
+
+impl Default for BinanceStreamSharding {
+    fn default() -> Self {
+        Self {
+            max_streams_per_connection: 200,
+            max_url_length: 4096,
+        }
+    }
+}
+
+impl BinanceStreamSharding {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_max_streams_per_connection(mut self, max_streams_per_connection: usize) -> Self {
+        self.max_streams_per_connection = max_streams_per_connection.max(1);
+        self
+    }
+
+    pub fn with_max_url_length(mut self, max_url_length: usize) -> Self {
+        self.max_url_length = max_url_length;
+        self
+    }
+
+    /// Whether a connection to `ws_url` carrying `streams` can also carry `stream`.
+    pub fn fits(&self, ws_url: &str, streams: &[String], stream: &str) -> bool {
+        if streams.len() >= self.max_streams_per_connection {
+            return false;
+        }
+        // "?streams=" and a "/" between every stream.
+        let length = ws_url.len()
+            + "?streams=".len()
+            + streams.iter().map(|s| s.len() + 1).sum::<usize>()
+            + stream.len();
+        streams.is_empty() || length <= self.max_url_length
+    }
+
+    /// Splits `streams` into the streams of each connection to `ws_url`, in order.
+    ///
+    /// A stream too long to share a URL with any other gets a connection of its own.
+    pub fn plan(&self, ws_url: &str, streams: Vec<String>) -> Vec<Vec<String>> {
+        let mut shards: Vec<Vec<String>> = Vec::new();
+        for stream in streams {
+            match shards.last_mut() {
+                Some(shard) if self.fits(ws_url, shard, &stream) => shard.push(stream),
+                _ => shards.push(vec![stream]),
+            }
+        }
+        shards
+    }
+}
@@ -1,8 +1,9 @@
 use super::shared::Result;
 use super::BaseClient;
 use crate::model::{
-    AccountInformation, AllOrderReq, Balance, MarketPair, Order, OrderCanceled, OrderRequest,
-    TimeInForce, TradeHistory, TradeHistoryReq, ORDER_SIDE_BUY, ORDER_SIDE_SELL, ORDER_TYPE_LIMIT,
+    AccountInformation, AllOrderReq, Balance, CancelReplaceRequest, CancelReplaceResponse,
+    MarketPair, OcoRequest, Order, OrderCanceled, OrderList, OrderRequest, TimeInForce,
+    TradeHistory, TradeHistoryReq, ORDER_SIDE_BUY, ORDER_SIDE_SELL, ORDER_TYPE_LIMIT,
     ORDER_TYPE_LIMIT_MAKER, ORDER_TYPE_MARKET,
 };
 use ecbt_exchange::errors::EcbtError;
@@ -76,6 +77,20 @@ impl BaseClient {
         Ok(order)
     }
 
+    pub async fn get_order_by_client_id(
+        &self,
+        symbol: &str,
+        client_order_id: &str,
+    ) -> Result<Order> {
+        let params = json! {{"symbol": symbol, "origClientOrderId": client_order_id}};
+
+        let order = self
+            .transport
+            .signed_get("/api/v3/order", Some(&params))
+            .await?;
+        Ok(order)
+    }
+
     // Place a LIMIT order - BUY
     pub async fn limit_buy(
         &self,
@@ -84,6 +99,7 @@ impl BaseClient {
         price: Decimal,
         tif: TimeInForce,
         post_only: bool,
+        client_order_id: Option<String>,
     ) -> Result<Order> {
         let (order_type, time_in_force) = match post_only {
             true => (ORDER_TYPE_LIMIT_MAKER.to_string(), None),
@@ -92,14 +108,22 @@ impl BaseClient {
 
         let buy: OrderRequest = OrderRequest {
             symbol: pair.symbol,
-            quantity: qty.round_dp(pair.base_increment.normalize().scale()),
-            price: Some(price.round_dp_with_strategy(
-                pair.quote_increment.normalize().scale(),
+            quantity: round_to_increment(
+                qty,
+                pair.base_increment,
+                RoundingStrategy::MidpointNearestEven,
+            ),
+            price: Some(round_to_increment(
+                price,
+                pair.quote_increment,
                 RoundingStrategy::ToZero,
             )),
             order_side: ORDER_SIDE_BUY.to_string(),
             order_type,
             time_in_force,
+            new_client_order_id: client_order_id,
+            stop_price: None,
+            trailing_delta: None,
         };
 
         let transaction = self
@@ -119,6 +143,7 @@ impl BaseClient {
         price: Decimal,
         tif: TimeInForce,
         post_only: bool,
+        client_order_id: Option<String>,
     ) -> Result<Order> {
         let (order_type, time_in_force) = match post_only {
             true => (ORDER_TYPE_LIMIT_MAKER.to_string(), None),
@@ -127,14 +152,22 @@ impl BaseClient {
 
         let sell: OrderRequest = OrderRequest {
             symbol: pair.symbol,
-            quantity: qty.round_dp(pair.base_increment.normalize().scale()),
-            price: Some(price.round_dp_with_strategy(
-                pair.quote_increment.normalize().scale(),
+            quantity: round_to_increment(
+                qty,
+                pair.base_increment,
+                RoundingStrategy::MidpointNearestEven,
+            ),
+            price: Some(round_to_increment(
+                price,
+                pair.quote_increment,
                 RoundingStrategy::AwayFromZero,
             )),
             order_side: ORDER_SIDE_SELL.to_string(),
             order_type,
             time_in_force,
+            new_client_order_id: client_order_id,
+            stop_price: None,
+            trailing_delta: None,
         };
 
         let transaction = self
@@ -146,14 +179,26 @@ impl BaseClient {
     }
 
     // Place a MARKET order - BUY
-    pub async fn market_buy(&self, pair: MarketPairInfo, qty: Decimal) -> Result<Order> {
+    pub async fn market_buy(
+        &self,
+        pair: MarketPairInfo,
+        qty: Decimal,
+        client_order_id: Option<String>,
+    ) -> Result<Order> {
         let buy: OrderRequest = OrderRequest {
             symbol: pair.symbol,
-            quantity: qty.round_dp(pair.base_increment.normalize().scale()),
+            quantity: round_to_increment(
+                qty,
+                pair.base_increment,
+                RoundingStrategy::MidpointNearestEven,
+            ),
             price: None,
             order_side: ORDER_SIDE_BUY.to_string(),
             order_type: ORDER_TYPE_MARKET.to_string(),
             time_in_force: None,
+            new_client_order_id: client_order_id,
+            stop_price: None,
+            trailing_delta: None,
         };
 
         let transaction = self
@@ -165,14 +210,26 @@ impl BaseClient {
     }
 
     // Place a MARKET order - SELL
-    pub async fn market_sell(&self, pair: MarketPairInfo, qty: Decimal) -> Result<Order> {
+    pub async fn market_sell(
+        &self,
+        pair: MarketPairInfo,
+        qty: Decimal,
+        client_order_id: Option<String>,
+    ) -> Result<Order> {
         let sell: OrderRequest = OrderRequest {
             symbol: pair.symbol,
-            quantity: qty.round_dp(pair.base_increment.normalize().scale()),
+            quantity: round_to_increment(
+                qty,
+                pair.base_increment,
+                RoundingStrategy::MidpointNearestEven,
+            ),
             price: None,
             order_side: ORDER_SIDE_SELL.to_string(),
             order_type: ORDER_TYPE_MARKET.to_string(),
             time_in_force: None,
+            new_client_order_id: client_order_id,
+            stop_price: None,
+            trailing_delta: None,
         };
 
         let transaction = self
@@ -182,6 +239,67 @@ impl BaseClient {
         Ok(transaction)
     }
 
+    // Place an order of any type, e.g. a STOP_LOSS_LIMIT order
+    pub async fn new_order(&self, order: OrderRequest) -> Result<Order> {
+        let transaction = self
+            .transport
+            .signed_post("/api/v3/order", Some(&order))
+            .await?;
+
+        Ok(transaction)
+    }
+
+    // Cancel an order and place its replacement in a single request
+    pub async fn cancel_replace_order(
+        &self,
+        order: CancelReplaceRequest,
+    ) -> Result<CancelReplaceResponse> {
+        let replaced = self
+            .transport
+            .signed_post("/api/v3/order/cancelReplace", Some(&order))
+            .await?;
+
+        Ok(replaced)
+    }
+
+    // Place an OCO order list, whose orders cancel each other
+    pub async fn new_oco_order(&self, order: OcoRequest) -> Result<OrderList> {
+        let order_list = self
+            .transport
+            .signed_post("/api/v3/order/oco", Some(&order))
+            .await?;
+
+        Ok(order_list)
+    }
+
+    pub async fn get_order_list(&self, order_list_id: u64) -> Result<OrderList> {
+        let params = json! {{"orderListId": order_list_id}};
+
+        let order_list = self
+            .transport
+            .signed_get("/api/v3/orderList", Some(&params))
+            .await?;
+        Ok(order_list)
+    }
+
+    // All current open order lists
+    pub async fn get_open_order_lists(&self) -> Result<Vec<OrderList>> {
+        let order_lists = self
+            .transport
+            .signed_get::<_, ()>("/api/v3/openOrderList", None)
+            .await?;
+        Ok(order_lists)
+    }
+
+    pub async fn cancel_order_list(&self, symbol: &str, order_list_id: u64) -> Result<OrderList> {
+        let params = json! {{"symbol":symbol, "orderListId":order_list_id}};
+        let order_list = self
+            .transport
+            .signed_delete("/api/v3/orderList", Some(&params))
+            .await?;
+        Ok(order_list)
+    }
+
     // Check an order's status
     pub async fn cancel_order(&self, symbol: &str, order_id: u64) -> Result<OrderCanceled> {
         let params = json! {{"symbol":symbol, "orderId":order_id}};
@@ -215,3 +333,12 @@ impl BaseClient {
         Ok(trade_history)
     }
 }
+
+/// Rounds `value` to the decimal places of `increment`, unless the pair has no increment.
+fn round_to_increment(value: Decimal, increment: Decimal, strategy: RoundingStrategy) -> Decimal {
+    if increment.is_zero() {
+        value
+    } else {
+        value.round_dp_with_strategy(increment.normalize().scale(), strategy)
+    }
+}
@@ -0,0 +1,76 @@
+use crate::model::websocket::{BinanceSubscription, BinanceWebsocketMessage, UserOrderUpdate};
+use crate::model::OrderExecType;
+use ecbt_exchange::model::websocket::AccountOrders;
+use ecbt_exchange::model::Order;
+use std::collections::HashSet;
+
+/// This struct selects the user data stream messages requested by the account subscriptions
+/// of a stream, so the events that don't match are dropped before reaching the subscriber.
+#[derive(Debug, Clone, Default)]
+pub struct AccountFilter {
+    orders: Vec<AccountOrders>,
+    trades: HashSet<String>,
+    assets: HashSet<String>,
+}
+
+impl AccountFilter {
+    pub fn new<'a>(subscriptions: impl IntoIterator<Item = &'a BinanceSubscription>) -> Self {
+        let mut filter = Self::default();
+        for subscription in subscriptions {
+            match subscription {
+                BinanceSubscription::UserOrders(orders) => filter.orders.push(orders.clone()),
+                BinanceSubscription::UserTrades(symbol) => {
+                    filter.trades.insert(symbol.clone());
+                }
+                BinanceSubscription::UserBalance(base, quote) => {
+                    filter.assets.insert(base.clone());
+                    filter.assets.insert(quote.clone());
+                }
+                _ => (),
+            }
+        }
+        filter
+    }
+
+    /// Returns the messages to forward for `message`: an execution report is forwarded as
+    /// an order update and, when it is a fill, as a trade. Other messages pass through.
+    pub fn apply(&self, message: BinanceWebsocketMessage) -> Vec<BinanceWebsocketMessage> {
+        match message {
+            BinanceWebsocketMessage::UserOrderUpdate(update) => {
+                let mut messages = Vec::new();
+                if self.accepts_order(&update) {
+                    messages.push(BinanceWebsocketMessage::UserOrderUpdate(update.clone()));
+                }
+                if matches!(update.execution_type, OrderExecType::Trade)
+                    && self.trades.contains(&update.symbol)
+                {
+                    messages.push(BinanceWebsocketMessage::UserTrade(update));
+                }
+                messages
+            }
+            BinanceWebsocketMessage::UserAccountUpdate(mut update) => {
+                update
+                    .balance
+                    .retain(|balance| self.assets.contains(&balance.asset));
+                if update.balance.is_empty() {
+                    Vec::new()
+                } else {
+                    vec![BinanceWebsocketMessage::UserAccountUpdate(update)]
+                }
+            }
+            message => vec![message],
+        }
+    }
+
+    fn accepts_order(&self, update: &UserOrderUpdate) -> bool {
+        if self.orders.is_empty() {
+            return false;
+        }
+        let order = Order::from(update.clone());
+        self.orders.iter().any(|orders| {
+            orders.accepts(&order, |market_pair| {
+                crate::model::MarketPair::from(market_pair.clone()).0
+            })
+        })
+    }
+}
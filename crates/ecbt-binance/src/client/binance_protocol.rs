@@ -0,0 +1,79 @@
+use super::shared::Result;
+use crate::model::websocket::{BinanceSubscription, BinanceWebsocketMessage};
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::stream::{ProtocolMessage, SubscriptionProtocol};
+use serde::Serialize;
+use serde_json::Value;
+
+#[derive(Serialize)]
+struct Request {
+    method: &'static str,
+    params: Vec<String>,
+    id: u64,
+}
+
+/// This struct represents the `SUBSCRIBE` and `UNSUBSCRIBE` methods of Binance combined
+/// streams, used by the `LiveConnection` of `BinanceWebsocket::live_connection`.
+///
+/// Account subscriptions need a user data stream, which a live connection subscribes to with
+/// `BinanceSubscription::UserData` and a listen key.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct BinanceProtocol;
+
+impl BinanceProtocol {
+    fn request(
+        method: &'static str,
+        id: u64,
+        subscription: &BinanceSubscription,
+    ) -> Result<String> {
+        if subscription.is_account() {
+            return Err(EcbtError::InvalidParameter(format!(
+                "{:?} can't be added to a live connection",
+                subscription
+            )));
+        }
+        let request = Request {
+            method,
+            params: vec![subscription.to_string()],
+            id,
+        };
+        Ok(serde_json::to_string(&request)?)
+    }
+}
+
+impl SubscriptionProtocol for BinanceProtocol {
+    type Subscription = BinanceSubscription;
+    type Message = BinanceWebsocketMessage;
+
+    fn subscribe_request(&self, id: u64, subscription: &BinanceSubscription) -> Result<String> {
+        Self::request("SUBSCRIBE", id, subscription)
+    }
+
+    fn unsubscribe_request(&self, id: u64, subscription: &BinanceSubscription) -> Result<String> {
+        Self::request("UNSUBSCRIBE", id, subscription)
+    }
+
+    fn parse(&self, text: &str) -> Result<ProtocolMessage<BinanceWebsocketMessage>> {
+        let value: Value = serde_json::from_str(text)?;
+        let topic = value
+            .get("stream")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        Ok(match serde_json::from_value(value)? {
+            BinanceWebsocketMessage::SubscriptionAck { id } => ProtocolMessage::Ack(id),
+            BinanceWebsocketMessage::SubscriptionError { id, message, .. } => {
+                ProtocolMessage::Rejected(id, message)
+            }
+            message => ProtocolMessage::Data(topic, message),
+        })
+    }
+
+    fn routes(
+        &self,
+        subscription: &BinanceSubscription,
+        topic: Option<&str>,
+        _message: &BinanceWebsocketMessage,
+    ) -> bool {
+        topic == Some(subscription.to_string().as_str())
+    }
+}
@@ -0,0 +1,112 @@
+use crate::model::websocket::Depth;
+use ecbt_exchange::book::OrderBook;
+use ecbt_exchange::model::{OrderBookResponse, Side};
+
+/// This enum represents the state of a `DepthSynchronizer` after it consumed a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthSync {
+    /// The book is consistent and includes the message.
+    Synced,
+    /// The message was older than the book and was dropped.
+    Stale,
+    /// The book is out of sync; a fresh `BaseClient::get_depth` snapshot must be applied.
+    NeedsSnapshot,
+    /// The message was buffered until the snapshot already requested is applied.
+    Buffered,
+}
+
+/// This struct maintains an `OrderBook` from a REST snapshot and `@depth` diffs, following
+/// Binance's rules for managing a local order book.
+///
+/// Diffs received before a snapshot is applied are buffered, a snapshot being requested once
+/// per resync rather than for every buffered diff. Diffs whose `final_update_id`
+/// is not newer than the book are dropped, and a diff whose `first_update_id` skips past the
+/// next expected id is a gap, which resets the book until a new snapshot arrives.
+#[derive(Debug, Clone, Default)]
+pub struct DepthSynchronizer {
+    book: OrderBook,
+    synced: bool,
+    /// Whether a snapshot was requested since the book was last reset.
+    requested: bool,
+    buffer: Vec<Depth>,
+}
+
+impl DepthSynchronizer {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn book(&self) -> &OrderBook {
+        &self.book
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.synced
+    }
+
+    /// Applies a diff, or buffers it while waiting for a snapshot. `DepthSync::NeedsSnapshot`
+    /// is returned once per resync, the diffs that follow it being `DepthSync::Buffered`.
+    pub fn push(&mut self, depth: Depth) -> DepthSync {
+        if !self.synced {
+            self.buffer.push(depth);
+            return self.request_snapshot();
+        }
+
+        let sync = self.apply(&depth);
+        if sync == DepthSync::NeedsSnapshot {
+            self.reset();
+            self.buffer.push(depth);
+            return self.request_snapshot();
+        }
+        sync
+    }
+
+    /// Replaces the book with a snapshot and replays the buffered diffs on top of it.
+    pub fn apply_snapshot(&mut self, snapshot: &OrderBookResponse) -> DepthSync {
+        self.book.replace(snapshot);
+        self.synced = true;
+        self.requested = false;
+
+        let buffer = std::mem::take(&mut self.buffer);
+        for (index, depth) in buffer.iter().enumerate() {
+            if self.apply(depth) == DepthSync::NeedsSnapshot {
+                // The snapshot is older than the buffered diffs, keep them for the next one.
+                self.reset();
+                self.buffer = buffer[index..].to_vec();
+                return self.request_snapshot();
+            }
+        }
+        DepthSync::Synced
+    }
+
+    fn apply(&mut self, depth: &Depth) -> DepthSync {
+        let next = self.book.update_id().unwrap_or_default() + 1;
+        if depth.final_update_id < next {
+            return DepthSync::Stale;
+        }
+        if depth.first_update_id > next {
+            return DepthSync::NeedsSnapshot;
+        }
+
+        for level in &depth.bids {
+            self.book.update(&Side::Buy, level.price, level.qty);
+        }
+        for level in &depth.asks {
+            self.book.update(&Side::Sell, level.price, level.qty);
+        }
+        self.book.set_update_id(Some(depth.final_update_id));
+        DepthSync::Synced
+    }
+
+    fn request_snapshot(&mut self) -> DepthSync {
+        match std::mem::replace(&mut self.requested, true) {
+            true => DepthSync::Buffered,
+            false => DepthSync::NeedsSnapshot,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.synced = false;
+        self.book.clear();
+    }
+}
@@ -1,8 +1,8 @@
 use super::shared::Result;
 use super::BaseClient;
 use crate::model::{
-    BookTickers, KlineParams, KlineSummaries, KlineSummary, MarketPair, OrderBook, PriceStats,
-    Prices, SymbolPrice, Ticker,
+    AveragePrice, BookTickers, KlineParams, KlineSummaries, KlineSummary, MarketPair, OrderBook,
+    PriceStats, Prices, SymbolPrice, Ticker,
 };
 pub use ecbt_exchange::EcbtError;
 use rust_decimal::prelude::Decimal;
@@ -41,6 +41,14 @@ impl BaseClient {
             .await
     }
 
+    // Current average price for ONE symbol, the reference of its price bands.
+    pub async fn get_average_price<S: Into<MarketPair>>(&self, symbol: S) -> Result<AveragePrice> {
+        let symbol = symbol.into().0;
+        let params = json! {{"symbol": symbol}};
+
+        self.transport.get("/api/v3/avgPrice", Some(&params)).await
+    }
+
     // Symbols order book ticker
     // -> Best price/qty on the order book for ALL symbols.
     pub async fn get_all_book_tickers(&self) -> Result<BookTickers> {
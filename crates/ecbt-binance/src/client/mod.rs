@@ -1,11 +1,20 @@
 //! This module is used to make calls to api and connect to the websockets
 mod account;
+mod account_filter;
 mod base_client;
+mod binance_protocol;
+mod depth_synchronizer;
 mod general;
 mod market;
+mod shard_pool;
 pub mod stream;
 mod userstream;
 
 pub use super::shared;
 pub(crate) use super::transport::Transport;
+pub use account_filter::AccountFilter;
 pub use base_client::BaseClient;
+pub use binance_protocol::BinanceProtocol;
+pub use depth_synchronizer::{DepthSync, DepthSynchronizer};
+pub use shard_pool::ShardHealth;
+pub(crate) use shard_pool::{ShardPool, ShardRegistry};
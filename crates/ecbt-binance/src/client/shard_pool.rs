@@ -0,0 +1,403 @@
+use super::shared::Result;
+use crate::model::websocket::BinanceWebsocketMessage;
+use crate::{BinanceParameters, BinanceStreamSharding};
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::stream::{reconnecting_stream, ReconnectPolicy};
+use futures::stream::{self, BoxStream, SelectAll};
+use futures::{future, FutureExt, SinkExt, StreamExt};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+
+type Connection = BoxStream<'static, Result<BinanceWebsocketMessage>>;
+/// Messages of a shard, followed by `None` once its connection is gone for good.
+type Tagged = (usize, Option<Result<BinanceWebsocketMessage>>);
+pub(crate) type DisconnectionSenders = Arc<Mutex<Vec<UnboundedSender<()>>>>;
+
+/// This struct represents the state of a combined stream connection of a sharded stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShardHealth {
+    pub id: usize,
+    pub streams: Vec<String>,
+    /// False from a drop of the connection until it is re-established.
+    pub connected: bool,
+    pub messages: u64,
+    pub reconnects: u32,
+    pub last_message: Option<Instant>,
+}
+
+/// This struct holds the health of the shards of every open stream of a websocket.
+#[derive(Debug, Default)]
+pub(crate) struct ShardRegistry {
+    ids: AtomicUsize,
+    shards: Mutex<Vec<ShardHealth>>,
+}
+
+impl ShardRegistry {
+    pub fn snapshot(&self) -> Vec<ShardHealth> {
+        self.shards
+            .lock()
+            .map(|shards| shards.clone())
+            .unwrap_or_default()
+    }
+
+    fn insert(&self, streams: Vec<String>) -> usize {
+        let id = self.ids.fetch_add(1, Ordering::SeqCst);
+        if let Ok(mut shards) = self.shards.lock() {
+            shards.push(ShardHealth {
+                id,
+                streams,
+                connected: true,
+                messages: 0,
+                reconnects: 0,
+                last_message: None,
+            });
+        }
+        id
+    }
+
+    fn update(&self, id: usize, update: impl FnOnce(&mut ShardHealth)) {
+        if let Ok(mut shards) = self.shards.lock() {
+            if let Some(shard) = shards.iter_mut().find(|shard| shard.id == id) {
+                update(shard);
+            }
+        }
+    }
+
+    fn remove(&self, id: usize) {
+        if let Ok(mut shards) = self.shards.lock() {
+            shards.retain(|shard| shard.id != id);
+        }
+    }
+}
+
+struct Shard {
+    streams: Arc<Mutex<Vec<String>>>,
+    /// Sends text frames on the current connection of the shard.
+    frames: Arc<Mutex<Option<UnboundedSender<String>>>>,
+}
+
+/// This struct spreads the streams of a stream over combined stream connections, the shards,
+/// and merges their messages.
+///
+/// With a reconnect policy every shard reconnects on its own, and a shard whose policy runs
+/// out of attempts is rebalanced: its streams are subscribed on the other shards that have
+/// room for them and on new shards for the rest, then `Reconnected` is yielded. Streams a
+/// shard refuses to subscribe are moved onto new shards as well, and streams no shard could
+/// be opened for are kept for the next rebalance. Without a policy the streams of a shard end
+/// with its connection.
+pub(crate) struct ShardPool {
+    ws_url: &'static str,
+    sharding: BinanceStreamSharding,
+    reconnect_policy: Option<ReconnectPolicy>,
+    disconnection_senders: DisconnectionSenders,
+    disconnections: Arc<AtomicUsize>,
+    generation: usize,
+    registry: Arc<ShardRegistry>,
+    requests: u64,
+    /// Subscribe requests sent by a rebalance, with the shard and the streams they add.
+    awaiting: BTreeMap<u64, (usize, Vec<String>)>,
+    /// Streams that lost their shard and aren't carried by another one yet.
+    pending: Vec<String>,
+    shards: BTreeMap<usize, Shard>,
+    connections: SelectAll<BoxStream<'static, Tagged>>,
+}
+
+impl ShardPool {
+    pub fn new(
+        ws_url: &'static str,
+        parameters: &BinanceParameters,
+        disconnection_senders: DisconnectionSenders,
+        disconnections: Arc<AtomicUsize>,
+        registry: Arc<ShardRegistry>,
+    ) -> Self {
+        let generation = disconnections.load(Ordering::SeqCst);
+        Self {
+            ws_url,
+            sharding: parameters.stream_sharding.clone(),
+            reconnect_policy: parameters.reconnect_policy.clone(),
+            disconnection_senders,
+            disconnections,
+            generation,
+            registry,
+            requests: 0,
+            awaiting: BTreeMap::new(),
+            pending: Vec::new(),
+            shards: BTreeMap::new(),
+            connections: SelectAll::new(),
+        }
+    }
+
+    /// Opens the shards carrying `streams`.
+    pub async fn open(&mut self, streams: Vec<String>) -> Result<()> {
+        for streams in self.sharding.plan(self.ws_url, streams) {
+            self.open_shard(streams).await?;
+        }
+        Ok(())
+    }
+
+    /// Returns the next message of any shard, `None` once every shard is gone.
+    pub async fn next(&mut self) -> Option<Result<BinanceWebsocketMessage>> {
+        loop {
+            let (id, message) = self.connections.next().await?;
+            match message {
+                Some(Ok(BinanceWebsocketMessage::SubscriptionAck { id: Some(request) }))
+                    if self.awaiting.contains_key(&request) =>
+                {
+                    self.awaiting.remove(&request);
+                }
+                Some(Ok(BinanceWebsocketMessage::SubscriptionError {
+                    id: Some(request), ..
+                })) if self.awaiting.contains_key(&request) => {
+                    if let Some((shard, streams)) = self.awaiting.remove(&request) {
+                        self.unassign(shard, &streams);
+                        self.pending.extend(streams);
+                    }
+                    if let Err(error) = self.open_pending().await {
+                        return Some(Err(error));
+                    }
+                }
+                Some(message) => {
+                    self.record(id, &message);
+                    return Some(message);
+                }
+                None if self.reconnect_policy.is_none() || self.is_closed() => {
+                    self.remove(id);
+                }
+                None => {
+                    let rebalanced = self.rebalance(id).await;
+                    return Some(rebalanced.map(|_| BinanceWebsocketMessage::Reconnected));
+                }
+            }
+        }
+    }
+
+    async fn open_shard(&mut self, streams: Vec<String>) -> Result<()> {
+        let shard = Shard {
+            streams: Arc::new(Mutex::new(streams.clone())),
+            frames: Default::default(),
+        };
+        let ws_url = self.ws_url;
+        let shard_streams = shard.streams.clone();
+        let shard_frames = shard.frames.clone();
+        let senders = self.disconnection_senders.clone();
+        // Reconnections carry the streams rebalanced onto the shard meanwhile.
+        let connect = move || {
+            let streams = shard_streams
+                .lock()
+                .map(|streams| streams.clone())
+                .unwrap_or_default();
+            let frames = shard_frames.clone();
+            let senders = senders.clone();
+            async move {
+                let (connection, sender) = open_connection(ws_url, &streams, &senders).await?;
+                if let Ok(mut frames) = frames.lock() {
+                    *frames = Some(sender);
+                }
+                Ok(connection)
+            }
+            .boxed()
+        };
+
+        let connection = connect().await?;
+        let connection = match self.reconnect_policy.clone() {
+            None => connection,
+            Some(policy) => {
+                let disconnections = self.disconnections.clone();
+                let generation = self.generation;
+                reconnecting_stream(connection, policy, connect, move || {
+                    disconnections.load(Ordering::SeqCst) != generation
+                })
+            }
+        };
+
+        let id = self.registry.insert(streams);
+        let tagged = connection
+            .map(move |message| (id, Some(message)))
+            .chain(stream::once(future::ready((id, None))));
+        self.connections.push(tagged.boxed());
+        self.shards.insert(id, shard);
+        Ok(())
+    }
+
+    /// Moves the streams of the dropped shard `id`, and the pending ones, onto the other
+    /// shards and new ones.
+    async fn rebalance(&mut self, id: usize) -> Result<()> {
+        let orphans = self.remove(id);
+        self.pending.extend(orphans);
+        let mut orphans = std::mem::take(&mut self.pending).into_iter().peekable();
+        for (id, shard) in &self.shards {
+            let frames = match shard.frames.lock().ok().and_then(|frames| frames.clone()) {
+                Some(frames) => frames,
+                None => continue,
+            };
+            let mut streams = match shard.streams.lock() {
+                Ok(streams) => streams,
+                Err(_) => continue,
+            };
+            let mut added = Vec::new();
+            while let Some(stream) =
+                orphans.next_if(|stream| self.sharding.fits(self.ws_url, &streams, stream))
+            {
+                streams.push(stream.clone());
+                added.push(stream);
+            }
+            if added.is_empty() {
+                continue;
+            }
+            self.requests += 1;
+            let request = json!({ "method": "SUBSCRIBE", "params": added, "id": self.requests });
+            if frames.send(request.to_string()).is_err() {
+                // The connection is gone, the streams go to a new shard instead.
+                let kept = streams.len() - added.len();
+                streams.truncate(kept);
+                self.pending.extend(added);
+                continue;
+            }
+            self.registry
+                .update(*id, |health| health.streams = streams.clone());
+            self.awaiting.insert(self.requests, (*id, added));
+        }
+        self.pending.extend(orphans);
+        self.open_pending().await
+    }
+
+    /// Opens new shards for the pending streams, retrying as the reconnect policy allows. The
+    /// streams that are still without a shard stay pending.
+    async fn open_pending(&mut self) -> Result<()> {
+        let policy = self.reconnect_policy.clone().unwrap_or_default();
+        let mut attempts = 0;
+        loop {
+            let pending = std::mem::take(&mut self.pending);
+            let mut plan = self.sharding.plan(self.ws_url, pending).into_iter();
+            let mut failure = None;
+            for streams in plan.by_ref() {
+                if let Err(error) = self.open_shard(streams.clone()).await {
+                    self.pending = streams;
+                    failure = Some(error);
+                    break;
+                }
+            }
+            let error = match failure {
+                Some(error) => error,
+                None => return Ok(()),
+            };
+            self.pending.extend(plan.flatten());
+            attempts += 1;
+            if self.is_closed() || !policy.allows(attempts) {
+                return Err(error);
+            }
+            tokio::time::sleep(policy.backoff(attempts - 1)).await;
+        }
+    }
+
+    fn record(&self, id: usize, message: &Result<BinanceWebsocketMessage>) {
+        self.registry.update(id, |health| match message {
+            Ok(BinanceWebsocketMessage::Disconnected) => health.connected = false,
+            Ok(BinanceWebsocketMessage::Reconnected) => {
+                health.connected = true;
+                health.reconnects += 1;
+            }
+            Ok(_) => {
+                health.messages += 1;
+                health.last_message = Some(Instant::now());
+            }
+            Err(_) => (),
+        });
+    }
+
+    /// Takes `streams` off the shard `id`.
+    fn unassign(&self, id: usize, streams: &[String]) {
+        if let Some(shard) = self.shards.get(&id) {
+            if let Ok(mut carried) = shard.streams.lock() {
+                carried.retain(|stream| !streams.contains(stream));
+                self.registry
+                    .update(id, |health| health.streams = carried.clone());
+            }
+        }
+    }
+
+    /// Forgets the shard `id`, returning its streams.
+    fn remove(&mut self, id: usize) -> Vec<String> {
+        self.registry.remove(id);
+        self.awaiting.retain(|_, (shard, _)| *shard != id);
+        self.shards
+            .remove(&id)
+            .and_then(|shard| shard.streams.lock().ok().map(|streams| streams.clone()))
+            .unwrap_or_default()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.disconnections.load(Ordering::SeqCst) != self.generation
+    }
+}
+
+impl Drop for ShardPool {
+    fn drop(&mut self) {
+        for id in self.shards.keys() {
+            self.registry.remove(*id);
+        }
+    }
+}
+
+/// Opens a combined stream connection to `ws_url` carrying `streams`, returning its messages
+/// and a sender of text frames. The connection is closed once the sender is dropped, or by a
+/// message on the sender it registers in `disconnection_senders`.
+async fn open_connection(
+    ws_url: &str,
+    streams: &[String],
+    disconnection_senders: &DisconnectionSenders,
+) -> Result<(Connection, UnboundedSender<String>)> {
+    let endpoint = url::Url::parse(&format!("{}?streams={}", ws_url, streams.join("/")))
+        .map_err(EcbtError::UrlParserError)?;
+    let (ws_stream, _) = connect_async(endpoint).await?;
+
+    let (mut sink, stream) = ws_stream.split();
+    let (frame_sender, mut frames) = unbounded_channel::<String>();
+    let (disconnection_sender, mut disconnection_receiver) = unbounded_channel();
+    tokio::spawn(async move {
+        loop {
+            tokio::select! {
+                frame = frames.recv() => match frame {
+                    Some(frame) => {
+                        if sink.send(Message::Text(frame)).await.is_err() {
+                            break;
+                        }
+                    }
+                    None => break,
+                },
+                _ = disconnection_receiver.recv() => break,
+            }
+        }
+        sink.close().await.ok();
+    });
+
+    if let Ok(mut senders) = disconnection_senders.lock() {
+        // Connections that ended dropped their receiver, their senders are closed.
+        senders.retain(|sender| !sender.is_closed());
+        senders.push(disconnection_sender);
+    }
+
+    let s = stream.map(|message| match message {
+        Ok(msg) => parse_message(msg),
+        Err(_) => Err(EcbtError::SocketError()),
+    });
+
+    Ok((s.boxed(), frame_sender))
+}
+
+fn parse_message(ws_message: Message) -> Result<BinanceWebsocketMessage> {
+    let msg = match ws_message {
+        Message::Text(m) => m,
+        Message::Binary(b) => return Ok(BinanceWebsocketMessage::Binary(b)),
+        Message::Pong(..) => return Ok(BinanceWebsocketMessage::Pong),
+        Message::Ping(..) => return Ok(BinanceWebsocketMessage::Ping),
+        Message::Close(..) => return Ok(BinanceWebsocketMessage::Close),
+    };
+
+    serde_json::from_str(&msg).map_err(EcbtError::JsonError)
+}
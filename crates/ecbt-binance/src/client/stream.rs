@@ -1,38 +1,220 @@
+use super::shard_pool::DisconnectionSenders;
 use super::shared::Result;
+use super::{
+    AccountFilter, BaseClient, BinanceProtocol, DepthSync, DepthSynchronizer, ShardHealth,
+    ShardPool, ShardRegistry, Transport,
+};
 use crate::{
     model::websocket::{BinanceSubscription, BinanceWebsocketMessage},
     BinanceParameters,
 };
 use async_trait::async_trait;
+use ecbt_exchange::book::OrderBook;
 use ecbt_exchange::errors::EcbtError;
 use ecbt_exchange::exchange::Environment;
-use ecbt_exchange::stream::{ExchangeStream, Subscriptions};
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::OrderBookResponse;
+use ecbt_exchange::stream::{ExchangeStream, LiveConnection, Subscriptions};
 use ecbt_exchange::{
-    model::websocket::EcbtWebSocketMessage, model::websocket::Subscription,
-    model::websocket::WebSocketResponse,
+    model::websocket::EcbtWebSocketMessage, model::websocket::OrderBookUpdate,
+    model::websocket::Subscription, model::websocket::WebSocketResponse,
 };
-use futures::{stream::BoxStream, SinkExt, StreamExt};
-use serde::{de, Deserialize, Serialize};
+use futures::future::{BoxFuture, Either};
+use futures::{stream, stream::BoxStream, FutureExt, StreamExt};
+use serde::{de, Deserialize};
 use serde_json::Value;
-use std::sync::Mutex;
-use std::{convert::TryFrom, fmt::Display};
-use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::{
+    convert::{TryFrom, TryInto},
+    fmt::Display,
+    slice,
+    time::Duration,
+};
+use tokio::sync::mpsc::unbounded_channel;
+use tokio::sync::oneshot;
+use tokio::time::{interval_at, Instant};
 
 const WS_URL_PROD: &str = "wss://stream.binance.com:9443/stream";
 const WS_URL_SANDBOX: &str = "wss://testnet.binance.vision/stream";
+const DEPTH_UPDATE_SPEED: u16 = 100;
+const DEPTH_SNAPSHOT_LIMIT: u64 = 1000;
+/// Listen keys expire after 60 minutes unless kept alive.
+const USER_STREAM_KEEP_ALIVE: Duration = Duration::from_secs(30 * 60);
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(untagged)]
-enum Either<L, R> {
-    Left(L),
-    Right(R),
+/// The state of a `BinanceWebsocket::managed_order_book` stream, whose snapshot is fetched
+/// while the diffs received meanwhile are buffered.
+struct DepthStream {
+    diffs: BoxStream<'static, Result<BinanceWebsocketMessage>>,
+    sync: DepthSynchronizer,
+    snapshot: Option<BoxFuture<'static, Result<OrderBookResponse>>>,
+    client: BaseClient,
+    market_pair: MarketPair,
 }
 
 /// This struct is used for websocket communications with ecbt-binance ecbt-exchange
 pub struct BinanceWebsocket {
     parameters: BinanceParameters,
-    disconnection_senders: Mutex<Vec<UnboundedSender<()>>>,
+    /// The REST transport of the user data stream requests, rate limited and retried like
+    /// the one of `Binance`.
+    transport: Transport,
+    disconnection_senders: DisconnectionSenders,
+    /// Incremented by every `disconnect`, so reconnecting streams know they were closed.
+    disconnections: Arc<AtomicUsize>,
+    shards: Arc<ShardRegistry>,
+}
+
+impl BinanceWebsocket {
+    /// Streams a locally maintained order book of `market_pair`, yielding the full book after
+    /// every applied `@depth` diff.
+    ///
+    /// The book starts from a `client.get_depth` snapshot and is resynchronized from a new one
+    /// whenever a gap is detected in the diffs, so every yielded book is consistent. A single
+    /// snapshot is requested per resync, the diffs received while it is fetched being buffered.
+    pub async fn managed_order_book(
+        &self,
+        client: BaseClient,
+        market_pair: MarketPair,
+    ) -> Result<BoxStream<'static, Result<OrderBook>>> {
+        let symbol = crate::model::MarketPair::from(market_pair.clone()).0;
+        let subscription = BinanceSubscription::Depth(symbol, Some(DEPTH_UPDATE_SPEED));
+        let diffs = self
+            .create_stream_specific(slice::from_ref(&subscription).into())
+            .await?;
+        let state = DepthStream {
+            diffs,
+            sync: DepthSynchronizer::new(),
+            snapshot: None,
+            client,
+            market_pair,
+        };
+
+        let books = stream::unfold(state, |mut state| async move {
+            loop {
+                let event = match state.snapshot.as_mut() {
+                    Some(snapshot) => tokio::select! {
+                        snapshot = snapshot => Either::Right(snapshot),
+                        message = state.diffs.next() => Either::Left(message?),
+                    },
+                    None => Either::Left(state.diffs.next().await?),
+                };
+
+                let outcome = match event {
+                    Either::Left(Ok(BinanceWebsocketMessage::Depth(depth))) => {
+                        state.sync.push(depth)
+                    }
+                    Either::Left(Ok(BinanceWebsocketMessage::Reconnected)) => {
+                        state.sync = DepthSynchronizer::new();
+                        state.snapshot = None;
+                        continue;
+                    }
+                    Either::Left(Ok(BinanceWebsocketMessage::Close)) => {
+                        return Some((Err(EcbtError::SocketError()), state))
+                    }
+                    Either::Left(Ok(_)) => continue,
+                    Either::Left(Err(err)) => return Some((Err(err), state)),
+                    Either::Right(Ok(snapshot)) => {
+                        state.snapshot = None;
+                        state.sync.apply_snapshot(&snapshot)
+                    }
+                    Either::Right(Err(err)) => {
+                        // The next diff requests a new snapshot.
+                        state.sync = DepthSynchronizer::new();
+                        state.snapshot = None;
+                        return Some((Err(err), state));
+                    }
+                };
+
+                match outcome {
+                    DepthSync::NeedsSnapshot => {
+                        let client = state.client.clone();
+                        let market_pair = state.market_pair.clone();
+                        state.snapshot = Some(
+                            async move {
+                                client
+                                    .get_depth(market_pair, DEPTH_SNAPSHOT_LIMIT)
+                                    .await
+                                    .map(Into::into)
+                            }
+                            .boxed(),
+                        );
+                    }
+                    DepthSync::Synced => {
+                        let book = state.sync.book().clone();
+                        return Some((Ok(book), state));
+                    }
+                    DepthSync::Stale | DepthSync::Buffered => {}
+                }
+            }
+        });
+
+        Ok(books.boxed())
+    }
+
+    /// Returns the health of the combined stream connections of the open streams, whose
+    /// subscriptions are sharded following `BinanceParameters::stream_sharding`.
+    pub fn shards(&self) -> Vec<ShardHealth> {
+        self.shards.snapshot()
+    }
+
+    /// Opens a combined stream connection without streams, whose subscriptions are added and
+    /// removed while it is open.
+    pub async fn live_connection(&self) -> Result<LiveConnection<BinanceProtocol>> {
+        let ws_url = match self.parameters.environment {
+            Environment::Sandbox => WS_URL_SANDBOX,
+            Environment::Production => WS_URL_PROD,
+        };
+        let endpoint = url::Url::parse(ws_url).map_err(EcbtError::UrlParserError)?;
+        LiveConnection::connect(endpoint, BinanceProtocol).await
+    }
+
+    /// Starts a user data stream and keeps its listen key alive until the websocket is
+    /// disconnected or the returned receiver is dropped, then closes it.
+    ///
+    /// The receiver gets the error of a keep alive that failed, after which the listen key
+    /// expires.
+    async fn start_user_stream(&self) -> Result<(String, oneshot::Receiver<EcbtError>)> {
+        let client = self.user_stream_client()?;
+        let listen_key = client.user_stream_start().await?.listen_key;
+
+        let (alive_sender, alive_receiver) = oneshot::channel::<EcbtError>();
+        let (disconnection_sender, mut disconnection_receiver) = unbounded_channel();
+        if let Ok(mut senders) = self.disconnection_senders.lock() {
+            // Connections that ended dropped their receiver, their senders are closed.
+            senders.retain(|sender| !sender.is_closed());
+            senders.push(disconnection_sender);
+        }
+        let key = listen_key.clone();
+        tokio::spawn(async move {
+            let mut alive_sender = alive_sender;
+            let start = Instant::now() + USER_STREAM_KEEP_ALIVE;
+            let mut keep_alive = interval_at(start, USER_STREAM_KEEP_ALIVE);
+            loop {
+                tokio::select! {
+                    _ = keep_alive.tick() => (),
+                    _ = disconnection_receiver.recv() => break,
+                    _ = alive_sender.closed() => break,
+                }
+                if let Err(error) = client.user_stream_keep_alive(&key).await {
+                    client.user_stream_close(&key).await.ok();
+                    alive_sender.send(error).ok();
+                    return;
+                }
+            }
+            client.user_stream_close(&key).await.ok();
+        });
+
+        Ok((listen_key, alive_receiver))
+    }
+
+    fn user_stream_client(&self) -> Result<BaseClient> {
+        if self.parameters.credentials.is_none() {
+            return Err(EcbtError::NoApiKeySet());
+        }
+        Ok(BaseClient {
+            transport: self.transport.clone(),
+        })
+    }
 }
 
 #[async_trait]
@@ -42,13 +224,18 @@ impl ExchangeStream for BinanceWebsocket {
     type Response = BinanceWebsocketMessage;
 
     async fn new(parameters: Self::InitParams) -> Result<Self> {
+        let (transport, _) = parameters.transport()?;
         Ok(BinanceWebsocket {
             parameters,
+            transport,
             disconnection_senders: Default::default(),
+            disconnections: Default::default(),
+            shards: Default::default(),
         })
     }
 
     async fn disconnect(&self) {
+        self.disconnections.fetch_add(1, Ordering::SeqCst);
         if let Ok(mut senders) = self.disconnection_senders.lock() {
             for sender in senders.iter() {
                 sender.send(()).ok();
@@ -61,38 +248,59 @@ impl ExchangeStream for BinanceWebsocket {
         &self,
         subscriptions: Subscriptions<Self::Subscription>,
     ) -> Result<BoxStream<'static, Result<Self::Response>>> {
-        let streams = subscriptions
+        let (account, market): (Vec<_>, Vec<_>) = subscriptions
+            .into_iter()
+            .partition(BinanceSubscription::is_account);
+        let mut streams = market
             .into_iter()
             .map(|bs| bs.to_string())
-            .collect::<Vec<String>>()
-            .join("/");
+            .collect::<Vec<String>>();
+        let mut user_stream = None;
+        if !account.is_empty() {
+            let (listen_key, alive) = self.start_user_stream().await?;
+            streams.push(listen_key);
+            user_stream = Some((AccountFilter::new(&account), alive));
+        }
 
         let ws_url = match self.parameters.environment {
             Environment::Sandbox => WS_URL_SANDBOX,
             Environment::Production => WS_URL_PROD,
         };
-        let endpoint = url::Url::parse(&format!("{}?streams={}", ws_url, streams.to_lowercase()))
-            .map_err(EcbtError::UrlParserError)?;
-        let (ws_stream, _) = connect_async(endpoint).await?;
+        let mut pool = ShardPool::new(
+            ws_url,
+            &self.parameters,
+            self.disconnection_senders.clone(),
+            self.disconnections.clone(),
+            self.shards.clone(),
+        );
+        pool.open(streams).await?;
+        let stream = stream::unfold(pool, |mut pool| async move {
+            let message = pool.next().await?;
+            Some((message, pool))
+        })
+        .boxed();
 
-        let (mut sink, stream) = ws_stream.split();
-        let (disconnection_sender, mut disconnection_receiver) = unbounded_channel();
-        tokio::spawn(async move {
-            if disconnection_receiver.recv().await.is_some() {
-                sink.close().await.ok();
+        Ok(match user_stream {
+            None => stream,
+            Some((filter, alive)) => {
+                let messages = stream.flat_map(move |message| {
+                    stream::iter(match message {
+                        Ok(message) => filter.apply(message).into_iter().map(Ok).collect(),
+                        Err(err) => vec![Err(err)],
+                    })
+                });
+                // The listen key is kept alive as long as the stream holds `alive`. Once a
+                // keep alive failed the stream ends with its error, as the key expires.
+                stream::unfold(Some((messages, alive.fuse())), |state| async move {
+                    let (mut messages, mut alive) = state?;
+                    tokio::select! {
+                        message = messages.next() => Some((message?, Some((messages, alive)))),
+                        Ok(error) = &mut alive => Some((Err(error), None)),
+                    }
+                })
+                .boxed()
             }
-        });
-
-        if let Ok(mut senders) = self.disconnection_senders.lock() {
-            senders.push(disconnection_sender);
-        }
-
-        let s = stream.map(|message| match message {
-            Ok(msg) => parse_message(msg),
-            Err(_) => Err(EcbtError::SocketError()),
-        });
-
-        Ok(s.boxed())
+        })
     }
 }
 
@@ -103,75 +311,141 @@ struct BinanceWebsocketStream {
     pub data: Value,
 }
 
+// Messages of streams and events this version doesn't know of, or whose data it can't read,
+// are `Unknown`, so new Binance streams and changed payloads never fail a connection.
 impl<'de> Deserialize<'de> for BinanceWebsocketMessage {
     fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
     where
         D: serde::Deserializer<'de>,
     {
-        let stream: BinanceWebsocketStream = BinanceWebsocketStream::deserialize(deserializer)?;
-
-        if stream.name.ends_with("@aggTrade") {
-            Ok(BinanceWebsocketMessage::AggregateTrade(
-                serde_json::from_value(stream.data).map_err(de::Error::custom)?,
-            ))
-        } else if stream.name.contains("@trade") {
-            Ok(BinanceWebsocketMessage::Trade(
-                serde_json::from_value(stream.data).map_err(de::Error::custom)?,
-            ))
-        } else if stream.name.contains("@kline_") {
-            Ok(BinanceWebsocketMessage::Candlestick(
-                serde_json::from_value(stream.data).map_err(de::Error::custom)?,
-            ))
-        } else if stream.name.contains("@ticker") {
-            Ok(BinanceWebsocketMessage::Ticker(
-                serde_json::from_value(stream.data).map_err(de::Error::custom)?,
-            ))
-        } else if stream.name.eq("!ticker@arr") {
-            Ok(BinanceWebsocketMessage::TickerAll(
-                serde_json::from_value(stream.data).map_err(de::Error::custom)?,
-            ))
-        } else if stream.name.ends_with("@miniTicker") {
-            Ok(BinanceWebsocketMessage::MiniTicker(
-                serde_json::from_value(stream.data).map_err(de::Error::custom)?,
-            ))
-        } else if stream.name.ends_with("!miniTicker@arr") {
-            Ok(BinanceWebsocketMessage::MiniTickerAll(
-                serde_json::from_value(stream.data).map_err(de::Error::custom)?,
-            ))
-        } else if stream.name.ends_with("@depth") {
-            Ok(BinanceWebsocketMessage::Depth(
-                serde_json::from_value(stream.data).map_err(de::Error::custom)?,
-            ))
-        } else if stream.name.contains("@depth") {
-            Ok(BinanceWebsocketMessage::OrderBook(
-                serde_json::from_value(stream.data).map_err(de::Error::custom)?,
-            ))
-        } else {
-            panic!("Not supported Subscription");
+        let value = Value::deserialize(deserializer)?;
+        if value.get("stream").is_none() {
+            return Ok(request_response(value));
+        }
+        let stream: BinanceWebsocketStream =
+            serde_json::from_value(value).map_err(de::Error::custom)?;
+
+        Ok(
+            stream_message(&stream.name, &stream.data).unwrap_or(
+                BinanceWebsocketMessage::Unknown {
+                    stream: stream.name,
+                    payload: stream.data,
+                },
+            ),
+        )
+    }
+}
+
+/// Reads the `data` of the stream `name`, `None` if the stream is unknown or its data doesn't
+/// have the expected shape.
+fn stream_message(name: &str, data: &Value) -> Option<BinanceWebsocketMessage> {
+    // User data streams are named after their listen key, their events tell them apart.
+    match data.get("e").and_then(Value::as_str) {
+        Some("executionReport") => {
+            return typed(data).map(BinanceWebsocketMessage::UserOrderUpdate)
         }
+        Some("outboundAccountPosition") => {
+            return typed(data).map(BinanceWebsocketMessage::UserAccountUpdate)
+        }
+        _ => (),
+    }
+
+    if name.ends_with("@bookTicker") || name.eq("!bookTicker") {
+        typed(data).map(BinanceWebsocketMessage::BookTicker)
+    } else if name.ends_with("@aggTrade") {
+        typed(data).map(BinanceWebsocketMessage::AggregateTrade)
+    } else if name.contains("@trade") {
+        typed(data).map(BinanceWebsocketMessage::Trade)
+    } else if name.contains("@kline_") {
+        typed(data).map(BinanceWebsocketMessage::Candlestick)
+    } else if name.contains("@ticker") {
+        typed(data).map(BinanceWebsocketMessage::Ticker)
+    } else if name.eq("!ticker@arr") {
+        typed(data).map(BinanceWebsocketMessage::TickerAll)
+    } else if name.ends_with("@miniTicker") {
+        typed(data).map(BinanceWebsocketMessage::MiniTicker)
+    } else if name.ends_with("!miniTicker@arr") {
+        typed(data).map(BinanceWebsocketMessage::MiniTickerAll)
+    } else if is_diff_depth(name) {
+        typed(data).map(BinanceWebsocketMessage::Depth)
+    } else if name.contains("@depth") {
+        typed(data).map(BinanceWebsocketMessage::OrderBook)
+    } else {
+        None
+    }
+}
+
+fn typed<'a, T: Deserialize<'a>>(data: &'a Value) -> Option<T> {
+    T::deserialize(data).ok()
+}
+
+/// Diff depth streams are named `<symbol>@depth` or `<symbol>@depth@<speed>ms`, partial book
+/// streams `<symbol>@depth<levels>` with an optional speed.
+fn is_diff_depth(name: &str) -> bool {
+    match name.split_once("@depth") {
+        Some((_, speed)) => speed.is_empty() || speed.starts_with('@'),
+        None => false,
+    }
+}
+
+/// Reads a message that belongs to no stream: requests are answered with
+/// `{"result": null, "id": 1}`, or with an error either at the top level or in an `error`
+/// object.
+fn request_response(value: Value) -> BinanceWebsocketMessage {
+    let id = value.get("id").and_then(Value::as_u64);
+    if value.get("result").is_some() {
+        return BinanceWebsocketMessage::SubscriptionAck { id };
+    }
+    let error = value.get("error").unwrap_or(&value);
+    match error.get("msg").and_then(Value::as_str) {
+        Some(message) => BinanceWebsocketMessage::SubscriptionError {
+            id,
+            code: error.get("code").and_then(Value::as_i64),
+            message: message.to_string(),
+        },
+        None => BinanceWebsocketMessage::Unknown {
+            stream: String::new(),
+            payload: value,
+        },
     }
 }
 
 impl Display for BinanceSubscription {
+    // Symbols are lowercase in stream names, while intervals ("1m" and "1M") and listen keys
+    // are case sensitive.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            BinanceSubscription::AggregateTrade(ref symbol) => write!(f, "{}@aggTrade", symbol),
+            BinanceSubscription::AggregateTrade(ref symbol) => {
+                write!(f, "{}@aggTrade", symbol.to_lowercase())
+            }
             BinanceSubscription::Candlestick(ref symbol, ref interval) => {
-                write!(f, "{}@kline_{}", symbol, interval)
+                write!(f, "{}@kline_{}", symbol.to_lowercase(), interval)
             }
             BinanceSubscription::Depth(ref symbol, interval) => match interval {
-                None => write!(f, "{}@depth", symbol),
-                Some(i) => write!(f, "{}@depth@{}ms", symbol, i),
+                None => write!(f, "{}@depth", symbol.to_lowercase()),
+                Some(i) => write!(f, "{}@depth@{}ms", symbol.to_lowercase(), i),
             },
-            BinanceSubscription::MiniTicker(symbol) => write!(f, "{}@miniTicker", symbol),
+            BinanceSubscription::MiniTicker(symbol) => {
+                write!(f, "{}@miniTicker", symbol.to_lowercase())
+            }
             BinanceSubscription::MiniTickerAll => write!(f, "!miniTicker@arr"),
             BinanceSubscription::OrderBook(ref symbol, depth) => {
-                write!(f, "{}@depth{}", symbol, depth)
+                write!(f, "{}@depth{}", symbol.to_lowercase(), depth)
+            }
+            BinanceSubscription::Ticker(ref symbol) => {
+                write!(f, "{}@ticker", symbol.to_lowercase())
             }
-            BinanceSubscription::Ticker(ref symbol) => write!(f, "{}@ticker", symbol),
             BinanceSubscription::TickerAll => write!(f, "!ticker@arr"),
-            BinanceSubscription::Trade(ref symbol) => write!(f, "{}@trade", symbol),
+            BinanceSubscription::BookTicker(ref symbol) => {
+                write!(f, "{}@bookTicker", symbol.to_lowercase())
+            }
+            BinanceSubscription::BookTickerAll => write!(f, "!bookTicker"),
+            BinanceSubscription::Trade(ref symbol) => write!(f, "{}@trade", symbol.to_lowercase()),
             BinanceSubscription::UserData(ref key) => write!(f, "{}", key),
+            // Served by the user data stream started with the connection.
+            BinanceSubscription::UserOrders(_)
+            | BinanceSubscription::UserTrades(_)
+            | BinanceSubscription::UserBalance(..) => write!(f, "userData"),
         }
     }
 }
@@ -179,12 +453,27 @@ impl Display for BinanceSubscription {
 impl From<Subscription> for BinanceSubscription {
     fn from(subscription: Subscription) -> Self {
         match subscription {
+            Subscription::Ticker(symbol) => {
+                BinanceSubscription::Ticker(crate::model::MarketPair::from(symbol).0)
+            }
             Subscription::OrderBookUpdates(symbol) => {
                 BinanceSubscription::Depth(crate::model::MarketPair::from(symbol).0, None)
             }
             Subscription::Trades(symbol) => {
                 BinanceSubscription::Trade(crate::model::MarketPair::from(symbol).0)
             }
+            Subscription::Candles(symbol, interval) => BinanceSubscription::Candlestick(
+                crate::model::MarketPair::from(symbol).0,
+                <&str>::from(interval).to_string(),
+            ),
+            Subscription::AccountOrders(orders) => BinanceSubscription::UserOrders(orders),
+            Subscription::AccountTrades(symbol) => {
+                BinanceSubscription::UserTrades(crate::model::MarketPair::from(symbol).0)
+            }
+            Subscription::AccountBalance(symbol) => BinanceSubscription::UserBalance(
+                symbol.0.to_string().to_uppercase(),
+                symbol.1.to_string().to_uppercase(),
+            ),
         }
     }
 }
@@ -195,28 +484,64 @@ impl TryFrom<BinanceWebsocketMessage> for WebSocketResponse<BinanceWebsocketMess
     fn try_from(value: BinanceWebsocketMessage) -> Result<Self> {
         match value {
             BinanceWebsocketMessage::Depth(orderbook) => Ok(WebSocketResponse::Generic(
-                EcbtWebSocketMessage::OrderBook(orderbook.into()),
+                EcbtWebSocketMessage::OrderBookDiff(OrderBookUpdate {
+                    market_pair: orderbook.symbol.clone(),
+                    book: orderbook.into(),
+                }),
             )),
             BinanceWebsocketMessage::Trade(trade) => Ok(WebSocketResponse::Generic(
                 EcbtWebSocketMessage::Trades(trade.into()),
             )),
+            BinanceWebsocketMessage::AggregateTrade(trade) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Trades(vec![trade.into()]),
+            )),
+            BinanceWebsocketMessage::BookTicker(ticker) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::BookTicker(ticker.into()),
+            )),
+            BinanceWebsocketMessage::Ticker(ticker) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Ticker(ticker.into()),
+            )),
+            BinanceWebsocketMessage::MiniTicker(ticker) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Ticker(ticker.into()),
+            )),
+            BinanceWebsocketMessage::Candlestick(candlestick) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Candle(candlestick.try_into()?),
+            )),
+            BinanceWebsocketMessage::UserOrderUpdate(update) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::OrderUpdate(update.into()),
+            )),
+            BinanceWebsocketMessage::UserTrade(update) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Fill(update.into()),
+            )),
+            BinanceWebsocketMessage::UserAccountUpdate(update) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::BalanceUpdate(
+                    update.balance.into_iter().map(Into::into).collect(),
+                ),
+            )),
+            BinanceWebsocketMessage::SubscriptionAck { id } => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::SubscriptionAck {
+                    request_id: id,
+                    channels: Vec::new(),
+                },
+            )),
+            BinanceWebsocketMessage::SubscriptionError { id, code, message } => Ok(
+                WebSocketResponse::Generic(EcbtWebSocketMessage::SubscriptionError {
+                    request_id: id,
+                    code,
+                    message,
+                }),
+            ),
             BinanceWebsocketMessage::Ping => {
                 Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::Ping))
             }
             BinanceWebsocketMessage::Close => Err(EcbtError::SocketError()),
+            BinanceWebsocketMessage::Disconnected => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Disconnected,
+            )),
+            BinanceWebsocketMessage::Reconnected => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Reconnected,
+            )),
             _ => Ok(WebSocketResponse::Raw(value)),
         }
     }
 }
-
-fn parse_message(ws_message: Message) -> Result<BinanceWebsocketMessage> {
-    let msg = match ws_message {
-        Message::Text(m) => m,
-        Message::Binary(b) => return Ok(BinanceWebsocketMessage::Binary(b)),
-        Message::Pong(..) => return Ok(BinanceWebsocketMessage::Pong),
-        Message::Ping(..) => return Ok(BinanceWebsocketMessage::Ping),
-        Message::Close(..) => return Ok(BinanceWebsocketMessage::Close),
-    };
-
-    serde_json::from_str(&msg).map_err(EcbtError::JsonError)
-}
@@ -12,13 +12,13 @@ impl BaseClient {
         Ok(user_data_stream)
     }
 
-    /// Current open orders on a symbol
+    /// Extends the validity of a listen key by 60 minutes
     pub async fn user_stream_keep_alive(&self, listen_key: &str) -> Result<Success> {
         let success = self
             .transport
             .put(
                 USER_DATA_STREAM,
-                Some(&vec![("listen_key", listen_key.to_string())]),
+                Some(&vec![("listenKey", listen_key.to_string())]),
             )
             .await?;
         Ok(success)
@@ -29,7 +29,7 @@ impl BaseClient {
             .transport
             .delete(
                 USER_DATA_STREAM,
-                Some(&vec![("listen_key", listen_key.to_string())]),
+                Some(&vec![("listenKey", listen_key.to_string())]),
             )
             .await?;
         Ok(success)
@@ -9,43 +9,66 @@ use client::BaseClient;
 use ecbt_exchange::{
     errors::EcbtError,
     model::{
-        AskBid, Balance, CancelAllOrdersRequest, CancelOrderRequest, Candle, EcbtOrderRequest,
+        AmendMethod, AmendOrderRequest, AmendedOrder, AskBid, Balance, CancelAllOrdersRequest,
+        CancelOrderRequest, Candle, ConditionalOrderRequest, EcbtOrderRequest,
         GetHistoricRatesRequest, GetHistoricTradesRequest, GetOrderHistoryRequest, GetOrderRequest,
-        GetPriceTickerRequest, Liquidity, OpenMarketOrderRequest, Order, OrderBookRequest,
-        OrderBookResponse, OrderCanceled, OrderStatus, OrderType, Paginator, Side, Ticker,
-        TimeInForce, Trade, TradeHistoryRequest, Transaction,
+        GetPriceTickerRequest, Interval, Liquidity, OcoOrder, OcoOrderRequest,
+        OpenMarketOrderRequest, Order, OrderBookRequest, OrderBookResponse, OrderCanceled,
+        OrderStatus, OrderTrigger, OrderType, Paginator, Side, Ticker, TimeInForce, Trade,
+        TradeHistoryRequest, TrailingOffset, Transaction,
     },
 };
 use model::KlineSummaries;
-use model::{websocket::TradeMessage, SymbolFilter, ORDER_TYPE_LIMIT, ORDER_TYPE_MARKET};
+use model::{
+    websocket::TradeMessage, CANCEL_REPLACE_MODE_STOP_ON_FAILURE, ORDER_SIDE_BUY, ORDER_SIDE_SELL,
+    ORDER_TYPE_LIMIT, ORDER_TYPE_LIMIT_MAKER, ORDER_TYPE_MARKET, ORDER_TYPE_STOP_LOSS,
+    ORDER_TYPE_STOP_LOSS_LIMIT, ORDER_TYPE_TAKE_PROFIT, ORDER_TYPE_TAKE_PROFIT_LIMIT,
+};
+use rust_decimal::Decimal;
 use std::convert::TryFrom;
-use transport::Transport;
 
 use crate::shared::Result;
 
 mod binance_content_error;
 mod binance_credentials;
 mod binance_parameters;
+mod binance_request_weigher;
+mod binance_stream_sharding;
 mod transport;
 
 pub use binance_content_error::*;
 pub use binance_credentials::*;
 pub use binance_parameters::*;
+pub use binance_request_weigher::*;
+pub use binance_stream_sharding::*;
 pub use transport::*;
 
 pub mod client;
+#[cfg(test)]
+mod tests;
 
 pub use client::stream::BinanceWebsocket;
-use ecbt_exchange::exchange::Environment;
-use ecbt_exchange::info::{ExchangeInfo, ExchangeInfoRetrieval, MarketPairHandle, MarketPairInfo};
+use ecbt_exchange::info::{
+    ExchangeInfo, ExchangeInfoRetrieval, MarketPairHandle, MarketPairInfo, OrderValidation,
+};
 use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::websocket::{BookTickerUpdate, CandleUpdate, TickerUpdate};
+use ecbt_exchange::rate_limit::{RateLimitRule, RateLimiter};
+use ecbt_exchange::retry::{new_client_order_id, place_order, RetryPolicy};
 use ecbt_exchange::{Exchange, ExchangeAccount, ExchangeMarketData};
+use std::future::Future;
+use std::sync::Arc;
 
 /// The main struct of the ecbt-binance module
 #[derive(Clone)]
 pub struct Binance {
     pub exchange_info: ExchangeInfo,
     pub client: BaseClient,
+    /// Updated with the limits published in `exchangeInfo` when the market info is refreshed.
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    pub order_validation: OrderValidation,
+    /// Orders are given a client order id and reconciled by it on ambiguous failures if set.
+    pub retry_policy: Option<RetryPolicy>,
 }
 
 #[async_trait]
@@ -54,23 +77,13 @@ impl Exchange for Binance {
     type InnerClient = BaseClient;
 
     async fn new(parameters: Self::InitParams) -> Result<Self> {
-        let binance = match parameters.credentials {
-            Some(credentials) => Binance {
-                exchange_info: ExchangeInfo::new(),
-                client: BaseClient {
-                    transport: Transport::with_credential(
-                        &credentials.api_key,
-                        &credentials.api_secret,
-                        parameters.environment == Environment::Sandbox,
-                    )?,
-                },
-            },
-            None => Binance {
-                exchange_info: ExchangeInfo::new(),
-                client: BaseClient {
-                    transport: Transport::new(parameters.environment == Environment::Sandbox)?,
-                },
-            },
+        let (transport, rate_limiter) = parameters.transport()?;
+        let binance = Binance {
+            exchange_info: ExchangeInfo::new(),
+            client: BaseClient { transport },
+            rate_limiter,
+            order_validation: parameters.order_validation,
+            retry_policy: parameters.retry_policy,
         };
 
         binance.refresh_market_info().await?;
@@ -82,51 +95,68 @@ impl Exchange for Binance {
     }
 }
 
+impl Binance {
+    /// Sends an order once, or with a client order id it can be reconciled by when a retry
+    /// policy is set.
+    async fn place_order<F, Fut>(
+        &self,
+        symbol: &str,
+        client_order_id: Option<String>,
+        place: F,
+    ) -> Result<Order>
+    where
+        F: Fn(Option<String>) -> Fut,
+        Fut: Future<Output = Result<model::Order>>,
+    {
+        let retry_policy = match &self.retry_policy {
+            Some(retry_policy) => retry_policy,
+            None => return place(client_order_id).await.map(Into::into),
+        };
+        let client_order_id = client_order_id.unwrap_or_else(new_client_order_id);
+        place_order(
+            retry_policy,
+            || async { place(Some(client_order_id.clone())).await.map(Into::into) },
+            || async {
+                self.client
+                    .get_order_by_client_id(symbol, &client_order_id)
+                    .await
+                    .map(Into::into)
+            },
+        )
+        .await
+    }
+
+    /// Returns the average price Binance checks price bands and market order notionals
+    /// against, when the validation of an order `needs` it.
+    async fn reference_price(&self, pair: &MarketPairInfo, needs: bool) -> Result<Option<Decimal>> {
+        if !needs || self.order_validation == OrderValidation::Off {
+            return Ok(None);
+        }
+        self.client
+            .get_average_price(model::MarketPair(pair.symbol.clone()))
+            .await
+            .map(|average_price| Some(average_price.price))
+    }
+}
+
 #[async_trait]
 impl ExchangeInfoRetrieval for Binance {
     async fn retrieve_pairs(&self) -> Result<Vec<MarketPairInfo>> {
-        self.client.get_exchange_info().await.map(|v| {
-            v.symbols
-                .into_iter()
-                .map(|symbol| {
-                    let lot_size = symbol
-                        .filters
+        let exchange_information = self.client.get_exchange_info().await;
+        if let (Ok(exchange_information), Some(rate_limiter)) =
+            (&exchange_information, &self.rate_limiter)
+        {
+            if !exchange_information.rate_limits.is_empty() {
+                rate_limiter.set_rules(
+                    exchange_information
+                        .rate_limits
                         .iter()
-                        .find_map(|f| match f {
-                            SymbolFilter::LotSize {
-                                max_qty: _,
-                                min_qty: _,
-                                step_size,
-                            } => Some(step_size),
-                            _ => None,
-                        })
-                        .expect("Couldn't find lot size.");
-
-                    let tick_size = symbol
-                        .filters
-                        .iter()
-                        .find_map(|f| match f {
-                            SymbolFilter::PriceFilter {
-                                min_price: _,
-                                max_price: _,
-                                tick_size,
-                            } => Some(tick_size),
-                            _ => None,
-                        })
-                        .expect("Couldn't find tick size.");
-
-                    MarketPairInfo {
-                        base: symbol.base_asset,
-                        quote: symbol.quote_asset,
-                        symbol: symbol.symbol,
-                        base_increment: *lot_size,
-                        quote_increment: *tick_size,
-                        min_base_trade_size: None,
-                        min_quote_trade_size: None,
-                    }
-                })
-                .collect()
-        })
+                        .map(RateLimitRule::from)
+                        .collect(),
+                )?;
+            }
+        }
+        exchange_information.map(|v| v.symbols.iter().map(MarketPairInfo::from).collect())
     }
 
     async fn refresh_market_info(&self) -> Result<Vec<MarketPairHandle>> {
@@ -175,41 +205,82 @@ impl ExchangeMarketData for Binance {
 impl ExchangeAccount for Binance {
     async fn limit_buy(&self, req: &EcbtOrderRequest) -> Result<Order> {
         let pair = self.get_pair(&req.market_pair).await?.read()?;
-        self.client
-            .limit_buy(
-                pair,
-                req.size,
-                req.price,
-                model::TimeInForce::from(req.time_in_force),
-                req.post_only,
-            )
-            .await
-            .map(Into::into)
+        let reference_price = self
+            .reference_price(&pair, pair.has_price_band(&Side::Buy))
+            .await?;
+        let req =
+            &pair.validate_limit_order(&Side::Buy, req, self.order_validation, reference_price)?;
+        self.place_order(
+            &pair.symbol,
+            req.client_order_id.clone(),
+            |client_order_id| {
+                self.client.limit_buy(
+                    pair.clone(),
+                    req.size,
+                    req.price,
+                    model::TimeInForce::from(req.time_in_force),
+                    req.post_only,
+                    client_order_id,
+                )
+            },
+        )
+        .await
     }
     async fn limit_sell(&self, req: &EcbtOrderRequest) -> Result<Order> {
         let pair = self.get_pair(&req.market_pair).await?.read()?;
-        self.client
-            .limit_sell(
-                pair,
-                req.size,
-                req.price,
-                model::TimeInForce::from(req.time_in_force),
-                req.post_only,
-            )
-            .await
-            .map(Into::into)
+        let reference_price = self
+            .reference_price(&pair, pair.has_price_band(&Side::Sell))
+            .await?;
+        let req =
+            &pair.validate_limit_order(&Side::Sell, req, self.order_validation, reference_price)?;
+        self.place_order(
+            &pair.symbol,
+            req.client_order_id.clone(),
+            |client_order_id| {
+                self.client.limit_sell(
+                    pair.clone(),
+                    req.size,
+                    req.price,
+                    model::TimeInForce::from(req.time_in_force),
+                    req.post_only,
+                    client_order_id,
+                )
+            },
+        )
+        .await
     }
 
     async fn market_buy(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
         let pair = self.get_pair(&req.market_pair).await?.read()?;
-        self.client.market_buy(pair, req.size).await.map(Into::into)
+        let reference_price = self
+            .reference_price(&pair, pair.has_notional_limits())
+            .await?;
+        let req = &pair.validate_market_order(req, self.order_validation, reference_price)?;
+        self.place_order(
+            &pair.symbol,
+            req.client_order_id.clone(),
+            |client_order_id| {
+                self.client
+                    .market_buy(pair.clone(), req.size, client_order_id)
+            },
+        )
+        .await
     }
     async fn market_sell(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
         let pair = self.get_pair(&req.market_pair).await?.read()?;
-        self.client
-            .market_sell(pair, req.size)
-            .await
-            .map(Into::into)
+        let reference_price = self
+            .reference_price(&pair, pair.has_notional_limits())
+            .await?;
+        let req = &pair.validate_market_order(req, self.order_validation, reference_price)?;
+        self.place_order(
+            &pair.symbol,
+            req.client_order_id.clone(),
+            |client_order_id| {
+                self.client
+                    .market_sell(pair.clone(), req.size, client_order_id)
+            },
+        )
+        .await
     }
     async fn cancel_order(&self, req: &CancelOrderRequest) -> Result<OrderCanceled> {
         if let Some(pair) = req.market_pair.as_ref() {
@@ -279,6 +350,93 @@ impl ExchangeAccount for Binance {
             .expect("ecbt-binance order id did not parse as u64");
         self.client.get_order(&pair, u64_id).await.map(Into::into)
     }
+
+    async fn place_conditional_order(&self, req: &ConditionalOrderRequest) -> Result<Order> {
+        let pair = self.get_pair(&req.market_pair).await?.read()?;
+        let req = &pair.validate_conditional_order(req, self.order_validation)?;
+        let order = model::OrderRequest::try_from(req)?;
+        self.place_order(
+            &pair.symbol,
+            req.client_order_id.clone(),
+            |new_client_order_id| {
+                self.client.new_order(model::OrderRequest {
+                    new_client_order_id,
+                    ..order.clone()
+                })
+            },
+        )
+        .await
+    }
+
+    /// Binance cancels the order and places its replacement in one request, which places
+    /// nothing when the cancellation fails. The replacement is the part of `req.size` the
+    /// order didn't fill when it was fetched before the request.
+    async fn amend_order(&self, req: &AmendOrderRequest) -> Result<AmendedOrder> {
+        let pair = self.get_pair(&req.market_pair).await?.read()?;
+        let amended = self
+            .get_order(&GetOrderRequest {
+                id: req.id.clone(),
+                market_pair: Some(pair.symbol.clone()),
+            })
+            .await?;
+        let size = req.unfilled_size(&amended)?;
+        let reference_price = self
+            .reference_price(&pair, pair.has_price_band(&req.side))
+            .await?;
+        let replacement = pair.validate_limit_order(
+            &req.side,
+            &EcbtOrderRequest {
+                size,
+                ..req.replacement()
+            },
+            self.order_validation,
+            reference_price,
+        )?;
+        let order = model::CancelReplaceRequest::try_from(&AmendOrderRequest {
+            client_order_id: replacement.client_order_id.clone(),
+            size: replacement.size,
+            price: replacement.price,
+            ..req.clone()
+        })?;
+        let order = self
+            .place_order(
+                &pair.symbol,
+                replacement.client_order_id,
+                |new_client_order_id| {
+                    let order = model::CancelReplaceRequest {
+                        new_client_order_id,
+                        ..order.clone()
+                    };
+                    async move {
+                        self.client
+                            .cancel_replace_order(order)
+                            .await
+                            .map(|replaced| replaced.new_order_response)
+                    }
+                },
+            )
+            .await?;
+        Ok(AmendedOrder {
+            order,
+            replaced_order_id: req.id.clone(),
+            replaced_client_order_id: amended.client_order_id,
+            method: AmendMethod::Native,
+        })
+    }
+
+    /// Binance places the orders as an OCO order list, which is sent once whatever the retry
+    /// policy.
+    async fn place_oco_order(&self, req: &OcoOrderRequest) -> Result<OcoOrder> {
+        let pair = self.get_pair(&req.market_pair).await?.read()?;
+        let reference_price = self
+            .reference_price(&pair, pair.has_price_band(&req.side))
+            .await?;
+        let req = &pair.validate_oco_order(req, self.order_validation, reference_price)?;
+        self.client
+            .new_oco_order(model::OcoRequest::from(req))
+            .await
+            .and_then(OcoOrder::try_from)
+    }
 }
 
 impl From<model::OrderBook> for OrderBookResponse {
@@ -303,6 +461,80 @@ impl From<model::websocket::Depth> for OrderBookResponse {
     }
 }
 
+impl From<model::websocket::Ticker> for TickerUpdate {
+    fn from(ticker: model::websocket::Ticker) -> Self {
+        Self {
+            market_pair: ticker.symbol,
+            ticker: Ticker {
+                price: Some(ticker.current_close),
+                price_24h: Some(ticker.open),
+            },
+            best_bid: Some(ticker.best_bid),
+            best_ask: Some(ticker.best_ask),
+            time: Some(ticker.event_time),
+        }
+    }
+}
+
+impl From<model::websocket::MiniTicker> for TickerUpdate {
+    fn from(ticker: model::websocket::MiniTicker) -> Self {
+        Self {
+            market_pair: ticker.symbol,
+            ticker: Ticker {
+                price: Some(ticker.close),
+                price_24h: Some(ticker.open),
+            },
+            best_bid: None,
+            best_ask: None,
+            time: Some(ticker.event_time),
+        }
+    }
+}
+
+impl From<model::websocket::BookTicker> for BookTickerUpdate {
+    fn from(ticker: model::websocket::BookTicker) -> Self {
+        Self {
+            market_pair: ticker.symbol,
+            best_bid: AskBid {
+                price: ticker.bid_price,
+                qty: ticker.bid_qty,
+            },
+            best_ask: AskBid {
+                price: ticker.ask_price,
+                qty: ticker.ask_qty,
+            },
+            update_id: Some(ticker.update_id),
+            time: None,
+        }
+    }
+}
+
+impl TryFrom<model::websocket::CandlestickMessage> for CandleUpdate {
+    type Error = EcbtError;
+
+    fn try_from(candlestick: model::websocket::CandlestickMessage) -> Result<Self> {
+        let kline = candlestick.kline;
+        let decimal = |value: &str| {
+            value.parse::<Decimal>().map_err(|err| {
+                EcbtError::NotParsableResponse(format!("Error:{} Payload: {}", err, value))
+            })
+        };
+        Ok(Self {
+            market_pair: candlestick.symbol,
+            interval: Interval::try_from(kline.interval.as_str())?,
+            candle: Candle {
+                time: kline.start_time as u64,
+                low: decimal(&kline.low)?,
+                high: decimal(&kline.high)?,
+                open: decimal(&kline.open)?,
+                close: decimal(&kline.close)?,
+                volume: decimal(&kline.volume)?,
+            },
+            closed: kline.is_final_bar,
+        })
+    }
+}
+
 impl From<model::websocket::TradeMessage> for Vec<Trade> {
     fn from(trade_message: model::websocket::TradeMessage) -> Self {
         vec![Trade {
@@ -344,6 +576,85 @@ impl From<TradeMessage> for Trade {
     }
 }
 
+impl From<model::websocket::AggregateTrade> for Trade {
+    fn from(trade: model::websocket::AggregateTrade) -> Self {
+        Self {
+            id: trade.aggregated_trade_id.to_string(),
+            // An aggregate trade groups the fills of a taker order against several makers.
+            buyer_order_id: None,
+            seller_order_id: None,
+            market_pair: trade.symbol,
+            price: trade.price,
+            qty: trade.qty,
+            fees: None,
+            side: match trade.is_buyer_maker {
+                true => Side::Sell,
+                false => Side::Buy,
+            },
+            liquidity: None,
+            created_at: trade.trade_order_time.to_string(),
+        }
+    }
+}
+
+impl From<model::websocket::UserOrderUpdate> for Order {
+    fn from(update: model::websocket::UserOrderUpdate) -> Self {
+        // A cancellation reports its own request id, the order keeps the original one.
+        let client_order_id = update
+            .original_client_order_id
+            .filter(|id| !id.is_empty())
+            .unwrap_or(update.new_client_order_id);
+        Self {
+            id: update.order_id.to_string(),
+            market_pair: update.symbol,
+            client_order_id: Some(client_order_id),
+            created_at: Some(update.order_creation_time),
+            order_type: update.order_type.into(),
+            side: update.side.into(),
+            status: update.order_status.into(),
+            size: update.qty,
+            price: (!update.price.is_zero()).then_some(update.price),
+            remaining: Some(update.qty - update.accumulated_qty_filled_trades),
+            trades: Vec::new(),
+        }
+    }
+}
+
+impl From<model::websocket::UserOrderUpdate> for Trade {
+    fn from(update: model::websocket::UserOrderUpdate) -> Self {
+        let side: Side = update.side.into();
+        let (buyer_order_id, seller_order_id) = match side {
+            Side::Buy => (Some(update.order_id.to_string()), None),
+            Side::Sell => (None, Some(update.order_id.to_string())),
+        };
+        Self {
+            id: update.trade_id.to_string(),
+            buyer_order_id,
+            seller_order_id,
+            market_pair: update.symbol,
+            price: update.price_last_filled_trade,
+            qty: update.qty_last_filled_trade,
+            fees: Some(update.commission),
+            side,
+            liquidity: Some(match update.is_maker {
+                true => Liquidity::Maker,
+                false => Liquidity::Taker,
+            }),
+            created_at: update.trade_order_time.to_string(),
+        }
+    }
+}
+
+impl From<model::websocket::AccountUpdateBalance> for Balance {
+    fn from(balance: model::websocket::AccountUpdateBalance) -> Self {
+        Self {
+            asset: balance.asset,
+            free: balance.free,
+            total: balance.locked + balance.free,
+        }
+    }
+}
+
 impl From<model::AskBid> for AskBid {
     fn from(bids: model::AskBid) -> Self {
         Self {
@@ -366,9 +677,20 @@ impl From<model::Transaction> for Transaction<u64> {
 
 impl From<model::Order> for Order {
     fn from(order: model::Order) -> Self {
+        let trailing = order.trailing_delta.is_some();
         let order_type = match order.type_name.as_str() {
-            ORDER_TYPE_LIMIT => OrderType::Limit,
+            ORDER_TYPE_LIMIT | ORDER_TYPE_LIMIT_MAKER => OrderType::Limit,
             ORDER_TYPE_MARKET => OrderType::Market,
+            ORDER_TYPE_STOP_LOSS | ORDER_TYPE_TAKE_PROFIT if trailing => {
+                OrderType::TrailingStopMarket
+            }
+            ORDER_TYPE_STOP_LOSS_LIMIT | ORDER_TYPE_TAKE_PROFIT_LIMIT if trailing => {
+                OrderType::TrailingStopLimit
+            }
+            ORDER_TYPE_STOP_LOSS => OrderType::StopMarket,
+            ORDER_TYPE_STOP_LOSS_LIMIT => OrderType::StopLimit,
+            ORDER_TYPE_TAKE_PROFIT => OrderType::TakeProfitMarket,
+            ORDER_TYPE_TAKE_PROFIT_LIMIT => OrderType::TakeProfitLimit,
             _ => OrderType::Unknown,
         };
 
@@ -458,6 +780,125 @@ impl TryFrom<&GetOrderHistoryRequest> for model::AllOrderReq {
     }
 }
 
+impl TryFrom<&ConditionalOrderRequest> for model::OrderRequest {
+    type Error = EcbtError;
+    fn try_from(req: &ConditionalOrderRequest) -> Result<Self> {
+        let (stop_price, trailing_delta, take_profit) = match req.trigger {
+            OrderTrigger::Stop { trigger_price } => (Some(trigger_price), None, false),
+            OrderTrigger::TakeProfit { trigger_price } => (Some(trigger_price), None, true),
+            OrderTrigger::TrailingStop {
+                offset: TrailingOffset::BasisPoints(delta),
+            } => (None, Some(delta), false),
+            OrderTrigger::TrailingStop {
+                offset: TrailingOffset::Price(_),
+            } => {
+                return Err(EcbtError::InvalidParameter(
+                    "Binance trailing stops take an offset in basis points".to_string(),
+                ))
+            }
+        };
+        let order_type = match (take_profit, req.limit_price.is_some()) {
+            (false, false) => ORDER_TYPE_STOP_LOSS,
+            (false, true) => ORDER_TYPE_STOP_LOSS_LIMIT,
+            (true, false) => ORDER_TYPE_TAKE_PROFIT,
+            (true, true) => ORDER_TYPE_TAKE_PROFIT_LIMIT,
+        };
+        let order_side = match req.side {
+            Side::Buy => ORDER_SIDE_BUY,
+            Side::Sell => ORDER_SIDE_SELL,
+        };
+
+        Ok(Self {
+            symbol: crate::model::MarketPair::from(req.market_pair.clone()).0,
+            quantity: req.size,
+            price: req.limit_price,
+            order_side: order_side.to_string(),
+            order_type: order_type.to_string(),
+            time_in_force: req.limit_price.map(|_| model::TimeInForce::GTC),
+            new_client_order_id: req.client_order_id.clone(),
+            stop_price,
+            trailing_delta,
+        })
+    }
+}
+
+impl TryFrom<&AmendOrderRequest> for model::CancelReplaceRequest {
+    type Error = EcbtError;
+    fn try_from(req: &AmendOrderRequest) -> Result<Self> {
+        let cancel_order_id = req.id.parse::<u64>().map_err(|_| {
+            EcbtError::InvalidParameter(format!("{} is not a Binance order id", req.id))
+        })?;
+        let (order_type, time_in_force) = match req.post_only {
+            true => (ORDER_TYPE_LIMIT_MAKER, None),
+            false => (ORDER_TYPE_LIMIT, Some(req.time_in_force.into())),
+        };
+        let order_side = match req.side {
+            Side::Buy => ORDER_SIDE_BUY,
+            Side::Sell => ORDER_SIDE_SELL,
+        };
+
+        Ok(Self {
+            symbol: crate::model::MarketPair::from(req.market_pair.clone()).0,
+            cancel_order_id,
+            cancel_replace_mode: CANCEL_REPLACE_MODE_STOP_ON_FAILURE.to_string(),
+            quantity: req.size,
+            price: Some(req.price),
+            order_side: order_side.to_string(),
+            order_type: order_type.to_string(),
+            time_in_force,
+            new_client_order_id: req.client_order_id.clone(),
+        })
+    }
+}
+
+impl From<&OcoOrderRequest> for model::OcoRequest {
+    fn from(req: &OcoOrderRequest) -> Self {
+        let order_side = match req.side {
+            Side::Buy => ORDER_SIDE_BUY,
+            Side::Sell => ORDER_SIDE_SELL,
+        };
+
+        Self {
+            symbol: crate::model::MarketPair::from(req.market_pair.clone()).0,
+            list_client_order_id: req.client_order_id.clone(),
+            order_side: order_side.to_string(),
+            quantity: req.size,
+            price: req.limit_price,
+            stop_price: req.stop_price,
+            stop_limit_price: req.stop_limit_price,
+            stop_limit_time_in_force: req.stop_limit_price.map(|_| model::TimeInForce::GTC),
+        }
+    }
+}
+
+impl TryFrom<model::OrderList> for OcoOrder {
+    type Error = EcbtError;
+    fn try_from(order_list: model::OrderList) -> Result<Self> {
+        let order_list_id = order_list.order_list_id.to_string();
+        let (mut stop_orders, mut limit_orders): (Vec<Order>, Vec<Order>) = order_list
+            .order_reports
+            .into_iter()
+            .map(Order::from)
+            .partition(|order| {
+                matches!(
+                    order.order_type,
+                    OrderType::StopMarket | OrderType::StopLimit
+                )
+            });
+        match (limit_orders.pop(), stop_orders.pop()) {
+            (Some(limit_order), Some(stop_order)) => Ok(Self {
+                order_list_id: Some(order_list_id),
+                limit_order,
+                stop_order,
+            }),
+            _ => Err(EcbtError::NotParsableResponse(format!(
+                "Order list {} is not a limit order and a stop order",
+                order_list_id
+            ))),
+        }
+    }
+}
+
 impl TryFrom<&TradeHistoryRequest> for model::TradeHistoryReq {
     type Error = EcbtError;
     fn try_from(trade_history: &TradeHistoryRequest) -> Result<Self> {
@@ -529,6 +970,28 @@ impl From<Paginator> for model::Paginator {
     }
 }
 
+impl From<model::Side> for Side {
+    fn from(side: model::Side) -> Side {
+        match side {
+            model::Side::Buy => Side::Buy,
+            model::Side::Sell => Side::Sell,
+        }
+    }
+}
+
+impl From<model::OrderType> for OrderType {
+    fn from(order_type: model::OrderType) -> OrderType {
+        match order_type {
+            model::OrderType::Limit | model::OrderType::LimitMaker => OrderType::Limit,
+            model::OrderType::Market => OrderType::Market,
+            model::OrderType::StopLossLimit => OrderType::StopLimit,
+            model::OrderType::StopLoss => OrderType::StopMarket,
+            model::OrderType::TakeProfitLimit => OrderType::TakeProfitLimit,
+            model::OrderType::TakeProfit => OrderType::TakeProfitMarket,
+        }
+    }
+}
+
 impl From<model::OrderStatus> for OrderStatus {
     fn from(status: model::OrderStatus) -> OrderStatus {
         match status {
@@ -0,0 +1,12 @@
+use super::shared::string_to_decimal;
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents the average price of a symbol over the last `mins` minutes
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AveragePrice {
+    pub mins: u64,
+    #[serde(with = "string_to_decimal")]
+    pub price: Decimal,
+}
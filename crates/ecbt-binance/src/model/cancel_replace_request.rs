@@ -0,0 +1,28 @@
+use super::shared::string_to_decimal;
+use super::shared::string_to_opt_decimal;
+use super::TimeInForce;
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents the cancellation of an order and the order replacing it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelReplaceRequest {
+    pub symbol: String,
+    pub cancel_order_id: u64,
+    pub cancel_replace_mode: String,
+    #[serde(with = "string_to_decimal")]
+    pub quantity: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "string_to_opt_decimal")]
+    pub price: Option<Decimal>,
+    #[serde(rename = "side")]
+    pub order_side: String,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_client_order_id: Option<String>,
+}
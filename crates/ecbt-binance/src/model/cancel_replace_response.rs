@@ -0,0 +1,14 @@
+use super::Order;
+use super::OrderCanceled;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents a canceled order and the order that replaced it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelReplaceResponse {
+    pub cancel_result: String,
+    pub new_order_result: String,
+    pub cancel_response: OrderCanceled,
+    pub new_order_response: Order,
+}
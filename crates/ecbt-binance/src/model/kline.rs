@@ -13,9 +13,9 @@ pub struct Kline {
     #[serde(rename = "i")]
     pub interval: String,
     #[serde(rename = "f")]
-    pub first_trade_id: i32,
+    pub first_trade_id: i64,
     #[serde(rename = "L")]
-    pub last_trade_id: i32,
+    pub last_trade_id: i64,
     #[serde(rename = "o")]
     pub open: String,
     #[serde(rename = "c")]
@@ -27,7 +27,7 @@ pub struct Kline {
     #[serde(rename = "v")]
     pub volume: String,
     #[serde(rename = "n")]
-    pub number_of_trades: i32,
+    pub number_of_trades: i64,
     #[serde(rename = "x")]
     pub is_final_bar: bool,
     #[serde(rename = "q")]
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This enum represents the status of an order list
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ListStatusType {
+    Response,
+    ExecStarted,
+    AllDone,
+}
@@ -3,15 +3,24 @@
 pub const ORDER_TYPE_LIMIT: &str = "LIMIT";
 pub const ORDER_TYPE_LIMIT_MAKER: &str = "LIMIT_MAKER";
 pub const ORDER_TYPE_MARKET: &str = "MARKET";
+pub const ORDER_TYPE_STOP_LOSS: &str = "STOP_LOSS";
+pub const ORDER_TYPE_STOP_LOSS_LIMIT: &str = "STOP_LOSS_LIMIT";
+pub const ORDER_TYPE_TAKE_PROFIT: &str = "TAKE_PROFIT";
+pub const ORDER_TYPE_TAKE_PROFIT_LIMIT: &str = "TAKE_PROFIT_LIMIT";
 pub const ORDER_SIDE_BUY: &str = "BUY";
 pub const ORDER_SIDE_SELL: &str = "SELL";
 pub const TIME_IN_FORCE_GTC: &str = "GTC";
+/// Places the new order of a cancel-replace only if the cancellation succeeded.
+pub const CANCEL_REPLACE_MODE_STOP_ON_FAILURE: &str = "STOP_ON_FAILURE";
 
 mod account_information;
 mod all_order_req;
 mod ask_bid;
+mod average_price;
 mod balance;
 mod book_tickers;
+mod cancel_replace_request;
+mod cancel_replace_response;
 mod exchange_filter;
 mod exchange_information;
 mod interval;
@@ -19,11 +28,16 @@ mod kline;
 mod kline_params;
 mod kline_summaries;
 mod kline_summary;
+mod list_order_status;
+mod list_status_type;
 mod market_pair;
+mod oco_request;
 mod order;
 mod order_book;
 mod order_canceled;
 mod order_exec_type;
+mod order_list;
+mod order_list_order;
 mod order_reject_reason;
 mod order_request;
 mod order_status;
@@ -51,8 +65,11 @@ pub use super::shared;
 pub use account_information::AccountInformation;
 pub use all_order_req::AllOrderReq;
 pub use ask_bid::AskBid;
+pub use average_price::AveragePrice;
 pub use balance::Balance;
 pub use book_tickers::BookTickers;
+pub use cancel_replace_request::CancelReplaceRequest;
+pub use cancel_replace_response::CancelReplaceResponse;
 pub use exchange_filter::ExchangeFilter;
 pub use exchange_information::ExchangeInformation;
 pub use interval::Interval;
@@ -60,11 +77,16 @@ pub use kline::Kline;
 pub use kline_params::KlineParams;
 pub use kline_summaries::KlineSummaries;
 pub use kline_summary::KlineSummary;
+pub use list_order_status::ListOrderStatus;
+pub use list_status_type::ListStatusType;
 pub use market_pair::MarketPair;
+pub use oco_request::OcoRequest;
 pub use order::Order;
 pub use order_book::OrderBook;
 pub use order_canceled::OrderCanceled;
 pub use order_exec_type::OrderExecType;
+pub use order_list::OrderList;
+pub use order_list_order::OrderListOrder;
 pub use order_reject_reason::OrderRejectReason;
 pub use order_request::OrderRequest;
 pub use order_status::OrderStatus;
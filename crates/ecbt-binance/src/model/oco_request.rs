@@ -0,0 +1,29 @@
+use super::shared::string_to_decimal;
+use super::shared::string_to_opt_decimal;
+use super::TimeInForce;
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents an OCO order list request, a limit maker order and a stop loss
+/// order canceling each other
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoRequest {
+    pub symbol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_client_order_id: Option<String>,
+    #[serde(rename = "side")]
+    pub order_side: String,
+    #[serde(with = "string_to_decimal")]
+    pub quantity: Decimal,
+    #[serde(with = "string_to_decimal")]
+    pub price: Decimal,
+    #[serde(with = "string_to_decimal")]
+    pub stop_price: Decimal,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(with = "string_to_opt_decimal")]
+    pub stop_limit_price: Option<Decimal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_limit_time_in_force: Option<TimeInForce>,
+}
@@ -29,6 +29,8 @@ pub struct Order {
     #[serde(default, with = "string_to_opt_decimal")]
     pub stop_price: Option<Decimal>,
     #[serde(default)]
+    pub trailing_delta: Option<u32>,
+    #[serde(default)]
     pub iceberg_qty: Option<String>,
     #[serde(default)]
     pub time: Option<u64>,
@@ -0,0 +1,23 @@
+use super::ListOrderStatus;
+use super::ListStatusType;
+use super::Order;
+use super::OrderListOrder;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents an order list, e.g. the two orders of an OCO
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderList {
+    pub order_list_id: u64,
+    pub contingency_type: String,
+    pub list_status_type: ListStatusType,
+    pub list_order_status: ListOrderStatus,
+    pub list_client_order_id: String,
+    pub transaction_time: u64,
+    pub symbol: String,
+    pub orders: Vec<OrderListOrder>,
+    /// The orders in full, only returned when the list is placed or canceled.
+    #[serde(default)]
+    pub order_reports: Vec<Order>,
+}
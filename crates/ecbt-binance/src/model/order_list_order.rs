@@ -0,0 +1,11 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents an order of an order list
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderListOrder {
+    pub symbol: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+}
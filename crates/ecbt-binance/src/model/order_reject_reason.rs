@@ -6,4 +6,16 @@ use serde::Serialize;
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderRejectReason {
     None,
+    UnknownInstrument,
+    MarketClosed,
+    PriceQtyExceedHardLimits,
+    UnknownOrder,
+    DuplicateOrder,
+    UnknownAccount,
+    InsufficientBalance,
+    AccountInactive,
+    AccountCannotSettle,
+    /// A reason added by Binance after this list.
+    #[serde(other)]
+    Other,
 }
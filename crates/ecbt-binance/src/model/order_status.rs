@@ -2,9 +2,10 @@ use serde::Deserialize;
 use serde::Serialize;
 
 /// This enum represents an order status
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderStatus {
+    #[default]
     New,
     PartiallyFilled,
     Filled,
@@ -13,9 +14,3 @@ pub enum OrderStatus {
     Rejected,
     Expired,
 }
-
-impl Default for OrderStatus {
-    fn default() -> Self {
-        Self::New
-    }
-}
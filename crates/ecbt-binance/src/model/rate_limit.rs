@@ -1,13 +1,41 @@
 use super::Interval;
 use super::RateLimitType;
+use ecbt_exchange::rate_limit::{RateLimitKind, RateLimitRule};
 use serde::Deserialize;
 use serde::Serialize;
+use std::time::Duration;
 
 /// This struct represents the rate limit
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RateLimit {
-    rate_limit_type: RateLimitType,
-    interval: Interval,
-    limit: u64,
+    pub rate_limit_type: RateLimitType,
+    pub interval: Interval,
+    #[serde(default = "default_interval_num")]
+    pub interval_num: u64,
+    pub limit: u64,
+}
+
+fn default_interval_num() -> u64 {
+    1
+}
+
+impl From<&RateLimit> for RateLimitRule {
+    fn from(rate_limit: &RateLimit) -> Self {
+        let kind = match rate_limit.rate_limit_type {
+            RateLimitType::Orders => RateLimitKind::Orders,
+            RateLimitType::RequestWeight => RateLimitKind::Weight,
+            RateLimitType::RawRequests => RateLimitKind::Requests,
+        };
+        let seconds = match rate_limit.interval {
+            Interval::Second => 1,
+            Interval::Minute => 60,
+            Interval::Day => 86400,
+        };
+        RateLimitRule::new(
+            kind,
+            Duration::from_secs(seconds * rate_limit.interval_num),
+            rate_limit.limit.try_into().unwrap_or(u32::MAX),
+        )
+    }
 }
@@ -1,4 +1,6 @@
 use super::SymbolFilter;
+use ecbt_exchange::info::{MarketPairInfo, OrderConstraints};
+use rust_decimal::Decimal;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -15,3 +17,89 @@ pub struct Symbol {
     pub iceberg_allowed: bool,
     pub filters: Vec<SymbolFilter>,
 }
+
+impl From<&Symbol> for MarketPairInfo {
+    fn from(symbol: &Symbol) -> Self {
+        let non_zero = |value: &Decimal| Some(*value).filter(|value| !value.is_zero());
+        let mut info = MarketPairInfo {
+            base: symbol.base_asset.clone(),
+            quote: symbol.quote_asset.clone(),
+            symbol: symbol.symbol.clone(),
+            base_increment: Decimal::ZERO,
+            quote_increment: Decimal::ZERO,
+            min_base_trade_size: None,
+            min_quote_trade_size: None,
+            constraints: OrderConstraints::default(),
+        };
+
+        for filter in &symbol.filters {
+            match filter {
+                SymbolFilter::LotSize {
+                    min_qty,
+                    max_qty,
+                    step_size,
+                } => {
+                    info.base_increment = *step_size;
+                    info.min_base_trade_size = non_zero(min_qty);
+                    info.constraints.max_size = non_zero(max_qty);
+                }
+                SymbolFilter::PriceFilter {
+                    min_price,
+                    max_price,
+                    tick_size,
+                } => {
+                    info.quote_increment = *tick_size;
+                    info.constraints.min_price = non_zero(min_price);
+                    info.constraints.max_price = non_zero(max_price);
+                }
+                SymbolFilter::MinNotional { min_notional } => {
+                    info.min_quote_trade_size = non_zero(min_notional);
+                }
+                SymbolFilter::Notional {
+                    min_notional,
+                    max_notional,
+                } => {
+                    info.min_quote_trade_size = non_zero(min_notional);
+                    info.constraints.max_notional = non_zero(max_notional);
+                }
+                SymbolFilter::PercentPrice {
+                    multiplier_up,
+                    multiplier_down,
+                    ..
+                } => {
+                    info.constraints.bid_multiplier_up = non_zero(multiplier_up);
+                    info.constraints.bid_multiplier_down = non_zero(multiplier_down);
+                    info.constraints.ask_multiplier_up = non_zero(multiplier_up);
+                    info.constraints.ask_multiplier_down = non_zero(multiplier_down);
+                }
+                SymbolFilter::PercentPriceBySide {
+                    bid_multiplier_up,
+                    bid_multiplier_down,
+                    ask_multiplier_up,
+                    ask_multiplier_down,
+                    ..
+                } => {
+                    info.constraints.bid_multiplier_up = non_zero(bid_multiplier_up);
+                    info.constraints.bid_multiplier_down = non_zero(bid_multiplier_down);
+                    info.constraints.ask_multiplier_up = non_zero(ask_multiplier_up);
+                    info.constraints.ask_multiplier_down = non_zero(ask_multiplier_down);
+                }
+                SymbolFilter::MarketLotSize {
+                    min_qty,
+                    max_qty,
+                    step_size,
+                } => {
+                    info.constraints.market_min_size = non_zero(min_qty);
+                    info.constraints.market_max_size = non_zero(max_qty);
+                    info.constraints.market_size_increment = non_zero(step_size);
+                }
+                SymbolFilter::MaxNumOrders { max_num_orders } => {
+                    info.constraints.max_num_orders = Some(*max_num_orders);
+                }
+                _ => {}
+            }
+        }
+
+        info
+    }
+}
@@ -30,6 +30,13 @@ pub enum SymbolFilter {
         min_notional: Decimal,
     },
     #[serde(rename_all = "camelCase")]
+    Notional {
+        #[serde(with = "string_to_decimal")]
+        min_notional: Decimal,
+        #[serde(with = "string_to_decimal")]
+        max_notional: Decimal,
+    },
+    #[serde(rename_all = "camelCase")]
     MaxNumAlgoOrders { max_num_algo_orders: u64 },
     #[serde(rename_all = "camelCase")]
     MaxPosition {
@@ -49,6 +56,18 @@ pub enum SymbolFilter {
         avg_price_mins: u64,
     },
     #[serde(rename_all = "camelCase")]
+    PercentPriceBySide {
+        #[serde(with = "string_to_decimal")]
+        bid_multiplier_up: Decimal,
+        #[serde(with = "string_to_decimal")]
+        bid_multiplier_down: Decimal,
+        #[serde(with = "string_to_decimal")]
+        ask_multiplier_up: Decimal,
+        #[serde(with = "string_to_decimal")]
+        ask_multiplier_down: Decimal,
+        avg_price_mins: u64,
+    },
+    #[serde(rename_all = "camelCase")]
     MarketLotSize {
         #[serde(with = "string_to_decimal")]
         min_qty: Decimal,
@@ -64,4 +83,7 @@ pub enum SymbolFilter {
         min_trailing_below_delta: u64,
         max_trailing_below_delta: u64,
     },
+    /// Filters that are not modeled yet.
+    #[serde(other)]
+    Other,
 }
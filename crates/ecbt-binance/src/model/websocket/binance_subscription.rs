@@ -1,3 +1,5 @@
+use ecbt_exchange::model::websocket::AccountOrders;
+
 /// Used for subscriptions
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum BinanceSubscription {
@@ -15,8 +17,30 @@ pub enum BinanceSubscription {
     /// Symbol
     Ticker(String),
     TickerAll,
+    /// Symbol, best bid and ask
+    BookTicker(String),
+    /// Best bid and ask of every symbol
+    BookTickerAll,
     /// (Symbol, Depth)
     OrderBook(String, i64),
     /// (Symbol, Interval)
     Depth(String, Option<u16>),
+    /// Order updates of a user data stream passing the filters
+    UserOrders(AccountOrders),
+    /// Symbol, fills of the orders from a user data stream
+    UserTrades(String),
+    /// (Base asset, Quote asset), balance changes from a user data stream
+    UserBalance(String, String),
+}
+
+impl BinanceSubscription {
+    /// Whether the subscription is served by a user data stream started for the stream.
+    pub fn is_account(&self) -> bool {
+        matches!(
+            self,
+            BinanceSubscription::UserOrders(_)
+                | BinanceSubscription::UserTrades(_)
+                | BinanceSubscription::UserBalance(..)
+        )
+    }
 }
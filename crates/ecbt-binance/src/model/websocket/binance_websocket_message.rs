@@ -1,5 +1,6 @@
 use super::AccountUpdate;
 use super::AggregateTrade;
+use super::BookTicker;
 use super::CandlestickMessage;
 use super::Depth;
 use super::MiniTicker;
@@ -7,12 +8,16 @@ use super::OrderBook;
 use super::Ticker;
 use super::TradeMessage;
 use super::UserOrderUpdate;
+use ecbt_exchange::stream::ConnectionEvent;
 use serde::Serialize;
+use serde_json::Value;
 
 /// This enum represents the types of websocket messages
 #[derive(Debug, Clone, Serialize)]
 pub enum BinanceWebsocketMessage {
     UserOrderUpdate(UserOrderUpdate),
+    /// An execution report of a fill, for `BinanceSubscription::UserTrades`.
+    UserTrade(UserOrderUpdate),
     UserAccountUpdate(AccountUpdate),
     AggregateTrade(AggregateTrade),
     Trade(TradeMessage),
@@ -21,10 +26,37 @@ pub enum BinanceWebsocketMessage {
     MiniTickerAll(Vec<MiniTicker>),
     Ticker(Ticker),
     TickerAll(Vec<Ticker>),
+    BookTicker(BookTicker),
     OrderBook(OrderBook),
     Depth(Depth),
     Ping,
     Pong,
     Close,
     Binary(Vec<u8>), // Unexpected, unparsed
+    /// A `SUBSCRIBE` or `UNSUBSCRIBE` request was accepted.
+    SubscriptionAck {
+        id: Option<u64>,
+    },
+    /// A request was rejected, or the connection reported an error.
+    SubscriptionError {
+        id: Option<u64>,
+        code: Option<i64>,
+        message: String,
+    },
+    /// A message of a stream or event this version doesn't know of, kept as received.
+    Unknown {
+        stream: String,
+        payload: Value,
+    },
+    Disconnected,
+    Reconnected,
+}
+
+impl From<ConnectionEvent> for BinanceWebsocketMessage {
+    fn from(event: ConnectionEvent) -> Self {
+        match event {
+            ConnectionEvent::Disconnected => BinanceWebsocketMessage::Disconnected,
+            ConnectionEvent::Reconnected => BinanceWebsocketMessage::Reconnected,
+        }
+    }
 }
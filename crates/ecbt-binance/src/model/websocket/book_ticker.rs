@@ -0,0 +1,21 @@
+use super::shared::string_to_decimal;
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents a change of the best bid or ask of a symbol
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BookTicker {
+    #[serde(rename = "u")]
+    pub update_id: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "b", with = "string_to_decimal")]
+    pub bid_price: Decimal,
+    #[serde(rename = "B", with = "string_to_decimal")]
+    pub bid_qty: Decimal,
+    #[serde(rename = "a", with = "string_to_decimal")]
+    pub ask_price: Decimal,
+    #[serde(rename = "A", with = "string_to_decimal")]
+    pub ask_qty: Decimal,
+}
@@ -15,6 +15,7 @@ mod account_update_balance;
 mod aggregate_trade;
 mod binance_subscription;
 mod binance_websocket_message;
+mod book_ticker;
 mod candlestick_message;
 mod depth;
 mod mini_ticker;
@@ -28,6 +29,7 @@ pub use account_update_balance::AccountUpdateBalance;
 pub use aggregate_trade::AggregateTrade;
 pub use binance_subscription::BinanceSubscription;
 pub use binance_websocket_message::BinanceWebsocketMessage;
+pub use book_ticker::BookTicker;
 pub use candlestick_message::CandlestickMessage;
 pub use depth::Depth;
 pub use mini_ticker::MiniTicker;
@@ -37,8 +37,9 @@ pub struct UserOrderUpdate {
     pub iceberg_qty: Decimal,
     #[serde(skip_serializing)]
     pub g: i32,
-    #[serde(skip_serializing, rename = "C")]
-    pub c_ignore: Option<String>,
+    /// The id of the canceled order when `new_client_order_id` is the cancel request id.
+    #[serde(rename = "C")]
+    pub original_client_order_id: Option<String>,
     #[serde(rename = "x")]
     pub execution_type: OrderExecType,
     #[serde(rename = "X")]
@@ -66,10 +67,10 @@ pub struct UserOrderUpdate {
     #[serde(skip_serializing)]
     pub w: bool,
     #[serde(rename = "m")]
-    pub is_buyer_maker: bool,
+    pub is_maker: bool,
     #[serde(skip_serializing, rename = "M")]
     pub m_ignore: bool,
-    #[serde(skip_serializing, rename = "O")]
+    #[serde(rename = "O")]
     pub order_creation_time: u64,
     #[serde(skip_serializing, rename = "Z", with = "string_to_decimal")]
     pub cumulative_quote_asset_transacted_qty: Decimal,
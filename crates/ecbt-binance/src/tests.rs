@@ -0,0 +1,1054 @@
+use crate::client::{
+    AccountFilter, BaseClient, BinanceProtocol, DepthSync, DepthSynchronizer, ShardPool,
+    ShardRegistry,
+};
+use crate::model::websocket::{BinanceSubscription, BinanceWebsocketMessage, Depth};
+use crate::model::AskBid;
+use crate::{
+    Binance, BinanceContentError, BinanceCredentials, BinanceParameters, BinanceRequestWeigher,
+    BinanceStreamSharding, Transport,
+};
+use async_trait::async_trait;
+use ecbt_exchange::errors::{EcbtError, ExchangeErrorContext};
+use ecbt_exchange::model::currency::Currency;
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::websocket::{
+    AccountOrders, EcbtWebSocketMessage, Subscription, WebSocketResponse,
+};
+use ecbt_exchange::model::{
+    self, AmendOrderRequest, BracketOrderRequest, ConditionalOrderRequest, Interval, Liquidity,
+    OcoOrder, OrderBookResponse, OrderStatus, OrderTrigger, OrderType, Side, TimeInForce,
+    TrailingOffset,
+};
+use ecbt_exchange::rate_limit::{RateLimitKind, RateLimitUsage, RequestPriority, RequestWeigher};
+use ecbt_exchange::shared::Result;
+use ecbt_exchange::stream::{ProtocolMessage, ReconnectPolicy, SubscriptionProtocol};
+use ecbt_exchange::transport::{HttpRequest, HttpResponse, HttpTransport};
+use ecbt_exchange::{Exchange, ExchangeAccount};
+use futures::{SinkExt, StreamExt};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Method, StatusCode};
+use rust_decimal::prelude::Decimal;
+use rust_decimal_macros::dec;
+use std::convert::{TryFrom, TryInto};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+
+#[derive(Debug)]
+struct RecordingTransport {
+    requests: Mutex<Vec<HttpRequest>>,
+    status: StatusCode,
+    body: &'static str,
+}
+
+impl RecordingTransport {
+    fn new(body: &'static str) -> Arc<Self> {
+        Self::with_status(StatusCode::OK, body)
+    }
+
+    fn with_status(status: StatusCode, body: &'static str) -> Arc<Self> {
+        Arc::new(Self {
+            requests: Mutex::new(Vec::new()),
+            status,
+            body,
+        })
+    }
+
+    fn last_request(&self) -> HttpRequest {
+        self.requests
+            .lock()
+            .unwrap()
+            .last()
+            .cloned()
+            .expect("No request was sent.")
+    }
+}
+
+#[async_trait]
+impl HttpTransport for RecordingTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let url = request.url.clone();
+        self.requests.lock().unwrap().push(request);
+        Ok(HttpResponse {
+            url,
+            status: self.status,
+            headers: HeaderMap::new(),
+            body: self.body.as_bytes().to_vec(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn unsigned_request_uses_configured_transport() {
+    let http = RecordingTransport::new(r#"{"serverTime":1660000000000}"#);
+    let client = BaseClient {
+        transport: Transport::new(http.clone(), true).unwrap(),
+    };
+
+    let server_time = client.get_server_time().await.unwrap();
+    assert_eq!(server_time.server_time, 1660000000000);
+
+    let request = http.last_request();
+    assert_eq!(request.method, Method::GET);
+    assert_eq!(
+        request.url.as_str(),
+        "https://testnet.binance.vision/api/v3/time"
+    );
+    assert!(request.headers.get("X-MBX-APIKEY").is_none());
+}
+
+#[tokio::test]
+async fn server_errors_carry_their_context() {
+    let http = RecordingTransport::with_status(
+        StatusCode::SERVICE_UNAVAILABLE,
+        r#"{"code":-1008,"msg":"Server is currently overloaded with other requests."}"#,
+    );
+    let client = BaseClient {
+        transport: Transport::new(http, true).unwrap(),
+    };
+
+    match client.get_server_time().await {
+        Err(EcbtError::ServiceUnavailable(context)) => assert_eq!(
+            context,
+            ExchangeErrorContext::new("Server is currently overloaded with other requests.")
+                .with_code(-1008)
+                .with_endpoint("/api/v3/time")
+                .with_status(503)
+        ),
+        Err(other) => panic!("Unexpected error {:?}", other),
+        Ok(_) => panic!("The request should fail."),
+    }
+}
+
+#[tokio::test]
+async fn signed_request_is_signed_before_reaching_transport() {
+    let http = RecordingTransport::new("{}");
+    let transport = Transport::with_credential(http.clone(), "key", "secret", false).unwrap();
+
+    transport
+        .signed_post::<_, serde_json::Value>("/api/v3/order", Some(&[("symbol", "ETHBTC")]))
+        .await
+        .unwrap();
+
+    let request = http.last_request();
+    assert_eq!(request.method, Method::POST);
+    assert_eq!(request.headers.get("X-MBX-APIKEY").unwrap(), "key");
+    assert_eq!(request.body.as_deref(), Some(&b"symbol=ETHBTC"[..]));
+    let query: Vec<(String, String)> = request.url.query_pairs().into_owned().collect();
+    assert!(query.iter().any(|(key, _)| key == "timestamp"));
+    assert!(query.iter().any(|(key, _)| key == "signature"));
+}
+
+fn depth(first_update_id: u64, final_update_id: u64, bid: (Decimal, Decimal)) -> Depth {
+    Depth {
+        event_type: "depthUpdate".to_string(),
+        event_time: 0,
+        symbol: "ETHBTC".to_string(),
+        first_update_id,
+        final_update_id,
+        bids: vec![AskBid {
+            price: bid.0,
+            qty: bid.1,
+        }],
+        asks: vec![],
+    }
+}
+
+fn snapshot(last_update_id: u64) -> OrderBookResponse {
+    OrderBookResponse {
+        update_id: Some(last_update_id),
+        last_update_id: None,
+        bids: vec![model::AskBid {
+            price: dec!(1),
+            qty: dec!(10),
+        }],
+        asks: vec![model::AskBid {
+            price: dec!(2),
+            qty: dec!(10),
+        }],
+    }
+}
+
+#[test]
+fn depth_synchronizer_replays_buffered_diffs_after_snapshot() {
+    let mut sync = DepthSynchronizer::new();
+
+    assert_eq!(
+        sync.push(depth(95, 100, (dec!(1), dec!(5)))),
+        DepthSync::NeedsSnapshot
+    );
+    // The snapshot was requested by the first diff.
+    assert_eq!(
+        sync.push(depth(101, 103, (dec!(1.5), dec!(1)))),
+        DepthSync::Buffered
+    );
+    assert_eq!(sync.apply_snapshot(&snapshot(101)), DepthSync::Synced);
+
+    let book = sync.book();
+    assert_eq!(book.update_id(), Some(103));
+    assert_eq!(book.qty_at(&Side::Buy, dec!(1)), dec!(10));
+    assert_eq!(book.best_bid().unwrap().price, dec!(1.5));
+    assert_eq!(
+        sync.push(depth(102, 103, (dec!(1), dec!(0)))),
+        DepthSync::Stale
+    );
+    assert_eq!(
+        sync.push(depth(104, 104, (dec!(1), dec!(0)))),
+        DepthSync::Synced
+    );
+    assert_eq!(sync.book().qty_at(&Side::Buy, dec!(1)), dec!(0));
+}
+
+#[test]
+fn depth_synchronizer_resyncs_on_gap() {
+    let mut sync = DepthSynchronizer::new();
+    sync.push(depth(100, 100, (dec!(1), dec!(5))));
+    assert_eq!(sync.apply_snapshot(&snapshot(100)), DepthSync::Synced);
+
+    assert_eq!(
+        sync.push(depth(105, 106, (dec!(1), dec!(7)))),
+        DepthSync::NeedsSnapshot
+    );
+    assert!(!sync.is_synced());
+    assert!(sync.book().is_empty());
+    assert_eq!(
+        sync.push(depth(107, 107, (dec!(1), dec!(8)))),
+        DepthSync::Buffered
+    );
+
+    // A snapshot older than the buffered diffs is not enough.
+    assert_eq!(
+        sync.apply_snapshot(&snapshot(102)),
+        DepthSync::NeedsSnapshot
+    );
+    assert_eq!(sync.apply_snapshot(&snapshot(104)), DepthSync::Synced);
+    assert_eq!(sync.book().qty_at(&Side::Buy, dec!(1)), dec!(8));
+}
+
+#[test]
+fn diff_depth_streams_with_an_update_speed_are_depth_updates() {
+    let frame = r#"{"stream":"bnbbtc@depth@100ms","data":{"e":"depthUpdate","E":123456789,
+        "s":"BNBBTC","U":157,"u":160,"b":[["0.0024","10"]],"a":[["0.0026","100"]]}}"#;
+    match serde_json::from_str(frame).unwrap() {
+        BinanceWebsocketMessage::Depth(depth) => {
+            assert_eq!(depth.symbol, "BNBBTC");
+            assert_eq!(depth.first_update_id, 157);
+            assert_eq!(depth.final_update_id, 160);
+            assert_eq!(depth.bids[0].price, dec!(0.0024));
+            assert_eq!(depth.asks[0].qty, dec!(100));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+    // Diffs can't be read as a full book.
+    match generic(serde_json::from_str(frame).unwrap()) {
+        EcbtWebSocketMessage::OrderBookDiff(update) => {
+            assert_eq!(update.market_pair, "BNBBTC");
+            assert_eq!(update.book.bids[0].qty, dec!(10));
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+
+    let partial = r#"{"stream":"bnbbtc@depth5@100ms","data":{"lastUpdateId":160,
+        "bids":[["0.0024","10"]],"asks":[["0.0026","100"]]}}"#;
+    assert!(matches!(
+        serde_json::from_str(partial).unwrap(),
+        BinanceWebsocketMessage::OrderBook(_)
+    ));
+}
+
+#[test]
+fn request_weights_follow_the_endpoint_parameters() {
+    let cost = |method, url: &str| {
+        BinanceRequestWeigher.cost(&HttpRequest::new(method, Url::parse(url).unwrap()))
+    };
+    let base = "https://api.binance.com/api/v3";
+
+    assert_eq!(
+        cost(Method::GET, &format!("{}/depth?symbol=BTCUSDT", base)).weight,
+        5
+    );
+    assert_eq!(
+        cost(
+            Method::GET,
+            &format!("{}/depth?symbol=BTCUSDT&limit=1000", base)
+        )
+        .weight,
+        50
+    );
+    assert_eq!(
+        cost(Method::GET, &format!("{}/openOrders", base)).weight,
+        80
+    );
+    assert_eq!(
+        cost(Method::GET, &format!("{}/openOrders?symbol=BTCUSDT", base)).weight,
+        6
+    );
+
+    let order = cost(Method::POST, &format!("{}/order?signature=abc", base));
+    assert_eq!(order.orders, 1);
+    assert!(order.private);
+    assert_eq!(order.priority, RequestPriority::High);
+
+    assert_eq!(cost(Method::POST, &format!("{}/order/oco", base)).orders, 2);
+    assert_eq!(
+        cost(Method::GET, &format!("{}/orderList?orderListId=1", base)).weight,
+        4
+    );
+    let cancel = cost(Method::DELETE, &format!("{}/orderList", base));
+    assert_eq!(cancel.priority, RequestPriority::High);
+}
+
+#[test]
+fn usage_is_read_from_the_response_headers() {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-mbx-used-weight-1m", HeaderValue::from_static("120"));
+    headers.insert("x-mbx-order-count-10s", HeaderValue::from_static("3"));
+    headers.insert("x-mbx-used-weight", HeaderValue::from_static("120"));
+    let response = HttpResponse {
+        url: Url::parse("https://api.binance.com/api/v3/order").unwrap(),
+        status: StatusCode::OK,
+        headers,
+        body: Vec::new(),
+    };
+
+    let mut usage = BinanceRequestWeigher.usage(&response);
+    usage.sort_by_key(|usage| usage.interval);
+    assert_eq!(
+        usage,
+        vec![
+            RateLimitUsage {
+                kind: RateLimitKind::Orders,
+                interval: Duration::from_secs(10),
+                used: 3,
+            },
+            RateLimitUsage {
+                kind: RateLimitKind::Weight,
+                interval: Duration::from_secs(60),
+                used: 120,
+            },
+        ]
+    );
+}
+
+#[test]
+fn rejections_are_mapped_to_typed_errors() {
+    let mut headers = HeaderMap::new();
+    headers.insert("retry-after", HeaderValue::from_static("7"));
+    let response = |status| HttpResponse {
+        url: Url::parse("https://api.binance.com/api/v3/order").unwrap(),
+        status,
+        headers: headers.clone(),
+        body: Vec::new(),
+    };
+    let error = |status, body: &str| {
+        serde_json::from_str::<BinanceContentError>(body)
+            .unwrap()
+            .into_error(&response(status))
+    };
+
+    match error(
+        StatusCode::BAD_REQUEST,
+        r#"{"code":-2010,"msg":"Account has insufficient balance for requested action."}"#,
+    ) {
+        EcbtError::InsufficientBalance(context) => assert_eq!(
+            context,
+            ExchangeErrorContext::new("Account has insufficient balance for requested action.")
+                .with_code(-2010)
+                .with_endpoint("/api/v3/order")
+                .with_status(400)
+        ),
+        other => panic!("Unexpected error {:?}", other),
+    }
+    assert!(matches!(
+        error(
+            StatusCode::BAD_REQUEST,
+            r#"{"code":-1013,"msg":"Filter failure: PRICE_FILTER"}"#
+        ),
+        EcbtError::InvalidPrice(_)
+    ));
+    assert!(matches!(
+        error(
+            StatusCode::BAD_REQUEST,
+            r#"{"code":-1021,"msg":"Timestamp for this request is outside of the recvWindow."}"#
+        ),
+        EcbtError::TimestampOutsideRecvWindow(_)
+    ));
+    assert!(matches!(
+        error(
+            StatusCode::BAD_REQUEST,
+            r#"{"code":-2011,"msg":"Unknown order sent."}"#
+        ),
+        EcbtError::OrderNotFound(_)
+    ));
+    assert_eq!(
+        error(
+            StatusCode::TOO_MANY_REQUESTS,
+            r#"{"code":-1003,"msg":"Too many requests."}"#
+        )
+        .retry_after(),
+        Some(Duration::from_secs(7))
+    );
+    assert!(matches!(
+        error(
+            StatusCode::BAD_REQUEST,
+            r#"{"code":-1100,"msg":"Illegal characters."}"#
+        ),
+        EcbtError::Exchange(_)
+    ));
+}
+
+const EXECUTION_REPORT: &str = r#"{"stream":"listenkey","data":{"e":"executionReport",
+    "E":1499405658658,"s":"ETHBTC","c":"mUvoqJxFIILMdfAW5iGSOW","S":"BUY","o":"LIMIT",
+    "f":"GTC","q":"1.00000000","p":"0.10264410","P":"0.00000000","F":"0.00000000","g":-1,
+    "C":"","x":"TRADE","X":"PARTIALLY_FILLED","r":"NONE","i":4293153,"l":"0.40000000",
+    "z":"0.40000000","L":"0.10264410","n":"0.00010000","N":"BNB","T":1499405658657,
+    "t":1000,"I":8641984,"w":true,"m":true,"M":false,"O":1499405658657,
+    "Z":"0.04105764","Y":"0.04105764","Q":"0.00000000"}}"#;
+
+const ACCOUNT_POSITION: &str = r#"{"stream":"listenkey","data":{
+    "e":"outboundAccountPosition","E":1564034571105,"u":1564034571073,"B":[
+    {"a":"ETH","f":"10000.000000","l":"0.000000"},
+    {"a":"BNB","f":"5.000000","l":"1.000000"}]}}"#;
+
+fn generic(message: BinanceWebsocketMessage) -> EcbtWebSocketMessage {
+    match message.try_into() {
+        Ok(WebSocketResponse::Generic(message)) => message,
+        other => panic!("unexpected response {:?}", other),
+    }
+}
+
+#[test]
+fn user_data_events_are_filtered_and_converted() {
+    let market = MarketPair(Currency::ETH, Currency::BTC);
+    let orders = |side| {
+        Subscription::AccountOrders(AccountOrders {
+            market: Some(market.clone()),
+            order_type: None,
+            buy_or_sell: Some(side),
+            range: None,
+            status: Some(vec![OrderStatus::PartiallyFilled]),
+        })
+    };
+    let report: BinanceWebsocketMessage = serde_json::from_str(EXECUTION_REPORT).unwrap();
+    let position: BinanceWebsocketMessage = serde_json::from_str(ACCOUNT_POSITION).unwrap();
+
+    let subscriptions: Vec<BinanceSubscription> = vec![
+        orders(Side::Buy).into(),
+        Subscription::AccountTrades(market.clone()).into(),
+        Subscription::AccountBalance(market.clone()).into(),
+    ];
+    let filter = AccountFilter::new(&subscriptions);
+    let mut messages = filter.apply(report.clone()).into_iter().map(generic);
+    match messages.next() {
+        Some(EcbtWebSocketMessage::OrderUpdate(order)) => {
+            assert_eq!(order.market_pair, "ETHBTC");
+            assert_eq!(
+                order.client_order_id.as_deref(),
+                Some("mUvoqJxFIILMdfAW5iGSOW")
+            );
+            assert_eq!(order.remaining, Some(dec!(0.6)));
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+    match messages.next() {
+        Some(EcbtWebSocketMessage::Fill(trade)) => {
+            assert_eq!(trade.buyer_order_id.as_deref(), Some("4293153"));
+            assert_eq!((trade.price, trade.qty), (dec!(0.10264410), dec!(0.4)));
+            assert_eq!(trade.fees, Some(dec!(0.0001)));
+            assert_eq!(trade.liquidity, Some(Liquidity::Maker));
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+    assert!(messages.next().is_none());
+    match filter
+        .apply(position.clone())
+        .into_iter()
+        .map(generic)
+        .next()
+    {
+        Some(EcbtWebSocketMessage::BalanceUpdate(balances)) => {
+            assert_eq!(balances.len(), 1);
+            assert_eq!(balances[0].asset, "ETH");
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+
+    // The side filter rejects the order, and no balance of the pair changed.
+    let subscriptions: Vec<BinanceSubscription> = vec![
+        orders(Side::Sell).into(),
+        Subscription::AccountBalance(MarketPair(Currency::Other("LTC".into()), Currency::USDT))
+            .into(),
+    ];
+    let filter = AccountFilter::new(&subscriptions);
+    assert!(filter.apply(report).is_empty());
+    assert!(filter.apply(position).is_empty());
+}
+
+#[test]
+fn live_subscriptions_use_the_combined_stream_methods() {
+    let protocol = BinanceProtocol;
+    let trades = BinanceSubscription::Trade("BTCUSDT".to_string());
+
+    let request: serde_json::Value =
+        serde_json::from_str(&protocol.subscribe_request(7, &trades).unwrap()).unwrap();
+    assert_eq!(
+        request,
+        serde_json::json!({"method": "SUBSCRIBE", "params": ["btcusdt@trade"], "id": 7})
+    );
+    let balance = BinanceSubscription::UserBalance("BTC".to_string(), "USDT".to_string());
+    assert!(protocol.unsubscribe_request(8, &balance).is_err());
+
+    assert!(matches!(
+        protocol.parse(r#"{"result":null,"id":7}"#).unwrap(),
+        ProtocolMessage::Ack(Some(7))
+    ));
+    assert!(matches!(
+        protocol.parse(r#"{"code":2,"msg":"Invalid request","id":8}"#).unwrap(),
+        ProtocolMessage::Rejected(Some(8), reason) if reason == "Invalid request"
+    ));
+
+    let trade = r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":123456789,"s":"BTCUSDT",
+        "t":12345,"p":"0.001","q":"100","b":88,"a":50,"T":123456785,"m":true,"M":true}}"#;
+    match protocol.parse(trade).unwrap() {
+        ProtocolMessage::Data(topic, message) => {
+            assert!(protocol.routes(&trades, topic.as_deref(), &message));
+            let ticker = BinanceSubscription::Ticker("BTCUSDT".to_string());
+            assert!(!protocol.routes(&ticker, topic.as_deref(), &message));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[test]
+fn streams_are_sharded_by_count_and_url_length() {
+    let ws_url = "wss://stream.binance.com:9443/stream";
+    let streams: Vec<String> = (0..5).map(|i| format!("sym{}usdt@trade", i)).collect();
+
+    let sharding = BinanceStreamSharding::new().with_max_streams_per_connection(2);
+    let shards = sharding.plan(ws_url, streams.clone());
+    assert_eq!(shards.len(), 3);
+    assert_eq!(shards[0], streams[..2]);
+    assert_eq!(shards[2], streams[4..]);
+
+    // Each stream is 14 characters long, the URL with its query is 45 characters.
+    let sharding = BinanceStreamSharding::new().with_max_url_length(45 + 14 * 3 + 2);
+    let shards = sharding.plan(ws_url, streams.clone());
+    assert_eq!(shards.iter().map(Vec::len).collect::<Vec<_>>(), [3, 2]);
+    assert_eq!(shards.concat(), streams);
+
+    let sharding = BinanceStreamSharding::new().with_max_url_length(10);
+    assert!(sharding.fits(ws_url, &[], &streams[0]));
+    assert!(!sharding.fits(ws_url, &streams[..1], &streams[1]));
+}
+
+/// Serves combined streams, the shard carrying `a/b` drops once and is refused afterwards and
+/// the shard carrying `c` rejects subscribe requests.
+async fn serve_shards(listener: tokio::net::TcpListener) {
+    let mut dropped = false;
+    loop {
+        let (socket, _) = listener.accept().await.unwrap();
+        let mut request = [0; 64];
+        let read = socket.peek(&mut request).await.unwrap();
+        let request = String::from_utf8_lossy(&request[..read]);
+        let query = match request.split(['?', ' ']).nth(2) {
+            Some(query) => query.to_string(),
+            None => continue,
+        };
+        if query == "streams=a/b" && dropped {
+            continue;
+        }
+        let mut ws = tokio_tungstenite::accept_async(socket).await.unwrap();
+        dropped |= query == "streams=a/b";
+        tokio::spawn(async move {
+            match query.as_str() {
+                "streams=a/b" => return,
+                "streams=a" => {
+                    let ack = r#"{"result":null,"id":99}"#;
+                    ws.send(Message::Text(ack.to_string())).await.unwrap();
+                }
+                _ => (),
+            }
+            while let Some(Ok(Message::Text(request))) = ws.next().await {
+                let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+                let error = serde_json::json!({
+                    "error": { "code": 2, "msg": "Invalid request" },
+                    "id": request["id"],
+                });
+                ws.send(Message::Text(error.to_string())).await.unwrap();
+            }
+        });
+    }
+}
+
+#[tokio::test]
+async fn dropped_shards_are_rebalanced_onto_shards_that_accept_them() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let ws_url = format!("ws://{}/stream", listener.local_addr().unwrap());
+    tokio::spawn(serve_shards(listener));
+
+    let parameters = BinanceParameters::default()
+        .with_stream_sharding(BinanceStreamSharding::new().with_max_streams_per_connection(2))
+        .with_reconnect_policy(
+            ReconnectPolicy::new()
+                .with_initial_backoff(Duration::from_millis(1))
+                .with_jitter(0.0)
+                .with_max_attempts(1),
+        );
+    let registry = Arc::new(ShardRegistry::default());
+    let mut pool = ShardPool::new(
+        Box::leak(ws_url.into_boxed_str()),
+        &parameters,
+        Default::default(),
+        Default::default(),
+        registry.clone(),
+    );
+    pool.open(["a", "b", "c"].map(String::from).to_vec())
+        .await
+        .unwrap();
+
+    // `a` is refused by the shard of `c` and gets a shard of its own, whose ack is the first
+    // one the pool doesn't hold back.
+    let mut reconnected = false;
+    loop {
+        match tokio::time::timeout(Duration::from_secs(5), pool.next())
+            .await
+            .unwrap()
+        {
+            Some(Ok(BinanceWebsocketMessage::Reconnected)) => reconnected = true,
+            Some(Ok(BinanceWebsocketMessage::SubscriptionAck { id })) => {
+                assert_eq!(id, Some(99));
+                break;
+            }
+            Some(_) => (),
+            None => panic!("The pool ended"),
+        }
+    }
+    assert!(reconnected);
+    let streams: Vec<_> = registry
+        .snapshot()
+        .into_iter()
+        .map(|shard| shard.streams)
+        .collect();
+    assert_eq!(streams, [["c"], ["b"], ["a"]]);
+}
+
+#[test]
+fn book_tickers_and_aggregate_trades_are_converted() {
+    let subscription = BinanceSubscription::BookTicker("BNBUSDT".to_string());
+    assert_eq!(subscription.to_string(), "bnbusdt@bookTicker");
+    assert_eq!(
+        BinanceSubscription::BookTickerAll.to_string(),
+        "!bookTicker"
+    );
+
+    let book_ticker = r#"{"stream":"bnbusdt@bookTicker","data":{"u":400900217,"s":"BNBUSDT",
+        "b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}}"#;
+    match generic(serde_json::from_str(book_ticker).unwrap()) {
+        EcbtWebSocketMessage::BookTicker(update) => {
+            assert_eq!(update.market_pair, "BNBUSDT");
+            assert_eq!(update.best_bid.price, dec!(25.3519));
+            assert_eq!(update.best_ask.qty, dec!(40.66));
+            assert_eq!(update.update_id, Some(400900217));
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+
+    let aggregate_trade = r#"{"stream":"bnbbtc@aggTrade","data":{"e":"aggTrade","E":123456789,
+        "s":"BNBBTC","a":12345,"p":"0.001","q":"100","f":100,"l":105,"T":123456785,"m":true,
+        "M":true}}"#;
+    match generic(serde_json::from_str(aggregate_trade).unwrap()) {
+        EcbtWebSocketMessage::Trades(trades) => {
+            assert_eq!(trades.len(), 1);
+            assert_eq!(trades[0].id, "12345");
+            assert_eq!(trades[0].qty, dec!(100));
+            assert_eq!(trades[0].side, Side::Sell);
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+}
+
+#[test]
+fn tickers_and_klines_are_converted() {
+    let ticker = r#"{"stream":"bnbbtc@ticker","data":{"e":"24hrTicker","E":123456789,
+        "s":"BNBBTC","p":"0.0015","P":"250.00","w":"0.0018","x":"0.0009","c":"0.0025",
+        "Q":"10","b":"0.0024","B":"10","a":"0.0026","A":"100","o":"0.0010","h":"0.0025",
+        "l":"0.0010","v":"10000","q":"18","O":0,"C":86400000,"F":0,"L":18150,"n":18151}}"#;
+    match generic(serde_json::from_str(ticker).unwrap()) {
+        EcbtWebSocketMessage::Ticker(update) => {
+            assert_eq!(update.market_pair, "BNBBTC");
+            assert_eq!(update.ticker.price, Some(dec!(0.0025)));
+            assert_eq!(update.ticker.price_24h, Some(dec!(0.0010)));
+            assert_eq!(update.best_bid, Some(dec!(0.0024)));
+            assert_eq!(update.best_ask, Some(dec!(0.0026)));
+            assert_eq!(update.time, Some(123456789));
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+
+    let mini_ticker = r#"{"stream":"bnbbtc@miniTicker","data":{"e":"24hrMiniTicker",
+        "E":123456789,"s":"BNBBTC","c":"0.0025","o":"0.0010","h":"0.0025","l":"0.0010",
+        "v":"10000","q":"18"}}"#;
+    match generic(serde_json::from_str(mini_ticker).unwrap()) {
+        EcbtWebSocketMessage::Ticker(update) => {
+            assert_eq!(update.ticker.price, Some(dec!(0.0025)));
+            assert_eq!(update.ticker.price_24h, Some(dec!(0.0010)));
+            assert_eq!(update.best_bid, None);
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+
+    let kline = r#"{"stream":"bnbbtc@kline_1m","data":{"e":"kline","E":123456789,"s":"BNBBTC",
+        "k":{"t":123400000,"T":123460000,"s":"BNBBTC","i":"1m","f":100,"L":200,"o":"0.0010",
+        "c":"0.0020","h":"0.0025","l":"0.0015","v":"1000","n":100,"x":false,"q":"1.0000",
+        "V":"500","Q":"0.500","B":"123456"}}}"#;
+    match generic(serde_json::from_str(kline).unwrap()) {
+        EcbtWebSocketMessage::Candle(update) => {
+            assert_eq!(update.market_pair, "BNBBTC");
+            assert_eq!(update.interval, Interval::OneMinute);
+            assert_eq!(update.candle.time, 123400000);
+            assert_eq!(update.candle.open, dec!(0.0010));
+            assert_eq!(update.candle.high, dec!(0.0025));
+            assert_eq!(update.candle.low, dec!(0.0015));
+            assert_eq!(update.candle.close, dec!(0.0020));
+            assert_eq!(update.candle.volume, dec!(1000));
+            assert!(!update.closed);
+        }
+        other => panic!("unexpected message {:?}", other),
+    }
+}
+
+#[test]
+fn unknown_streams_and_request_responses_are_typed() {
+    let unknown = r#"{"stream":"btcusdt@avgPrice","data":{"e":"avgPrice","s":"BTCUSDT"}}"#;
+    match serde_json::from_str(unknown).unwrap() {
+        BinanceWebsocketMessage::Unknown { stream, payload } => {
+            assert_eq!(stream, "btcusdt@avgPrice");
+            assert_eq!(payload["s"], "BTCUSDT");
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+    // Known streams whose data can't be read are kept as they are.
+    let trade = r#"{"stream":"btcusdt@trade","data":{"e":"trade","s":"BTCUSDT","p":null}}"#;
+    match serde_json::from_str(trade).unwrap() {
+        BinanceWebsocketMessage::Unknown { stream, payload } => {
+            assert_eq!(stream, "btcusdt@trade");
+            assert_eq!(payload["e"], "trade");
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+    let event = r#"{"stream":"listenkey","data":{"e":"listenKeyExpired","E":1}}"#;
+    assert!(matches!(
+        serde_json::from_str(event).unwrap(),
+        BinanceWebsocketMessage::Unknown { .. }
+    ));
+
+    assert!(matches!(
+        generic(serde_json::from_str(r#"{"result":null,"id":3}"#).unwrap()),
+        EcbtWebSocketMessage::SubscriptionAck {
+            request_id: Some(3),
+            ..
+        }
+    ));
+    let error = r#"{"error":{"code":2,"msg":"Invalid request: unknown variable"},"id":4}"#;
+    match generic(serde_json::from_str(error).unwrap()) {
+        EcbtWebSocketMessage::SubscriptionError {
+            request_id,
+            code,
+            message,
+        } => {
+            assert_eq!(request_id, Some(4));
+            assert_eq!(code, Some(2));
+            assert_eq!(message, "Invalid request: unknown variable");
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[tokio::test]
+async fn conditional_orders_are_sent_as_stop_loss_and_take_profit_orders() {
+    let http = RecordingTransport::new(
+        r#"{"symbol":"BTCUSDT","orderId":28,"clientOrderId":"trail-1","price":"19000.00",
+        "origQty":"0.5","executedQty":"0","status":"NEW","timeInForce":"GTC",
+        "type":"STOP_LOSS_LIMIT","side":"SELL","stopPrice":"0","trailingDelta":50,
+        "time":1660000000000}"#,
+    );
+    let client = BaseClient {
+        transport: Transport::with_credential(http.clone(), "key", "secret", false).unwrap(),
+    };
+    let req = ConditionalOrderRequest {
+        client_order_id: Some("trail-1".to_string()),
+        market_pair: MarketPair(Currency::BTC, Currency::USDT),
+        side: Side::Sell,
+        size: dec!(0.5),
+        trigger: OrderTrigger::TrailingStop {
+            offset: TrailingOffset::BasisPoints(50),
+        },
+        limit_price: Some(dec!(19000)),
+    };
+
+    let order = crate::model::OrderRequest::try_from(&req).unwrap();
+    let order: model::Order = client.new_order(order).await.unwrap().into();
+    assert_eq!(order.order_type, OrderType::TrailingStopLimit);
+    assert_eq!(order.status, OrderStatus::New);
+
+    let body = String::from_utf8(http.last_request().body.unwrap()).unwrap();
+    assert!(body.contains("type=STOP_LOSS_LIMIT"));
+    assert!(body.contains("trailingDelta=50"));
+    assert!(body.contains("timeInForce=GTC"));
+    assert!(!body.contains("stopPrice"));
+
+    let take_profit = crate::model::OrderRequest::try_from(&ConditionalOrderRequest {
+        trigger: OrderTrigger::TakeProfit {
+            trigger_price: dec!(21000),
+        },
+        limit_price: None,
+        ..req.clone()
+    })
+    .unwrap();
+    assert_eq!(take_profit.order_type, "TAKE_PROFIT");
+    assert_eq!(take_profit.stop_price, Some(dec!(21000)));
+    assert!(take_profit.time_in_force.is_none());
+
+    assert!(matches!(
+        crate::model::OrderRequest::try_from(&ConditionalOrderRequest {
+            trigger: OrderTrigger::TrailingStop {
+                offset: TrailingOffset::Price(dec!(100)),
+            },
+            ..req
+        }),
+        Err(EcbtError::InvalidParameter(_))
+    ));
+}
+
+#[tokio::test]
+async fn amended_orders_are_cancel_replaced_in_one_request() {
+    let http = RecordingTransport::new(
+        r#"{"cancelResult":"SUCCESS","newOrderResult":"SUCCESS",
+        "cancelResponse":{"symbol":"BTCUSDT","origClientOrderId":"quote-1","orderId":28,
+        "clientOrderId":"cancel-1"},
+        "newOrderResponse":{"symbol":"BTCUSDT","orderId":29,"clientOrderId":"quote-2",
+        "price":"20010.00","origQty":"0.4","executedQty":"0","status":"NEW",
+        "timeInForce":"GTC","type":"LIMIT","side":"BUY","time":1660000000000}}"#,
+    );
+    let client = BaseClient {
+        transport: Transport::with_credential(http.clone(), "key", "secret", false).unwrap(),
+    };
+    let req = AmendOrderRequest {
+        id: "28".to_string(),
+        client_order_id: Some("quote-2".to_string()),
+        market_pair: MarketPair(Currency::BTC, Currency::USDT),
+        side: Side::Buy,
+        size: dec!(0.4),
+        price: dec!(20010),
+        time_in_force: TimeInForce::GoodTillCancelled,
+        post_only: false,
+    };
+
+    let order = crate::model::CancelReplaceRequest::try_from(&req).unwrap();
+    let replaced = client.cancel_replace_order(order).await.unwrap();
+    assert_eq!(replaced.cancel_response.order_id, 28);
+    let order: model::Order = replaced.new_order_response.into();
+    assert_eq!(order.id, "29");
+    assert_eq!(order.client_order_id.as_deref(), Some("quote-2"));
+    assert_eq!(order.order_type, OrderType::Limit);
+
+    let request = http.last_request();
+    assert_eq!(request.method, Method::POST);
+    assert_eq!(request.url.path(), "/api/v3/order/cancelReplace");
+    let body = String::from_utf8(request.body.unwrap()).unwrap();
+    assert!(body.contains("cancelOrderId=28"));
+    assert!(body.contains("cancelReplaceMode=STOP_ON_FAILURE"));
+    assert!(body.contains("newClientOrderId=quote-2"));
+    assert!(body.contains("type=LIMIT&"));
+
+    let post_only = crate::model::CancelReplaceRequest::try_from(&AmendOrderRequest {
+        post_only: true,
+        ..req.clone()
+    })
+    .unwrap();
+    assert_eq!(post_only.order_type, "LIMIT_MAKER");
+    assert!(post_only.time_in_force.is_none());
+
+    assert!(matches!(
+        crate::model::CancelReplaceRequest::try_from(&AmendOrderRequest {
+            id: "quote-1".to_string(),
+            ..req
+        }),
+        Err(EcbtError::InvalidParameter(_))
+    ));
+}
+
+/// Answers each request with the body of the first route its path ends with.
+#[derive(Debug)]
+struct RoutedTransport {
+    requests: Mutex<Vec<HttpRequest>>,
+    routes: Vec<(&'static str, &'static str)>,
+}
+
+#[async_trait]
+impl HttpTransport for RoutedTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let url = request.url.clone();
+        let (_, body) = self
+            .routes
+            .iter()
+            .find(|(path, _)| url.path().ends_with(path))
+            .expect("No route for the request.");
+        self.requests.lock().unwrap().push(request);
+        Ok(HttpResponse {
+            url,
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+        })
+    }
+}
+
+const EXCHANGE_INFO: &str = r#"{"timezone":"UTC","serverTime":1660000000000,"rateLimits":[],
+    "exchangeFilters":[],"symbols":[{"symbol":"BTCUSDT","status":"TRADING","baseAsset":"BTC",
+    "baseAssetPrecision":8,"quoteAsset":"USDT","quotePrecision":8,"orderTypes":["LIMIT"],
+    "icebergAllowed":true,"filters":[]}]}"#;
+
+#[tokio::test]
+async fn amend_order_replaces_what_the_order_did_not_fill() {
+    let http = Arc::new(RoutedTransport {
+        requests: Mutex::new(Vec::new()),
+        routes: vec![
+            ("/exchangeInfo", EXCHANGE_INFO),
+            (
+                "/order/cancelReplace",
+                r#"{"cancelResult":"SUCCESS","newOrderResult":"SUCCESS",
+                "cancelResponse":{"symbol":"BTCUSDT","origClientOrderId":"quote-1",
+                "orderId":28,"clientOrderId":"cancel-1"},
+                "newOrderResponse":{"symbol":"BTCUSDT","orderId":29,"clientOrderId":"quote-2",
+                "price":"20010.00","origQty":"0.6","executedQty":"0","status":"NEW",
+                "timeInForce":"GTC","type":"LIMIT","side":"BUY","time":1660000000000}}"#,
+            ),
+            (
+                "/v3/order",
+                r#"{"symbol":"BTCUSDT","orderId":28,"clientOrderId":"quote-1",
+                "price":"20000.00","origQty":"1.0","executedQty":"0.4",
+                "status":"PARTIALLY_FILLED","timeInForce":"GTC","type":"LIMIT","side":"BUY",
+                "time":1660000000000}"#,
+            ),
+        ],
+    });
+    let binance = Binance::new(BinanceParameters {
+        credentials: Some(BinanceCredentials {
+            api_key: "key".to_string(),
+            api_secret: "secret".to_string(),
+        }),
+        ..BinanceParameters::production().with_http_transport(http.clone())
+    })
+    .await
+    .unwrap();
+    let amend = AmendOrderRequest {
+        id: "28".to_string(),
+        client_order_id: Some("quote-2".to_string()),
+        market_pair: MarketPair(Currency::BTC, Currency::USDT),
+        side: Side::Buy,
+        size: dec!(1),
+        price: dec!(20010),
+        time_in_force: TimeInForce::GoodTillCancelled,
+        post_only: false,
+    };
+
+    let amended = binance.amend_order(&amend).await.unwrap();
+    assert_eq!(amended.method, model::AmendMethod::Native);
+    assert_eq!(amended.replaced_order_id, "28");
+    assert_eq!(amended.replaced_client_order_id.as_deref(), Some("quote-1"));
+    assert_eq!(amended.order.id, "29");
+    assert_eq!(amended.order.client_order_id.as_deref(), Some("quote-2"));
+
+    let request = http.requests.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(request.url.path(), "/api/v3/order/cancelReplace");
+    let body = String::from_utf8(request.body.unwrap()).unwrap();
+    assert!(body.contains("symbol=BTCUSDT"));
+    assert!(body.contains("cancelOrderId=28"));
+    // 0.4 of the order already filled.
+    assert!(body.contains("quantity=0.6"));
+
+    let sent = http.requests.lock().unwrap().len();
+    let filled = binance
+        .amend_order(&AmendOrderRequest {
+            size: dec!(0.4),
+            ..amend
+        })
+        .await;
+    assert!(matches!(filled, Err(EcbtError::InvalidQuantity(_))));
+    let requests = http.requests.lock().unwrap();
+    assert_eq!(requests.len(), sent + 1);
+    assert_eq!(requests[sent].url.path(), "/api/v3/order");
+}
+
+const OCO: &str = r#"{"orderListId":7,"contingencyType":"OCO","listStatusType":"EXEC_STARTED",
+    "listOrderStatus":"EXECUTING","listClientOrderId":"bracket-1","transactionTime":1660000000000,
+    "symbol":"BTCUSDT","orders":[{"symbol":"BTCUSDT","orderId":31,"clientOrderId":"stop-1"},
+    {"symbol":"BTCUSDT","orderId":32,"clientOrderId":"limit-1"}],
+    "orderReports":[{"symbol":"BTCUSDT","orderId":31,"orderListId":7,"clientOrderId":"stop-1",
+    "transactTime":1660000000000,"price":"18900.00","origQty":"0.5","executedQty":"0",
+    "status":"NEW","timeInForce":"GTC","type":"STOP_LOSS_LIMIT","side":"SELL",
+    "stopPrice":"19000.00"},{"symbol":"BTCUSDT","orderId":32,"orderListId":7,
+    "clientOrderId":"limit-1","transactTime":1660000000000,"price":"22000.00","origQty":"0.5",
+    "executedQty":"0","status":"NEW","timeInForce":"GTC","type":"LIMIT_MAKER","side":"SELL"}]}"#;
+
+#[tokio::test]
+async fn bracket_exits_are_placed_as_oco_order_lists() {
+    let http = RecordingTransport::new(OCO);
+    let client = BaseClient {
+        transport: Transport::with_credential(http.clone(), "key", "secret", false).unwrap(),
+    };
+    let bracket = BracketOrderRequest {
+        client_order_id: None,
+        market_pair: MarketPair(Currency::BTC, Currency::USDT),
+        side: Side::Buy,
+        size: dec!(0.5),
+        entry_price: None,
+        take_profit_price: dec!(22000),
+        stop_price: dec!(19000),
+        stop_limit_price: Some(dec!(18900)),
+    };
+
+    let order_list = client
+        .new_oco_order(crate::model::OcoRequest::from(&bracket.exits(dec!(0.5))))
+        .await
+        .unwrap();
+    assert_eq!(
+        order_list.list_status_type,
+        crate::model::ListStatusType::ExecStarted
+    );
+    assert_eq!(
+        order_list.list_order_status,
+        crate::model::ListOrderStatus::Executing
+    );
+    let body = String::from_utf8(http.last_request().body.unwrap()).unwrap();
+    assert!(body.contains("side=SELL"));
+    assert!(body.contains("price=22000"));
+    assert!(body.contains("stopPrice=19000"));
+    assert!(body.contains("stopLimitPrice=18900"));
+    assert!(body.contains("stopLimitTimeInForce=GTC"));
+
+    let oco = OcoOrder::try_from(order_list).unwrap();
+    assert_eq!(oco.order_list_id.as_deref(), Some("7"));
+    assert_eq!(oco.limit_order.id, "32");
+    assert_eq!(oco.limit_order.order_type, OrderType::Limit);
+    assert_eq!(oco.stop_order.id, "31");
+    assert_eq!(oco.stop_order.order_type, OrderType::StopLimit);
+
+    let order_list = client.get_order_list(7).await.unwrap();
+    assert_eq!(order_list.orders.len(), 2);
+    assert_eq!(http.last_request().method, Method::GET);
+    assert!(http
+        .last_request()
+        .url
+        .query()
+        .unwrap()
+        .contains("orderListId=7"));
+
+    client.cancel_order_list("BTCUSDT", 7).await.unwrap();
+    let request = http.last_request();
+    assert_eq!(request.method, Method::DELETE);
+    assert_eq!(request.url.path(), "/api/v3/orderList");
+}
@@ -1,14 +1,17 @@
 use hex::encode as hexify;
 use hmac::{Hmac, Mac};
 use reqwest::header;
-use reqwest::Response;
+use reqwest::Method;
 use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use sha2::Sha256;
+use std::sync::Arc;
 use url::Url;
 
+use ecbt_exchange::errors::ExchangeErrorContext;
 use ecbt_exchange::shared::utc_now;
+use ecbt_exchange::transport::{HttpRequest, HttpResponse, HttpTransport};
 use ecbt_exchange::EcbtError;
 
 use crate::BinanceContentError;
@@ -22,34 +25,32 @@ static RECV_WINDOW: usize = 7000;
 #[derive(Clone, Debug)]
 pub struct Transport {
     credential: Option<(String, String)>,
-    client: reqwest::Client,
+    http: Arc<dyn HttpTransport>,
+    headers: header::HeaderMap<header::HeaderValue>,
     pub recv_window: usize,
     base_url: String,
 }
 
 impl Transport {
-    pub fn new(sandbox: bool) -> Result<Self> {
-        let default_headers = Transport::default_headers(None);
-        let client = reqwest::Client::builder()
-            .default_headers(default_headers)
-            .build()?;
-
+    pub fn new(http: Arc<dyn HttpTransport>, sandbox: bool) -> Result<Self> {
         Ok(Transport {
             credential: None,
-            client,
+            http,
+            headers: Transport::default_headers(None),
             recv_window: RECV_WINDOW,
             base_url: Transport::get_base_url(sandbox),
         })
     }
 
-    pub fn with_credential(api_key: &str, api_secret: &str, sandbox: bool) -> Result<Self> {
-        let default_headers = Transport::default_headers(Some(api_key));
-        let client = reqwest::Client::builder()
-            .default_headers(default_headers)
-            .build()?;
-
+    pub fn with_credential(
+        http: Arc<dyn HttpTransport>,
+        api_key: &str,
+        api_secret: &str,
+        sandbox: bool,
+    ) -> Result<Self> {
         Ok(Transport {
-            client,
+            http,
+            headers: Transport::default_headers(Some(api_key)),
             credential: Some((api_key.into(), api_secret.into())),
             recv_window: RECV_WINDOW,
             base_url: Transport::get_base_url(sandbox),
@@ -91,9 +92,9 @@ impl Transport {
         S: Serialize,
     {
         let url = self.get_url(endpoint, params, false)?;
-        let request = self.client.get(url).send().await?;
+        let response = self.send::<()>(Method::GET, url, None).await?;
 
-        self.response_handler(request).await
+        self.response_handler(response)
     }
 
     pub async fn post<O, D>(&self, endpoint: &str, data: Option<&D>) -> Result<O>
@@ -102,20 +103,20 @@ impl Transport {
         D: Serialize,
     {
         let url = self.get_url::<()>(endpoint, None, false)?;
-        let request = self.client.post(url).form(&data).send().await?;
+        let response = self.send(Method::POST, url, data).await?;
 
-        self.response_handler(request).await
+        self.response_handler(response)
     }
 
-    pub async fn put<O, D>(&self, endpoint: &str, data: Option<D>) -> Result<O>
+    pub async fn put<O, D>(&self, endpoint: &str, data: Option<&D>) -> Result<O>
     where
         O: DeserializeOwned,
         D: Serialize,
     {
         let url = self.get_url::<()>(endpoint, None, false)?;
-        let request = self.client.put(url).form(&data).send().await?;
+        let response = self.send(Method::PUT, url, data).await?;
 
-        self.response_handler(request).await
+        self.response_handler(response)
     }
 
     pub async fn delete<O, Q>(&self, endpoint: &str, data: Option<&Q>) -> Result<O>
@@ -124,68 +125,56 @@ impl Transport {
         Q: Serialize,
     {
         let url = self.get_url::<()>(endpoint, None, false)?;
-        let request = self.client.delete(url).form(&data).send().await?;
+        let response = self.send(Method::DELETE, url, data).await?;
 
-        self.response_handler(request).await
+        self.response_handler(response)
     }
 
     pub async fn signed_get<O, S>(&self, endpoint: &str, params: Option<&S>) -> Result<O>
     where
         O: DeserializeOwned,
-        S: Serialize,
+        S: Serialize + Sync,
     {
-        let mut url = self.get_url(endpoint, params, true)?;
-
-        let (_, signature) = self.signature::<()>(&url, None)?;
-        url.query_pairs_mut().append_pair("signature", &signature);
-
-        let request = self.client.get(url).send().await?;
+        let response = self
+            .send_signed::<S, ()>(Method::GET, endpoint, params, None)
+            .await?;
 
-        self.response_handler(request).await
+        self.response_handler(response)
     }
 
     pub async fn signed_post<D, O>(&self, endpoint: &str, data: Option<&D>) -> Result<O>
     where
         O: DeserializeOwned,
-        D: Serialize,
+        D: Serialize + Sync,
     {
-        let mut url = self.get_url::<()>(endpoint, None, true)?;
-
-        let (_, signature) = self.signature(&url, data)?;
-        url.query_pairs_mut().append_pair("signature", &signature);
-
-        let request = self.client.post(url).form(&data).send().await?;
-        self.response_handler(request).await
+        let response = self
+            .send_signed::<(), D>(Method::POST, endpoint, None, data)
+            .await?;
+        self.response_handler(response)
     }
 
     pub async fn signed_put<O, Q>(&self, endpoint: &str, data: Option<&Q>) -> Result<O>
     where
         O: DeserializeOwned,
-        Q: Serialize,
+        Q: Serialize + Sync,
     {
-        let mut url = self.get_url::<()>(endpoint, None, true)?;
+        let response = self
+            .send_signed::<(), Q>(Method::PUT, endpoint, None, data)
+            .await?;
 
-        let (_, signature) = self.signature(&url, data)?;
-        url.query_pairs_mut().append_pair("signature", &signature);
-
-        let request = self.client.put(url).form(&data).send().await?;
-
-        self.response_handler(request).await
+        self.response_handler(response)
     }
 
     pub async fn signed_delete<O, Q>(&self, endpoint: &str, data: Option<&Q>) -> Result<O>
     where
         O: DeserializeOwned,
-        Q: Serialize,
+        Q: Serialize + Sync,
     {
-        let mut url = self.get_url::<()>(endpoint, None, true)?;
-
-        let (_, signature) = self.signature(&url, data)?;
-        url.query_pairs_mut().append_pair("signature", &signature);
-
-        let request = self.client.delete(url).form(&data).send().await?;
+        let response = self
+            .send_signed::<(), Q>(Method::DELETE, endpoint, None, data)
+            .await?;
 
-        self.response_handler(request).await
+        self.response_handler(response)
     }
 
     pub fn get_url<Q>(
@@ -244,20 +233,71 @@ impl Transport {
         Ok((key, signature))
     }
 
-    async fn response_handler<O>(&self, response: Response) -> Result<O>
+    async fn send<D>(&self, method: Method, url: Url, data: Option<&D>) -> Result<HttpResponse>
+    where
+        D: Serialize,
+    {
+        self.http.send(self.request(method, url, data)?).await
+    }
+
+    /// Sends a request signed with the current timestamp, which is signed again if the
+    /// request is retried.
+    async fn send_signed<Q, D>(
+        &self,
+        method: Method,
+        endpoint: &str,
+        params: Option<&Q>,
+        data: Option<&D>,
+    ) -> Result<HttpResponse>
+    where
+        Q: Serialize + Sync,
+        D: Serialize + Sync,
+    {
+        self.http
+            .send_with(&|| {
+                let mut url = self.get_url(endpoint, params, true)?;
+                let (_, signature) = self.signature(&url, data)?;
+                url.query_pairs_mut().append_pair("signature", &signature);
+                self.request(method.clone(), url, data)
+            })
+            .await
+    }
+
+    fn request<D>(&self, method: Method, url: Url, data: Option<&D>) -> Result<HttpRequest>
+    where
+        D: Serialize,
+    {
+        let mut request = HttpRequest::new(method, url).with_headers(self.headers.clone());
+        if let Some(data) = data {
+            request = request.with_body(serde_urlencoded::to_string(data)?);
+        }
+        Ok(request)
+    }
+
+    fn response_handler<O>(&self, response: HttpResponse) -> Result<O>
     where
         O: DeserializeOwned,
     {
-        match response.status() {
-            StatusCode::OK => Ok(response.json::<O>().await?),
-            StatusCode::INTERNAL_SERVER_ERROR => Err(EcbtError::InternalServerError()),
-            StatusCode::SERVICE_UNAVAILABLE => Err(EcbtError::ServiceUnavailable()),
-            StatusCode::UNAUTHORIZED => Err(EcbtError::Unauthorized()),
-            StatusCode::BAD_REQUEST => {
-                let error: BinanceContentError = response.json().await?;
-
-                Err(EcbtError::Generic(Box::new(error)))
-            }
+        match response.status {
+            StatusCode::OK => response.json::<O>(),
+            StatusCode::INTERNAL_SERVER_ERROR => Err(EcbtError::InternalServerError(
+                server_error_context(&response),
+            )),
+            StatusCode::SERVICE_UNAVAILABLE => Err(EcbtError::ServiceUnavailable(
+                server_error_context(&response),
+            )),
+            status if status.is_client_error() => match response.json::<BinanceContentError>() {
+                Ok(error) => Err(error.into_error(&response)),
+                Err(_) if status == StatusCode::UNAUTHORIZED => Err(EcbtError::Unauthorized()),
+                // 418 is how Binance reports an IP ban for ignoring 429s.
+                Err(_) if status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 => {
+                    Err(EcbtError::RateLimited {
+                        retry_after: response.retry_after(),
+                        context: Some(response.error_context(response.text())),
+                    })
+                }
+                Err(_) => Err(EcbtError::Exchange(response.error_context(response.text()))),
+            },
             s => Err(EcbtError::UnkownResponse(format!(
                 "Received response: {:?}",
                 s
@@ -265,3 +305,13 @@ impl Transport {
         }
     }
 }
+
+/// Returns the context of a server error, with the Binance error code when the body has one.
+fn server_error_context(response: &HttpResponse) -> ExchangeErrorContext {
+    match response.json::<BinanceContentError>() {
+        Ok(error) => response
+            .error_context(error.msg)
+            .with_code(error.code.into()),
+        Err(_) => response.error_context(response.text()),
+    }
+}
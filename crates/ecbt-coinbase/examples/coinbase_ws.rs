@@ -10,7 +10,8 @@ use ecbt_exchange::stream::ExchangeStream;
 async fn main() -> Result<()> {
     let binance_ws = CoinbaseWebsocket::new(CoinbaseParameters::sandbox()).await?;
     let symbol = "ETH-BTC".to_string();
-    binance_ws
+    // The subscription lasts as long as its handle.
+    let _handle = binance_ws
         .subscribe(CoinbaseSubscription::Matches(symbol), call_back)
         .await?;
 
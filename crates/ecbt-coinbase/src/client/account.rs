@@ -23,11 +23,22 @@ impl BaseClient {
             .await
     }
 
+    pub async fn get_order_by_client_oid(&self, client_oid: &str) -> Result<Order> {
+        self.transport
+            .signed_get::<_, ()>(&format!("/orders/client:{}", client_oid), None)
+            .await
+    }
+
     // TODO: refactor buy and sell in order creation in commun function
-    pub async fn market_buy(&self, pair: MarketPairInfo, size: Decimal) -> Result<Order> {
+    pub async fn market_buy(
+        &self,
+        pair: MarketPairInfo,
+        size: Decimal,
+        client_oid: Option<String>,
+    ) -> Result<Order> {
         let data = OrderRequest {
             product_id: pair.symbol,
-            client_oid: None,
+            client_oid,
             side: OrderSide::Buy,
             _type: OrderRequestType::Market {
                 _type: OrderRequestMarketType::Size {
@@ -45,10 +56,15 @@ impl BaseClient {
         Ok(transaction)
     }
 
-    pub async fn market_sell(&self, pair: MarketPairInfo, size: Decimal) -> Result<Order> {
+    pub async fn market_sell(
+        &self,
+        pair: MarketPairInfo,
+        size: Decimal,
+        client_oid: Option<String>,
+    ) -> Result<Order> {
         let data = OrderRequest {
             product_id: pair.symbol,
-            client_oid: None,
+            client_oid,
             side: OrderSide::Sell,
             _type: OrderRequestType::Market {
                 _type: OrderRequestMarketType::Size {
@@ -73,10 +89,11 @@ impl BaseClient {
         price: Decimal,
         time_in_force: OrderTimeInForce,
         post_only: bool,
+        client_oid: Option<String>,
     ) -> Result<Order> {
         let data = OrderRequest {
             product_id: pair.symbol,
-            client_oid: None,
+            client_oid,
             side: OrderSide::Buy,
             _type: OrderRequestType::Limit {
                 size: size.round_dp(pair.base_increment.normalize().scale()),
@@ -105,10 +122,11 @@ impl BaseClient {
         price: Decimal,
         time_in_force: OrderTimeInForce,
         post_only: bool,
+        client_oid: Option<String>,
     ) -> Result<Order> {
         let data = OrderRequest {
             product_id: pair.symbol,
-            client_oid: None,
+            client_oid,
             side: OrderSide::Sell,
             _type: OrderRequestType::Limit {
                 size: size.round_dp(pair.base_increment.normalize().scale()),
@@ -130,6 +148,16 @@ impl BaseClient {
         Ok(transaction)
     }
 
+    /// Places an order of any type, e.g. a stop order.
+    pub async fn new_order(&self, data: OrderRequest) -> Result<Order> {
+        let transaction = self
+            .transport
+            .signed_post::<_, (), _>("/orders", None, Some(&data))
+            .await?;
+
+        Ok(transaction)
+    }
+
     pub async fn cancel_order(&self, order_id: String, product_id: Option<&str>) -> Result<String> {
         let params = if let Some(product_id) = product_id {
             CancelOrder {
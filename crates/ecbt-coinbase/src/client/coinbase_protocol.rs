@@ -0,0 +1,107 @@
+use super::shared::Result;
+use super::stream::{auth, channel};
+use super::subscription_router::channels;
+use crate::model::websocket::{
+    Channel, ChannelType, CoinbaseSubscription, CoinbaseWebsocketMessage, Subscribe, SubscribeCmd,
+};
+use crate::CoinbaseCredentials;
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::stream::{ProtocolMessage, SubscriptionProtocol};
+
+/// This struct represents the `subscribe` and `unsubscribe` messages of the Coinbase feed, used
+/// by the `LiveConnection` of `CoinbaseWebsocket::live_connection`.
+///
+/// Coinbase acknowledges every request with the subscriptions of the connection, and answers
+/// the ones it rejects with an error, in the order they were sent. Subscriptions built from a
+/// whole stream, such as candles and level 2 books, can't be added to a live connection.
+#[derive(Debug, Clone, Default)]
+pub struct CoinbaseProtocol {
+    credentials: Option<CoinbaseCredentials>,
+}
+
+impl CoinbaseProtocol {
+    /// Creates a protocol signing its requests with `credentials`, required by the `user`
+    /// channel.
+    pub fn new(credentials: Option<CoinbaseCredentials>) -> Self {
+        Self { credentials }
+    }
+
+    fn request(
+        &self,
+        command: SubscribeCmd,
+        subscription: &CoinbaseSubscription,
+    ) -> Result<String> {
+        let (name, product_ids) = match subscription {
+            CoinbaseSubscription::Candles(..)
+            | CoinbaseSubscription::Level2Book(_)
+            | CoinbaseSubscription::UserOrders(..)
+            | CoinbaseSubscription::UserTrades(_) => {
+                return Err(EcbtError::InvalidParameter(format!(
+                    "{:?} can't be added to a live connection",
+                    subscription
+                )))
+            }
+            subscription => channel(subscription)?,
+        };
+        let auth = match &self.credentials {
+            Some(credentials) => Some(auth(credentials)),
+            None if name == ChannelType::User => return Err(EcbtError::NoApiKeySet()),
+            None => None,
+        };
+        let channel = match product_ids.is_empty() {
+            true => Channel::Name(name),
+            false => Channel::WithProduct { name, product_ids },
+        };
+        let subscribe = Subscribe {
+            _type: command,
+            product_ids: Vec::new(),
+            channels: vec![channel],
+            auth,
+        };
+        Ok(serde_json::to_string(&subscribe)?)
+    }
+}
+
+impl SubscriptionProtocol for CoinbaseProtocol {
+    type Subscription = CoinbaseSubscription;
+    type Message = CoinbaseWebsocketMessage;
+
+    fn subscribe_request(&self, _id: u64, subscription: &CoinbaseSubscription) -> Result<String> {
+        self.request(SubscribeCmd::Subscribe, subscription)
+    }
+
+    fn unsubscribe_request(&self, _id: u64, subscription: &CoinbaseSubscription) -> Result<String> {
+        self.request(SubscribeCmd::Unsubscribe, subscription)
+    }
+
+    fn parse(&self, text: &str) -> Result<ProtocolMessage<CoinbaseWebsocketMessage>> {
+        Ok(match serde_json::from_str(text)? {
+            CoinbaseWebsocketMessage::Subscriptions { .. } => ProtocolMessage::Ack(None),
+            CoinbaseWebsocketMessage::Error { message } => ProtocolMessage::Rejected(None, message),
+            message => ProtocolMessage::Data(None, message),
+        })
+    }
+
+    fn routes(
+        &self,
+        subscription: &CoinbaseSubscription,
+        _topic: Option<&str>,
+        message: &CoinbaseWebsocketMessage,
+    ) -> bool {
+        if let CoinbaseWebsocketMessage::Status { .. } = message {
+            return *subscription == CoinbaseSubscription::Status;
+        }
+        match (channels(message), channel(subscription)) {
+            (Some((names, product_id)), Ok((name, product_ids))) => {
+                // Batched channels send the messages of the channel they batch.
+                let name = match name {
+                    ChannelType::Level2Batch => ChannelType::Level2,
+                    ChannelType::TickerBatch => ChannelType::Ticker,
+                    name => name,
+                };
+                names.contains(&name) && product_ids.contains(&product_id)
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+use crate::model::websocket::Level2;
+use crate::model::OrderSide;
+use ecbt_exchange::book::OrderBook;
+use ecbt_exchange::model::Side;
+use std::collections::HashMap;
+
+/// This struct maintains the order book of every product of a `level2` channel.
+///
+/// A `snapshot` message replaces the book of its product and every `l2update` applies its
+/// changes on top of it. Updates of a product received before its snapshot are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct Level2Book {
+    books: HashMap<String, OrderBook>,
+}
+
+impl Level2Book {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn book(&self, product_id: &str) -> Option<&OrderBook> {
+        self.books.get(product_id)
+    }
+
+    /// Applies a message and returns the resulting book of its product.
+    pub fn apply(&mut self, level2: &Level2) -> Option<&OrderBook> {
+        match level2 {
+            Level2::Snapshot {
+                product_id,
+                bids,
+                asks,
+            } => {
+                let book = self.books.entry(product_id.clone()).or_default();
+                book.clear();
+                for bid in bids {
+                    book.update(&Side::Buy, bid.price, bid.size);
+                }
+                for ask in asks {
+                    book.update(&Side::Sell, ask.price, ask.size);
+                }
+                Some(book)
+            }
+            Level2::L2update {
+                product_id,
+                changes,
+            } => {
+                let book = self.books.get_mut(product_id)?;
+                for change in changes {
+                    let side = match change.side {
+                        OrderSide::Buy => Side::Buy,
+                        OrderSide::Sell => Side::Sell,
+                    };
+                    book.update(&side, change.price, change.size);
+                }
+                Some(book)
+            }
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use crate::model::websocket::{Change, Done, Full, Match, Open};
+use crate::model::{Book, BookRecordL3};
+use ecbt_exchange::book::OrderBook;
+use ecbt_exchange::model::Side;
+use rust_decimal::prelude::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+/// This enum represents the state of a `Level3Book` after it consumed a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level3Sync {
+    /// The book is consistent and includes the message.
+    Synced,
+    /// The message was older than the book and was dropped.
+    Stale,
+    /// The book is out of sync; a fresh `BaseClient::book` level 3 snapshot must be applied.
+    NeedsSnapshot,
+}
+
+/// This struct represents the place of a resting order in the queue of its price level
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueuePosition {
+    pub side: Side,
+    pub price: Decimal,
+    /// Remaining size of the order.
+    pub size: Decimal,
+    /// Number of orders of the level that are filled before this one.
+    pub orders_ahead: usize,
+    /// Remaining size of the orders ahead.
+    pub size_ahead: Decimal,
+}
+
+#[derive(Debug, Clone)]
+struct RestingOrder {
+    side: Side,
+    price: Decimal,
+    size: Decimal,
+}
+
+/// This struct maintains the order by order book of a product from a level 3 snapshot and
+/// the messages of its `full` channel.
+///
+/// Messages received before a snapshot is applied are buffered. Messages whose sequence is
+/// not newer than the book are dropped, and a message that skips a sequence number is a gap,
+/// which resets the book until a new snapshot arrives. Orders of a price level are kept in
+/// the order they were opened, which is the order they are filled in, and an order whose
+/// price changes loses its place. The levels aggregating the orders are kept up to date
+/// along with them.
+#[derive(Debug, Clone, Default)]
+pub struct Level3Book {
+    orders: HashMap<String, RestingOrder>,
+    bids: BTreeMap<Decimal, Vec<String>>,
+    asks: BTreeMap<Decimal, Vec<String>>,
+    levels: OrderBook,
+    sequence: Option<usize>,
+    buffer: Vec<Full>,
+}
+
+impl Level3Book {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sequence of the last snapshot or message applied to the book.
+    pub fn sequence(&self) -> Option<usize> {
+        self.sequence
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Applies a message, or buffers it while waiting for a snapshot.
+    pub fn push(&mut self, message: Full) -> Level3Sync {
+        if !self.is_synced() {
+            self.buffer.push(message);
+            return Level3Sync::NeedsSnapshot;
+        }
+
+        let sync = self.apply(&message);
+        if sync == Level3Sync::NeedsSnapshot {
+            self.reset();
+            self.buffer.push(message);
+        }
+        sync
+    }
+
+    /// Replaces the book with a snapshot and replays the buffered messages on top of it.
+    pub fn apply_snapshot(&mut self, snapshot: &Book<BookRecordL3>) -> Level3Sync {
+        self.reset();
+        for record in &snapshot.bids {
+            self.open(&record.order_id, Side::Buy, record.price, record.size);
+        }
+        for record in &snapshot.asks {
+            self.open(&record.order_id, Side::Sell, record.price, record.size);
+        }
+        self.set_sequence(snapshot.sequence);
+
+        let buffer = std::mem::take(&mut self.buffer);
+        for (index, message) in buffer.iter().enumerate() {
+            if self.apply(message) == Level3Sync::NeedsSnapshot {
+                // The snapshot is older than the buffered messages, keep them for the next one.
+                self.reset();
+                self.buffer = buffer[index..].to_vec();
+                return Level3Sync::NeedsSnapshot;
+            }
+        }
+        Level3Sync::Synced
+    }
+
+    /// The orders aggregated into price levels.
+    pub fn level2(&self) -> &OrderBook {
+        &self.levels
+    }
+
+    /// Returns where a resting order stands in its price level, if it is on the book.
+    pub fn queue_position(&self, order_id: &str) -> Option<QueuePosition> {
+        let order = self.orders.get(order_id)?;
+        let queue = self.levels(&order.side).get(&order.price)?;
+        let orders_ahead = queue.iter().position(|id| id == order_id)?;
+        let size_ahead = queue[..orders_ahead]
+            .iter()
+            .filter_map(|id| self.orders.get(id))
+            .map(|order| order.size)
+            .sum();
+        Some(QueuePosition {
+            side: order.side.clone(),
+            price: order.price,
+            size: order.size,
+            orders_ahead,
+            size_ahead,
+        })
+    }
+
+    fn apply(&mut self, message: &Full) -> Level3Sync {
+        // Activations don't change the book, and some done messages have no sequence.
+        if let Some(sequence) = message.sequence().copied() {
+            let next = self.sequence.unwrap_or_default() + 1;
+            if sequence < next {
+                return Level3Sync::Stale;
+            }
+            if sequence > next {
+                return Level3Sync::NeedsSnapshot;
+            }
+            self.set_sequence(sequence);
+        }
+
+        match message {
+            Full::Open(Open {
+                order_id,
+                side,
+                price,
+                remaining_size,
+                ..
+            }) => self.open(order_id, side.clone().into(), *price, *remaining_size),
+            Full::Done(Done::Limit { order_id, .. })
+            | Full::Done(Done::Market { order_id, .. }) => self.remove(order_id),
+            Full::Match(Match {
+                maker_order_id,
+                size,
+                ..
+            }) => {
+                if let Some(order) = self.orders.get(maker_order_id) {
+                    self.resize(maker_order_id, order.size - *size);
+                }
+            }
+            Full::Change(Change {
+                order_id,
+                new_size,
+                price,
+                ..
+            }) => match (self.orders.get(order_id), *price) {
+                (Some(order), Some(price)) if price != order.price => {
+                    let side = order.side.clone();
+                    self.open(order_id, side, price, *new_size);
+                }
+                (Some(_), _) => self.resize(order_id, *new_size),
+                (None, _) => (),
+            },
+            Full::Received(_) | Full::Activate(_) => (),
+        }
+        Level3Sync::Synced
+    }
+
+    /// Adds an order at the back of its level, replacing any order with the same id.
+    fn open(&mut self, order_id: &str, side: Side, price: Decimal, size: Decimal) {
+        self.remove(order_id);
+        self.levels_mut(&side)
+            .entry(price)
+            .or_default()
+            .push(order_id.to_string());
+        self.add_to_level(&side, price, size);
+        self.orders
+            .insert(order_id.to_string(), RestingOrder { side, price, size });
+    }
+
+    fn resize(&mut self, order_id: &str, size: Decimal) {
+        if let Some(order) = self.orders.get_mut(order_id) {
+            let change = size - order.size;
+            order.size = size;
+            let (side, price) = (order.side.clone(), order.price);
+            self.add_to_level(&side, price, change);
+        }
+    }
+
+    fn remove(&mut self, order_id: &str) {
+        if let Some(order) = self.orders.remove(order_id) {
+            self.add_to_level(&order.side, order.price, -order.size);
+            let levels = self.levels_mut(&order.side);
+            if let Some(queue) = levels.get_mut(&order.price) {
+                queue.retain(|id| id != order_id);
+                if queue.is_empty() {
+                    levels.remove(&order.price);
+                }
+            }
+        }
+    }
+
+    fn add_to_level(&mut self, side: &Side, price: Decimal, size: Decimal) {
+        let size = (self.levels.qty_at(side, price) + size).max(Decimal::ZERO);
+        self.levels.update(side, price, size);
+    }
+
+    fn set_sequence(&mut self, sequence: usize) {
+        self.sequence = Some(sequence);
+        self.levels.set_update_id(Some(sequence as u64));
+    }
+
+    fn levels(&self, side: &Side) -> &BTreeMap<Decimal, Vec<String>> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    fn levels_mut(&mut self, side: &Side) -> &mut BTreeMap<Decimal, Vec<String>> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.orders.clear();
+        self.bids.clear();
+        self.asks.clear();
+        self.levels.clear();
+        self.sequence = None;
+    }
+}
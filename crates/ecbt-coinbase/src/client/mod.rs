@@ -2,9 +2,17 @@
 
 mod account;
 mod base_client;
+mod coinbase_protocol;
+mod level2_book;
+mod level3_book;
 mod market;
 pub mod stream;
+mod subscription_router;
 
 pub use super::shared;
 pub(crate) use super::transport::Transport;
 pub use base_client::BaseClient;
+pub use coinbase_protocol::CoinbaseProtocol;
+pub use level2_book::Level2Book;
+pub use level3_book::{Level3Book, Level3Sync, QueuePosition};
+pub use subscription_router::SubscriptionRouter;
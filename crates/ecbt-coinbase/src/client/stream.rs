@@ -1,64 +1,62 @@
 use super::shared::Result;
+use super::subscription_router::SubscriptionRouter;
+use super::{BaseClient, CoinbaseProtocol, Level2Book, Level3Book, Level3Sync};
 use crate::model::websocket::ChannelType;
 use crate::model::websocket::{
-    Channel, CoinbaseSubscription, CoinbaseWebsocketMessage, Subscribe, SubscribeCmd,
+    Auth, Channel, CoinbaseSubscription, CoinbaseWebsocketMessage, Subscribe, SubscribeCmd,
 };
-use crate::CoinbaseParameters;
+use crate::model::BookRecordL3;
+use crate::transport::sign;
+use crate::{CoinbaseCredentials, CoinbaseParameters};
 use async_trait::async_trait;
+use ecbt_exchange::book::OrderBook;
 use ecbt_exchange::errors::EcbtError;
 use ecbt_exchange::exchange::Environment;
-use ecbt_exchange::stream::{ExchangeStream, Subscriptions};
-use futures::stream::BoxStream;
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::stream::{reconnecting_stream, ExchangeStream, LiveConnection, Subscriptions};
+use futures::stream::{self, BoxStream};
 use futures::{
+    future,
     stream::{SplitStream, Stream},
-    SinkExt, StreamExt,
+    FutureExt, SinkExt, StreamExt,
 };
-use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
-use std::{collections::HashMap, pin::Pin, task::Poll};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use std::{pin::Pin, slice, task::Poll};
 use tokio::net::TcpStream;
 use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio::sync::oneshot;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
 
 const WS_URL_PROD: &str = "wss://ws-feed.exchange.coinbase.com";
 const WS_URL_SANDBOX: &str = "wss://ws-feed-public.sandbox.exchange.coinbase.com";
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
-#[serde(untagged)]
-enum Either<L, R> {
-    Left(L),
-    Right(R),
-}
-
 type WSStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 /// A websocket connection to Coinbase
 pub struct CoinbaseWebsocket {
     pub subscriptions: HashMap<CoinbaseSubscription, SplitStream<WSStream>>,
     pub parameters: CoinbaseParameters,
-    disconnection_senders: Mutex<Vec<UnboundedSender<()>>>,
+    disconnection_senders: Arc<Mutex<Vec<UnboundedSender<()>>>>,
+    /// Incremented by every `disconnect`, so reconnecting streams know they were closed.
+    disconnections: Arc<AtomicUsize>,
 }
 
 impl CoinbaseWebsocket {
     pub async fn subscribe_(&mut self, subscription: CoinbaseSubscription) -> Result<()> {
-        let (channels, product_ids) = match &subscription {
-            CoinbaseSubscription::Level2(product_id) => (
-                vec![Channel::Name(ChannelType::Level2)],
-                vec![product_id.clone()],
-            ),
-            CoinbaseSubscription::Heartbeat(product_id) => (
-                vec![Channel::Name(ChannelType::Heartbeat)],
-                vec![product_id.clone()],
-            ),
-            CoinbaseSubscription::Matches(product_id) => (
-                vec![Channel::Name(ChannelType::Matches)],
-                vec![product_id.clone()],
-            ),
+        let (channel_name, product_ids) = channel(&subscription)?;
+        let auth = match &self.parameters.credentials {
+            Some(credentials) => Some(auth(credentials)),
+            None if channel_name == ChannelType::User => return Err(EcbtError::NoApiKeySet()),
+            None => None,
         };
+        let channels = vec![Channel::Name(channel_name)];
         let subscribe = Subscribe {
             _type: SubscribeCmd::Subscribe,
-            auth: None,
+            auth,
             channels,
             product_ids,
         };
@@ -68,6 +66,108 @@ impl CoinbaseWebsocket {
         Ok(())
     }
 
+    /// Streams a locally maintained order book of `market_pair`, yielding the full book after
+    /// the `level2` snapshot and after every update.
+    pub async fn managed_order_book(
+        &self,
+        market_pair: MarketPair,
+    ) -> Result<BoxStream<'static, Result<OrderBook>>> {
+        let product_id = crate::model::MarketPair::from(market_pair).0;
+        let subscription = CoinbaseSubscription::Level2(product_id);
+        let messages = self
+            .create_stream_specific(slice::from_ref(&subscription).into())
+            .await?;
+
+        let mut books = Level2Book::new();
+        let books = messages.filter_map(move |message| {
+            future::ready(match message {
+                Ok(CoinbaseWebsocketMessage::Level2(level2)) => {
+                    books.apply(&level2).cloned().map(Ok)
+                }
+                Ok(CoinbaseWebsocketMessage::Error { message }) => {
+                    Some(Err(EcbtError::UnkownResponse(message)))
+                }
+                Ok(_) => None,
+                Err(err) => Some(Err(err)),
+            })
+        });
+
+        Ok(books.boxed())
+    }
+
+    /// Streams the price levels of a locally maintained order by order book of `market_pair`,
+    /// yielded after every `full` channel message applied to it.
+    ///
+    /// The book starts from a `client.book` level 3 snapshot and is resynchronized from a new
+    /// one whenever a sequence gap is detected, so every yielded book is consistent.
+    pub async fn managed_level3_book(
+        &self,
+        client: BaseClient,
+        market_pair: MarketPair,
+    ) -> Result<BoxStream<'static, Result<OrderBook>>> {
+        let product_id = crate::model::MarketPair::from(market_pair).0;
+        let subscription = CoinbaseSubscription::Full(product_id.clone());
+        let messages = self
+            .create_stream_specific(slice::from_ref(&subscription).into())
+            .await?;
+        let state = (messages, Level3Book::new(), client, product_id);
+
+        let books = stream::unfold(
+            state,
+            |(mut messages, mut book, client, product_id)| async move {
+                loop {
+                    let message = match messages.next().await? {
+                        Ok(CoinbaseWebsocketMessage::Full(message)) => message,
+                        Ok(CoinbaseWebsocketMessage::Reconnected) => {
+                            book = Level3Book::new();
+                            continue;
+                        }
+                        Ok(CoinbaseWebsocketMessage::Error { message }) => {
+                            return Some((
+                                Err(EcbtError::UnkownResponse(message)),
+                                (messages, book, client, product_id),
+                            ))
+                        }
+                        Ok(_) => continue,
+                        Err(err) => return Some((Err(err), (messages, book, client, product_id))),
+                    };
+
+                    let mut outcome = book.push(message);
+                    if outcome == Level3Sync::NeedsSnapshot {
+                        match client
+                            .book::<BookRecordL3, _>(crate::model::MarketPair(product_id.clone()))
+                            .await
+                        {
+                            Ok(snapshot) => outcome = book.apply_snapshot(&snapshot),
+                            Err(err) => {
+                                return Some((Err(err), (messages, book, client, product_id)))
+                            }
+                        }
+                    }
+                    if outcome == Level3Sync::Synced {
+                        let levels = book.level2().clone();
+                        return Some((Ok(levels), (messages, book, client, product_id)));
+                    }
+                }
+            },
+        );
+
+        Ok(books.boxed())
+    }
+
+    /// Opens a connection without subscriptions, whose subscriptions are added and removed
+    /// while it is open.
+    pub async fn live_connection(&self) -> Result<LiveConnection<CoinbaseProtocol>> {
+        let ws_url = if self.parameters.environment == Environment::Sandbox {
+            WS_URL_SANDBOX
+        } else {
+            WS_URL_PROD
+        };
+        let endpoint = url::Url::parse(ws_url)?;
+        let protocol = CoinbaseProtocol::new(self.parameters.credentials.clone());
+        LiveConnection::connect(endpoint, protocol).await
+    }
+
     pub async fn connect(&self, subscribe: Subscribe) -> Result<SplitStream<WSStream>> {
         let ws_url = if self.parameters.environment == Environment::Sandbox {
             WS_URL_SANDBOX
@@ -121,10 +221,12 @@ impl ExchangeStream for CoinbaseWebsocket {
             subscriptions: Default::default(),
             parameters,
             disconnection_senders: Default::default(),
+            disconnections: Default::default(),
         })
     }
 
     async fn disconnect(&self) {
+        self.disconnections.fetch_add(1, Ordering::SeqCst);
         if let Ok(mut senders) = self.disconnection_senders.lock() {
             for sender in senders.iter() {
                 sender.send(()).ok();
@@ -143,58 +245,234 @@ impl ExchangeStream for CoinbaseWebsocket {
             WS_URL_PROD
         };
         let endpoint = url::Url::parse(ws_url).expect("Couldn't parse url.");
-        let (ws_stream, _) = connect_async(endpoint).await?;
 
-        let (channel_name, product_ids) = match &subscription.as_slice()[0] {
-            CoinbaseSubscription::Level2(product_id) => {
-                (ChannelType::Level2, vec![product_id.clone()])
-            }
-            CoinbaseSubscription::Heartbeat(product_id) => {
-                (ChannelType::Heartbeat, vec![product_id.clone()])
-            }
-            CoinbaseSubscription::Matches(product_id) => {
-                (ChannelType::Matches, vec![product_id.clone()])
-            }
-        };
-        let channels = vec![Channel::Name(channel_name.clone())];
+        let subscriptions = subscription.as_slice().to_vec();
+        let channels = channels(&subscriptions)?;
+        let credentials = self.parameters.credentials.clone();
+        if credentials.is_none() && channels.contains_key(&ChannelType::User) {
+            return Err(EcbtError::NoApiKeySet());
+        }
         let subscribe = Subscribe {
             _type: SubscribeCmd::Subscribe,
             auth: None,
-            channels,
-            product_ids: product_ids.clone(),
+            channels: channels
+                .iter()
+                .map(|(name, product_ids)| match product_ids.is_empty() {
+                    true => Channel::Name(name.clone()),
+                    false => Channel::WithProduct {
+                        name: name.clone(),
+                        product_ids: product_ids.iter().cloned().collect(),
+                    },
+                })
+                .collect(),
+            product_ids: Vec::new(),
         };
-        let subscribe = serde_json::to_string(&subscribe)?;
-        let (mut sink, stream) = ws_stream.split();
-        let (disconnection_sender, mut disconnection_receiver) = unbounded_channel();
-        sink.send(Message::Text(subscribe)).await?;
-        tokio::spawn(async move {
-            if disconnection_receiver.recv().await.is_some() {
-                sink.close().await.ok();
+
+        let senders = self.disconnection_senders.clone();
+        let stream = open_connection(
+            endpoint.clone(),
+            subscribe.clone(),
+            credentials.clone(),
+            channels.clone(),
+            senders.clone(),
+        )
+        .await?;
+
+        let stream = match self.parameters.reconnect_policy.clone() {
+            None => stream,
+            Some(policy) => {
+                let disconnections = self.disconnections.clone();
+                let generation = disconnections.load(Ordering::SeqCst);
+                let reconnect = move || {
+                    open_connection(
+                        endpoint.clone(),
+                        subscribe.clone(),
+                        credentials.clone(),
+                        channels.clone(),
+                        senders.clone(),
+                    )
+                    .boxed()
+                };
+                reconnecting_stream(stream, policy, reconnect, move || {
+                    disconnections.load(Ordering::SeqCst) != generation
+                })
             }
-        });
+        };
 
-        if let Ok(mut senders) = self.disconnection_senders.lock() {
-            senders.push(disconnection_sender);
+        Ok(routed(stream, &subscriptions))
+    }
+}
+
+/// Dispatches the messages of a connection to its `subscriptions`, see `SubscriptionRouter`.
+pub(crate) fn routed(
+    stream: BoxStream<'static, Result<CoinbaseWebsocketMessage>>,
+    subscriptions: &[CoinbaseSubscription],
+) -> BoxStream<'static, Result<CoinbaseWebsocketMessage>> {
+    let mut router = SubscriptionRouter::new(subscriptions);
+    stream
+        .flat_map(move |message| {
+            let messages = match message {
+                Ok(message) => router.apply(message).into_iter().map(Ok).collect(),
+                Err(err) => vec![Err(err)],
+            };
+            stream::iter(messages)
+        })
+        .boxed()
+}
+
+/// The products of each channel of a connection, as sent in its `subscribe` message.
+type Channels = HashMap<ChannelType, BTreeSet<String>>;
+
+/// Merges the channels and products of `subscriptions`, so they share one connection.
+pub(crate) fn channels(subscriptions: &[CoinbaseSubscription]) -> Result<Channels> {
+    if subscriptions.is_empty() {
+        return Err(EcbtError::InvalidParameter(
+            "At least one subscription is required".to_string(),
+        ));
+    }
+    let mut channels = Channels::new();
+    for subscription in subscriptions {
+        let (name, product_ids) = channel(subscription)?;
+        channels.entry(name).or_default().extend(product_ids);
+    }
+    Ok(channels)
+}
+
+/// Returns the products of each channel of a `subscriptions` message, which lists every
+/// subscription of the connection.
+fn acknowledged(channels: &[Channel]) -> Channels {
+    let mut acknowledged = Channels::new();
+    for channel in channels {
+        match channel {
+            Channel::Name(name) => acknowledged.entry(name.clone()).or_default(),
+            Channel::WithProduct { name, product_ids } => {
+                let products = acknowledged.entry(name.clone()).or_default();
+                products.extend(product_ids.iter().cloned());
+                products
+            }
+        };
+    }
+    acknowledged
+}
+
+/// Whether a `subscriptions` message acknowledges every channel and product of `expected`.
+/// The connection may have other subscriptions, and they may be listed in any order.
+pub(crate) fn acknowledges(channels: &[Channel], expected: &Channels) -> bool {
+    let acknowledged = acknowledged(channels);
+    expected.iter().all(|(name, product_ids)| {
+        acknowledged
+            .get(name)
+            .is_some_and(|acknowledged| acknowledged.is_superset(product_ids))
+    })
+}
+
+/// Returns the channel and the products to subscribe to for `subscription`.
+pub(crate) fn channel(subscription: &CoinbaseSubscription) -> Result<(ChannelType, Vec<String>)> {
+    Ok(match subscription {
+        CoinbaseSubscription::Level2(product_id) | CoinbaseSubscription::Level2Book(product_id) => {
+            (ChannelType::Level2, vec![product_id.clone()])
+        }
+        CoinbaseSubscription::Heartbeat(product_id) => {
+            (ChannelType::Heartbeat, vec![product_id.clone()])
         }
-        let mut s = stream.map(|message| parse_message(message?));
-
-        let name = channel_name;
-        let product = Channel::WithProduct { name, product_ids };
-        let channels = vec![product];
-        let expected_response = CoinbaseWebsocketMessage::Subscriptions { channels };
-
-        let response = s.next().await;
-        if let Some(Ok(response)) = response {
-            if response == expected_response {
-                Ok(s.boxed())
-            } else {
-                Err(EcbtError::UnkownResponse(format!(
-                    "Response: {:#?}, expected response: {:#?}",
-                    response, expected_response
-                )))
+        CoinbaseSubscription::Matches(product_id)
+        | CoinbaseSubscription::Candles(product_id, _) => {
+            (ChannelType::Matches, vec![product_id.clone()])
+        }
+        CoinbaseSubscription::Ticker(product_id) => (ChannelType::Ticker, vec![product_id.clone()]),
+        CoinbaseSubscription::TickerBatch(product_id) => {
+            (ChannelType::TickerBatch, vec![product_id.clone()])
+        }
+        CoinbaseSubscription::Level2Batch(product_id) => {
+            (ChannelType::Level2Batch, vec![product_id.clone()])
+        }
+        CoinbaseSubscription::Status => (ChannelType::Status, Vec::new()),
+        CoinbaseSubscription::User(product_id)
+        | CoinbaseSubscription::UserOrders(product_id, _)
+        | CoinbaseSubscription::UserTrades(product_id) => {
+            (ChannelType::User, vec![product_id.clone()])
+        }
+        CoinbaseSubscription::Full(product_id) => (ChannelType::Full, vec![product_id.clone()]),
+        CoinbaseSubscription::Unsupported(subscription) => {
+            return Err(EcbtError::InvalidParameter(format!(
+                "{subscription:?} is not supported by Coinbase"
+            )))
+        }
+    })
+}
+
+/// Signs a subscription the way Coinbase authenticates websocket feeds, which is the signature
+/// of a `GET /users/self/verify` request. Signatures expire, so every connection signs again.
+pub(crate) fn auth(credentials: &CoinbaseCredentials) -> Auth {
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("Invalid SystemTime.")
+        .as_secs()
+        .to_string();
+    let message = format!("{}GET/users/self/verify", timestamp);
+    Auth {
+        signature: sign(&credentials.api_secret, &message),
+        key: credentials.api_key.clone(),
+        passphrase: credentials.passphrase.clone(),
+        timestamp,
+    }
+}
+
+/// Opens a connection to `endpoint`, sends `subscribe`, signed when `credentials` are set, and
+/// waits for the `subscriptions` message acknowledging the `expected` channels.
+/// The connection is closed by a message on the sender it registers in `disconnection_senders`.
+async fn open_connection(
+    endpoint: url::Url,
+    mut subscribe: Subscribe,
+    credentials: Option<CoinbaseCredentials>,
+    expected: Channels,
+    disconnection_senders: Arc<Mutex<Vec<UnboundedSender<()>>>>,
+) -> Result<BoxStream<'static, Result<CoinbaseWebsocketMessage>>> {
+    let (ws_stream, _) = connect_async(endpoint).await?;
+    subscribe.auth = credentials.as_ref().map(auth);
+    let subscribe = serde_json::to_string(&subscribe)?;
+    let (mut sink, stream) = ws_stream.split();
+    let (disconnection_sender, mut disconnection_receiver) = unbounded_channel();
+    // Dropped with the returned stream, which ends the task and closes its sender.
+    let (stream_alive, stream_dropped) = oneshot::channel::<()>();
+    sink.send(Message::Text(subscribe)).await?;
+    tokio::spawn(async move {
+        tokio::select! {
+            Some(()) = disconnection_receiver.recv() => {
+                sink.close().await.ok();
             }
-        } else {
-            Err(EcbtError::UnkownResponse("No response".to_string()))
+            _ = stream_dropped => (),
+        }
+    });
+
+    if let Ok(mut senders) = disconnection_senders.lock() {
+        // Connections that ended dropped their receiver, their senders are closed.
+        senders.retain(|sender| !sender.is_closed());
+        senders.push(disconnection_sender);
+    }
+    // Control frames are answered by tungstenite, only the data frames are parsed.
+    let mut s = stream
+        .filter(|message| {
+            future::ready(!matches!(
+                message,
+                Ok(Message::Ping(_)) | Ok(Message::Pong(_))
+            ))
+        })
+        .map(move |message| {
+            let _alive = &stream_alive;
+            parse_message(message?)
+        });
+
+    match s.next().await {
+        Some(Ok(CoinbaseWebsocketMessage::Subscriptions { channels }))
+            if acknowledges(&channels, &expected) =>
+        {
+            Ok(s.boxed())
         }
+        Some(Ok(response)) => Err(EcbtError::UnkownResponse(format!(
+            "Response: {:#?}, expected subscriptions: {:#?}",
+            response, expected
+        ))),
+        _ => Err(EcbtError::UnkownResponse("No response".to_string())),
     }
 }
@@ -0,0 +1,188 @@
+use super::Level2Book;
+use crate::model::websocket::{
+    parse_time, ChannelType, CoinbaseSubscription, CoinbaseWebsocketMessage, Full, Match,
+};
+use ecbt_exchange::model::websocket::{AccountOrders, EcbtWebSocketMessage};
+use ecbt_exchange::stream::CandleAggregator;
+
+#[derive(Debug, Clone)]
+enum Route {
+    /// Forwards the messages of a channel for a product as they are received.
+    Forward(ChannelType, String),
+    /// Builds candles from the matches of a product, with the last trade it aggregated.
+    Candles(String, CandleAggregator, Option<usize>),
+    /// Maintains the `level2` book of a product.
+    Level2Book(String, Level2Book),
+    UserOrders(String, AccountOrders),
+    UserTrades(String),
+}
+
+/// This struct dispatches the messages of a connection carrying several subscriptions, so
+/// each message reaches the subscriptions of its channel and product only.
+///
+/// A message is forwarded once when any subscription accepts it, the matches of a
+/// `CoinbaseSubscription::Candles` product are replaced by its candles and the `level2`
+/// messages of a `CoinbaseSubscription::Level2Book` product by its full book. Messages that
+/// belong to no product, such as errors and status updates, pass through.
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionRouter {
+    routes: Vec<Route>,
+}
+
+impl SubscriptionRouter {
+    pub fn new<'a>(subscriptions: impl IntoIterator<Item = &'a CoinbaseSubscription>) -> Self {
+        let routes = subscriptions
+            .into_iter()
+            .filter_map(|subscription| {
+                Some(match subscription.clone() {
+                    CoinbaseSubscription::Heartbeat(product_id) => {
+                        Route::Forward(ChannelType::Heartbeat, product_id)
+                    }
+                    CoinbaseSubscription::Ticker(product_id)
+                    | CoinbaseSubscription::TickerBatch(product_id) => {
+                        Route::Forward(ChannelType::Ticker, product_id)
+                    }
+                    CoinbaseSubscription::Level2(product_id)
+                    | CoinbaseSubscription::Level2Batch(product_id) => {
+                        Route::Forward(ChannelType::Level2, product_id)
+                    }
+                    CoinbaseSubscription::User(product_id) => {
+                        Route::Forward(ChannelType::User, product_id)
+                    }
+                    CoinbaseSubscription::Matches(product_id) => {
+                        Route::Forward(ChannelType::Matches, product_id)
+                    }
+                    CoinbaseSubscription::Full(product_id) => {
+                        Route::Forward(ChannelType::Full, product_id)
+                    }
+                    CoinbaseSubscription::Level2Book(product_id) => {
+                        Route::Level2Book(product_id, Level2Book::new())
+                    }
+                    CoinbaseSubscription::Candles(product_id, interval) => Route::Candles(
+                        product_id.clone(),
+                        CandleAggregator::new(product_id, interval),
+                        None,
+                    ),
+                    CoinbaseSubscription::UserOrders(product_id, orders) => {
+                        Route::UserOrders(product_id, orders)
+                    }
+                    CoinbaseSubscription::UserTrades(product_id) => Route::UserTrades(product_id),
+                    CoinbaseSubscription::Status | CoinbaseSubscription::Unsupported(_) => {
+                        return None
+                    }
+                })
+            })
+            .collect();
+        Self { routes }
+    }
+
+    /// Returns the messages to forward for `message`.
+    pub fn apply(&mut self, message: CoinbaseWebsocketMessage) -> Vec<CoinbaseWebsocketMessage> {
+        let (channels, product_id) = match channels(&message) {
+            Some(route) => route,
+            None => return vec![message],
+        };
+
+        let mut forward = false;
+        let mut derived = Vec::new();
+        for route in &mut self.routes {
+            match route {
+                Route::Forward(channel, product) => {
+                    forward |= *product == product_id && channels.contains(channel);
+                }
+                Route::Candles(product, aggregator, last_trade_id) => {
+                    let trade = match &message {
+                        CoinbaseWebsocketMessage::Match(trade)
+                        | CoinbaseWebsocketMessage::Full(Full::Match(trade)) => trade,
+                        _ => continue,
+                    };
+                    // A match is sent by every channel carrying it, it is aggregated once.
+                    if *product != product_id
+                        || last_trade_id.is_some_and(|last| trade.trade_id <= last)
+                    {
+                        continue;
+                    }
+                    if let Some(time) = parse_time(&trade.time) {
+                        *last_trade_id = Some(trade.trade_id);
+                        derived.extend(
+                            aggregator
+                                .push(time, trade.price, trade.size)
+                                .into_iter()
+                                .map(CoinbaseWebsocketMessage::Candle),
+                        );
+                    }
+                }
+                Route::Level2Book(product, books) => {
+                    let level2 = match &message {
+                        CoinbaseWebsocketMessage::Level2(level2) if *product == product_id => {
+                            level2
+                        }
+                        _ => continue,
+                    };
+                    if let Some(book) = books.apply(level2) {
+                        derived.push(CoinbaseWebsocketMessage::OrderBook {
+                            product_id: product_id.clone(),
+                            book: book.into(),
+                        });
+                    }
+                }
+                Route::UserOrders(product, orders) => {
+                    if *product != product_id || !channels.contains(&ChannelType::User) {
+                        continue;
+                    }
+                    if let CoinbaseWebsocketMessage::Full(full) = &message {
+                        forward |= match full.clone().into_generic() {
+                            Some(EcbtWebSocketMessage::OrderUpdate(order)) => orders
+                                .accepts(&order, |market_pair| {
+                                    crate::model::MarketPair::from(market_pair.clone()).0
+                                }),
+                            _ => false,
+                        };
+                    }
+                }
+                Route::UserTrades(product) => {
+                    forward |= *product == product_id
+                        && channels.contains(&ChannelType::User)
+                        && matches!(message, CoinbaseWebsocketMessage::Full(Full::Match(_)));
+                }
+            }
+        }
+
+        let mut messages = Vec::new();
+        if forward {
+            messages.push(message);
+        }
+        messages.extend(derived);
+        messages
+    }
+}
+
+/// Returns the channels that can carry `message` and its product, or `None` when it belongs to
+/// no product.
+pub(crate) fn channels(message: &CoinbaseWebsocketMessage) -> Option<(Vec<ChannelType>, String)> {
+    Some(match message {
+        CoinbaseWebsocketMessage::Heartbeat { product_id, .. } => {
+            (vec![ChannelType::Heartbeat], product_id.clone())
+        }
+        CoinbaseWebsocketMessage::Ticker(ticker) => {
+            (vec![ChannelType::Ticker], ticker.product_id().to_string())
+        }
+        CoinbaseWebsocketMessage::Level2(level2) => {
+            (vec![ChannelType::Level2], level2.product_id().to_string())
+        }
+        CoinbaseWebsocketMessage::Match(Match { product_id, .. }) => {
+            (vec![ChannelType::Matches], product_id.clone())
+        }
+        CoinbaseWebsocketMessage::Full(full) => {
+            let mut channels = vec![ChannelType::Full];
+            if matches!(full, Full::Match(_)) {
+                channels.push(ChannelType::Matches);
+            }
+            if full.user_id().is_some() {
+                channels.push(ChannelType::User);
+            }
+            (channels, full.product_id().to_string())
+        }
+        _ => return None,
+    })
+}
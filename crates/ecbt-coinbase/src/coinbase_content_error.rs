@@ -1,14 +1,63 @@
+use ecbt_exchange::errors::{match_error_message, EcbtError, ErrorConstructor};
+use ecbt_exchange::transport::HttpResponse;
+use reqwest::StatusCode;
 use serde::Deserialize;
 use serde::Serialize;
 use std::fmt;
 use thiserror::Error;
 
+/// Coinbase has no error codes, so rejections are told apart by their message.
+const ERROR_MESSAGES: &[(&str, ErrorConstructor)] = &[
+    ("insufficient funds", EcbtError::InsufficientBalance),
+    ("notfound", EcbtError::OrderNotFound),
+    ("order not found", EcbtError::OrderNotFound),
+    ("order already done", EcbtError::OrderNotFound),
+    ("duplicate client_oid", EcbtError::DuplicateOrder),
+    ("post only", EcbtError::PostOnlyWouldTake),
+    ("price is too", EcbtError::InvalidPrice),
+    ("invalid price", EcbtError::InvalidPrice),
+    ("size is too", EcbtError::InvalidQuantity),
+    ("invalid size", EcbtError::InvalidQuantity),
+    ("funds is too", EcbtError::InvalidQuantity),
+    (
+        "request timestamp expired",
+        EcbtError::TimestampOutsideRecvWindow,
+    ),
+    ("trading is disabled", EcbtError::MarketClosed),
+    ("cancel only mode", EcbtError::MarketClosed),
+    ("limit only mode", EcbtError::MarketClosed),
+    ("delisted", EcbtError::MarketClosed),
+    ("invalid api key", EcbtError::AuthenticationRejected),
+    ("invalid signature", EcbtError::AuthenticationRejected),
+    ("invalid passphrase", EcbtError::AuthenticationRejected),
+];
+
 /// This struct represents the coinbase content errors
 #[derive(Serialize, Deserialize, Debug, Error)]
 pub struct CoinbaseContentError {
     pub message: String,
 }
 
+impl CoinbaseContentError {
+    /// Maps the error to a typed [`EcbtError`] carrying the endpoint and status of
+    /// `response`.
+    pub fn into_error(self, response: &HttpResponse) -> EcbtError {
+        let context = response.error_context(self.message);
+        if response.status == StatusCode::TOO_MANY_REQUESTS {
+            return EcbtError::RateLimited {
+                retry_after: response.retry_after(),
+                context: Some(context),
+            };
+        }
+        match match_error_message(ERROR_MESSAGES, context) {
+            EcbtError::Exchange(context) if response.status == StatusCode::UNAUTHORIZED => {
+                EcbtError::AuthenticationRejected(context)
+            }
+            error => error,
+        }
+    }
+}
+
 impl fmt::Display for CoinbaseContentError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "error message: {}", self.message)
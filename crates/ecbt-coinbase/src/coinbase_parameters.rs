@@ -1,11 +1,27 @@
 use super::CoinbaseCredentials;
 use ecbt_exchange::exchange::Environment;
+use ecbt_exchange::info::OrderValidation;
+use ecbt_exchange::rate_limit::RateLimitMode;
+use ecbt_exchange::retry::RetryPolicy;
+use ecbt_exchange::stream::ReconnectPolicy;
+use ecbt_exchange::transport::HttpTransport;
+use std::sync::Arc;
 
 /// This struct represents the coinbase parameters
 #[derive(Default, Clone, Debug)]
 pub struct CoinbaseParameters {
     pub environment: Environment,
     pub credentials: Option<CoinbaseCredentials>,
+    /// The HTTP transport used for REST calls, defaults to a `ReqwestTransport`.
+    pub http_transport: Option<Arc<dyn HttpTransport>>,
+    /// How websocket streams reconnect when dropped, streams end with the connection if unset.
+    pub reconnect_policy: Option<ReconnectPolicy>,
+    /// Whether REST requests over the Coinbase limits wait or fail, they are not limited if unset.
+    pub rate_limit: Option<RateLimitMode>,
+    /// How orders are checked against the product limits before being sent.
+    pub order_validation: OrderValidation,
+    /// How failed REST requests are retried, nothing is retried if unset.
+    pub retry_policy: Option<RetryPolicy>,
 }
 
 impl CoinbaseParameters {
@@ -22,4 +38,29 @@ impl CoinbaseParameters {
             ..Default::default()
         }
     }
+
+    pub fn with_http_transport(mut self, http_transport: Arc<dyn HttpTransport>) -> Self {
+        self.http_transport = Some(http_transport);
+        self
+    }
+
+    pub fn with_reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = Some(reconnect_policy);
+        self
+    }
+
+    pub fn with_rate_limit(mut self, rate_limit: RateLimitMode) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    pub fn with_order_validation(mut self, order_validation: OrderValidation) -> Self {
+        self.order_validation = order_validation;
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
 }
@@ -0,0 +1,40 @@
+use ecbt_exchange::rate_limit::{
+    RateLimitKind, RateLimitRule, RequestCost, RequestPriority, RequestWeigher,
+};
+use ecbt_exchange::transport::HttpRequest;
+use reqwest::Method;
+use std::time::Duration;
+
+/// The request costs of the Coinbase Exchange API, which limits public and private requests
+/// per second separately.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoinbaseRequestWeigher;
+
+impl CoinbaseRequestWeigher {
+    pub fn rules() -> Vec<RateLimitRule> {
+        vec![
+            RateLimitRule::new(RateLimitKind::PublicRequests, Duration::from_secs(1), 10),
+            RateLimitRule::new(RateLimitKind::PrivateRequests, Duration::from_secs(1), 15),
+        ]
+    }
+}
+
+impl RequestWeigher for CoinbaseRequestWeigher {
+    fn cost(&self, request: &HttpRequest) -> RequestCost {
+        let order_action =
+            request.method != Method::GET && request.url.path().starts_with("/orders");
+        let orders = match (&request.method, request.url.path()) {
+            (&Method::POST, "/orders") => 1,
+            _ => 0,
+        };
+
+        RequestCost::new()
+            .with_orders(orders)
+            .with_private(request.headers.contains_key("CB-ACCESS-SIGN"))
+            .with_priority(if order_action {
+                RequestPriority::High
+            } else {
+                RequestPriority::Normal
+            })
+    }
+}
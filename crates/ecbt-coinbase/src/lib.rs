@@ -33,13 +33,15 @@ use ecbt_exchange::*;
 use ecbt_exchange::{
     errors::EcbtError,
     model::{
-        AskBid, Balance, CancelAllOrdersRequest, CancelOrderRequest, Candle, EcbtOrderRequest,
-        GetHistoricRatesRequest, GetHistoricTradesRequest, GetOrderHistoryRequest, GetOrderRequest,
-        GetPriceTickerRequest, Liquidity, OpenMarketOrderRequest, Order, OrderBookRequest,
-        OrderBookResponse, OrderCanceled, OrderStatus, OrderType, Paginator, Side, Ticker,
-        TimeInForce, Trade, TradeHistoryRequest,
+        AskBid, Balance, CancelAllOrdersRequest, CancelOrderRequest, Candle,
+        ConditionalOrderRequest, EcbtOrderRequest, GetHistoricRatesRequest,
+        GetHistoricTradesRequest, GetOrderHistoryRequest, GetOrderRequest, GetPriceTickerRequest,
+        Liquidity, OcoOrder, OcoOrderRequest, OpenMarketOrderRequest, Order, OrderBookRequest,
+        OrderBookResponse, OrderCanceled, OrderStatus, OrderTrigger, OrderType, Paginator, Side,
+        Ticker, TimeInForce, Trade, TradeHistoryRequest,
     },
 };
+use rust_decimal::Decimal;
 use std::convert::TryFrom;
 use time::Duration;
 use transport::Transport;
@@ -48,21 +50,33 @@ pub mod client;
 mod coinbase_content_error;
 mod coinbase_credentials;
 mod coinbase_parameters;
+mod coinbase_request_weigher;
 pub mod model;
+#[cfg(test)]
+mod tests;
 mod transport;
 
 pub use crate::client::stream::CoinbaseWebsocket;
 pub use coinbase_content_error::CoinbaseContentError;
 pub use coinbase_credentials::CoinbaseCredentials;
 pub use coinbase_parameters::CoinbaseParameters;
+pub use coinbase_request_weigher::CoinbaseRequestWeigher;
 use ecbt_exchange::exchange::Environment;
 use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::rate_limit::{RateLimitedTransport, RateLimiter};
+use ecbt_exchange::retry::{new_client_order_id, place_order, RetryPolicy, RetryingTransport};
 pub use ecbt_exchange::shared;
+use ecbt_exchange::transport::{http_transport_or_default, HttpTransport};
+use std::future::Future;
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct Coinbase {
     pub exchange_info: ExchangeInfo,
     pub client: BaseClient,
+    pub order_validation: OrderValidation,
+    /// Orders are given a client_oid and reconciled by it on ambiguous failures if set.
+    pub retry_policy: Option<RetryPolicy>,
 }
 
 #[async_trait]
@@ -71,24 +85,34 @@ impl Exchange for Coinbase {
     type InnerClient = BaseClient;
 
     async fn new(parameters: Self::InitParams) -> Result<Self> {
-        let coinbase = match parameters.credentials {
-            Some(credentials) => Coinbase {
-                exchange_info: ExchangeInfo::new(),
-                client: BaseClient {
-                    transport: Transport::with_credential(
-                        &credentials.api_key,
-                        &credentials.api_secret,
-                        &credentials.passphrase,
-                        parameters.environment == Environment::Sandbox,
-                    )?,
-                },
-            },
-            None => Coinbase {
-                exchange_info: ExchangeInfo::new(),
-                client: BaseClient {
-                    transport: Transport::new(parameters.environment == Environment::Sandbox)?,
-                },
-            },
+        let mut http = http_transport_or_default(parameters.http_transport)?;
+        if let Some(mode) = parameters.rate_limit {
+            http = Arc::new(RateLimitedTransport::new(
+                http,
+                Arc::new(RateLimiter::new(CoinbaseRequestWeigher::rules(), mode)),
+                Arc::new(CoinbaseRequestWeigher),
+            )) as Arc<dyn HttpTransport>;
+        }
+        if let Some(retry_policy) = &parameters.retry_policy {
+            http = Arc::new(RetryingTransport::new(http, retry_policy.clone()))
+                as Arc<dyn HttpTransport>;
+        }
+        let sandbox = parameters.environment == Environment::Sandbox;
+        let transport = match parameters.credentials {
+            Some(credentials) => Transport::with_credential(
+                http,
+                &credentials.api_key,
+                &credentials.api_secret,
+                &credentials.passphrase,
+                sandbox,
+            )?,
+            None => Transport::new(http, sandbox)?,
+        };
+        let coinbase = Coinbase {
+            exchange_info: ExchangeInfo::new(),
+            client: BaseClient { transport },
+            order_validation: parameters.order_validation,
+            retry_policy: parameters.retry_policy,
         };
 
         coinbase.refresh_market_info().await?;
@@ -100,22 +124,52 @@ impl Exchange for Coinbase {
     }
 }
 
+impl Coinbase {
+    /// Sends an order once, or with a client_oid it can be reconciled by when a retry policy
+    /// is set.
+    async fn place_order<F, Fut>(&self, client_oid: Option<String>, place: F) -> Result<Order>
+    where
+        F: Fn(Option<String>) -> Fut,
+        Fut: Future<Output = Result<model::Order>>,
+    {
+        let retry_policy = match &self.retry_policy {
+            Some(retry_policy) => retry_policy,
+            None => return place(client_oid).await.map(Into::into),
+        };
+        let client_oid = client_oid.unwrap_or_else(new_client_order_id);
+        place_order(
+            retry_policy,
+            || async { place(Some(client_oid.clone())).await.map(Into::into) },
+            || async {
+                self.client
+                    .get_order_by_client_oid(&client_oid)
+                    .await
+                    .map(Into::into)
+            },
+        )
+        .await
+    }
+
+    /// Returns the last traded price to check price bands and market order notionals
+    /// against, when the validation of an order `needs` it.
+    async fn reference_price(&self, pair: &MarketPairInfo, needs: bool) -> Result<Option<Decimal>> {
+        if !needs || self.order_validation == OrderValidation::Off {
+            return Ok(None);
+        }
+        self.client
+            .ticker(model::MarketPair(pair.symbol.clone()))
+            .await
+            .map(|ticker| Some(ticker.price))
+    }
+}
+
 #[async_trait]
 impl ExchangeInfoRetrieval for Coinbase {
     async fn retrieve_pairs(&self) -> Result<Vec<MarketPairInfo>> {
-        self.client.products().await.map(|v| {
-            v.into_iter()
-                .map(|product| MarketPairInfo {
-                    symbol: product.id,
-                    base: product.base_currency,
-                    quote: product.quote_currency,
-                    base_increment: product.base_increment,
-                    quote_increment: product.quote_increment,
-                    min_base_trade_size: None,
-                    min_quote_trade_size: None,
-                })
-                .collect()
-        })
+        self.client
+            .products()
+            .await
+            .map(|v| v.iter().map(MarketPairInfo::from).collect())
     }
 
     async fn refresh_market_info(&self) -> Result<Vec<MarketPairHandle>> {
@@ -189,11 +243,25 @@ impl From<model::Order> for Order {
             } => (Some(price), size, OrderType::Limit),
             model::OrderType::Market { size, funds: _ } => (None, size, OrderType::Market),
         };
+        // Stop losses fire below the stop price and entries above it.
+        let order_type = match (&order.stop, &order.side, order_type) {
+            (None, _, order_type) => order_type,
+            (Some(stop), side, order_type) => {
+                let against =
+                    (stop._type == model::OrderStopType::Loss) == (*side == model::OrderSide::Sell);
+                match (against, order_type) {
+                    (true, OrderType::Limit) => OrderType::StopLimit,
+                    (true, _) => OrderType::StopMarket,
+                    (false, OrderType::Limit) => OrderType::TakeProfitLimit,
+                    (false, _) => OrderType::TakeProfitMarket,
+                }
+            }
+        };
 
         Self {
             id: order.id,
             market_pair: order.product_id,
-            client_order_id: None,
+            client_order_id: order.client_oid,
             created_at: Some((timestamp_mills(&order.created_at)) as u64),
             order_type,
             side: order.side.into(),
@@ -210,43 +278,66 @@ impl From<model::Order> for Order {
 impl ExchangeAccount for Coinbase {
     async fn limit_buy(&self, req: &EcbtOrderRequest) -> Result<Order> {
         let pair = self.get_pair(&req.market_pair).await?.read()?;
-        self.client
-            .limit_buy(
-                pair,
+        let reference_price = self
+            .reference_price(&pair, pair.has_price_band(&Side::Buy))
+            .await?;
+        let req =
+            &pair.validate_limit_order(&Side::Buy, req, self.order_validation, reference_price)?;
+        self.place_order(req.client_order_id.clone(), |client_oid| {
+            self.client.limit_buy(
+                pair.clone(),
                 req.size,
                 req.price,
                 model::OrderTimeInForce::from(req.time_in_force),
                 req.post_only,
+                client_oid,
             )
-            .await
-            .map(Into::into)
+        })
+        .await
     }
 
     async fn limit_sell(&self, req: &EcbtOrderRequest) -> Result<Order> {
         let pair = self.get_pair(&req.market_pair).await?.read()?;
-        self.client
-            .limit_sell(
-                pair,
+        let reference_price = self
+            .reference_price(&pair, pair.has_price_band(&Side::Sell))
+            .await?;
+        let req =
+            &pair.validate_limit_order(&Side::Sell, req, self.order_validation, reference_price)?;
+        self.place_order(req.client_order_id.clone(), |client_oid| {
+            self.client.limit_sell(
+                pair.clone(),
                 req.size,
                 req.price,
                 model::OrderTimeInForce::from(req.time_in_force),
                 req.post_only,
+                client_oid,
             )
-            .await
-            .map(Into::into)
+        })
+        .await
     }
 
     async fn market_buy(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
         let pair = self.get_pair(&req.market_pair).await?.read()?;
-        self.client.market_buy(pair, req.size).await.map(Into::into)
+        let reference_price = self
+            .reference_price(&pair, pair.has_notional_limits())
+            .await?;
+        let req = &pair.validate_market_order(req, self.order_validation, reference_price)?;
+        self.place_order(req.client_order_id.clone(), |client_oid| {
+            self.client.market_buy(pair.clone(), req.size, client_oid)
+        })
+        .await
     }
 
     async fn market_sell(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
         let pair = self.get_pair(&req.market_pair).await?.read()?;
-        self.client
-            .market_sell(pair, req.size)
-            .await
-            .map(Into::into)
+        let reference_price = self
+            .reference_price(&pair, pair.has_notional_limits())
+            .await?;
+        let req = &pair.validate_market_order(req, self.order_validation, reference_price)?;
+        self.place_order(req.client_order_id.clone(), |client_oid| {
+            self.client.market_sell(pair.clone(), req.size, client_oid)
+        })
+        .await
     }
 
     async fn cancel_order(&self, req: &CancelOrderRequest) -> Result<OrderCanceled> {
@@ -308,6 +399,27 @@ impl ExchangeAccount for Coinbase {
 
         self.client.get_order(id).await.map(Into::into)
     }
+
+    /// Coinbase holds the funds of every open order, including the stop orders that haven't
+    /// fired, so the limit order and the stop order of a pair can't both rest on the book.
+    async fn place_oco_order(&self, req: &OcoOrderRequest) -> Result<OcoOrder> {
+        Err(EcbtError::UnsupportedOrderType(
+            req.stop_order().order_type(),
+        ))
+    }
+
+    async fn place_conditional_order(&self, req: &ConditionalOrderRequest) -> Result<Order> {
+        let pair = self.get_pair(&req.market_pair).await?.read()?;
+        let req = &pair.validate_conditional_order(req, self.order_validation)?;
+        let order = model::OrderRequest::try_from(req)?;
+        self.place_order(req.client_order_id.clone(), |client_oid| {
+            self.client.new_order(model::OrderRequest {
+                client_oid,
+                ..order.clone()
+            })
+        })
+        .await
+    }
 }
 
 impl From<model::Account> for Balance {
@@ -382,6 +494,45 @@ impl TryFrom<&GetHistoricRatesRequest> for model::CandleRequestParams {
     }
 }
 
+impl TryFrom<&ConditionalOrderRequest> for model::OrderRequest {
+    type Error = EcbtError;
+    /// Coinbase only has stop limit orders, a stop loss fires when the last trade price drops
+    /// to the stop price and a stop entry when it rises to it.
+    fn try_from(req: &ConditionalOrderRequest) -> Result<Self> {
+        let (stop_price, price, against) = match (req.trigger, req.limit_price) {
+            (OrderTrigger::Stop { trigger_price }, Some(price)) => (trigger_price, price, true),
+            (OrderTrigger::TakeProfit { trigger_price }, Some(price)) => {
+                (trigger_price, price, false)
+            }
+            _ => return Err(EcbtError::UnsupportedOrderType(req.order_type())),
+        };
+        let side = match req.side {
+            Side::Buy => model::OrderSide::Buy,
+            Side::Sell => model::OrderSide::Sell,
+        };
+        let stop_type = match against == (side == model::OrderSide::Sell) {
+            true => model::OrderStopType::Loss,
+            false => model::OrderStopType::Entry,
+        };
+
+        Ok(Self {
+            side,
+            client_oid: req.client_order_id.clone(),
+            product_id: crate::model::MarketPair::from(req.market_pair.clone()).0,
+            _type: model::OrderRequestType::Limit {
+                price,
+                size: req.size,
+                post_only: false,
+                time_in_force: Some(model::OrderTimeInForce::GTC),
+            },
+            stop: Some(model::OrderStop {
+                stop_price,
+                _type: stop_type,
+            }),
+        })
+    }
+}
+
 impl From<&GetOrderHistoryRequest> for model::GetOrderRequest {
     fn from(req: &GetOrderHistoryRequest) -> Self {
         Self {
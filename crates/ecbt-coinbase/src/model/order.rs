@@ -14,6 +14,7 @@ use time::OffsetDateTime;
 pub struct Order {
     pub id: String,
     pub product_id: String,
+    pub client_oid: Option<String>,
     pub side: OrderSide,
     pub stp: Option<String>,
     #[serde(flatten)]
@@ -6,7 +6,7 @@ use serde::Serialize;
 /// This struct represents an order stop
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OrderStop {
-    stop_price: Decimal,
+    pub stop_price: Decimal,
     #[serde(rename = "stop")]
-    _type: OrderStopType,
+    pub _type: OrderStopType,
 }
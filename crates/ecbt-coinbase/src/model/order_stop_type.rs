@@ -2,7 +2,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 /// This struct represents the type stop of an order
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub enum OrderStopType {
     Loss,
@@ -1,7 +1,9 @@
 use super::shared::string_to_decimal;
+use ecbt_exchange::info::{MarketPairInfo, OrderConstraints};
 use rust_decimal::prelude::Decimal;
 use serde::Deserialize;
 use serde::Serialize;
+use std::str::FromStr;
 
 /// This struct represents a product
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -27,3 +29,25 @@ pub struct Product {
     pub post_only: bool,
     pub trading_disabled: bool,
 }
+
+impl From<&Product> for MarketPairInfo {
+    fn from(product: &Product) -> Self {
+        let non_zero = |value: Decimal| Some(value).filter(|value| !value.is_zero());
+        let funds = |funds: &str| Decimal::from_str(funds).ok().and_then(non_zero);
+
+        MarketPairInfo {
+            symbol: product.id.clone(),
+            base: product.base_currency.clone(),
+            quote: product.quote_currency.clone(),
+            base_increment: product.base_increment,
+            quote_increment: product.quote_increment,
+            min_base_trade_size: non_zero(product.base_min_size),
+            min_quote_trade_size: funds(&product.min_market_funds),
+            constraints: OrderConstraints {
+                max_size: non_zero(product.base_max_size),
+                max_notional: funds(&product.max_market_funds),
+                ..Default::default()
+            },
+        }
+    }
+}
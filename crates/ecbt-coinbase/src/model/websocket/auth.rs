@@ -2,7 +2,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 /// This struct contains the auth fields
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Auth {
     pub signature: String,
     pub key: String,
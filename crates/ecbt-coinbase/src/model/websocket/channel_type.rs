@@ -7,8 +7,18 @@ use serde::Serialize;
 pub enum ChannelType {
     Heartbeat,
     Ticker,
+    /// The ticker channel batched every 5 seconds
+    #[serde(rename = "ticker_batch")]
+    TickerBatch,
     Level2,
+    /// The level2 channel batched every 50 milliseconds
+    #[serde(rename = "level2_batch")]
+    Level2Batch,
     Matches,
     Full,
     User,
+    Status,
+    /// A channel this version doesn't know of.
+    #[serde(other)]
+    Unknown,
 }
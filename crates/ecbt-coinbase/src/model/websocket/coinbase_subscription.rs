@@ -1,26 +1,61 @@
-use ecbt_exchange::model::websocket::Subscription;
+use ecbt_exchange::model::websocket::{AccountOrders, Subscription};
+use ecbt_exchange::model::Interval;
 
 /// This enum represents a coinbase subscription
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CoinbaseSubscription {
     Heartbeat(String),
-    // Status,
-    // Ticker(String),
+    Status,
+    Ticker(String),
+    /// Ticker updates batched by Coinbase, for many products on one connection
+    TickerBatch(String),
     Level2(String),
-    // User,
+    /// Level 2 updates batched by Coinbase, for many products on one connection
+    Level2Batch(String),
+    /// Full order books maintained from the `level2` channel, after its snapshot and every
+    /// update.
+    Level2Book(String),
+    /// Every event of the orders of the authenticated user
+    User(String),
+    /// (Product, Filters), order updates of the `user` channel passing the filters
+    UserOrders(String, AccountOrders),
+    /// Matches of the orders of the authenticated user
+    UserTrades(String),
     Matches(String),
-    // FullChannel
+    Full(String),
+    /// Candles built from the `matches` channel, as Coinbase doesn't stream candles.
+    Candles(String, Interval),
+    /// A generic subscription Coinbase has no channel for, it fails when subscribed.
+    Unsupported(Subscription),
 }
 
 impl From<Subscription> for CoinbaseSubscription {
     fn from(subscription: Subscription) -> Self {
         match subscription {
             Subscription::OrderBookUpdates(symbol) => {
-                CoinbaseSubscription::Level2(crate::model::MarketPair::from(symbol).0)
+                CoinbaseSubscription::Level2Book(crate::model::MarketPair::from(symbol).0)
             }
             Subscription::Trades(symbol) => {
                 CoinbaseSubscription::Matches(crate::model::MarketPair::from(symbol).0)
-            } // Subscription::Ticker(ticket) =>
+            }
+            Subscription::Ticker(symbol) => {
+                CoinbaseSubscription::Ticker(crate::model::MarketPair::from(symbol).0)
+            }
+            Subscription::Candles(symbol, interval) => {
+                CoinbaseSubscription::Candles(crate::model::MarketPair::from(symbol).0, interval)
+            }
+            Subscription::AccountTrades(symbol) => {
+                CoinbaseSubscription::UserTrades(crate::model::MarketPair::from(symbol).0)
+            }
+            // The `user` channel needs the products, and Coinbase streams no balances.
+            Subscription::AccountOrders(orders) => match orders.market.clone() {
+                Some(market) => CoinbaseSubscription::UserOrders(
+                    crate::model::MarketPair::from(market).0,
+                    orders,
+                ),
+                None => CoinbaseSubscription::Unsupported(Subscription::AccountOrders(orders)),
+            },
+            Subscription::AccountBalance(_) => CoinbaseSubscription::Unsupported(subscription),
         }
     }
 }
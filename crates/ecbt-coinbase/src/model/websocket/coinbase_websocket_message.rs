@@ -3,9 +3,16 @@ use super::Full;
 use super::InputMessage;
 use super::Level2;
 use super::Match;
+use super::StatusCurrency;
+use super::StatusProduct;
 use super::Ticker;
+use ecbt_exchange::model::websocket::CandleUpdate;
+use ecbt_exchange::model::OrderBookResponse;
+use ecbt_exchange::stream::ConnectionEvent;
+use serde::de;
 use serde::Deserialize;
 use serde::Deserializer;
+use serde_json::Value;
 
 /// This enum represents a coinbase websocket message
 #[derive(Debug, Clone, PartialEq)]
@@ -19,6 +26,10 @@ pub enum CoinbaseWebsocketMessage {
         product_id: String,
         time: String,
     },
+    Status {
+        products: Vec<StatusProduct>,
+        currencies: Vec<StatusCurrency>,
+    },
     Ticker(Ticker),
     Level2(Level2),
     Match(Match),
@@ -26,6 +37,29 @@ pub enum CoinbaseWebsocketMessage {
     Error {
         message: String,
     },
+    /// A message of a type this version doesn't know of, kept as received.
+    Unknown {
+        stream: String,
+        payload: Value,
+    },
+    /// A candle built from the matches of a `CoinbaseSubscription::Candles`.
+    Candle(CandleUpdate),
+    /// The full book of a `CoinbaseSubscription::Level2Book` product.
+    OrderBook {
+        product_id: String,
+        book: OrderBookResponse,
+    },
+    Disconnected,
+    Reconnected,
+}
+
+impl From<ConnectionEvent> for CoinbaseWebsocketMessage {
+    fn from(event: ConnectionEvent) -> Self {
+        match event {
+            ConnectionEvent::Disconnected => CoinbaseWebsocketMessage::Disconnected,
+            ConnectionEvent::Reconnected => CoinbaseWebsocketMessage::Reconnected,
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for CoinbaseWebsocketMessage {
@@ -33,7 +67,21 @@ impl<'de> Deserialize<'de> for CoinbaseWebsocketMessage {
     where
         D: Deserializer<'de>,
     {
-        Deserialize::deserialize(deserializer).map(|input_msg: InputMessage| input_msg.into())
+        // Messages of types this version doesn't know of are `Unknown`, so new Coinbase
+        // messages never fail a connection.
+        let value = Value::deserialize(deserializer)?;
+        let input_msg = InputMessage::deserialize(&value).map_err(de::Error::custom)?;
+        Ok(match input_msg {
+            InputMessage::Unknown => CoinbaseWebsocketMessage::Unknown {
+                stream: value
+                    .get("type")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                payload: value,
+            },
+            input_msg => input_msg.into(),
+        })
     }
 }
 
@@ -54,6 +102,13 @@ impl From<InputMessage> for CoinbaseWebsocketMessage {
                 product_id,
                 time,
             },
+            InputMessage::Status {
+                products,
+                currencies,
+            } => CoinbaseWebsocketMessage::Status {
+                products,
+                currencies,
+            },
             InputMessage::Ticker(ticker) => CoinbaseWebsocketMessage::Ticker(ticker),
             InputMessage::Snapshot {
                 product_id,
@@ -83,6 +138,10 @@ impl From<InputMessage> for CoinbaseWebsocketMessage {
                 CoinbaseWebsocketMessage::Full(Full::Activate(activate))
             }
             InputMessage::Error { message } => CoinbaseWebsocketMessage::Error { message },
+            InputMessage::Unknown => CoinbaseWebsocketMessage::Unknown {
+                stream: String::new(),
+                payload: Value::Null,
+            },
         }
     }
 }
@@ -31,6 +31,33 @@ impl Full {
         }
     }
 
+    pub fn product_id(&self) -> &str {
+        match self {
+            Full::Received(Received::Limit { product_id, .. }) => product_id,
+            Full::Received(Received::Market { product_id, .. }) => product_id,
+            Full::Open(Open { product_id, .. }) => product_id,
+            Full::Done(Done::Limit { product_id, .. }) => product_id,
+            Full::Done(Done::Market { product_id, .. }) => product_id,
+            Full::Match(Match { product_id, .. }) => product_id,
+            Full::Change(Change { product_id, .. }) => product_id,
+            Full::Activate(Activate { product_id, .. }) => product_id,
+        }
+    }
+
+    /// The user the order belongs to, only set on authenticated feeds for the user's orders.
+    pub fn user_id(&self) -> Option<&String> {
+        match self {
+            Full::Received(Received::Limit { user_id, .. }) => user_id.as_ref(),
+            Full::Received(Received::Market { .. }) => None,
+            Full::Open(Open { user_id, .. }) => user_id.as_ref(),
+            Full::Done(Done::Limit { user_id, .. }) => user_id.as_ref(),
+            Full::Done(Done::Market { .. }) => None,
+            Full::Match(Match { user_id, .. }) => user_id.as_ref(),
+            Full::Change(Change { user_id, .. }) => user_id.as_ref(),
+            Full::Activate(Activate { user_id, .. }) => user_id.as_ref(),
+        }
+    }
+
     pub fn time(&self) -> Option<&String> {
         match self {
             Full::Received(Received::Limit { time, .. }) => Some(time),
@@ -7,6 +7,8 @@ use super::Level2UpdateRecord;
 use super::Match;
 use super::Open;
 use super::Received;
+use super::StatusCurrency;
+use super::StatusProduct;
 use super::Ticker;
 use serde::Deserialize;
 
@@ -24,6 +26,10 @@ pub enum InputMessage {
         product_id: String,
         time: String,
     },
+    Status {
+        products: Vec<StatusProduct>,
+        currencies: Vec<StatusCurrency>,
+    },
     Ticker(Ticker),
     Snapshot {
         product_id: String,
@@ -44,4 +50,7 @@ pub enum InputMessage {
     Error {
         message: String,
     },
+    /// A message type this version doesn't know of.
+    #[serde(other)]
+    Unknown,
 }
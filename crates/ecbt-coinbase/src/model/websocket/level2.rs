@@ -15,3 +15,12 @@ pub enum Level2 {
         changes: Vec<Level2UpdateRecord>,
     },
 }
+
+impl Level2 {
+    pub fn product_id(&self) -> &str {
+        match self {
+            Level2::Snapshot { product_id, .. } => product_id,
+            Level2::L2update { product_id, .. } => product_id,
+        }
+    }
+}
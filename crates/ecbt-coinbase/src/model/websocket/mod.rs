@@ -1,10 +1,21 @@
 use ecbt_exchange::errors::EcbtError;
 use ecbt_exchange::model::websocket::EcbtWebSocketMessage;
+use ecbt_exchange::model::websocket::OrderBookUpdate;
+use ecbt_exchange::model::websocket::TickerUpdate;
 use ecbt_exchange::model::websocket::WebSocketResponse;
 use ecbt_exchange::model::AskBid;
+use ecbt_exchange::model::Liquidity;
+use ecbt_exchange::model::Order;
 use ecbt_exchange::model::OrderBookResponse;
+use ecbt_exchange::model::OrderStatus;
+use ecbt_exchange::model::OrderType;
+use ecbt_exchange::model::Side;
+use ecbt_exchange::shared::timestamp_mills;
 use ecbt_exchange::shared::Result;
+use rust_decimal::Decimal;
 use std::convert::{TryFrom, TryInto};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
 
 use super::OrderSide;
 
@@ -25,6 +36,8 @@ mod match_;
 mod open;
 mod reason;
 mod received;
+mod status_currency;
+mod status_product;
 mod stop_type;
 mod subscribe;
 mod subscribe_cmd;
@@ -49,6 +62,8 @@ pub use match_::Match;
 pub use open::Open;
 pub use reason::Reason;
 pub use received::Received;
+pub use status_currency::StatusCurrency;
+pub use status_product::StatusProduct;
 pub use stop_type::StopType;
 pub use subscribe::Subscribe;
 pub use subscribe_cmd::SubscribeCmd;
@@ -59,23 +74,217 @@ impl TryFrom<CoinbaseWebsocketMessage> for WebSocketResponse<CoinbaseWebsocketMe
 
     fn try_from(value: CoinbaseWebsocketMessage) -> Result<Self> {
         match value {
-            CoinbaseWebsocketMessage::Level2(level2) => {
-                Ok(WebSocketResponse::Generic(level2.try_into()?))
-            }
+            CoinbaseWebsocketMessage::Level2(level2) => Ok(match level2 {
+                Level2::Snapshot { .. } => WebSocketResponse::Generic(level2.try_into()?),
+                // Updates only carry the changed levels, `Level2Book` subscriptions stream
+                // the books they build.
+                Level2::L2update { .. } => {
+                    WebSocketResponse::Raw(CoinbaseWebsocketMessage::Level2(level2))
+                }
+            }),
+            CoinbaseWebsocketMessage::OrderBook { product_id, book } => Ok(
+                WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(OrderBookUpdate {
+                    market_pair: product_id,
+                    book,
+                })),
+            ),
             CoinbaseWebsocketMessage::Match(match_) => {
                 Ok(WebSocketResponse::Generic(match_.into()))
             }
-            CoinbaseWebsocketMessage::Full(full) => Ok(WebSocketResponse::Generic(full.into())),
+            CoinbaseWebsocketMessage::Full(full) => Ok(match full.clone().into_generic() {
+                Some(message) => WebSocketResponse::Generic(message),
+                None => WebSocketResponse::Raw(CoinbaseWebsocketMessage::Full(full)),
+            }),
+            CoinbaseWebsocketMessage::Ticker(ticker) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Ticker(ticker.into()),
+            )),
+            CoinbaseWebsocketMessage::Candle(candle) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Candle(candle),
+            )),
+            CoinbaseWebsocketMessage::Subscriptions { channels } => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::SubscriptionAck {
+                    request_id: None,
+                    channels: channels.iter().filter_map(channel_name).collect(),
+                },
+            )),
+            CoinbaseWebsocketMessage::Error { message } => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::SubscriptionError {
+                    request_id: None,
+                    code: None,
+                    message,
+                },
+            )),
+            CoinbaseWebsocketMessage::Disconnected => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Disconnected,
+            )),
+            CoinbaseWebsocketMessage::Reconnected => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Reconnected,
+            )),
             _ => Ok(WebSocketResponse::Raw(value)),
         }
     }
 }
 
-impl From<Full> for EcbtWebSocketMessage {
-    fn from(from: Full) -> Self {
-        match from {
-            Full::Match(match_) => match_.into(),
-            _ => todo!("Full is not fully implemented :)"),
+/// Returns the name of `channel` as Coinbase spells it.
+fn channel_name(channel: &Channel) -> Option<String> {
+    let name = match channel {
+        Channel::Name(name) | Channel::WithProduct { name, .. } => name,
+    };
+    serde_json::to_value(name)
+        .ok()?
+        .as_str()
+        .map(str::to_string)
+}
+
+impl Full {
+    /// Converts the message to a generic event: matches of the authenticated user are fills,
+    /// other matches are trades and the other messages about the orders of the authenticated
+    /// user are order updates. Messages about the orders of other users, which the public
+    /// `full` channel sends, and activations, which don't tell the order side, have no
+    /// generic event.
+    pub fn into_generic(self) -> Option<EcbtWebSocketMessage> {
+        if self.user_id().is_none() && !matches!(self, Full::Match(_)) {
+            return None;
+        }
+        let update = |id, market_pair, side: OrderSide, status| Order {
+            id,
+            market_pair,
+            client_order_id: None,
+            created_at: None,
+            order_type: OrderType::Unknown,
+            side: side.into(),
+            status,
+            size: Decimal::ZERO,
+            price: None,
+            remaining: None,
+            trades: Vec::new(),
+        };
+        let done = |reason| match reason {
+            Reason::Filled => OrderStatus::Filled,
+            Reason::Canceled => OrderStatus::Canceled,
+        };
+        let order = match self {
+            Full::Match(match_) if match_.user_id.is_some() => {
+                return Some(EcbtWebSocketMessage::Fill(match_.into()))
+            }
+            Full::Match(match_) => return Some(match_.into()),
+            Full::Activate(_) => return None,
+            Full::Received(Received::Limit {
+                time,
+                product_id,
+                order_id,
+                client_oid,
+                size,
+                price,
+                side,
+                ..
+            }) => Order {
+                client_order_id: client_oid,
+                created_at: parse_time(&time),
+                order_type: OrderType::Limit,
+                size,
+                price: Some(price),
+                remaining: Some(size),
+                ..update(order_id, product_id, side, OrderStatus::New)
+            },
+            Full::Received(Received::Market {
+                time,
+                product_id,
+                order_id,
+                client_oid,
+                side,
+                ..
+            }) => Order {
+                client_order_id: client_oid,
+                created_at: parse_time(&time),
+                order_type: OrderType::Market,
+                ..update(order_id, product_id, side, OrderStatus::New)
+            },
+            Full::Open(Open {
+                product_id,
+                order_id,
+                price,
+                remaining_size,
+                side,
+                ..
+            }) => Order {
+                order_type: OrderType::Limit,
+                price: Some(price),
+                remaining: Some(remaining_size),
+                ..update(order_id, product_id, side, OrderStatus::Open)
+            },
+            Full::Done(Done::Limit {
+                product_id,
+                order_id,
+                price,
+                reason,
+                side,
+                remaining_size,
+                ..
+            }) => Order {
+                order_type: OrderType::Limit,
+                price: Some(price),
+                remaining: Some(remaining_size),
+                ..update(order_id, product_id, side, done(reason))
+            },
+            Full::Done(Done::Market {
+                product_id,
+                order_id,
+                reason,
+                side,
+                ..
+            }) => Order {
+                order_type: OrderType::Market,
+                ..update(order_id, product_id, side, done(reason))
+            },
+            Full::Change(Change {
+                order_id,
+                product_id,
+                new_size,
+                price,
+                side,
+                ..
+            }) => Order {
+                size: new_size,
+                price,
+                remaining: Some(new_size),
+                ..update(order_id, product_id, side, OrderStatus::Open)
+            },
+        };
+        Some(EcbtWebSocketMessage::OrderUpdate(order))
+    }
+}
+
+impl From<Match> for Trade {
+    /// Converts a match of the authenticated user, seen from the side of their order.
+    fn from(match_: Match) -> Self {
+        let maker = match_.maker_user_id.is_some() && match_.maker_user_id == match_.user_id;
+        // The side of a match is the side of its maker order.
+        let side = match (maker, match_.side) {
+            (true, side) => side.into(),
+            (false, OrderSide::Buy) => Side::Sell,
+            (false, OrderSide::Sell) => Side::Buy,
+        };
+        let (buyer_order_id, seller_order_id) = match side {
+            Side::Buy if maker => (Some(match_.maker_order_id), Some(match_.taker_order_id)),
+            Side::Buy => (Some(match_.taker_order_id), Some(match_.maker_order_id)),
+            Side::Sell if maker => (Some(match_.taker_order_id), Some(match_.maker_order_id)),
+            Side::Sell => (Some(match_.maker_order_id), Some(match_.taker_order_id)),
+        };
+        Trade {
+            id: match_.trade_id.to_string(),
+            buyer_order_id,
+            seller_order_id,
+            market_pair: match_.product_id,
+            price: match_.price,
+            qty: match_.size,
+            fees: None,
+            side,
+            liquidity: Some(match maker {
+                true => Liquidity::Maker,
+                false => Liquidity::Taker,
+            }),
+            created_at: match_.time,
         }
     }
 }
@@ -109,45 +318,79 @@ impl From<Match> for EcbtWebSocketMessage {
     }
 }
 
+impl From<Ticker> for TickerUpdate {
+    fn from(ticker: Ticker) -> Self {
+        match ticker {
+            Ticker::Full {
+                product_id,
+                price,
+                best_bid,
+                best_ask,
+                open_24h,
+                time,
+                ..
+            } => TickerUpdate {
+                market_pair: product_id,
+                ticker: ecbt_exchange::model::Ticker {
+                    price: Some(price),
+                    price_24h: open_24h,
+                },
+                best_bid,
+                best_ask,
+                time: parse_time(&time),
+            },
+            Ticker::Empty {
+                product_id, price, ..
+            } => TickerUpdate {
+                market_pair: product_id,
+                ticker: ecbt_exchange::model::Ticker {
+                    price,
+                    price_24h: None,
+                },
+                best_bid: None,
+                best_ask: None,
+                time: None,
+            },
+        }
+    }
+}
+
+/// Parses an RFC 3339 time sent by Coinbase to milliseconds since the epoch.
+pub(crate) fn parse_time(time: &str) -> Option<u64> {
+    OffsetDateTime::parse(time, &Rfc3339)
+        .ok()
+        .map(|time| timestamp_mills(&time) as u64)
+}
+
 impl TryFrom<Level2> for EcbtWebSocketMessage {
     type Error = EcbtError;
 
+    /// Converts a snapshot to the book it holds, updates aren't books.
     fn try_from(level2: Level2) -> std::result::Result<Self, Self::Error> {
-        // FIXME: How can we get the update id?
-        let last_update_id = None;
-        let update_id = None;
-        Ok(match level2 {
-            Level2::Snapshot { asks, bids, .. } => {
+        match level2 {
+            Level2::Snapshot {
+                product_id,
+                asks,
+                bids,
+            } => {
                 let bids = bids.iter().map(|bid| bid.into()).collect();
                 let asks = asks.iter().map(|ask| ask.into()).collect();
                 let order_book_response = OrderBookResponse {
                     bids,
                     asks,
-                    update_id,
-                    last_update_id,
+                    update_id: None,
+                    last_update_id: None,
                 };
-                EcbtWebSocketMessage::OrderBook(order_book_response)
+                Ok(EcbtWebSocketMessage::OrderBook(OrderBookUpdate {
+                    market_pair: product_id,
+                    book: order_book_response,
+                }))
             }
-            Level2::L2update { changes, .. } => {
-                let bids = changes
-                    .iter()
-                    .filter(|change| change.side == OrderSide::Buy)
-                    .map(|change| change.into())
-                    .collect();
-                let asks = changes
-                    .iter()
-                    .filter(|change| change.side == OrderSide::Sell)
-                    .map(|change| change.into())
-                    .collect();
-                let order_book_response = OrderBookResponse {
-                    bids,
-                    asks,
-                    update_id,
-                    last_update_id,
-                };
-                EcbtWebSocketMessage::OrderBook(order_book_response)
-            }
-        })
+            Level2::L2update { product_id, .. } => Err(EcbtError::UnkownResponse(format!(
+                "The level2 update of {} isn't a full book",
+                product_id
+            ))),
+        }
     }
 }
 
@@ -0,0 +1,11 @@
+use serde::Deserialize;
+
+/// This struct represents the status of a currency sent on the `status` channel
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusCurrency {
+    pub id: String,
+    pub name: String,
+    pub status: String,
+    #[serde(default)]
+    pub status_message: Option<String>,
+}
@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+/// This struct represents the status of a product sent on the `status` channel
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+pub struct StatusProduct {
+    pub id: String,
+    pub base_currency: String,
+    pub quote_currency: String,
+    pub status: String,
+    #[serde(default)]
+    pub status_message: Option<String>,
+}
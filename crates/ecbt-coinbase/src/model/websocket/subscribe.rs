@@ -5,7 +5,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 /// This struct represents a subscribe
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Subscribe {
     #[serde(rename = "type")]
     pub _type: SubscribeCmd,
@@ -2,8 +2,9 @@ use serde::Deserialize;
 use serde::Serialize;
 
 /// This enum represents a subscribe command
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum SubscribeCmd {
     Subscribe,
+    Unsubscribe,
 }
@@ -23,6 +23,8 @@ pub enum Ticker {
         best_bid: Option<Decimal>,
         #[serde(with = "string_to_opt_decimal")]
         best_ask: Option<Decimal>,
+        #[serde(default, with = "string_to_opt_decimal")]
+        open_24h: Option<Decimal>,
     },
     Empty {
         sequence: usize,
@@ -33,10 +35,10 @@ pub enum Ticker {
 }
 
 impl Ticker {
-    pub fn price(&self) -> Decimal {
+    pub fn price(&self) -> Option<Decimal> {
         match self {
-            Ticker::Full { price, .. } => *price,
-            Ticker::Empty { price, .. } => price.expect("Couldn't get price."),
+            Ticker::Full { price, .. } => Some(*price),
+            Ticker::Empty { price, .. } => *price,
         }
     }
 
@@ -47,6 +49,13 @@ impl Ticker {
         }
     }
 
+    pub fn product_id(&self) -> &str {
+        match self {
+            Ticker::Full { product_id, .. } => product_id,
+            Ticker::Empty { product_id, .. } => product_id,
+        }
+    }
+
     pub fn sequence(&self) -> &usize {
         match self {
             Ticker::Full { sequence, .. } => sequence,
@@ -56,14 +65,14 @@ impl Ticker {
 
     pub fn bid(&self) -> Option<Decimal> {
         match self {
-            Ticker::Full { best_bid, .. } => Some(best_bid.expect("Couldn't get best bid.")),
+            Ticker::Full { best_bid, .. } => *best_bid,
             Ticker::Empty { .. } => None,
         }
     }
 
     pub fn ask(&self) -> Option<Decimal> {
         match self {
-            Ticker::Full { best_ask, .. } => Some(best_ask.expect("Couldn't get best ask.")),
+            Ticker::Full { best_ask, .. } => *best_ask,
             Ticker::Empty { .. } => None,
         }
     }
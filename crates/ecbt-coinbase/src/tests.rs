@@ -0,0 +1,573 @@
+use crate::client::stream::{acknowledges, channels, routed};
+use crate::client::{CoinbaseProtocol, Level2Book, Level3Book, Level3Sync, SubscriptionRouter};
+use crate::model::websocket::{CoinbaseSubscription, CoinbaseWebsocketMessage};
+use crate::model::{Book, BookRecordL3};
+use crate::{Coinbase, CoinbaseParameters};
+use async_trait::async_trait;
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::model::currency::Currency;
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::websocket::{EcbtWebSocketMessage, Subscription, WebSocketResponse};
+use ecbt_exchange::model::{
+    ConditionalOrderRequest, Interval, Liquidity, OcoOrderRequest, Order, OrderStatus,
+    OrderTrigger, OrderType, Side,
+};
+use ecbt_exchange::shared::Result;
+use ecbt_exchange::stream::{ProtocolMessage, SubscriptionProtocol};
+use ecbt_exchange::transport::{HttpRequest, HttpResponse, HttpTransport};
+use ecbt_exchange::{Exchange, ExchangeAccount};
+use futures::{stream, StreamExt};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::convert::TryFrom;
+use std::slice;
+use std::sync::{Arc, Mutex};
+
+/// Answers every request with an empty list.
+#[derive(Debug, Default)]
+struct RecordingTransport {
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+#[async_trait]
+impl HttpTransport for RecordingTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let url = request.url.clone();
+        self.requests.lock().unwrap().push(request);
+        Ok(HttpResponse {
+            url,
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: b"[]".to_vec(),
+        })
+    }
+}
+
+fn level2(json: &str) -> crate::model::websocket::Level2 {
+    match serde_json::from_str(json).unwrap() {
+        CoinbaseWebsocketMessage::Level2(level2) => level2,
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[test]
+fn level2_book_applies_snapshot_then_updates() {
+    let mut books = Level2Book::new();
+
+    let update =
+        level2(r#"{"type":"l2update","product_id":"BTC-USD","changes":[["buy","99.00","1"]]}"#);
+    assert!(books.apply(&update).is_none());
+
+    let snapshot = level2(
+        r#"{"type":"snapshot","product_id":"BTC-USD",
+            "bids":[["100.00","1.5"],["99.50","2"]],"asks":[["101.00","3"]]}"#,
+    );
+    let book = books.apply(&snapshot).unwrap();
+    assert_eq!(book.best_bid().unwrap().price, dec!(100));
+    assert_eq!(book.best_ask().unwrap().qty, dec!(3));
+
+    let update = level2(
+        r#"{"type":"l2update","product_id":"BTC-USD",
+            "changes":[["buy","100.00","0"],["sell","100.50","1"]]}"#,
+    );
+    let book = books.apply(&update).unwrap();
+    assert_eq!(book.best_bid().unwrap().price, dec!(99.5));
+    assert_eq!(book.qty_at(&Side::Sell, dec!(100.5)), dec!(1));
+    assert_eq!(book.vwap(&Side::Buy, dec!(2)), Some(dec!(100.75)));
+}
+
+#[tokio::test]
+async fn order_book_subscriptions_stream_full_books() {
+    let subscription = CoinbaseSubscription::from(Subscription::OrderBookUpdates(MarketPair(
+        Currency::BTC,
+        Currency::USD,
+    )));
+    let messages = [
+        r#"{"type":"l2update","product_id":"BTC-USD","changes":[["buy","99.00","1"]]}"#,
+        r#"{"type":"snapshot","product_id":"BTC-USD",
+            "bids":[["100.00","1.5"],["99.50","2"]],"asks":[["101.00","3"]]}"#,
+        r#"{"type":"l2update","product_id":"ETH-USD","changes":[["buy","9.00","1"]]}"#,
+        r#"{"type":"l2update","product_id":"BTC-USD",
+            "changes":[["buy","100.00","0"],["sell","100.50","1"]]}"#,
+    ];
+    let messages = stream::iter(messages.map(|json| Ok(message(json)))).boxed();
+
+    let books: Vec<_> = routed(messages, slice::from_ref(&subscription))
+        .map(|message| WebSocketResponse::try_from(message.unwrap()).unwrap())
+        .collect()
+        .await;
+    let books: Vec<_> = books
+        .into_iter()
+        .map(|message| match message {
+            WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(update)) => {
+                assert_eq!(update.market_pair, "BTC-USD");
+                update.book
+            }
+            message => panic!("Unexpected message: {:?}", message),
+        })
+        .collect();
+    assert_eq!(books.len(), 2);
+    assert_eq!((books[0].bids.len(), books[0].asks.len()), (2, 1));
+    // The update is applied to the snapshot, not sent as the changed levels alone.
+    assert_eq!((books[1].bids.len(), books[1].asks.len()), (1, 2));
+    assert_eq!(books[1].bids[0].price, dec!(99.5));
+    assert_eq!(books[1].asks[0].price, dec!(100.5));
+}
+
+fn generic(json: &str) -> EcbtWebSocketMessage {
+    match serde_json::from_str(json).unwrap() {
+        CoinbaseWebsocketMessage::Full(full) => full.into_generic().unwrap(),
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[test]
+fn user_channel_messages_are_order_updates_and_fills() {
+    let received = generic(
+        r#"{"type":"received","time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD",
+            "sequence":10,"order_id":"d50ec984-77a8-460a-b958-66f114b0de9b","size":"1.34",
+            "price":"502.1","side":"buy","order_type":"limit","client_oid":"my-order",
+            "user_id":"5844eceecf7e803e259d0365","profile_id":"765d1549"}"#,
+    );
+    match received {
+        EcbtWebSocketMessage::OrderUpdate(order) => {
+            assert_eq!(order.client_order_id.as_deref(), Some("my-order"));
+            assert_eq!(order.status, OrderStatus::New);
+            assert_eq!(order.order_type, OrderType::Limit);
+            assert_eq!((order.size, order.price), (dec!(1.34), Some(dec!(502.1))));
+            assert_eq!(order.created_at, Some(1415348367028));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    let done = generic(
+        r#"{"type":"done","time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD",
+            "sequence":10,"price":"200.2","order_id":"d50ec984-77a8-460a-b958-66f114b0de9b",
+            "reason":"canceled","side":"sell","remaining_size":"0.2",
+            "user_id":"5844eceecf7e803e259d0365","profile_id":"765d1549"}"#,
+    );
+    match done {
+        EcbtWebSocketMessage::OrderUpdate(order) => {
+            assert_eq!(order.status, OrderStatus::Canceled);
+            assert_eq!(order.side, Side::Sell);
+            assert_eq!(order.remaining, Some(dec!(0.2)));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    // The maker sold, so the authenticated taker bought.
+    let fill = generic(
+        r#"{"type":"match","trade_id":10,"sequence":50,"maker_order_id":"maker",
+            "taker_order_id":"taker","time":"2014-11-07T08:19:27.028459Z",
+            "product_id":"BTC-USD","size":"5.23512","price":"400.23","side":"sell",
+            "taker_user_id":"5844eceecf7e803e259d0365","user_id":"5844eceecf7e803e259d0365",
+            "taker_profile_id":"765d1549","profile_id":"765d1549"}"#,
+    );
+    match fill {
+        EcbtWebSocketMessage::Fill(trade) => {
+            assert_eq!(trade.side, Side::Buy);
+            assert_eq!(trade.liquidity, Some(Liquidity::Taker));
+            assert_eq!(trade.buyer_order_id.as_deref(), Some("taker"));
+            assert_eq!(trade.seller_order_id.as_deref(), Some("maker"));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    // Matches of other users are public trades.
+    let trade = generic(
+        r#"{"type":"match","trade_id":11,"sequence":51,"maker_order_id":"maker",
+            "taker_order_id":"taker","time":"2014-11-07T08:19:27.028459Z",
+            "product_id":"BTC-USD","size":"1","price":"400.23","side":"sell"}"#,
+    );
+    assert!(matches!(trade, EcbtWebSocketMessage::Trades(_)));
+
+    // Orders of other users, sent by the public full channel, are not order updates.
+    let public = full(
+        r#"{"type":"received","time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD",
+            "sequence":12,"order_id":"d50ec984-77a8-460a-b958-66f114b0de9b","size":"1.34",
+            "price":"502.1","side":"buy","order_type":"limit"}"#,
+    );
+    assert!(public.into_generic().is_none());
+}
+
+fn full(json: &str) -> crate::model::websocket::Full {
+    match serde_json::from_str(json).unwrap() {
+        CoinbaseWebsocketMessage::Full(full) => full,
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+fn open(sequence: usize, order_id: &str, side: &str, price: &str, size: &str) -> String {
+    format!(
+        r#"{{"type":"open","time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD",
+            "sequence":{sequence},"order_id":"{order_id}","price":"{price}",
+            "remaining_size":"{size}","side":"{side}"}}"#
+    )
+}
+
+fn record(order_id: &str, price: Decimal, size: Decimal) -> BookRecordL3 {
+    BookRecordL3 {
+        price,
+        size,
+        order_id: order_id.to_string(),
+    }
+}
+
+#[test]
+fn level3_book_tracks_queue_positions() {
+    let mut book = Level3Book::new();
+
+    // Messages received before the snapshot are buffered and replayed, older ones dropped.
+    let early = full(&open(10, "stale", "buy", "99.00", "5"));
+    assert_eq!(book.push(early), Level3Sync::NeedsSnapshot);
+    let buffered = full(&open(11, "c", "buy", "100.00", "3"));
+    assert_eq!(book.push(buffered), Level3Sync::NeedsSnapshot);
+
+    let snapshot = Book {
+        sequence: 10,
+        bids: vec![
+            record("a", dec!(100), dec!(1)),
+            record("b", dec!(100), dec!(2)),
+        ],
+        asks: vec![record("d", dec!(101), dec!(4))],
+    };
+    assert_eq!(book.apply_snapshot(&snapshot), Level3Sync::Synced);
+    assert_eq!(book.sequence(), Some(11));
+    assert!(book.queue_position("stale").is_none());
+
+    let position = book.queue_position("c").unwrap();
+    assert_eq!(position.side, Side::Buy);
+    assert_eq!(position.orders_ahead, 2);
+    assert_eq!(position.size_ahead, dec!(3));
+
+    let matched = full(
+        r#"{"type":"match","trade_id":1,"sequence":12,"maker_order_id":"a",
+            "taker_order_id":"t","time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD",
+            "size":"0.5","price":"100.00","side":"buy"}"#,
+    );
+    assert_eq!(book.push(matched), Level3Sync::Synced);
+    let changed = full(
+        r#"{"type":"change","time":"2014-11-07T08:19:27.028459Z","sequence":13,
+            "order_id":"b","product_id":"BTC-USD","new_size":"1.5","old_size":"2",
+            "price":"100.00","side":"buy"}"#,
+    );
+    assert_eq!(book.push(changed), Level3Sync::Synced);
+    let done = full(
+        r#"{"type":"done","time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD",
+            "sequence":14,"price":"100.00","order_id":"a","reason":"filled","side":"buy",
+            "remaining_size":"0"}"#,
+    );
+    assert_eq!(book.push(done), Level3Sync::Synced);
+
+    let position = book.queue_position("c").unwrap();
+    assert_eq!(position.orders_ahead, 1);
+    assert_eq!(position.size_ahead, dec!(1.5));
+
+    let level2 = book.level2();
+    assert_eq!(level2.update_id(), Some(14));
+    assert_eq!(level2.qty_at(&Side::Buy, dec!(100)), dec!(4.5));
+    assert_eq!(level2.best_ask().unwrap().price, dec!(101));
+
+    // A sequence gap invalidates the book until a new snapshot is applied.
+    let gap = full(&open(16, "e", "sell", "102.00", "1"));
+    assert_eq!(book.push(gap), Level3Sync::NeedsSnapshot);
+    assert!(!book.is_synced());
+    assert!(book.queue_position("c").is_none());
+}
+
+#[test]
+fn level3_book_moves_reopened_and_repriced_orders() {
+    let mut book = Level3Book::new();
+    let snapshot = Book {
+        sequence: 10,
+        bids: vec![
+            record("a", dec!(100), dec!(1)),
+            record("b", dec!(100), dec!(2)),
+        ],
+        asks: Vec::new(),
+    };
+    assert_eq!(book.apply_snapshot(&snapshot), Level3Sync::Synced);
+
+    // An order opened again replaces the one on the book.
+    let reopened = full(&open(11, "a", "buy", "100.00", "1"));
+    assert_eq!(book.push(reopened), Level3Sync::Synced);
+    assert_eq!(book.level2().qty_at(&Side::Buy, dec!(100)), dec!(3));
+    assert_eq!(book.queue_position("a").unwrap().orders_ahead, 1);
+
+    // A new price moves the order to the back of its new level.
+    let repriced = full(
+        r#"{"type":"change","time":"2014-11-07T08:19:27.028459Z","sequence":12,
+            "order_id":"b","product_id":"BTC-USD","new_size":"2","old_size":"2",
+            "price":"99.00","side":"buy"}"#,
+    );
+    assert_eq!(book.push(repriced), Level3Sync::Synced);
+    let position = book.queue_position("b").unwrap();
+    assert_eq!((position.price, position.orders_ahead), (dec!(99), 0));
+    assert_eq!(book.queue_position("a").unwrap().orders_ahead, 0);
+    assert_eq!(book.level2().qty_at(&Side::Buy, dec!(100)), dec!(1));
+    assert_eq!(book.level2().qty_at(&Side::Buy, dec!(99)), dec!(2));
+    assert_eq!(book.level2().update_id(), Some(12));
+}
+
+fn message(json: &str) -> CoinbaseWebsocketMessage {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn subscription_router_dispatches_by_channel_and_product() {
+    let subscriptions = [
+        CoinbaseSubscription::Level2Batch("BTC-USD".to_string()),
+        CoinbaseSubscription::Matches("ETH-USD".to_string()),
+        CoinbaseSubscription::Candles("BTC-USD".to_string(), Interval::OneMinute),
+    ];
+    let mut router = SubscriptionRouter::new(&subscriptions);
+
+    let btc_update =
+        message(r#"{"type":"l2update","product_id":"BTC-USD","changes":[["buy","99.00","1"]]}"#);
+    assert_eq!(router.apply(btc_update.clone()), vec![btc_update]);
+    let eth_update =
+        message(r#"{"type":"l2update","product_id":"ETH-USD","changes":[["buy","9.00","1"]]}"#);
+    assert!(router.apply(eth_update).is_empty());
+
+    let trade = |product_id: &str, trade_id: usize| {
+        message(&format!(
+            r#"{{"type":"match","trade_id":{trade_id},"sequence":{trade_id},
+                "maker_order_id":"m","taker_order_id":"t","time":"2014-11-07T08:19:27.028459Z",
+                "product_id":"{product_id}","size":"2","price":"400.23","side":"sell"}}"#
+        ))
+    };
+    let eth_trade = trade("ETH-USD", 1);
+    assert_eq!(router.apply(eth_trade.clone()), vec![eth_trade]);
+
+    // The matches of the candles product are replaced by its candle, and aggregated once.
+    match router.apply(trade("BTC-USD", 2)).as_slice() {
+        [CoinbaseWebsocketMessage::Candle(candle)] => {
+            assert_eq!(candle.market_pair, "BTC-USD");
+            assert_eq!(candle.candle.volume, dec!(2));
+        }
+        messages => panic!("Unexpected messages: {:?}", messages),
+    }
+    match router.apply(trade("BTC-USD", 2)).as_slice() {
+        [] => (),
+        messages => panic!("Unexpected messages: {:?}", messages),
+    }
+
+    let error = message(r#"{"type":"error","message":"Failed to subscribe"}"#);
+    assert_eq!(router.apply(error.clone()), vec![error]);
+}
+
+#[test]
+fn tickers_and_candles_are_converted() {
+    let generic = |message: CoinbaseWebsocketMessage| match WebSocketResponse::try_from(message) {
+        Ok(WebSocketResponse::Generic(message)) => message,
+        other => panic!("Unexpected response: {:?}", other),
+    };
+
+    let ticker = message(
+        r#"{"type":"ticker","trade_id":20153558,"sequence":3262786978,
+            "time":"2017-09-02T17:05:49.250000Z","product_id":"BTC-USD","price":"4388.01",
+            "side":"buy","last_size":"0.03","best_bid":"4388","best_ask":"4388.01",
+            "open_24h":"4200"}"#,
+    );
+    match generic(ticker) {
+        EcbtWebSocketMessage::Ticker(update) => {
+            assert_eq!(update.market_pair, "BTC-USD");
+            assert_eq!(update.ticker.price, Some(dec!(4388.01)));
+            assert_eq!(update.ticker.price_24h, Some(dec!(4200)));
+            assert_eq!(update.best_bid, Some(dec!(4388)));
+            assert_eq!(update.best_ask, Some(dec!(4388.01)));
+            assert_eq!(update.time, Some(1504371949250));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    let mut router = SubscriptionRouter::new(&[CoinbaseSubscription::Candles(
+        "BTC-USD".to_string(),
+        Interval::FiveMinutes,
+    )]);
+    let trade = message(
+        r#"{"type":"match","trade_id":2,"sequence":2,"maker_order_id":"m",
+            "taker_order_id":"t","time":"2014-11-07T08:19:27.028459Z","product_id":"BTC-USD",
+            "size":"0.5","price":"400.23","side":"sell"}"#,
+    );
+    match router.apply(trade).pop().map(generic) {
+        Some(EcbtWebSocketMessage::Candle(update)) => {
+            assert_eq!(update.market_pair, "BTC-USD");
+            assert_eq!(update.interval, Interval::FiveMinutes);
+            assert_eq!(update.candle.open, dec!(400.23));
+            assert_eq!(update.candle.close, dec!(400.23));
+            assert_eq!(update.candle.volume, dec!(0.5));
+            assert!(!update.closed);
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[test]
+fn live_subscriptions_use_subscribe_and_unsubscribe_messages() {
+    let protocol = CoinbaseProtocol::new(None);
+    let level2 = CoinbaseSubscription::Level2Batch("BTC-USD".to_string());
+
+    let request: serde_json::Value =
+        serde_json::from_str(&protocol.unsubscribe_request(1, &level2).unwrap()).unwrap();
+    assert_eq!(
+        request,
+        serde_json::json!({"type": "unsubscribe", "product_ids": [],
+            "channels": [{"name": "level2_batch", "product_ids": ["BTC-USD"]}]})
+    );
+    let candles = CoinbaseSubscription::Candles("BTC-USD".to_string(), Interval::OneMinute);
+    assert!(protocol.subscribe_request(2, &candles).is_err());
+    let user = CoinbaseSubscription::User("BTC-USD".to_string());
+    assert!(matches!(
+        protocol.subscribe_request(3, &user),
+        Err(EcbtError::NoApiKeySet())
+    ));
+
+    assert_eq!(
+        protocol
+            .parse(r#"{"type":"subscriptions","channels":[]}"#)
+            .unwrap(),
+        ProtocolMessage::Ack(None)
+    );
+    let update = r#"{"type":"l2update","product_id":"BTC-USD","changes":[["buy","99.00","1"]]}"#;
+    match protocol.parse(update).unwrap() {
+        ProtocolMessage::Data(topic, message) => {
+            assert!(protocol.routes(&level2, topic.as_deref(), &message));
+            let other = CoinbaseSubscription::Level2("ETH-USD".to_string());
+            assert!(!protocol.routes(&other, topic.as_deref(), &message));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[test]
+fn unknown_messages_and_subscription_events_are_typed() {
+    let message: CoinbaseWebsocketMessage =
+        serde_json::from_str(r#"{"type":"auction","product_id":"BTC-USD","sequence":3}"#).unwrap();
+    match message {
+        CoinbaseWebsocketMessage::Unknown { stream, payload } => {
+            assert_eq!(stream, "auction");
+            assert_eq!(payload["sequence"], 3);
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    let subscriptions = r#"{"type":"subscriptions","channels":[{"name":"level2",
+        "product_ids":["BTC-USD"]},{"name":"rfq_matches","product_ids":["BTC-USD"]}]}"#;
+    let message: CoinbaseWebsocketMessage = serde_json::from_str(subscriptions).unwrap();
+    match WebSocketResponse::try_from(message).unwrap() {
+        WebSocketResponse::Generic(EcbtWebSocketMessage::SubscriptionAck {
+            request_id,
+            channels,
+        }) => {
+            assert_eq!(request_id, None);
+            assert_eq!(channels, ["level2", "unknown"]);
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    let error = r#"{"type":"error","message":"Failed to subscribe","reason":"bad product"}"#;
+    let message: CoinbaseWebsocketMessage = serde_json::from_str(error).unwrap();
+    assert!(matches!(
+        WebSocketResponse::try_from(message).unwrap(),
+        WebSocketResponse::Generic(EcbtWebSocketMessage::SubscriptionError { message, .. })
+            if message == "Failed to subscribe"
+    ));
+}
+
+#[test]
+fn conditional_orders_are_stop_limit_orders() {
+    let req = ConditionalOrderRequest {
+        client_order_id: None,
+        market_pair: MarketPair(Currency::BTC, Currency::USD),
+        side: Side::Sell,
+        size: dec!(0.01),
+        trigger: OrderTrigger::TakeProfit {
+            trigger_price: dec!(30000),
+        },
+        limit_price: Some(dec!(29900)),
+    };
+    let order = serde_json::to_value(crate::model::OrderRequest::try_from(&req).unwrap()).unwrap();
+    assert_eq!(order["product_id"], "BTC-USD");
+    assert_eq!(order["type"], "limit");
+    // A sell taking profit fires when the price rises, like a stop entry.
+    assert_eq!(order["stop"], "entry");
+    assert_eq!(order["stop_price"], "30000");
+
+    let stop_market = ConditionalOrderRequest {
+        trigger: OrderTrigger::Stop {
+            trigger_price: dec!(25000),
+        },
+        limit_price: None,
+        ..req
+    };
+    assert!(matches!(
+        crate::model::OrderRequest::try_from(&stop_market),
+        Err(EcbtError::UnsupportedOrderType(OrderType::StopMarket))
+    ));
+
+    let order: crate::model::Order = serde_json::from_str(
+        r#"{"id":"d0c5340b-6d6c-49d9-b567-48c4bfca13d2","price":"24900.00",
+            "size":"0.01000000","product_id":"BTC-USD","side":"sell","stp":"dc","type":"limit",
+            "time_in_force":"GTC","post_only":false,"created_at":"2016-12-08T20:02:28.53864Z",
+            "fill_fees":"0.0000000000000000","filled_size":"0.00000000",
+            "executed_value":"0.0000000000000000","status":"active","settled":false,
+            "stop":"loss","stop_price":"25000.00"}"#,
+    )
+    .unwrap();
+    let order = Order::from(order);
+    assert_eq!(order.order_type, OrderType::StopLimit);
+    assert_eq!(order.status, OrderStatus::Active);
+}
+
+#[test]
+fn subscriptions_acknowledging_more_channels_are_accepted() {
+    let expected = channels(&[
+        CoinbaseSubscription::Ticker("BTC-USD".to_string()),
+        CoinbaseSubscription::Ticker("ETH-USD".to_string()),
+    ])
+    .unwrap();
+    let acknowledgement = |json| match message(json) {
+        CoinbaseWebsocketMessage::Subscriptions { channels } => channels,
+        message => panic!("Unexpected message: {:?}", message),
+    };
+
+    let reordered = acknowledgement(
+        r#"{"type":"subscriptions","channels":[{"name":"heartbeat","product_ids":["BTC-USD"]},
+            {"name":"ticker","product_ids":["ETH-USD","LTC-USD","BTC-USD"]}]}"#,
+    );
+    assert!(acknowledges(&reordered, &expected));
+
+    let missing = acknowledgement(
+        r#"{"type":"subscriptions","channels":[{"name":"ticker","product_ids":["BTC-USD"]}]}"#,
+    );
+    assert!(!acknowledges(&missing, &expected));
+}
+
+#[tokio::test]
+async fn oco_orders_are_unsupported_as_open_orders_hold_their_funds() {
+    let http = Arc::new(RecordingTransport::default());
+    let coinbase = Coinbase::new(CoinbaseParameters::sandbox().with_http_transport(http.clone()))
+        .await
+        .unwrap();
+    let sent = http.requests.lock().unwrap().len();
+
+    let oco = coinbase
+        .place_oco_order(&OcoOrderRequest {
+            client_order_id: None,
+            market_pair: MarketPair(Currency::BTC, Currency::USD),
+            side: Side::Sell,
+            size: dec!(0.01),
+            limit_price: dec!(30000),
+            stop_price: dec!(25000),
+            stop_limit_price: Some(dec!(24900)),
+        })
+        .await;
+    assert!(matches!(
+        oco,
+        Err(EcbtError::UnsupportedOrderType(OrderType::StopLimit))
+    ));
+    assert_eq!(http.requests.lock().unwrap().len(), sent);
+}
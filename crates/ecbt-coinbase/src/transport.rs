@@ -1,53 +1,57 @@
 use super::shared::Result;
 use crate::CoinbaseContentError;
 use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::transport::{HttpRequest, HttpResponse, HttpTransport};
 use hmac::{Hmac, Mac};
 use reqwest::header;
-use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use reqwest::{Method, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use sha2::Sha256;
+use std::sync::Arc;
 use std::time::SystemTime;
 use url::Url;
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Signs `message` with the base64 encoded `api_secret`, as Coinbase expects for REST requests
+/// and websocket subscriptions.
+pub(crate) fn sign(api_secret: &str, message: &str) -> String {
+    let key = base64::decode(api_secret).expect("Failed to base64 decode Coinbase API secret");
+    let mut mac = HmacSha256::new_from_slice(&key).expect("Couldn't create HMAC-SHA256.");
+    mac.update(message.as_bytes());
+    base64::encode(mac.finalize().into_bytes())
+}
+
 #[derive(Clone, Debug)]
 pub struct Transport {
     api_secret: Option<String>,
-    client: reqwest::Client,
+    http: Arc<dyn HttpTransport>,
+    headers: header::HeaderMap<header::HeaderValue>,
     base_url: String,
 }
 
 impl Transport {
-    pub fn new(sandbox: bool) -> Result<Self> {
-        let default_headers = Transport::default_headers();
-
-        let client = reqwest::Client::builder()
-            .default_headers(default_headers)
-            .build()?;
-
+    pub fn new(http: Arc<dyn HttpTransport>, sandbox: bool) -> Result<Self> {
         Ok(Transport {
-            client,
+            http,
+            headers: Transport::default_headers(),
             api_secret: None,
             base_url: Transport::get_base_url(sandbox),
         })
     }
 
     pub fn with_credential(
+        http: Arc<dyn HttpTransport>,
         api_key: &str,
         api_secret: &str,
         passphrase: &str,
         sandbox: bool,
     ) -> Result<Self> {
-        let default_headers = Transport::default_headers_with_auth(api_key, passphrase);
-        let client = reqwest::Client::builder()
-            .default_headers(default_headers)
-            .build()?;
-
         Ok(Transport {
             api_secret: Some(String::from(api_secret)),
-            client,
+            http,
+            headers: Transport::default_headers_with_auth(api_key, passphrase),
             base_url: Transport::get_base_url(sandbox),
         })
     }
@@ -103,9 +107,10 @@ impl Transport {
         S: Serialize,
     {
         let url = self.get_url(endpoint, params)?;
-        let request = self.client.get(url).send().await?;
+        let request = HttpRequest::new(Method::GET, url).with_headers(self.headers.clone());
+        let resp = self.http.send(request).await?;
 
-        self.response_handler(request).await
+        self.response_handler(resp)
     }
 
     pub async fn signed_get<O, S>(&self, endpoint: &str, params: Option<&S>) -> Result<O>
@@ -115,11 +120,9 @@ impl Transport {
     {
         let url = self.get_url(endpoint, params)?;
 
-        let request = self.build_request::<()>(url, Method::GET, None)?;
+        let resp = self.send_signed::<()>(url, Method::GET, None).await?;
 
-        let resp = request.send().await?;
-
-        self.response_handler(resp).await
+        self.response_handler(resp)
     }
 
     pub async fn signed_post<O, P, D>(
@@ -131,13 +134,12 @@ impl Transport {
     where
         O: DeserializeOwned,
         P: Serialize,
-        D: Serialize,
+        D: Serialize + Sync,
     {
         let url = self.get_url(endpoint, params)?;
-        let request = self.build_request(url, Method::POST, data)?;
-        let resp = request.send().await?;
+        let resp = self.send_signed(url, Method::POST, data).await?;
 
-        self.response_handler(resp).await
+        self.response_handler(resp)
     }
 
     pub async fn signed_delete<O, P, D>(
@@ -149,13 +151,28 @@ impl Transport {
     where
         O: DeserializeOwned,
         P: Serialize,
-        D: Serialize + std::fmt::Debug,
+        D: Serialize + Sync + std::fmt::Debug,
     {
         let url = self.get_url(endpoint, params)?;
-        let request = self.build_request(url, Method::DELETE, data)?;
-        let request = request.send().await?;
+        let resp = self.send_signed(url, Method::DELETE, data).await?;
 
-        self.response_handler(request).await
+        self.response_handler(resp)
+    }
+
+    /// Sends a request signed with the current timestamp, which is signed again if the
+    /// request is retried.
+    async fn send_signed<D>(
+        &self,
+        url: Url,
+        method: Method,
+        data: Option<&D>,
+    ) -> Result<HttpResponse>
+    where
+        D: Serialize + Sync,
+    {
+        self.http
+            .send_with(&|| self.build_request(url.clone(), method.clone(), data))
+            .await
     }
 
     pub fn build_request<D>(
@@ -163,7 +180,7 @@ impl Transport {
         url: Url,
         method: Method,
         data: Option<&D>,
-    ) -> Result<RequestBuilder>
+    ) -> Result<HttpRequest>
     where
         D: Serialize,
     {
@@ -174,14 +191,21 @@ impl Transport {
 
         let signature = self.signature(&url, since_epoch_seconds, &method, data)?;
 
-        let mut request = self
-            .client
-            .request(method, url)
-            .header("CB-ACCESS-SIGN", signature)
-            .header("CB-ACCESS-TIMESTAMP", since_epoch_seconds.to_string());
+        let mut request = HttpRequest::new(method, url)
+            .with_headers(self.headers.clone())
+            .with_header("CB-ACCESS-SIGN", header::HeaderValue::from_str(&signature)?)
+            .with_header(
+                "CB-ACCESS-TIMESTAMP",
+                header::HeaderValue::from_str(&since_epoch_seconds.to_string())?,
+            );
 
-        request = if data.is_some() {
-            request.json(&data)
+        request = if let Some(data) = data {
+            request
+                .with_header(
+                    header::CONTENT_TYPE,
+                    header::HeaderValue::from_static("application/json"),
+                )
+                .with_body(serde_json::to_vec(data)?)
         } else {
             request
         };
@@ -219,9 +243,6 @@ impl Transport {
             None => Err(EcbtError::NoApiKeySet()),
             Some(v) => Ok(v),
         }?;
-        let key = base64::decode(api_secret).expect("Failed to base64 decode Coinbase API secret");
-        let mut mac = HmacSha256::new_from_slice(&key).expect("Couldn't create HMAC-SHA256.");
-
         let prefix: String = timestamp.to_string() + method.as_str();
 
         let body = if body.is_some() {
@@ -236,36 +257,34 @@ impl Transport {
 
         let sign_message = format!("{}{}{}", prefix, path, body);
 
-        mac.update(sign_message.as_bytes());
-        let signature = base64::encode(mac.finalize().into_bytes());
-        Ok(signature)
+        Ok(sign(api_secret, &sign_message))
     }
 
-    async fn response_handler<O>(&self, response: Response) -> Result<O>
+    fn response_handler<O>(&self, response: HttpResponse) -> Result<O>
     where
         O: DeserializeOwned,
     {
-        match response.status() {
+        match response.status {
             StatusCode::OK => {
-                let text = response.text().await?;
+                let text = response.text();
                 serde_json::from_str::<O>(&text).map_err(move |err| {
                     EcbtError::NotParsableResponse(format!("Error:{} Payload: {}", err, text))
                 })
             }
 
-            StatusCode::INTERNAL_SERVER_ERROR => Err(EcbtError::InternalServerError()),
-            StatusCode::SERVICE_UNAVAILABLE => Err(EcbtError::ServiceUnavailable()),
-            StatusCode::UNAUTHORIZED => {
-                let text = response.text().await?;
-                println!("{}", text);
-                Err(EcbtError::Unauthorized())
-            }
-            StatusCode::BAD_REQUEST => {
-                let error: CoinbaseContentError = response.json().await?;
-                Err(EcbtError::Generic(Box::new(error)))
-            }
+            StatusCode::INTERNAL_SERVER_ERROR => Err(EcbtError::InternalServerError(
+                response.error_context(response.text()),
+            )),
+            StatusCode::SERVICE_UNAVAILABLE => Err(EcbtError::ServiceUnavailable(
+                response.error_context(response.text()),
+            )),
+            status if status.is_client_error() => match response.json::<CoinbaseContentError>() {
+                Ok(error) => Err(error.into_error(&response)),
+                Err(_) if status == StatusCode::UNAUTHORIZED => Err(EcbtError::Unauthorized()),
+                Err(_) => Err(EcbtError::Exchange(response.error_context(response.text()))),
+            },
             s => {
-                let text = response.text().await?;
+                let text = response.text();
                 Err(EcbtError::UnkownResponse(format!(
                     "Received response: {:?}, value: {}",
                     s, text
@@ -0,0 +1,7 @@
+//! Locally maintained order books built from exchange snapshots and updates.
+mod order_book;
+#[cfg(test)]
+mod tests;
+
+pub use crate::shared;
+pub use order_book::OrderBook;
@@ -0,0 +1,177 @@
+use crate::model::{AskBid, OrderBookResponse, Side};
+use rust_decimal::prelude::Decimal;
+use std::collections::BTreeMap;
+
+/// This struct represents a full order book, aggregated by price level.
+///
+/// Exchange adapters keep it in sync with their snapshot and update messages. Wherever a
+/// `Side` selects a side of the book, `Side::Buy` means the bids and `Side::Sell` the asks.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct OrderBook {
+    bids: BTreeMap<Decimal, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+    update_id: Option<u64>,
+}
+
+impl OrderBook {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Id of the last snapshot or update applied to the book.
+    pub fn update_id(&self) -> Option<u64> {
+        self.update_id
+    }
+
+    pub fn set_update_id(&mut self, update_id: Option<u64>) {
+        self.update_id = update_id;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    /// Removes every level.
+    pub fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.update_id = None;
+    }
+
+    /// Replaces the whole book with a snapshot.
+    pub fn replace(&mut self, snapshot: &OrderBookResponse) {
+        self.clear();
+        for level in &snapshot.bids {
+            self.update(&Side::Buy, level.price, level.qty);
+        }
+        for level in &snapshot.asks {
+            self.update(&Side::Sell, level.price, level.qty);
+        }
+        self.update_id = snapshot.update_id;
+    }
+
+    /// Sets the quantity of a level, removing it when `qty` is zero.
+    pub fn update(&mut self, side: &Side, price: Decimal, qty: Decimal) {
+        let levels = self.levels_mut(side);
+        if qty.is_zero() {
+            levels.remove(&price);
+        } else {
+            levels.insert(price, qty);
+        }
+    }
+
+    pub fn best_bid(&self) -> Option<AskBid> {
+        self.bids.iter().next_back().map(|(price, qty)| AskBid {
+            price: *price,
+            qty: *qty,
+        })
+    }
+
+    pub fn best_ask(&self) -> Option<AskBid> {
+        self.asks.iter().next().map(|(price, qty)| AskBid {
+            price: *price,
+            qty: *qty,
+        })
+    }
+
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+
+    pub fn mid_price(&self) -> Option<Decimal> {
+        Some((self.best_ask()?.price + self.best_bid()?.price) / Decimal::TWO)
+    }
+
+    /// Quantity resting at exactly `price`, zero if there is no such level.
+    pub fn qty_at(&self, side: &Side, price: Decimal) -> Decimal {
+        self.levels(side).get(&price).copied().unwrap_or_default()
+    }
+
+    /// Best `depth` bids, best first.
+    pub fn bids(&self, depth: usize) -> Vec<AskBid> {
+        self.bids
+            .iter()
+            .rev()
+            .take(depth)
+            .map(|(price, qty)| AskBid {
+                price: *price,
+                qty: *qty,
+            })
+            .collect()
+    }
+
+    /// Best `depth` asks, best first.
+    pub fn asks(&self, depth: usize) -> Vec<AskBid> {
+        self.asks
+            .iter()
+            .take(depth)
+            .map(|(price, qty)| AskBid {
+                price: *price,
+                qty: *qty,
+            })
+            .collect()
+    }
+
+    /// Volume-weighted average price of taking `size` from the book with an order on `side`,
+    /// i.e. a buy walks the asks. `None` if the book cannot fill `size`.
+    pub fn vwap(&self, side: &Side, size: Decimal) -> Option<Decimal> {
+        if size <= Decimal::ZERO {
+            return None;
+        }
+        let levels: Box<dyn Iterator<Item = (&Decimal, &Decimal)>> = match side {
+            Side::Buy => Box::new(self.asks.iter()),
+            Side::Sell => Box::new(self.bids.iter().rev()),
+        };
+
+        let mut left = size;
+        let mut notional = Decimal::ZERO;
+        for (price, qty) in levels {
+            let taken = left.min(*qty);
+            notional += price * taken;
+            left -= taken;
+            if left.is_zero() {
+                return Some(notional / size);
+            }
+        }
+        None
+    }
+
+    /// The best `depth` levels of each side, or all of them.
+    pub fn snapshot(&self, depth: Option<usize>) -> OrderBookResponse {
+        let depth = depth.unwrap_or(usize::MAX);
+        OrderBookResponse {
+            update_id: self.update_id,
+            last_update_id: None,
+            bids: self.bids(depth),
+            asks: self.asks(depth),
+        }
+    }
+
+    fn levels(&self, side: &Side) -> &BTreeMap<Decimal, Decimal> {
+        match side {
+            Side::Buy => &self.bids,
+            Side::Sell => &self.asks,
+        }
+    }
+
+    fn levels_mut(&mut self, side: &Side) -> &mut BTreeMap<Decimal, Decimal> {
+        match side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        }
+    }
+}
+
+impl From<&OrderBookResponse> for OrderBook {
+    fn from(snapshot: &OrderBookResponse) -> Self {
+        let mut book = OrderBook::new();
+        book.replace(snapshot);
+        book
+    }
+}
+
+impl From<&OrderBook> for OrderBookResponse {
+    fn from(book: &OrderBook) -> Self {
+        book.snapshot(None)
+    }
+}
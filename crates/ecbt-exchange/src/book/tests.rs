@@ -0,0 +1,66 @@
+use super::OrderBook;
+use crate::model::{AskBid, OrderBookResponse, Side};
+use rust_decimal_macros::dec;
+
+fn book() -> OrderBook {
+    OrderBook::from(&OrderBookResponse {
+        update_id: Some(7),
+        last_update_id: None,
+        bids: vec![
+            AskBid {
+                price: dec!(99),
+                qty: dec!(1),
+            },
+            AskBid {
+                price: dec!(98),
+                qty: dec!(2),
+            },
+        ],
+        asks: vec![
+            AskBid {
+                price: dec!(101),
+                qty: dec!(1),
+            },
+            AskBid {
+                price: dec!(102),
+                qty: dec!(3),
+            },
+        ],
+    })
+}
+
+#[test]
+fn best_levels_spread_and_mid() {
+    let book = book();
+
+    assert_eq!(book.update_id(), Some(7));
+    assert_eq!(book.best_bid().unwrap().price, dec!(99));
+    assert_eq!(book.best_ask().unwrap().price, dec!(101));
+    assert_eq!(book.spread(), Some(dec!(2)));
+    assert_eq!(book.mid_price(), Some(dec!(100)));
+}
+
+#[test]
+fn updates_set_and_remove_levels() {
+    let mut book = book();
+
+    book.update(&Side::Buy, dec!(100), dec!(5));
+    book.update(&Side::Sell, dec!(101), dec!(0));
+
+    assert_eq!(book.qty_at(&Side::Buy, dec!(100)), dec!(5));
+    assert_eq!(book.qty_at(&Side::Sell, dec!(101)), dec!(0));
+    assert_eq!(book.best_ask().unwrap().price, dec!(102));
+    assert_eq!(
+        book.bids(2).iter().map(|l| l.price).collect::<Vec<_>>(),
+        vec![dec!(100), dec!(99)]
+    );
+}
+
+#[test]
+fn vwap_walks_the_opposite_side() {
+    let book = book();
+
+    assert_eq!(book.vwap(&Side::Buy, dec!(2)), Some(dec!(101.5)));
+    assert_eq!(book.vwap(&Side::Sell, dec!(3)), Some(dec!(295) / dec!(3)));
+    assert_eq!(book.vwap(&Side::Buy, dec!(10)), None);
+}
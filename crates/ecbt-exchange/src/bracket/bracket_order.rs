@@ -0,0 +1,182 @@
+use super::BracketStatus;
+use crate::model::{
+    BracketOrderRequest, CancelOrderRequest, GetOrderRequest, OcoOrder, Order, OrderStatus, Side,
+};
+use crate::shared::Result;
+use crate::ExchangeAccount;
+use rust_decimal::prelude::Decimal;
+
+/// This struct represents a bracket order and the orders placed for it so far.
+///
+/// Nothing watches the orders in the background: call [`BracketOrder::update`] whenever one of
+/// them may have changed, e.g. on an order update of the account stream or periodically.
+#[derive(Clone, Debug)]
+pub struct BracketOrder {
+    pub request: BracketOrderRequest,
+    pub entry: Order,
+    pub exits: Option<OcoOrder>,
+    pub status: BracketStatus,
+}
+
+impl BracketOrder {
+    /// Places the entry order, and the exits right away if it filled.
+    pub async fn place<E>(exchange: &E, req: &BracketOrderRequest) -> Result<Self>
+    where
+        E: ExchangeAccount + Sync + ?Sized,
+    {
+        let entry = match (req.limit_entry(), &req.side) {
+            (Some(limit), Side::Buy) => exchange.limit_buy(&limit).await?,
+            (Some(limit), Side::Sell) => exchange.limit_sell(&limit).await?,
+            (None, Side::Buy) => exchange.market_buy(&req.market_entry()).await?,
+            (None, Side::Sell) => exchange.market_sell(&req.market_entry()).await?,
+        };
+        let mut bracket = Self {
+            request: req.clone(),
+            entry,
+            exits: None,
+            status: BracketStatus::Entering,
+        };
+        bracket.advance(exchange).await?;
+        Ok(bracket)
+    }
+
+    /// Fetches the orders of the bracket, placing the exits once the entry is done filling and
+    /// canceling the other exit once one fills where the exchange does not do it.
+    pub async fn update<E>(&mut self, exchange: &E) -> Result<BracketStatus>
+    where
+        E: ExchangeAccount + Sync + ?Sized,
+    {
+        match &mut self.exits {
+            None if self.status == BracketStatus::Entering => {
+                self.entry = exchange.get_order(&get_request(&self.entry)).await?;
+            }
+            Some(exits) if self.status == BracketStatus::Exiting => {
+                exits.limit_order = exchange.get_order(&get_request(&exits.limit_order)).await?;
+                exits.stop_order = exchange
+                    .get_conditional_order(&get_request(&exits.stop_order))
+                    .await?;
+            }
+            _ => return Ok(self.status),
+        }
+        self.advance(exchange).await?;
+        Ok(self.status)
+    }
+
+    /// Cancels the open orders of the bracket. Exits are not placed for what a canceled entry
+    /// may have filled.
+    pub async fn cancel<E>(&mut self, exchange: &E) -> Result<BracketStatus>
+    where
+        E: ExchangeAccount + Sync + ?Sized,
+    {
+        let exits = match &mut self.exits {
+            Some(exits) => exits,
+            None => {
+                if is_open(&self.entry) {
+                    exchange.cancel_order(&cancel_request(&self.entry)).await?;
+                    self.entry.status = OrderStatus::Canceled;
+                }
+                self.status = BracketStatus::Canceled;
+                return Ok(self.status);
+            }
+        };
+        if is_open(&exits.limit_order) {
+            exchange
+                .cancel_order(&cancel_request(&exits.limit_order))
+                .await?;
+            exits.limit_order.status = OrderStatus::Canceled;
+        }
+        if is_open(&exits.stop_order) {
+            // An order list is canceled with any of its orders.
+            if exits.order_list_id.is_none() {
+                exchange
+                    .cancel_conditional_order(&cancel_request(&exits.stop_order))
+                    .await?;
+            }
+            exits.stop_order.status = OrderStatus::Canceled;
+        }
+        self.advance(exchange).await?;
+        Ok(self.status)
+    }
+
+    async fn advance<E>(&mut self, exchange: &E) -> Result<()>
+    where
+        E: ExchangeAccount + Sync + ?Sized,
+    {
+        if self.exits.is_none() {
+            if is_open(&self.entry) {
+                self.status = BracketStatus::Entering;
+                return Ok(());
+            }
+            let filled = filled(&self.entry);
+            if filled.is_zero() {
+                self.status = BracketStatus::Canceled;
+                return Ok(());
+            }
+            let exits = exchange
+                .place_oco_order(&self.request.exits(filled))
+                .await?;
+            self.exits = Some(exits);
+        }
+
+        let exits = self.exits.as_mut().expect("Exits were just placed.");
+        if exits.order_list_id.is_none() {
+            if is_filling(&exits.limit_order) && is_open(&exits.stop_order) {
+                exchange
+                    .cancel_conditional_order(&cancel_request(&exits.stop_order))
+                    .await?;
+                exits.stop_order.status = OrderStatus::Canceled;
+            } else if is_filling(&exits.stop_order) && is_open(&exits.limit_order) {
+                exchange
+                    .cancel_order(&cancel_request(&exits.limit_order))
+                    .await?;
+                exits.limit_order.status = OrderStatus::Canceled;
+            }
+        }
+        self.status = if is_open(&exits.limit_order) || is_open(&exits.stop_order) {
+            BracketStatus::Exiting
+        } else if filled(&exits.limit_order).is_zero() && filled(&exits.stop_order).is_zero() {
+            BracketStatus::Canceled
+        } else {
+            BracketStatus::Closed
+        };
+        Ok(())
+    }
+}
+
+fn get_request(order: &Order) -> GetOrderRequest {
+    GetOrderRequest {
+        id: order.id.clone(),
+        market_pair: Some(order.market_pair.clone()),
+    }
+}
+
+fn cancel_request(order: &Order) -> CancelOrderRequest {
+    CancelOrderRequest {
+        id: order.id.clone(),
+        market_pair: Some(order.market_pair.clone()),
+    }
+}
+
+/// Whether the order may still fill.
+fn is_open(order: &Order) -> bool {
+    !matches!(
+        order.status,
+        OrderStatus::Filled | OrderStatus::Canceled | OrderStatus::Rejected | OrderStatus::Expired
+    )
+}
+
+fn is_filling(order: &Order) -> bool {
+    matches!(
+        order.status,
+        OrderStatus::PartiallyFilled | OrderStatus::Filled
+    )
+}
+
+/// Size the order filled, all of it when a filled order does not tell its remaining size.
+fn filled(order: &Order) -> Decimal {
+    match (order.remaining, &order.status) {
+        (Some(remaining), _) => order.size - remaining,
+        (None, OrderStatus::Filled) => order.size,
+        (None, _) => Decimal::ZERO,
+    }
+}
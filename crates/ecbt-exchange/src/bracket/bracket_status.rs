@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This enum represents the stage of a bracket order
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BracketStatus {
+    /// The entry order has not filled yet.
+    Entering,
+    /// The take profit and stop loss are open.
+    Exiting,
+    /// The position was closed by the take profit or the stop loss.
+    Closed,
+    /// Nothing was bought or sold, or the exits were canceled without filling.
+    Canceled,
+}
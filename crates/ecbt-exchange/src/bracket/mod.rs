@@ -0,0 +1,15 @@
+//! Bracket orders: an entry order followed by a take profit and a stop loss.
+//!
+//! The take profit and stop loss are placed with [`ExchangeAccount::place_oco_order`] once the
+//! entry filled. Exchanges with one-cancels-the-other orders cancel the other exit themselves,
+//! elsewhere [`BracketOrder::update`] cancels it once it sees one of them fill.
+//!
+//! [`ExchangeAccount::place_oco_order`]: crate::ExchangeAccount::place_oco_order
+mod bracket_order;
+mod bracket_status;
+#[cfg(test)]
+mod tests;
+
+pub use crate::shared;
+pub use bracket_order::BracketOrder;
+pub use bracket_status::BracketStatus;
@@ -0,0 +1,218 @@
+use super::{BracketOrder, BracketStatus};
+use crate::errors::{EcbtError, MissingImplementationContent};
+use crate::model::market_pair::{Currency, MarketPair};
+use crate::model::{
+    Balance, BracketOrderRequest, CancelAllOrdersRequest, CancelOrderRequest,
+    ConditionalOrderRequest, EcbtOrderRequest, GetOrderHistoryRequest, GetOrderRequest,
+    OpenMarketOrderRequest, Order, OrderCanceled, OrderStatus, OrderType, Paginator, Side, Trade,
+    TradeHistoryRequest,
+};
+use crate::shared::Result;
+use crate::ExchangeAccount;
+use async_trait::async_trait;
+use rust_decimal::prelude::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Mutex;
+
+/// Fails the calls a bracket order doesn't make.
+fn missing<T>(method: &str) -> Result<T> {
+    Err(EcbtError::MissingImplementation(
+        MissingImplementationContent {
+            message: format!("{} isn't used by bracket orders", method),
+        },
+    ))
+}
+
+/// Keeps the orders it is sent, which only fill when a test says so.
+#[derive(Debug, Default)]
+struct Account {
+    orders: Mutex<Vec<Order>>,
+}
+
+impl Account {
+    fn add(&self, side: Side, order_type: OrderType, size: Decimal, price: Decimal) -> Order {
+        let mut orders = self.orders.lock().unwrap();
+        let order = Order {
+            id: orders.len().to_string(),
+            market_pair: "BTCUSDT".to_string(),
+            client_order_id: None,
+            created_at: None,
+            order_type,
+            side,
+            status: OrderStatus::New,
+            size,
+            price: Some(price),
+            remaining: Some(size),
+            trades: Vec::new(),
+        };
+        orders.push(order.clone());
+        order
+    }
+
+    fn fill(&self, id: &str, remaining: Decimal) {
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders.iter_mut().find(|order| order.id == id).unwrap();
+        order.remaining = Some(remaining);
+        order.status = if remaining.is_zero() {
+            OrderStatus::Filled
+        } else {
+            OrderStatus::PartiallyFilled
+        };
+    }
+
+    fn status(&self, id: &str) -> OrderStatus {
+        let orders = self.orders.lock().unwrap();
+        orders
+            .iter()
+            .find(|order| order.id == id)
+            .unwrap()
+            .status
+            .clone()
+    }
+}
+
+#[async_trait]
+impl ExchangeAccount for Account {
+    async fn limit_buy(&self, req: &EcbtOrderRequest) -> Result<Order> {
+        Ok(self.add(Side::Buy, OrderType::Limit, req.size, req.price))
+    }
+
+    async fn limit_sell(&self, req: &EcbtOrderRequest) -> Result<Order> {
+        Ok(self.add(Side::Sell, OrderType::Limit, req.size, req.price))
+    }
+
+    async fn market_buy(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
+        let order = self.add(Side::Buy, OrderType::Market, req.size, dec!(100));
+        self.fill(&order.id, Decimal::ZERO);
+        self.get_order(&GetOrderRequest {
+            id: order.id,
+            market_pair: None,
+        })
+        .await
+    }
+
+    async fn market_sell(&self, _req: &OpenMarketOrderRequest) -> Result<Order> {
+        missing("market_sell")
+    }
+
+    async fn cancel_order(&self, req: &CancelOrderRequest) -> Result<OrderCanceled> {
+        let mut orders = self.orders.lock().unwrap();
+        let order = orders.iter_mut().find(|order| order.id == req.id).unwrap();
+        order.status = OrderStatus::Canceled;
+        Ok(OrderCanceled::from(req.id.clone()))
+    }
+
+    async fn cancel_all_orders(&self, _req: &CancelAllOrdersRequest) -> Result<Vec<OrderCanceled>> {
+        missing("cancel_all_orders")
+    }
+
+    async fn get_all_open_orders(&self) -> Result<Vec<Order>> {
+        missing("get_all_open_orders")
+    }
+
+    async fn get_order_history(&self, _req: &GetOrderHistoryRequest) -> Result<Vec<Order>> {
+        missing("get_order_history")
+    }
+
+    async fn get_trade_history(&self, _req: &TradeHistoryRequest) -> Result<Vec<Trade>> {
+        missing("get_trade_history")
+    }
+
+    async fn get_account_balances(&self, _paginator: Option<Paginator>) -> Result<Vec<Balance>> {
+        missing("get_account_balances")
+    }
+
+    async fn get_order(&self, req: &GetOrderRequest) -> Result<Order> {
+        let orders = self.orders.lock().unwrap();
+        Ok(orders
+            .iter()
+            .find(|order| order.id == req.id)
+            .unwrap()
+            .clone())
+    }
+
+    async fn place_conditional_order(&self, req: &ConditionalOrderRequest) -> Result<Order> {
+        Ok(self.add(
+            req.side.clone(),
+            req.order_type(),
+            req.size,
+            req.limit_price.unwrap_or_default(),
+        ))
+    }
+}
+
+fn bracket(entry_price: Option<Decimal>) -> BracketOrderRequest {
+    BracketOrderRequest {
+        client_order_id: None,
+        market_pair: MarketPair(Currency::BTC, Currency::USDT),
+        side: Side::Buy,
+        size: dec!(1),
+        entry_price,
+        take_profit_price: dec!(110),
+        stop_price: dec!(95),
+        stop_limit_price: None,
+    }
+}
+
+#[tokio::test]
+async fn exits_are_placed_after_the_entry_and_cancel_each_other() {
+    let account = Account::default();
+    let mut bracket = BracketOrder::place(&account, &bracket(Some(dec!(100))))
+        .await
+        .unwrap();
+    assert_eq!(bracket.status, BracketStatus::Entering);
+    assert_eq!(bracket.entry.order_type, OrderType::Limit);
+
+    account.fill(&bracket.entry.id, dec!(0.25));
+    assert_eq!(
+        bracket.update(&account).await.unwrap(),
+        BracketStatus::Entering
+    );
+    account.fill(&bracket.entry.id, Decimal::ZERO);
+    assert_eq!(
+        bracket.update(&account).await.unwrap(),
+        BracketStatus::Exiting
+    );
+
+    let exits = bracket.exits.clone().unwrap();
+    assert_eq!(exits.order_list_id, None);
+    assert_eq!(exits.limit_order.side, Side::Sell);
+    assert_eq!(exits.limit_order.price, Some(dec!(110)));
+    assert_eq!(exits.stop_order.side, Side::Sell);
+    assert_eq!(exits.stop_order.order_type, OrderType::StopMarket);
+    assert_eq!(exits.stop_order.size, dec!(1));
+
+    // The stop loss is canceled as soon as the take profit starts filling.
+    account.fill(&exits.limit_order.id, dec!(0.5));
+    assert_eq!(
+        bracket.update(&account).await.unwrap(),
+        BracketStatus::Exiting
+    );
+    assert_eq!(account.status(&exits.stop_order.id), OrderStatus::Canceled);
+
+    account.fill(&exits.limit_order.id, Decimal::ZERO);
+    assert_eq!(
+        bracket.update(&account).await.unwrap(),
+        BracketStatus::Closed
+    );
+    assert_eq!(
+        bracket.update(&account).await.unwrap(),
+        BracketStatus::Closed
+    );
+}
+
+#[tokio::test]
+async fn filled_market_entries_get_exits_right_away_until_canceled() {
+    let account = Account::default();
+    let mut bracket = BracketOrder::place(&account, &bracket(None)).await.unwrap();
+    assert_eq!(bracket.status, BracketStatus::Exiting);
+    assert_eq!(bracket.entry.order_type, OrderType::Market);
+
+    assert_eq!(
+        bracket.cancel(&account).await.unwrap(),
+        BracketStatus::Canceled
+    );
+    let exits = bracket.exits.unwrap();
+    assert_eq!(account.status(&exits.limit_order.id), OrderStatus::Canceled);
+    assert_eq!(account.status(&exits.stop_order.id), OrderStatus::Canceled);
+}
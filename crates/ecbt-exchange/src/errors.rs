@@ -1,5 +1,8 @@
+use crate::model::OrderType;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::Duration;
 use thiserror::Error;
 
 #[derive(Serialize, Deserialize, Debug, Error)]
@@ -13,31 +16,112 @@ impl fmt::Display for MissingImplementationContent {
     }
 }
 
+/// This struct represents where and how an exchange rejected a request
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExchangeErrorContext {
+    /// The raw error code of the exchange, for exchanges that have one.
+    pub code: Option<i64>,
+    /// The raw error message of the exchange.
+    pub message: String,
+    /// The path of the endpoint that returned the error.
+    pub endpoint: String,
+    /// The HTTP status of the response.
+    pub status: Option<u16>,
+}
+
+impl ExchangeErrorContext {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_code(mut self, code: i64) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    pub fn with_endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+impl fmt::Display for ExchangeErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)?;
+        if let Some(code) = self.code {
+            write!(f, " (code {})", code)?;
+        }
+        match self.status {
+            Some(status) => write!(f, " [{} {}]", status, self.endpoint),
+            None => write!(f, " [{}]", self.endpoint),
+        }
+    }
+}
+
+/// This enum represents why an order breaks the constraints of its market pair
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum OrderViolation {
+    #[error("size {0} must be positive")]
+    NonPositiveSize(Decimal),
+    #[error("price {0} must be positive")]
+    NonPositivePrice(Decimal),
+    #[error("size {size} is not a multiple of {increment}")]
+    SizeIncrement { size: Decimal, increment: Decimal },
+    #[error("price {price} is not a multiple of {increment}")]
+    PriceIncrement { price: Decimal, increment: Decimal },
+    #[error("size {size} is below the minimum of {minimum}")]
+    SizeBelowMinimum { size: Decimal, minimum: Decimal },
+    #[error("size {size} is above the maximum of {maximum}")]
+    SizeAboveMaximum { size: Decimal, maximum: Decimal },
+    #[error("price {price} is below the minimum of {minimum}")]
+    PriceBelowMinimum { price: Decimal, minimum: Decimal },
+    #[error("price {price} is above the maximum of {maximum}")]
+    PriceAboveMaximum { price: Decimal, maximum: Decimal },
+    #[error("notional {notional} is below the minimum of {minimum}")]
+    NotionalBelowMinimum { notional: Decimal, minimum: Decimal },
+    #[error("notional {notional} is above the maximum of {maximum}")]
+    NotionalAboveMaximum { notional: Decimal, maximum: Decimal },
+    #[error("price {price} is outside of the band {low:?} - {high:?}")]
+    PriceOutsideBand {
+        price: Decimal,
+        low: Option<Decimal>,
+        high: Option<Decimal>,
+    },
+}
+
 #[derive(Error, Debug)]
 pub enum EcbtError {
-    #[error("")]
+    #[error("{0}")]
     Generic(Box<dyn std::error::Error + Send + Sync>),
-    #[error("")]
+    #[error("no market pair")]
     NoMarketPair,
     #[error(transparent)]
     MissingImplementation(#[from] MissingImplementationContent),
-    #[error("")]
+    #[error("asset not found")]
     AssetNotFound(),
-    #[error("")]
+    #[error("no api key set")]
     NoApiKeySet(),
-    #[error("")]
-    InternalServerError(),
-    #[error("")]
-    ServiceUnavailable(),
-    #[error("")]
+    #[error("internal server error: {0}")]
+    InternalServerError(ExchangeErrorContext),
+    #[error("service unavailable: {0}")]
+    ServiceUnavailable(ExchangeErrorContext),
+    #[error("unauthorized")]
     Unauthorized(),
-    #[error("")]
+    #[error("symbol not found")]
     SymbolNotFound(),
-    #[error("")]
+    #[error("socket error")]
     SocketError(),
-    #[error("")]
+    #[error("websocket message not supported")]
     WebSocketMessageNotSupported(),
-    #[error("")]
+    #[error("failed to get the timestamp")]
     GetTimestampFailed(),
     #[error(transparent)]
     ReqError(#[from] reqwest::Error),
@@ -47,7 +131,7 @@ pub enum EcbtError {
     InvalidPayloadSignature(#[from] serde_urlencoded::ser::Error),
     #[error(transparent)]
     IoError(#[from] std::io::Error),
-    #[error("")]
+    #[error("lock poisoned")]
     PoisonError(),
     #[error(transparent)]
     JsonError(#[from] serde_json::Error),
@@ -56,7 +140,7 @@ pub enum EcbtError {
     #[error(transparent)]
     UrlParserError(#[from] url::ParseError),
     #[error(transparent)]
-    Tungstenite(#[from] tokio_tungstenite::tungstenite::Error),
+    Tungstenite(Box<tokio_tungstenite::tungstenite::Error>),
     #[error(transparent)]
     TimestampError(#[from] std::time::SystemTimeError),
     #[error("{0}")]
@@ -69,6 +153,98 @@ pub enum EcbtError {
     InvalidParameter(String),
     #[error("{0}")]
     TimeFormatError(String),
+    /// The exchange has no such kind of order, e.g. trailing stops.
+    #[error("{0:?} orders are not supported by the exchange")]
+    UnsupportedOrderType(OrderType),
+    #[error("rate limited, retry in {retry_after:?}")]
+    RateLimited {
+        retry_after: Option<Duration>,
+        context: Option<ExchangeErrorContext>,
+    },
+    #[error(transparent)]
+    InvalidOrder(#[from] OrderViolation),
+    #[error("insufficient balance: {0}")]
+    InsufficientBalance(ExchangeErrorContext),
+    #[error("order not found: {0}")]
+    OrderNotFound(ExchangeErrorContext),
+    #[error("duplicate order: {0}")]
+    DuplicateOrder(ExchangeErrorContext),
+    #[error("invalid price: {0}")]
+    InvalidPrice(ExchangeErrorContext),
+    #[error("invalid quantity: {0}")]
+    InvalidQuantity(ExchangeErrorContext),
+    #[error("post only order would take: {0}")]
+    PostOnlyWouldTake(ExchangeErrorContext),
+    #[error("timestamp outside of the receive window: {0}")]
+    TimestampOutsideRecvWindow(ExchangeErrorContext),
+    #[error("market closed: {0}")]
+    MarketClosed(ExchangeErrorContext),
+    #[error("authentication rejected: {0}")]
+    AuthenticationRejected(ExchangeErrorContext),
+    #[error("request timed out after {0:?}")]
+    RequestTimeout(Duration),
+    #[error("circuit open after repeated server errors, retry in {retry_after:?}")]
+    CircuitOpen { retry_after: Duration },
+    /// A rejection that no adapter mapping table recognizes.
+    #[error("exchange error: {0}")]
+    Exchange(ExchangeErrorContext),
+}
+
+impl EcbtError {
+    /// Returns what the exchange responded, for errors that come from an exchange rejection.
+    pub fn context(&self) -> Option<&ExchangeErrorContext> {
+        match self {
+            EcbtError::RateLimited { context, .. } => context.as_ref(),
+            EcbtError::InternalServerError(context)
+            | EcbtError::ServiceUnavailable(context)
+            | EcbtError::InsufficientBalance(context)
+            | EcbtError::OrderNotFound(context)
+            | EcbtError::DuplicateOrder(context)
+            | EcbtError::InvalidPrice(context)
+            | EcbtError::InvalidQuantity(context)
+            | EcbtError::PostOnlyWouldTake(context)
+            | EcbtError::TimestampOutsideRecvWindow(context)
+            | EcbtError::MarketClosed(context)
+            | EcbtError::AuthenticationRejected(context)
+            | EcbtError::Exchange(context) => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Returns how long to wait before sending the request again, if the error says so.
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            EcbtError::RateLimited { retry_after, .. } => *retry_after,
+            EcbtError::CircuitOpen { retry_after } => Some(*retry_after),
+            _ => None,
+        }
+    }
+}
+
+/// Builds a typed error from the context of an exchange rejection, e.g.
+/// `EcbtError::InsufficientBalance`.
+pub type ErrorConstructor = fn(ExchangeErrorContext) -> EcbtError;
+
+/// Maps an exchange error message to a typed error by matching, case insensitively, the
+/// first pattern of `table` that the message contains.
+pub fn match_error_message(
+    table: &[(&str, ErrorConstructor)],
+    context: ExchangeErrorContext,
+) -> EcbtError {
+    let message = context.message.to_lowercase();
+    match table
+        .iter()
+        .find(|(pattern, _)| message.contains(&pattern.to_lowercase()))
+    {
+        Some((_, error)) => error(context),
+        None => EcbtError::Exchange(context),
+    }
+}
+
+impl From<tokio_tungstenite::tungstenite::Error> for EcbtError {
+    fn from(error: tokio_tungstenite::tungstenite::Error) -> Self {
+        EcbtError::Tungstenite(Box::new(error))
+    }
 }
 
 pub type Result<T> = std::result::Result<T, crate::errors::EcbtError>;
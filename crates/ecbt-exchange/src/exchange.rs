@@ -1,11 +1,6 @@
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub enum Environment {
+    #[default]
     Production,
     Sandbox,
 }
-
-impl Default for Environment {
-    fn default() -> Self {
-        Self::Production
-    }
-}
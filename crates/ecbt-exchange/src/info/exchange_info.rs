@@ -31,8 +31,8 @@ impl ExchangeInfo {
     pub fn list_pairs(&self) -> Vec<MarketPairHandle> {
         let market_map = self.pairs.read().expect("Couldn't read pairs.");
         market_map
-            .iter()
-            .map(|(_symbol, market)| MarketPairHandle::new(market.clone()))
+            .values()
+            .map(|market| MarketPairHandle::new(market.clone()))
             .collect()
     }
 
@@ -1,3 +1,10 @@
+use super::shared::Result;
+use super::{OrderConstraints, OrderValidation};
+use crate::errors::OrderViolation;
+use crate::model::{
+    ConditionalOrderRequest, EcbtOrderRequest, OcoOrderRequest, OpenMarketOrderRequest,
+    OrderTrigger, Side,
+};
 use rust_decimal::Decimal;
 
 // TODO: Use MarketPair inside MarketPairInfo.
@@ -11,4 +18,255 @@ pub struct MarketPairInfo {
     pub quote_increment: Decimal,
     pub min_base_trade_size: Option<Decimal>,
     pub min_quote_trade_size: Option<Decimal>,
+    pub constraints: OrderConstraints,
+}
+
+impl MarketPairInfo {
+    /// Checks a limit order against the constraints of the pair, rounding it first with
+    /// `OrderValidation::Normalize`. The price band is only checked with a `reference_price`.
+    pub fn validate_limit_order(
+        &self,
+        side: &Side,
+        req: &EcbtOrderRequest,
+        validation: OrderValidation,
+        reference_price: Option<Decimal>,
+    ) -> Result<EcbtOrderRequest> {
+        let mut req = req.clone();
+        if validation == OrderValidation::Off {
+            return Ok(req);
+        }
+        if req.size <= Decimal::ZERO {
+            return Err(OrderViolation::NonPositiveSize(req.size).into());
+        }
+        if req.price <= Decimal::ZERO {
+            return Err(OrderViolation::NonPositivePrice(req.price).into());
+        }
+
+        let normalize = validation == OrderValidation::Normalize;
+        req.size = align_size(req.size, self.base_increment, normalize)?;
+        req.price = align_price(side, req.price, self.quote_increment, normalize)?;
+
+        check_size(req.size, self.min_size(), self.constraints.max_size)?;
+        self.check_price(req.price)?;
+        self.check_notional(req.size * req.price)?;
+        if let Some(reference_price) = reference_price {
+            self.check_band(side, req.price, reference_price)?;
+        }
+
+        Ok(req)
+    }
+
+    /// Checks a market order against the constraints of the pair, rounding it first with
+    /// `OrderValidation::Normalize`. The notional is only checked with a `reference_price`.
+    pub fn validate_market_order(
+        &self,
+        req: &OpenMarketOrderRequest,
+        validation: OrderValidation,
+        reference_price: Option<Decimal>,
+    ) -> Result<OpenMarketOrderRequest> {
+        let mut req = req.clone();
+        if validation == OrderValidation::Off {
+            return Ok(req);
+        }
+        if req.size <= Decimal::ZERO {
+            return Err(OrderViolation::NonPositiveSize(req.size).into());
+        }
+
+        let increment = self
+            .constraints
+            .market_size_increment
+            .unwrap_or(self.base_increment);
+        req.size = align_size(
+            req.size,
+            increment,
+            validation == OrderValidation::Normalize,
+        )?;
+        check_size(
+            req.size,
+            self.constraints.market_min_size.or_else(|| self.min_size()),
+            self.constraints
+                .market_max_size
+                .or(self.constraints.max_size),
+        )?;
+        if let Some(reference_price) = reference_price {
+            self.check_notional(req.size * reference_price)?;
+        }
+
+        Ok(req)
+    }
+
+    /// Checks a conditional order against the constraints of the pair, rounding it first with
+    /// `OrderValidation::Normalize`. Trigger prices are rounded like limit prices, and the
+    /// notional is checked with the limit price or else the trigger price.
+    pub fn validate_conditional_order(
+        &self,
+        req: &ConditionalOrderRequest,
+        validation: OrderValidation,
+    ) -> Result<ConditionalOrderRequest> {
+        let mut req = req.clone();
+        if validation == OrderValidation::Off {
+            return Ok(req);
+        }
+        if req.size <= Decimal::ZERO {
+            return Err(OrderViolation::NonPositiveSize(req.size).into());
+        }
+
+        let normalize = validation == OrderValidation::Normalize;
+        req.size = align_size(req.size, self.base_increment, normalize)?;
+        check_size(req.size, self.min_size(), self.constraints.max_size)?;
+        let mut trigger_price = None;
+        if let OrderTrigger::Stop {
+            trigger_price: price,
+        }
+        | OrderTrigger::TakeProfit {
+            trigger_price: price,
+        } = &mut req.trigger
+        {
+            if *price <= Decimal::ZERO {
+                return Err(OrderViolation::NonPositivePrice(*price).into());
+            }
+            *price = align_price(&req.side, *price, self.quote_increment, normalize)?;
+            self.check_price(*price)?;
+            trigger_price = Some(*price);
+        }
+        if let Some(price) = &mut req.limit_price {
+            if *price <= Decimal::ZERO {
+                return Err(OrderViolation::NonPositivePrice(*price).into());
+            }
+            *price = align_price(&req.side, *price, self.quote_increment, normalize)?;
+            self.check_price(*price)?;
+        }
+        if let Some(price) = req.limit_price.or(trigger_price) {
+            self.check_notional(req.size * price)?;
+        }
+
+        Ok(req)
+    }
+
+    /// Checks both orders of an OCO pair, like `validate_limit_order` and
+    /// `validate_conditional_order` do.
+    pub fn validate_oco_order(
+        &self,
+        req: &OcoOrderRequest,
+        validation: OrderValidation,
+        reference_price: Option<Decimal>,
+    ) -> Result<OcoOrderRequest> {
+        let limit_order =
+            self.validate_limit_order(&req.side, &req.limit_order(), validation, reference_price)?;
+        let stop_order = self.validate_conditional_order(&req.stop_order(), validation)?;
+        let stop_price = match stop_order.trigger {
+            OrderTrigger::Stop { trigger_price } => trigger_price,
+            _ => unreachable!("The stop order of an OCO pair has a stop trigger."),
+        };
+
+        Ok(OcoOrderRequest {
+            size: limit_order.size,
+            limit_price: limit_order.price,
+            stop_price,
+            stop_limit_price: stop_order.limit_price,
+            ..req.clone()
+        })
+    }
+
+    /// Whether `validate_limit_order` checks a price band on `side`, which needs a reference
+    /// price.
+    pub fn has_price_band(&self, side: &Side) -> bool {
+        let (up, down) = self.band_multipliers(side);
+        up.is_some() || down.is_some()
+    }
+
+    /// Whether `validate_market_order` checks a notional limit, which needs a reference price.
+    pub fn has_notional_limits(&self) -> bool {
+        self.min_quote_trade_size.is_some_and(|min| !min.is_zero())
+            || self.constraints.max_notional.is_some()
+    }
+
+    fn band_multipliers(&self, side: &Side) -> (Option<Decimal>, Option<Decimal>) {
+        match side {
+            Side::Buy => (
+                self.constraints.bid_multiplier_up,
+                self.constraints.bid_multiplier_down,
+            ),
+            Side::Sell => (
+                self.constraints.ask_multiplier_up,
+                self.constraints.ask_multiplier_down,
+            ),
+        }
+    }
+
+    fn min_size(&self) -> Option<Decimal> {
+        self.min_base_trade_size
+            .filter(|min| !min.is_zero())
+            .or_else(|| Some(self.base_increment).filter(|increment| !increment.is_zero()))
+    }
+
+    fn check_price(&self, price: Decimal) -> Result<()> {
+        if let Some(minimum) = self.constraints.min_price.filter(|min| price < *min) {
+            return Err(OrderViolation::PriceBelowMinimum { price, minimum }.into());
+        }
+        if let Some(maximum) = self.constraints.max_price.filter(|max| price > *max) {
+            return Err(OrderViolation::PriceAboveMaximum { price, maximum }.into());
+        }
+        Ok(())
+    }
+
+    fn check_notional(&self, notional: Decimal) -> Result<()> {
+        if let Some(minimum) = self.min_quote_trade_size.filter(|min| notional < *min) {
+            return Err(OrderViolation::NotionalBelowMinimum { notional, minimum }.into());
+        }
+        if let Some(maximum) = self.constraints.max_notional.filter(|max| notional > *max) {
+            return Err(OrderViolation::NotionalAboveMaximum { notional, maximum }.into());
+        }
+        Ok(())
+    }
+
+    fn check_band(&self, side: &Side, price: Decimal, reference_price: Decimal) -> Result<()> {
+        let (up, down) = self.band_multipliers(side);
+        let low = down.map(|multiplier| reference_price * multiplier);
+        let high = up.map(|multiplier| reference_price * multiplier);
+        if low.is_some_and(|low| price < low) || high.is_some_and(|high| price > high) {
+            return Err(OrderViolation::PriceOutsideBand { price, low, high }.into());
+        }
+        Ok(())
+    }
+}
+
+fn align_size(size: Decimal, increment: Decimal, normalize: bool) -> Result<Decimal> {
+    if increment.is_zero() || (size % increment).is_zero() {
+        Ok(size)
+    } else if normalize {
+        Ok(((size / increment).floor() * increment).normalize())
+    } else {
+        Err(OrderViolation::SizeIncrement { size, increment }.into())
+    }
+}
+
+fn align_price(
+    side: &Side,
+    price: Decimal,
+    increment: Decimal,
+    normalize: bool,
+) -> Result<Decimal> {
+    if increment.is_zero() || (price % increment).is_zero() {
+        Ok(price)
+    } else if normalize {
+        let ticks = price / increment;
+        let ticks = match side {
+            Side::Buy => ticks.floor(),
+            Side::Sell => ticks.ceil(),
+        };
+        Ok((ticks * increment).normalize())
+    } else {
+        Err(OrderViolation::PriceIncrement { price, increment }.into())
+    }
+}
+
+fn check_size(size: Decimal, minimum: Option<Decimal>, maximum: Option<Decimal>) -> Result<()> {
+    if let Some(minimum) = minimum.filter(|min| size < *min) {
+        return Err(OrderViolation::SizeBelowMinimum { size, minimum }.into());
+    }
+    if let Some(maximum) = maximum.filter(|max| size > *max) {
+        return Err(OrderViolation::SizeAboveMaximum { size, maximum }.into());
+    }
+    Ok(())
 }
@@ -2,6 +2,10 @@ mod exchange_info;
 mod exchange_info_retrieval;
 mod market_pair;
 mod market_pair_handle;
+mod order_constraints;
+mod order_validation;
+#[cfg(test)]
+mod tests;
 mod utils;
 
 pub use crate::shared;
@@ -9,4 +13,6 @@ pub use exchange_info::ExchangeInfo;
 pub use exchange_info_retrieval::ExchangeInfoRetrieval;
 pub use market_pair::MarketPairInfo;
 pub use market_pair_handle::MarketPairHandle;
+pub use order_constraints::OrderConstraints;
+pub use order_validation::OrderValidation;
 pub use utils::*;
@@ -0,0 +1,24 @@
+use rust_decimal::Decimal;
+
+/// This struct represents the limits an exchange puts on the orders of a market pair beyond
+/// its increments and minimum sizes. Unset limits are not checked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OrderConstraints {
+    pub max_size: Option<Decimal>,
+    pub min_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub max_notional: Option<Decimal>,
+    /// Size limits of market orders, the limit order ones apply when unset.
+    pub market_min_size: Option<Decimal>,
+    pub market_max_size: Option<Decimal>,
+    pub market_size_increment: Option<Decimal>,
+    /// The price band of buy orders, as multiples of a reference price such as the recent
+    /// average price.
+    pub bid_multiplier_up: Option<Decimal>,
+    pub bid_multiplier_down: Option<Decimal>,
+    /// The price band of sell orders, as multiples of the same reference price.
+    pub ask_multiplier_up: Option<Decimal>,
+    pub ask_multiplier_down: Option<Decimal>,
+    /// Maximum number of open orders on the market pair, informative only.
+    pub max_num_orders: Option<u64>,
+}
@@ -0,0 +1,13 @@
+/// This enum represents how orders are checked against the constraints of their market pair
+/// before being sent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OrderValidation {
+    /// Orders are sent as they are.
+    #[default]
+    Off,
+    /// Orders breaking any constraint are rejected.
+    Strict,
+    /// Sizes and prices are rounded to the increments of the market pair, toward the less
+    /// aggressive side, then the orders are checked like in `Strict`.
+    Normalize,
+}
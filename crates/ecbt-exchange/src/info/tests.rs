@@ -0,0 +1,237 @@
+use super::{MarketPairInfo, OrderConstraints, OrderValidation};
+use crate::errors::{EcbtError, OrderViolation};
+use crate::model::market_pair::{Currency, MarketPair};
+use crate::model::{
+    BracketOrderRequest, ConditionalOrderRequest, EcbtOrderRequest, OpenMarketOrderRequest,
+    OrderTrigger, OrderType, Side, TimeInForce, TrailingOffset,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+fn pair() -> MarketPairInfo {
+    MarketPairInfo {
+        base: "BTC".to_string(),
+        quote: "USDT".to_string(),
+        symbol: "BTCUSDT".to_string(),
+        base_increment: dec!(0.001),
+        quote_increment: dec!(0.5),
+        min_base_trade_size: Some(dec!(0.01)),
+        min_quote_trade_size: Some(dec!(10)),
+        constraints: OrderConstraints {
+            max_size: Some(dec!(100)),
+            bid_multiplier_up: Some(dec!(1.2)),
+            bid_multiplier_down: Some(dec!(0.5)),
+            ask_multiplier_up: Some(dec!(1.5)),
+            ask_multiplier_down: Some(dec!(0.8)),
+            ..Default::default()
+        },
+    }
+}
+
+fn limit(size: Decimal, price: Decimal) -> EcbtOrderRequest {
+    EcbtOrderRequest {
+        client_order_id: None,
+        market_pair: MarketPair(Currency::BTC, Currency::USDT),
+        size,
+        price,
+        time_in_force: TimeInForce::GoodTillCancelled,
+        post_only: false,
+    }
+}
+
+fn violation<T: std::fmt::Debug>(result: Result<T, EcbtError>) -> OrderViolation {
+    match result {
+        Err(EcbtError::InvalidOrder(violation)) => violation,
+        other => panic!("Expected an order violation, got {:?}", other),
+    }
+}
+
+#[test]
+fn normalize_rounds_toward_the_passive_side() {
+    let pair = pair();
+    let req = limit(dec!(0.0129), dec!(1000.3));
+
+    let buy = pair
+        .validate_limit_order(&Side::Buy, &req, OrderValidation::Normalize, None)
+        .unwrap();
+    assert_eq!((buy.size, buy.price), (dec!(0.012), dec!(1000)));
+
+    let sell = pair
+        .validate_limit_order(&Side::Sell, &req, OrderValidation::Normalize, None)
+        .unwrap();
+    assert_eq!((sell.size, sell.price), (dec!(0.012), dec!(1000.5)));
+
+    assert_eq!(
+        violation(pair.validate_limit_order(&Side::Buy, &req, OrderValidation::Strict, None)),
+        OrderViolation::SizeIncrement {
+            size: dec!(0.0129),
+            increment: dec!(0.001),
+        }
+    );
+    assert_eq!(
+        pair.validate_limit_order(&Side::Buy, &req, OrderValidation::Off, None)
+            .unwrap(),
+        req
+    );
+}
+
+#[test]
+fn limit_orders_are_checked_against_size_notional_and_band() {
+    let pair = pair();
+    let validate = |size, price, reference_price| {
+        pair.validate_limit_order(
+            &Side::Buy,
+            &limit(size, price),
+            OrderValidation::Normalize,
+            reference_price,
+        )
+    };
+
+    assert!(matches!(
+        violation(validate(dec!(0.005), dec!(5000), None)),
+        OrderViolation::SizeBelowMinimum { .. }
+    ));
+    assert!(matches!(
+        violation(validate(dec!(101), dec!(5000), None)),
+        OrderViolation::SizeAboveMaximum { .. }
+    ));
+    assert!(matches!(
+        violation(validate(dec!(0.01), dec!(500), None)),
+        OrderViolation::NotionalBelowMinimum { .. }
+    ));
+    assert!(matches!(
+        violation(validate(dec!(1), dec!(1300), Some(dec!(1000)))),
+        OrderViolation::PriceOutsideBand { .. }
+    ));
+    assert!(validate(dec!(1), dec!(1100), Some(dec!(1000))).is_ok());
+}
+
+#[test]
+fn price_bands_apply_to_their_side_only() {
+    let pair = pair();
+    let validate = |side, price| {
+        pair.validate_limit_order(
+            &side,
+            &limit(dec!(1), price),
+            OrderValidation::Strict,
+            Some(dec!(1000)),
+        )
+    };
+
+    assert!(validate(Side::Buy, dec!(600)).is_ok());
+    assert_eq!(
+        violation(validate(Side::Sell, dec!(600))),
+        OrderViolation::PriceOutsideBand {
+            price: dec!(600),
+            low: Some(dec!(800.0)),
+            high: Some(dec!(1500.0)),
+        }
+    );
+    assert!(validate(Side::Sell, dec!(1300)).is_ok());
+    assert!(matches!(
+        violation(validate(Side::Buy, dec!(1300))),
+        OrderViolation::PriceOutsideBand { .. }
+    ));
+    assert!(pair.has_price_band(&Side::Sell));
+    assert!(!MarketPairInfo {
+        constraints: OrderConstraints::default(),
+        ..pair.clone()
+    }
+    .has_price_band(&Side::Buy));
+}
+
+#[test]
+fn market_orders_use_the_market_constraints() {
+    let mut pair = pair();
+    pair.constraints.market_max_size = Some(dec!(5));
+    let req = OpenMarketOrderRequest {
+        client_order_id: None,
+        market_pair: MarketPair(Currency::BTC, Currency::USDT),
+        size: dec!(6),
+    };
+
+    assert!(matches!(
+        violation(pair.validate_market_order(&req, OrderValidation::Normalize, None)),
+        OrderViolation::SizeAboveMaximum { .. }
+    ));
+    assert!(matches!(
+        violation(pair.validate_market_order(
+            &OpenMarketOrderRequest {
+                size: dec!(0.01),
+                ..req
+            },
+            OrderValidation::Normalize,
+            Some(dec!(100)),
+        )),
+        OrderViolation::NotionalBelowMinimum { .. }
+    ));
+}
+
+#[test]
+fn conditional_orders_round_trigger_and_limit_prices() {
+    let pair = pair();
+    let req = ConditionalOrderRequest {
+        client_order_id: None,
+        market_pair: MarketPair(Currency::BTC, Currency::USDT),
+        side: Side::Sell,
+        size: dec!(0.0129),
+        trigger: OrderTrigger::Stop {
+            trigger_price: dec!(990.2),
+        },
+        limit_price: Some(dec!(985.1)),
+    };
+    assert_eq!(req.order_type(), OrderType::StopLimit);
+
+    let stop = pair
+        .validate_conditional_order(&req, OrderValidation::Normalize)
+        .unwrap();
+    assert_eq!(stop.size, dec!(0.012));
+    assert_eq!(
+        stop.trigger,
+        OrderTrigger::Stop {
+            trigger_price: dec!(990.5)
+        }
+    );
+    assert_eq!(stop.limit_price, Some(dec!(985.5)));
+
+    let trailing = ConditionalOrderRequest {
+        size: dec!(0.001),
+        trigger: OrderTrigger::TrailingStop {
+            offset: TrailingOffset::BasisPoints(50),
+        },
+        limit_price: None,
+        ..req
+    };
+    assert_eq!(trailing.order_type(), OrderType::TrailingStopMarket);
+    assert!(matches!(
+        violation(pair.validate_conditional_order(&trailing, OrderValidation::Strict)),
+        OrderViolation::SizeBelowMinimum { .. }
+    ));
+}
+
+#[test]
+fn bracket_exits_are_rounded_as_an_oco_pair() {
+    let bracket = BracketOrderRequest {
+        client_order_id: None,
+        market_pair: MarketPair(Currency::BTC, Currency::USDT),
+        side: Side::Buy,
+        size: dec!(0.5),
+        entry_price: Some(dec!(1000)),
+        take_profit_price: dec!(1100.2),
+        stop_price: dec!(950.2),
+        stop_limit_price: Some(dec!(940.4)),
+    };
+    let exits = pair()
+        .validate_oco_order(
+            &bracket.exits(dec!(0.0129)),
+            OrderValidation::Normalize,
+            None,
+        )
+        .unwrap();
+    assert_eq!(exits.side, Side::Sell);
+    assert_eq!(exits.size, dec!(0.012));
+    // Sell prices are rounded up, away from the book.
+    assert_eq!(exits.limit_price, dec!(1100.5));
+    assert_eq!(exits.stop_price, dec!(950.5));
+    assert_eq!(exits.stop_limit_price, Some(dec!(940.5)));
+}
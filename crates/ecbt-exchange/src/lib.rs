@@ -4,20 +4,25 @@ pub use errors::*;
 
 use crate::info::ExchangeInfoRetrieval;
 use crate::model::{
-    Balance, CancelAllOrdersRequest, CancelOrderRequest, Candle, EcbtOrderRequest,
-    GetHistoricRatesRequest, GetHistoricTradesRequest, GetOrderHistoryRequest, GetOrderRequest,
-    GetPriceTickerRequest, OpenMarketOrderRequest, Order, OrderBookRequest, OrderBookResponse,
-    OrderCanceled, OrderFilter, Paginator, Ticker, Trade, TradeHistoryRequest,
+    AmendMethod, AmendOrderRequest, AmendedOrder, Balance, CancelAllOrdersRequest,
+    CancelOrderRequest, Candle, ConditionalOrderRequest, EcbtOrderRequest, GetHistoricRatesRequest,
+    GetHistoricTradesRequest, GetOrderHistoryRequest, GetOrderRequest, GetPriceTickerRequest,
+    OcoOrder, OcoOrderRequest, OpenMarketOrderRequest, Order, OrderBookRequest, OrderBookResponse,
+    OrderCanceled, OrderFilter, Paginator, Side, Ticker, Trade, TradeHistoryRequest,
 };
-use crate::shared::Result;
 
+pub mod book;
+pub mod bracket;
 pub mod errors;
 pub mod exchange;
 pub mod info;
 pub mod message;
 pub mod model;
+pub mod rate_limit;
+pub mod retry;
 pub mod shared;
 pub mod stream;
+pub mod transport;
 
 #[async_trait]
 pub trait Exchange: ExchangeInfoRetrieval + ExchangeAccount + ExchangeMarketData + Sized {
@@ -49,6 +54,91 @@ pub trait ExchangeAccount {
     async fn get_account_balances(&self, paginator: Option<Paginator>) -> Result<Vec<Balance>>;
     async fn get_order(&self, req: &GetOrderRequest) -> Result<Order>;
 
+    /// Places a stop, take profit or trailing stop order, failing with
+    /// `EcbtError::UnsupportedOrderType` on exchanges without that kind of order.
+    async fn place_conditional_order(&self, req: &ConditionalOrderRequest) -> Result<Order> {
+        Err(EcbtError::UnsupportedOrderType(req.order_type()))
+    }
+
+    /// Returns a conditional order, by default from the orders of the exchange.
+    async fn get_conditional_order(&self, req: &GetOrderRequest) -> Result<Order> {
+        self.get_order(req).await
+    }
+
+    /// Cancels a conditional order that has not fired, by default like any order.
+    async fn cancel_conditional_order(&self, req: &CancelOrderRequest) -> Result<OrderCanceled> {
+        self.cancel_order(req).await
+    }
+
+    /// Replaces the price and size of a resting limit order. By default the order is canceled
+    /// and the replacement only placed once the cancellation succeeded, so that both are never
+    /// open at once; if placing the replacement fails, the order stays canceled.
+    ///
+    /// The replacement is the part of `req.size` the canceled order didn't fill, the amendment
+    /// fails with `EcbtError::InvalidQuantity` when nothing is left.
+    async fn amend_order(&self, req: &AmendOrderRequest) -> Result<AmendedOrder>
+    where
+        Self: ExchangeInfoRetrieval,
+    {
+        let symbol = self.get_pair(&req.market_pair).await?.read()?.symbol;
+        self.cancel_order(&CancelOrderRequest {
+            id: req.id.clone(),
+            market_pair: Some(symbol.clone()),
+        })
+        .await?;
+        let canceled = self
+            .get_order(&GetOrderRequest {
+                id: req.id.clone(),
+                market_pair: Some(symbol),
+            })
+            .await?;
+        let size = req.unfilled_size(&canceled)?;
+        let replacement = EcbtOrderRequest {
+            size,
+            ..req.replacement()
+        };
+        let order = match req.side {
+            Side::Buy => self.limit_buy(&replacement).await?,
+            Side::Sell => self.limit_sell(&replacement).await?,
+        };
+        Ok(AmendedOrder {
+            order,
+            replaced_order_id: req.id.clone(),
+            replaced_client_order_id: canceled.client_order_id,
+            method: AmendMethod::CancelThenPlace,
+        })
+    }
+
+    /// Places a limit order and a stop order that cancel each other. By default they are
+    /// placed as two independent orders, the limit order being canceled if the stop order is
+    /// rejected, and it is up to the caller to cancel one once the other fills, as
+    /// `bracket::BracketOrder` does.
+    ///
+    /// Exchanges that hold the funds of open stop orders can't rest both orders and return
+    /// `EcbtError::UnsupportedOrderType`.
+    async fn place_oco_order(&self, req: &OcoOrderRequest) -> Result<OcoOrder> {
+        let limit_order = match req.side {
+            Side::Buy => self.limit_buy(&req.limit_order()).await?,
+            Side::Sell => self.limit_sell(&req.limit_order()).await?,
+        };
+        let stop_order = match self.place_conditional_order(&req.stop_order()).await {
+            Ok(stop_order) => stop_order,
+            Err(err) => {
+                self.cancel_order(&CancelOrderRequest {
+                    id: limit_order.id,
+                    market_pair: Some(limit_order.market_pair),
+                })
+                .await?;
+                return Err(err);
+            }
+        };
+        Ok(OcoOrder {
+            order_list_id: None,
+            limit_order,
+            stop_order,
+        })
+    }
+
     async fn get_open_orders(&self, order_filter: &OrderFilter) -> Result<Vec<Order>> {
         Ok(self
             .get_all_open_orders()
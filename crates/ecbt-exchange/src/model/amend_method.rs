@@ -0,0 +1,12 @@
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This enum represents how an order was amended
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AmendMethod {
+    /// The exchange replaced the order in a single request.
+    Native,
+    /// The order was canceled, then its replacement placed once the cancellation succeeded.
+    CancelThenPlace,
+}
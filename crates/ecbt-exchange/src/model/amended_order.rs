@@ -0,0 +1,15 @@
+use super::AmendMethod;
+use super::Order;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents the order that replaced an amended order
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AmendedOrder {
+    pub order: Order,
+    pub replaced_order_id: String,
+    /// The client order id of the replaced order, when the exchange reports it.
+    pub replaced_client_order_id: Option<String>,
+    pub method: AmendMethod,
+}
@@ -7,7 +7,7 @@ use time::Duration;
 use crate::{EcbtError, Result};
 
 /// This enum represents a time interval
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Interval {
     #[serde(rename = "1m")]
     OneMinute,
@@ -108,3 +108,30 @@ impl From<Interval> for &str {
         }
     }
 }
+
+impl TryFrom<&str> for Interval {
+    type Error = EcbtError;
+    fn try_from(interval: &str) -> Result<Self> {
+        match interval {
+            "1m" => Ok(Interval::OneMinute),
+            "3m" => Ok(Interval::ThreeMinutes),
+            "5m" => Ok(Interval::FiveMinutes),
+            "15m" => Ok(Interval::FifteenMinutes),
+            "30m" => Ok(Interval::ThirtyMinutes),
+            "1h" => Ok(Interval::OneHour),
+            "2h" => Ok(Interval::TwoHours),
+            "4h" => Ok(Interval::FourHours),
+            "6h" => Ok(Interval::SixHours),
+            "8h" => Ok(Interval::EightHours),
+            "12h" => Ok(Interval::TwelveHours),
+            "1d" => Ok(Interval::OneDay),
+            "3d" => Ok(Interval::ThreeDays),
+            "1w" => Ok(Interval::OneWeek),
+            "1M" | "1mo" => Ok(Interval::OneMonth),
+            _ => Err(EcbtError::InvalidParameter(format!(
+                "{} is not a valid interval",
+                interval
+            ))),
+        }
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 pub use crate::model::currency::Currency;
 
@@ -13,8 +14,8 @@ impl MarketPair {
 }
 
 /// to_symbol
-impl ToString for MarketPair {
-    fn to_string(&self) -> String {
-        format!("{}{}", self.0, self.1).to_uppercase()
+impl fmt::Display for MarketPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&format!("{}{}", self.0, self.1).to_uppercase())
     }
 }
@@ -1,3 +1,5 @@
+pub mod amend_method;
+pub mod amended_order;
 pub mod ask_bid;
 pub mod balance;
 pub mod candle;
@@ -5,10 +7,12 @@ pub mod currency;
 pub mod interval;
 pub mod liquidity;
 pub mod market_pair;
+pub mod oco_order;
 pub mod order;
 pub mod order_canceled;
 pub mod order_filter;
 pub mod order_status;
+pub mod order_trigger;
 pub mod order_type;
 pub mod paginator;
 pub mod request;
@@ -17,17 +21,22 @@ pub mod ticker;
 pub mod time_in_force;
 pub mod time_in_force_visitor;
 pub mod trade;
+pub mod trailing_offset;
 pub mod transaction;
 
+pub use amend_method::AmendMethod;
+pub use amended_order::AmendedOrder;
 pub use ask_bid::AskBid;
 pub use balance::Balance;
 pub use candle::Candle;
 pub use interval::Interval;
 pub use liquidity::Liquidity;
+pub use oco_order::OcoOrder;
 pub use order::Order;
 pub use order_canceled::OrderCanceled;
 pub use order_filter::OrderFilter;
 pub use order_status::OrderStatus;
+pub use order_trigger::OrderTrigger;
 pub use order_type::OrderType;
 pub use paginator::Paginator;
 pub use request::*;
@@ -36,6 +45,7 @@ pub use ticker::Ticker;
 pub use time_in_force::TimeInForce;
 pub use time_in_force_visitor::TimeInForceVisitor;
 pub use trade::Trade;
+pub use trailing_offset::TrailingOffset;
 pub use transaction::Transaction;
 
 pub mod websocket;
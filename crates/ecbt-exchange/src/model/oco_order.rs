@@ -0,0 +1,14 @@
+use super::Order;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents the limit order and the stop order of a one-cancels-the-other pair
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OcoOrder {
+    /// Id of the order list on exchanges that cancel the other order themselves, `None` when
+    /// the pair was placed as two independent orders.
+    pub order_list_id: Option<String>,
+    pub limit_order: Order,
+    pub stop_order: Order,
+}
@@ -0,0 +1,19 @@
+use super::TrailingOffset;
+
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This enum represents the condition that sends a conditional order to the book
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderTrigger {
+    /// Fires once the price moves against the side of the order through `trigger_price`, up
+    /// for buys and down for sells.
+    Stop { trigger_price: Decimal },
+    /// Fires once the price moves in favor of the side of the order through `trigger_price`,
+    /// down for buys and up for sells.
+    TakeProfit { trigger_price: Decimal },
+    /// Fires once the price retraces by `offset` from its best level since the order was placed.
+    TrailingStop { offset: TrailingOffset },
+}
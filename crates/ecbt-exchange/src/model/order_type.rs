@@ -9,5 +9,9 @@ pub enum OrderType {
     Market,
     StopLimit,
     StopMarket,
+    TakeProfitLimit,
+    TakeProfitMarket,
+    TrailingStopLimit,
+    TrailingStopMarket,
     Unknown,
 }
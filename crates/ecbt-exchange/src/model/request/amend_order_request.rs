@@ -0,0 +1,60 @@
+use crate::errors::{EcbtError, ExchangeErrorContext};
+use crate::model::market_pair::MarketPair;
+use crate::model::{EcbtOrderRequest, Order, Side, TimeInForce};
+use crate::retry::new_client_order_id;
+use crate::shared::Result;
+
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents a new price and size for the resting limit order `id`, which is
+/// replaced by an order with `client_order_id`, or with a new client order id if it is `None`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct AmendOrderRequest {
+    pub id: String,
+    pub client_order_id: Option<String>,
+    pub market_pair: MarketPair,
+    pub side: Side,
+    pub size: Decimal,
+    pub price: Decimal,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
+}
+
+impl AmendOrderRequest {
+    /// Returns the limit order replacing the amended one, which always has a client order id.
+    pub fn replacement(&self) -> EcbtOrderRequest {
+        EcbtOrderRequest {
+            client_order_id: Some(
+                self.client_order_id
+                    .clone()
+                    .unwrap_or_else(new_client_order_id),
+            ),
+            market_pair: self.market_pair.clone(),
+            size: self.size,
+            price: self.price,
+            time_in_force: self.time_in_force,
+            post_only: self.post_only,
+        }
+    }
+    /// Returns the part of `size` that `order`, the amended order, didn't fill, so fills and
+    /// replacement never exceed `size`. Fails with `EcbtError::InvalidQuantity` when nothing is
+    /// left to place.
+    pub fn unfilled_size(&self, order: &Order) -> Result<Decimal> {
+        let remaining = order.remaining.ok_or_else(|| {
+            EcbtError::UnkownResponse(format!("The filled size of order {} is unknown", self.id))
+        })?;
+        let filled = order.size - remaining;
+        let size = self.size - filled;
+        if size <= Decimal::ZERO {
+            return Err(EcbtError::InvalidQuantity(ExchangeErrorContext::new(
+                format!(
+                    "Order {} filled {} of {}, nothing is left to place",
+                    self.id, filled, self.size
+                ),
+            )));
+        }
+        Ok(size)
+    }
+}
@@ -0,0 +1,60 @@
+use crate::model::market_pair::MarketPair;
+use crate::model::{EcbtOrderRequest, OcoOrderRequest, OpenMarketOrderRequest, Side, TimeInForce};
+
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents an entry order followed by a take profit and a stop loss closing the
+/// position it opened. The entry is a limit order at `entry_price`, or a market order without one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BracketOrderRequest {
+    pub client_order_id: Option<String>,
+    pub market_pair: MarketPair,
+    pub side: Side,
+    pub size: Decimal,
+    pub entry_price: Option<Decimal>,
+    pub take_profit_price: Decimal,
+    pub stop_price: Decimal,
+    /// Price of the limit order placed when the stop loss fires, a market order without one.
+    pub stop_limit_price: Option<Decimal>,
+}
+
+impl BracketOrderRequest {
+    /// Returns the entry order if it is a limit order.
+    pub fn limit_entry(&self) -> Option<EcbtOrderRequest> {
+        self.entry_price.map(|price| EcbtOrderRequest {
+            client_order_id: self.client_order_id.clone(),
+            market_pair: self.market_pair.clone(),
+            size: self.size,
+            price,
+            time_in_force: TimeInForce::GoodTillCancelled,
+            post_only: false,
+        })
+    }
+
+    /// Returns the entry order as a market order.
+    pub fn market_entry(&self) -> OpenMarketOrderRequest {
+        OpenMarketOrderRequest {
+            client_order_id: self.client_order_id.clone(),
+            market_pair: self.market_pair.clone(),
+            size: self.size,
+        }
+    }
+
+    /// Returns the take profit and stop loss closing `size` of the position.
+    pub fn exits(&self, size: Decimal) -> OcoOrderRequest {
+        OcoOrderRequest {
+            client_order_id: None,
+            market_pair: self.market_pair.clone(),
+            side: match self.side {
+                Side::Buy => Side::Sell,
+                Side::Sell => Side::Buy,
+            },
+            size,
+            limit_price: self.take_profit_price,
+            stop_price: self.stop_price,
+            stop_limit_price: self.stop_limit_price,
+        }
+    }
+}
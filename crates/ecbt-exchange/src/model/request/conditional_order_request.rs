@@ -0,0 +1,32 @@
+use crate::model::market_pair::MarketPair;
+use crate::model::{OrderTrigger, OrderType, Side};
+
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents an order held by the exchange until its trigger fires, then placed
+/// as a limit order at `limit_price`, or as a market order without one
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ConditionalOrderRequest {
+    pub client_order_id: Option<String>,
+    pub market_pair: MarketPair,
+    pub side: Side,
+    pub size: Decimal,
+    pub trigger: OrderTrigger,
+    pub limit_price: Option<Decimal>,
+}
+
+impl ConditionalOrderRequest {
+    /// Returns the type of the order, e.g. `OrderType::StopLimit` for a stop with a limit price.
+    pub fn order_type(&self) -> OrderType {
+        match (self.trigger, self.limit_price) {
+            (OrderTrigger::Stop { .. }, Some(_)) => OrderType::StopLimit,
+            (OrderTrigger::Stop { .. }, None) => OrderType::StopMarket,
+            (OrderTrigger::TakeProfit { .. }, Some(_)) => OrderType::TakeProfitLimit,
+            (OrderTrigger::TakeProfit { .. }, None) => OrderType::TakeProfitMarket,
+            (OrderTrigger::TrailingStop { .. }, Some(_)) => OrderType::TrailingStopLimit,
+            (OrderTrigger::TrailingStop { .. }, None) => OrderType::TrailingStopMarket,
+        }
+    }
+}
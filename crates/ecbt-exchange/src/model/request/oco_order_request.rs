@@ -0,0 +1,49 @@
+use crate::model::market_pair::MarketPair;
+use crate::model::{ConditionalOrderRequest, EcbtOrderRequest, OrderTrigger, Side, TimeInForce};
+
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This struct represents a limit order and a stop order of the same side and size, the first
+/// to fill, even partially, canceling the other
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct OcoOrderRequest {
+    /// Client id of the order list on exchanges that have them.
+    pub client_order_id: Option<String>,
+    pub market_pair: MarketPair,
+    pub side: Side,
+    pub size: Decimal,
+    pub limit_price: Decimal,
+    pub stop_price: Decimal,
+    /// Price of the limit order placed when the stop fires, a market order without one.
+    pub stop_limit_price: Option<Decimal>,
+}
+
+impl OcoOrderRequest {
+    /// Returns the limit order of the pair.
+    pub fn limit_order(&self) -> EcbtOrderRequest {
+        EcbtOrderRequest {
+            client_order_id: None,
+            market_pair: self.market_pair.clone(),
+            size: self.size,
+            price: self.limit_price,
+            time_in_force: TimeInForce::GoodTillCancelled,
+            post_only: false,
+        }
+    }
+
+    /// Returns the stop order of the pair.
+    pub fn stop_order(&self) -> ConditionalOrderRequest {
+        ConditionalOrderRequest {
+            client_order_id: None,
+            market_pair: self.market_pair.clone(),
+            side: self.side.clone(),
+            size: self.size,
+            trigger: OrderTrigger::Stop {
+                trigger_price: self.stop_price,
+            },
+            limit_price: self.stop_limit_price,
+        }
+    }
+}
@@ -6,8 +6,9 @@ use serde::Serializer;
 use time::Duration;
 
 /// This enum represents time in force
-#[derive(Clone, Debug, PartialEq, Copy)]
+#[derive(Clone, Debug, PartialEq, Copy, Default)]
 pub enum TimeInForce {
+    #[default]
     GoodTillCancelled,
     ImmediateOrCancelled,
     FillOrKill,
@@ -38,9 +39,3 @@ impl Serialize for TimeInForce {
         serializer.serialize_str(s.as_str())
     }
 }
-
-impl Default for TimeInForce {
-    fn default() -> Self {
-        TimeInForce::GoodTillCancelled
-    }
-}
@@ -0,0 +1,13 @@
+use rust_decimal::prelude::Decimal;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// This enum represents how far a trailing stop follows the price
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TrailingOffset {
+    /// A distance in the quote currency.
+    Price(Decimal),
+    /// A distance in hundredths of a percent of the price.
+    BasisPoints(u32),
+}
@@ -1,6 +1,7 @@
-use super::{OrderBookResponse, Trade};
+use super::{AskBid, Balance, Candle, Interval, Order, OrderBookResponse, Ticker, Trade};
 use crate::model::market_pair::MarketPair;
 use crate::model::{OrderStatus, OrderType, Side};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 
@@ -14,15 +15,84 @@ pub struct AccountOrders {
     pub status: Option<Vec<OrderStatus>>,
 }
 
+impl AccountOrders {
+    /// Whether `order` passes every filter that is set, `to_symbol` converts `market` to the
+    /// exchange symbol of `order.market_pair` and `range` applies to `order.created_at`.
+    pub fn accepts(&self, order: &Order, to_symbol: impl Fn(&MarketPair) -> String) -> bool {
+        self.market
+            .as_ref()
+            .is_none_or(|market| to_symbol(market) == order.market_pair)
+            && self
+                .order_type
+                .as_ref()
+                .is_none_or(|order_types| order_types.contains(&order.order_type))
+            && self
+                .buy_or_sell
+                .as_ref()
+                .is_none_or(|side| *side == order.side)
+            && self.range.as_ref().is_none_or(|range| {
+                order
+                    .created_at
+                    .is_some_and(|created_at| range.contains(&created_at))
+            })
+            && self
+                .status
+                .as_ref()
+                .is_none_or(|status| status.contains(&order.status))
+    }
+}
+
 /// This enum represents a subscription
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Subscription {
-    // Ticker(MarketPair),           // symbol
+    Ticker(MarketPair),           // symbol
     OrderBookUpdates(MarketPair), // symbol
     Trades(MarketPair),           // symbol
-                                  // AccountTrades(MarketPair),    // symbol
-                                  // AccountBalance(MarketPair),   // symbol
-                                  // AccountOrders(AccountOrders),
+    /// Symbol and candle interval.
+    Candles(MarketPair, Interval),
+    AccountTrades(MarketPair),  // symbol
+    AccountBalance(MarketPair), // symbol
+    AccountOrders(AccountOrders),
+}
+
+/// This struct represents a ticker update of a market pair
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TickerUpdate {
+    pub market_pair: String,
+    pub ticker: Ticker,
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+    /// Milliseconds since the epoch, if the exchange sends it.
+    pub time: Option<u64>,
+}
+
+/// This struct represents the order book of a market pair, or the levels of it that changed
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct OrderBookUpdate {
+    pub market_pair: String,
+    pub book: OrderBookResponse,
+}
+
+/// This struct represents a change of the best bid or ask of a market pair
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BookTickerUpdate {
+    pub market_pair: String,
+    pub best_bid: AskBid,
+    pub best_ask: AskBid,
+    /// Identifier of the order book update, if the exchange sends it.
+    pub update_id: Option<u64>,
+    /// Milliseconds since the epoch, if the exchange sends it.
+    pub time: Option<u64>,
+}
+
+/// This struct represents a candle update of a market pair
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CandleUpdate {
+    pub market_pair: String,
+    pub interval: Interval,
+    pub candle: Candle,
+    /// Whether the candle is final, otherwise it is still updated by new trades.
+    pub closed: bool,
 }
 
 /// This enum represents a websocket response
@@ -36,7 +106,37 @@ pub enum WebSocketResponse<T> {
 #[derive(Debug, Clone, Serialize)]
 pub enum EcbtWebSocketMessage {
     Ping,
-    OrderBook(OrderBookResponse),
-    // OrderBookDiff(OrderBookResponse),
+    /// The full order book of a market, as sent by Coinbase, FTX and the mock exchanges.
+    OrderBook(OrderBookUpdate),
+    /// The levels of an order book that changed since the previous diff, a quantity of zero
+    /// removing the level, as sent by Binance. The book is only consistent when maintained from
+    /// a snapshot, as `ecbt_binance::BinanceWebsocket::managed_order_book` does.
+    OrderBookDiff(OrderBookUpdate),
     Trades(Vec<Trade>),
+    Ticker(TickerUpdate),
+    /// The best bid or ask of a market changed.
+    BookTicker(BookTickerUpdate),
+    Candle(CandleUpdate),
+    /// An order of the account was placed or changed.
+    OrderUpdate(Order),
+    /// An order of the account was filled, partially or fully.
+    Fill(Trade),
+    /// The balances of the account that changed.
+    BalanceUpdate(Vec<Balance>),
+    /// The exchange acknowledged a subscription request, with the id of the request and the
+    /// channels now subscribed when it reports them.
+    SubscriptionAck {
+        request_id: Option<u64>,
+        channels: Vec<String>,
+    },
+    /// The exchange rejected a subscription request or reported an error on the connection.
+    SubscriptionError {
+        request_id: Option<u64>,
+        code: Option<i64>,
+        message: String,
+    },
+    /// The connection dropped, messages may be missed until `Reconnected`.
+    Disconnected,
+    /// The connection was re-established and the subscriptions replayed.
+    Reconnected,
 }
@@ -0,0 +1,27 @@
+//! This module provides the request rate limiting shared by the exchange adapters.
+//!
+//! Each adapter describes what a request costs with a [`RequestWeigher`] and which limits the
+//! exchange enforces with [`RateLimitRule`]s. A [`RateLimitedTransport`] then waits for, or
+//! rejects, every request that would exceed a limit before it reaches the network.
+mod rate_limit_kind;
+mod rate_limit_mode;
+mod rate_limit_rule;
+mod rate_limit_usage;
+mod rate_limited_transport;
+mod rate_limiter;
+mod request_cost;
+mod request_priority;
+mod request_weigher;
+#[cfg(test)]
+mod tests;
+
+pub use crate::shared;
+pub use rate_limit_kind::RateLimitKind;
+pub use rate_limit_mode::RateLimitMode;
+pub use rate_limit_rule::RateLimitRule;
+pub use rate_limit_usage::RateLimitUsage;
+pub use rate_limited_transport::RateLimitedTransport;
+pub use rate_limiter::RateLimiter;
+pub use request_cost::RequestCost;
+pub use request_priority::RequestPriority;
+pub use request_weigher::RequestWeigher;
@@ -0,0 +1,14 @@
+/// This enum represents what a rate limit counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RateLimitKind {
+    /// The weight of the requests, as published by the exchange per endpoint.
+    Weight,
+    /// Every request counts as one.
+    Requests,
+    /// Every unauthenticated request counts as one.
+    PublicRequests,
+    /// Every authenticated request counts as one.
+    PrivateRequests,
+    /// The number of orders placed.
+    Orders,
+}
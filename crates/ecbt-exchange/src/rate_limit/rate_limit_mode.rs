@@ -0,0 +1,9 @@
+/// This enum represents what happens to a request that would exceed a rate limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitMode {
+    /// Wait until the request fits in the limits.
+    #[default]
+    Queue,
+    /// Fail with `EcbtError::RateLimited` right away.
+    Reject,
+}
@@ -0,0 +1,21 @@
+use super::RateLimitKind;
+use std::time::Duration;
+
+/// This struct represents a limit enforced by an exchange: at most `limit` of `kind` per
+/// `interval`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitRule {
+    pub kind: RateLimitKind,
+    pub interval: Duration,
+    pub limit: u32,
+}
+
+impl RateLimitRule {
+    pub fn new(kind: RateLimitKind, interval: Duration, limit: u32) -> Self {
+        Self {
+            kind,
+            interval,
+            limit,
+        }
+    }
+}
@@ -0,0 +1,11 @@
+use super::RateLimitKind;
+use std::time::Duration;
+
+/// This struct represents the usage of a limit reported by an exchange, typically in the
+/// headers of a response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitUsage {
+    pub kind: RateLimitKind,
+    pub interval: Duration,
+    pub used: u32,
+}
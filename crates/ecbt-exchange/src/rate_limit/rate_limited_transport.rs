@@ -0,0 +1,69 @@
+use super::shared::Result;
+use super::{RateLimiter, RequestWeigher};
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use std::sync::Arc;
+
+/// An [`HttpTransport`] that holds every request until it fits in the rate limits of the
+/// exchange before handing it to the wrapped transport.
+#[derive(Debug, Clone)]
+pub struct RateLimitedTransport {
+    inner: Arc<dyn HttpTransport>,
+    limiter: Arc<RateLimiter>,
+    weigher: Arc<dyn RequestWeigher>,
+}
+
+impl RateLimitedTransport {
+    pub fn new(
+        inner: Arc<dyn HttpTransport>,
+        limiter: Arc<RateLimiter>,
+        weigher: Arc<dyn RequestWeigher>,
+    ) -> Self {
+        Self {
+            inner,
+            limiter,
+            weigher,
+        }
+    }
+
+    pub fn limiter(&self) -> &Arc<RateLimiter> {
+        &self.limiter
+    }
+
+    /// Records the usage reported by `response` and pauses when it reports an exceeded limit.
+    fn observe(&self, response: HttpResponse) -> Result<HttpResponse> {
+        for usage in self.weigher.usage(&response) {
+            self.limiter.record(&usage)?;
+        }
+        // 418 is how Binance reports an IP ban for ignoring 429s.
+        if response.status == StatusCode::TOO_MANY_REQUESTS || response.status.as_u16() == 418 {
+            if let Some(retry_after) = response.retry_after() {
+                self.limiter.pause(retry_after)?;
+            }
+        }
+
+        Ok(response)
+    }
+}
+
+#[async_trait]
+impl HttpTransport for RateLimitedTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        self.limiter.acquire(&self.weigher.cost(&request)).await?;
+        let response = self.inner.send(request).await?;
+        self.observe(response)
+    }
+
+    /// Weighs a first build of the request, then builds the one sent once the limits allow
+    /// it, so a request held in `RateLimitMode::Queue` isn't timestamped and signed before it
+    /// waited.
+    async fn send_with(
+        &self,
+        build: &(dyn Fn() -> Result<HttpRequest> + Send + Sync),
+    ) -> Result<HttpResponse> {
+        self.limiter.acquire(&self.weigher.cost(&build()?)).await?;
+        let response = self.inner.send_with(build).await?;
+        self.observe(response)
+    }
+}
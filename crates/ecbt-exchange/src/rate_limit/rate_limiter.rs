@@ -0,0 +1,223 @@
+use super::{RateLimitMode, RateLimitRule, RateLimitUsage, RequestCost, RequestPriority};
+use crate::errors::EcbtError;
+use crate::shared::Result;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// How long a `Normal` request waits before checking again whether `High` requests are done.
+const PRIORITY_YIELD: Duration = Duration::from_millis(10);
+const DEFAULT_RESERVE: f64 = 0.1;
+
+#[derive(Debug)]
+struct Window {
+    rule: RateLimitRule,
+    started: Instant,
+    used: u32,
+}
+
+impl Window {
+    fn new(rule: RateLimitRule, now: Instant) -> Self {
+        Self {
+            rule,
+            started: now,
+            used: 0,
+        }
+    }
+
+    fn roll(&mut self, now: Instant) {
+        if now >= self.resets_at() {
+            self.started = now;
+            self.used = 0;
+        }
+    }
+
+    fn resets_at(&self) -> Instant {
+        self.started + self.rule.interval
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    windows: Vec<Window>,
+    paused_until: Option<Instant>,
+}
+
+/// Decrements the number of waiting `High` requests when one is done waiting.
+struct Waiting<'a>(&'a AtomicUsize);
+
+impl<'a> Waiting<'a> {
+    fn new(counter: &'a AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// This struct represents the request budget of one exchange.
+///
+/// Every rule is tracked in a fixed window that starts with the first request after the
+/// previous one expired. `Normal` requests may only use the share of each limit that is not
+/// reserved for `High` ones, and wait while any `High` request is waiting.
+#[derive(Debug)]
+pub struct RateLimiter {
+    mode: RateLimitMode,
+    reserve: f64,
+    state: Mutex<State>,
+    waiting_high: AtomicUsize,
+}
+
+impl RateLimiter {
+    pub fn new(rules: Vec<RateLimitRule>, mode: RateLimitMode) -> Self {
+        let now = Instant::now();
+        Self {
+            mode,
+            reserve: DEFAULT_RESERVE,
+            state: Mutex::new(State {
+                windows: rules
+                    .into_iter()
+                    .map(|rule| Window::new(rule, now))
+                    .collect(),
+                paused_until: None,
+            }),
+            waiting_high: AtomicUsize::new(0),
+        }
+    }
+
+    /// Set the fraction of every limit, between 0 and 1, that only `High` requests may use.
+    pub fn with_reserve(mut self, reserve: f64) -> Self {
+        self.reserve = reserve.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn mode(&self) -> RateLimitMode {
+        self.mode
+    }
+
+    pub fn rules(&self) -> Result<Vec<RateLimitRule>> {
+        Ok(self
+            .state()?
+            .windows
+            .iter()
+            .map(|window| window.rule)
+            .collect())
+    }
+
+    /// Replace the rules, e.g. with the ones published by the exchange. The usage of the
+    /// rules that keep their kind and interval is preserved.
+    pub fn set_rules(&self, rules: Vec<RateLimitRule>) -> Result<()> {
+        let now = Instant::now();
+        let mut state = self.state()?;
+        let windows = rules
+            .into_iter()
+            .map(|rule| {
+                match state.windows.iter().find(|window| {
+                    window.rule.kind == rule.kind && window.rule.interval == rule.interval
+                }) {
+                    Some(window) => Window {
+                        rule,
+                        started: window.started,
+                        used: window.used,
+                    },
+                    None => Window::new(rule, now),
+                }
+            })
+            .collect();
+        state.windows = windows;
+        Ok(())
+    }
+
+    /// Wait until `cost` fits in every limit and count it, or fail with
+    /// `EcbtError::RateLimited` in `RateLimitMode::Reject`.
+    pub async fn acquire(&self, cost: &RequestCost) -> Result<()> {
+        let _waiting =
+            (cost.priority == RequestPriority::High).then(|| Waiting::new(&self.waiting_high));
+        loop {
+            let wait = match self.try_acquire(cost)? {
+                None => return Ok(()),
+                Some(wait) => wait,
+            };
+            if self.mode == RateLimitMode::Reject {
+                return Err(EcbtError::RateLimited {
+                    retry_after: Some(wait),
+                    context: None,
+                });
+            }
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Count `cost` if it fits in every limit, otherwise return how long to wait before
+    /// trying again.
+    pub fn try_acquire(&self, cost: &RequestCost) -> Result<Option<Duration>> {
+        let now = Instant::now();
+        let mut state = self.state()?;
+
+        if let Some(paused_until) = state.paused_until {
+            if now < paused_until {
+                return Ok(Some(paused_until - now));
+            }
+            state.paused_until = None;
+        }
+        let high = cost.priority == RequestPriority::High;
+        if !high && self.waiting_high.load(Ordering::SeqCst) > 0 {
+            return Ok(Some(PRIORITY_YIELD));
+        }
+
+        let mut wait = None;
+        for window in state.windows.iter_mut() {
+            window.roll(now);
+            let amount = cost.amount(window.rule.kind);
+            let limit = if high {
+                window.rule.limit
+            } else {
+                (f64::from(window.rule.limit) * (1.0 - self.reserve)) as u32
+            };
+            // A request larger than the whole limit still goes through on a fresh window.
+            if amount > 0 && window.used > 0 && window.used + amount > limit {
+                wait = wait.max(Some(window.resets_at() - now));
+            }
+        }
+        if wait.is_some() {
+            return Ok(wait);
+        }
+
+        for window in state.windows.iter_mut() {
+            window.used += cost.amount(window.rule.kind);
+        }
+        Ok(None)
+    }
+
+    /// Take the usage reported by the exchange into account.
+    pub fn record(&self, usage: &RateLimitUsage) -> Result<()> {
+        let now = Instant::now();
+        let mut state = self.state()?;
+        if let Some(window) = state
+            .windows
+            .iter_mut()
+            .find(|window| window.rule.kind == usage.kind && window.rule.interval == usage.interval)
+        {
+            window.roll(now);
+            window.used = window.used.max(usage.used);
+        }
+        Ok(())
+    }
+
+    /// Hold every request for `duration`, e.g. when the exchange answered with a 429.
+    pub fn pause(&self, duration: Duration) -> Result<()> {
+        let until = Instant::now() + duration;
+        let mut state = self.state()?;
+        state.paused_until = state.paused_until.max(Some(until));
+        Ok(())
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, State>> {
+        self.state.lock().map_err(|_| EcbtError::PoisonError())
+    }
+}
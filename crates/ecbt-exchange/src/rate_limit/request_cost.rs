@@ -0,0 +1,58 @@
+use super::{RateLimitKind, RequestPriority};
+
+/// This struct represents what a single request counts against the rate limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestCost {
+    pub weight: u32,
+    pub orders: u32,
+    pub private: bool,
+    pub priority: RequestPriority,
+}
+
+impl Default for RequestCost {
+    fn default() -> Self {
+        Self {
+            weight: 1,
+            orders: 0,
+            private: false,
+            priority: RequestPriority::Normal,
+        }
+    }
+}
+
+impl RequestCost {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_weight(mut self, weight: u32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    pub fn with_orders(mut self, orders: u32) -> Self {
+        self.orders = orders;
+        self
+    }
+
+    pub fn with_private(mut self, private: bool) -> Self {
+        self.private = private;
+        self
+    }
+
+    pub fn with_priority(mut self, priority: RequestPriority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// How much of a limit of `kind` this request uses.
+    pub fn amount(&self, kind: RateLimitKind) -> u32 {
+        match kind {
+            RateLimitKind::Weight => self.weight,
+            RateLimitKind::Requests => 1,
+            RateLimitKind::PublicRequests => u32::from(!self.private),
+            RateLimitKind::PrivateRequests => u32::from(self.private),
+            RateLimitKind::Orders => self.orders,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+/// This enum represents the priority of a request when the rate limits are tight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RequestPriority {
+    /// Market data and account queries.
+    #[default]
+    Normal,
+    /// Order placements and cancellations, which go before any waiting `Normal` request and
+    /// may use the capacity reserved for them.
+    High,
+}
@@ -0,0 +1,15 @@
+use super::{RateLimitUsage, RequestCost};
+use crate::transport::{HttpRequest, HttpResponse};
+use std::fmt::Debug;
+
+/// The exchange specific part of rate limiting: what each request costs and how much of the
+/// limits the exchange reports as used.
+pub trait RequestWeigher: Debug + Send + Sync {
+    fn cost(&self, request: &HttpRequest) -> RequestCost;
+
+    /// The usage reported by `response`, which takes precedence over the local count when
+    /// it is higher, e.g. because another client shares the same limits.
+    fn usage(&self, _response: &HttpResponse) -> Vec<RateLimitUsage> {
+        Vec::new()
+    }
+}
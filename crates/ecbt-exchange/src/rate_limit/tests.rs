@@ -0,0 +1,142 @@
+use super::{
+    RateLimitKind, RateLimitMode, RateLimitRule, RateLimitUsage, RateLimitedTransport, RateLimiter,
+    RequestCost, RequestPriority, RequestWeigher,
+};
+use crate::errors::EcbtError;
+use crate::retry::{RetryPolicy, RetryingTransport};
+use crate::shared::Result;
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use url::Url;
+
+fn limiter(limit: u32, interval: Duration, mode: RateLimitMode) -> RateLimiter {
+    RateLimiter::new(
+        vec![RateLimitRule::new(RateLimitKind::Weight, interval, limit)],
+        mode,
+    )
+    .with_reserve(0.0)
+}
+
+#[tokio::test]
+async fn rejects_requests_over_the_limit() {
+    let limiter = limiter(10, Duration::from_secs(60), RateLimitMode::Reject);
+    let cost = RequestCost::new().with_weight(4);
+
+    limiter.acquire(&cost).await.unwrap();
+    limiter.acquire(&cost).await.unwrap();
+    assert!(matches!(
+        limiter.acquire(&cost).await,
+        Err(EcbtError::RateLimited { .. })
+    ));
+    limiter
+        .acquire(&RequestCost::new().with_weight(2))
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn queues_requests_until_the_window_resets() {
+    let interval = Duration::from_millis(50);
+    let limiter = limiter(1, interval, RateLimitMode::Queue);
+    let started = Instant::now();
+
+    limiter.acquire(&RequestCost::new()).await.unwrap();
+    limiter.acquire(&RequestCost::new()).await.unwrap();
+    assert!(started.elapsed() >= interval);
+}
+
+#[tokio::test]
+async fn reserves_capacity_for_order_actions() {
+    let limiter = limiter(10, Duration::from_secs(60), RateLimitMode::Reject).with_reserve(0.5);
+    let data = RequestCost::new().with_weight(5);
+    let order = RequestCost::new()
+        .with_weight(5)
+        .with_priority(RequestPriority::High);
+
+    limiter.acquire(&data).await.unwrap();
+    assert!(limiter.acquire(&data).await.is_err());
+    limiter.acquire(&order).await.unwrap();
+}
+
+#[tokio::test]
+async fn follows_the_usage_reported_by_the_exchange() {
+    let interval = Duration::from_secs(60);
+    let limiter = limiter(10, interval, RateLimitMode::Reject);
+    limiter
+        .record(&RateLimitUsage {
+            kind: RateLimitKind::Weight,
+            interval,
+            used: 9,
+        })
+        .unwrap();
+
+    limiter.acquire(&RequestCost::new()).await.unwrap();
+    assert!(limiter.acquire(&RequestCost::new()).await.is_err());
+}
+
+#[derive(Debug)]
+struct UnitWeigher;
+
+impl RequestWeigher for UnitWeigher {
+    fn cost(&self, _request: &HttpRequest) -> RequestCost {
+        RequestCost::new()
+    }
+}
+
+/// Keeps the requests it is sent.
+#[derive(Debug, Default)]
+struct RecordingTransport {
+    requests: Mutex<Vec<HttpRequest>>,
+}
+
+#[async_trait]
+impl HttpTransport for RecordingTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let url = request.url.clone();
+        self.requests.lock().unwrap().push(request);
+        Ok(HttpResponse {
+            url,
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: Vec::new(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn queued_requests_are_signed_once_the_limiter_releases_them() {
+    let interval = Duration::from_millis(50);
+    let limiter = Arc::new(limiter(1, interval, RateLimitMode::Queue));
+    let inner = Arc::new(RecordingTransport::default());
+    let transport =
+        RateLimitedTransport::new(inner.clone(), limiter.clone(), Arc::new(UnitWeigher));
+    limiter.acquire(&RequestCost::new()).await.unwrap();
+
+    let started = Instant::now();
+    // Stands for a signed request, timestamped when it is built.
+    let build = || {
+        let timestamp = started.elapsed().as_millis();
+        let url = format!(
+            "https://api.binance.com/api/v3/order?timestamp={}",
+            timestamp
+        );
+        Ok(HttpRequest::new(Method::POST, Url::parse(&url)?))
+    };
+    transport.send_with(&build).await.unwrap();
+
+    let timestamp = |request: HttpRequest| -> u128 {
+        request.url.query_pairs().next().unwrap().1.parse().unwrap()
+    };
+    let request = inner.requests.lock().unwrap().pop().unwrap();
+    assert!(timestamp(request) >= interval.as_millis());
+
+    // Adapters retry the requests released by the limiter.
+    let transport = RetryingTransport::new(Arc::new(transport), RetryPolicy::default());
+    transport.send_with(&build).await.unwrap();
+    let request = inner.requests.lock().unwrap().pop().unwrap();
+    assert!(timestamp(request) >= 2 * interval.as_millis());
+}
@@ -0,0 +1,79 @@
+use crate::errors::EcbtError;
+use crate::shared::Result;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::time::Instant;
+
+#[derive(Debug, Default)]
+struct State {
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+/// This struct represents a circuit breaker that stops requests to an exchange that keeps
+/// failing.
+///
+/// The circuit opens after `failure_threshold` consecutive failures and rejects every request
+/// until `cooldown` has passed. A single request is then let through to probe the exchange
+/// while the others are still rejected: a success closes the circuit again while a failure
+/// reopens it right away. Another probe is let through if the first one is not answered
+/// within another `cooldown`.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Default::default(),
+        }
+    }
+
+    /// Fail with `EcbtError::CircuitOpen` while the circuit is open.
+    pub fn check(&self) -> Result<()> {
+        let mut state = self.state()?;
+        match state.open_until {
+            Some(open_until) if open_until > Instant::now() => Err(EcbtError::CircuitOpen {
+                retry_after: open_until - Instant::now(),
+            }),
+            Some(_) => {
+                // Half open: the circuit stays open for the other requests while this one
+                // probes, and one more failure is enough to open it again.
+                state.open_until = Some(Instant::now() + self.cooldown);
+                state.failures = self.failure_threshold - 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn record_success(&self) -> Result<()> {
+        *self.state()? = State::default();
+        Ok(())
+    }
+
+    pub fn record_failure(&self) -> Result<()> {
+        let mut state = self.state()?;
+        state.failures += 1;
+        if state.failures >= self.failure_threshold {
+            state.open_until = Some(Instant::now() + self.cooldown);
+        }
+        Ok(())
+    }
+
+    pub fn is_open(&self) -> Result<bool> {
+        Ok(self
+            .state()?
+            .open_until
+            .is_some_and(|open_until| open_until > Instant::now()))
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, State>> {
+        self.state.lock().map_err(|_| EcbtError::PoisonError())
+    }
+}
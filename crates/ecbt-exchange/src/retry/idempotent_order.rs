@@ -0,0 +1,79 @@
+use super::shared::Result;
+use super::RetryPolicy;
+use crate::errors::EcbtError;
+use crate::model::Order;
+use rand::Rng;
+use std::future::Future;
+
+/// Returns a random UUID v4, which every supported exchange accepts as a client order id.
+pub fn new_client_order_id() -> String {
+    let mut bytes: [u8; 16] = rand::thread_rng().gen();
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{:02x}", byte)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Places an order with `place` and retries it without ever placing it twice.
+///
+/// `place` must send the same client order id on every call and `lookup` must fetch the order
+/// by that id. When a failure leaves it unknown whether the exchange accepted the order, the
+/// order is looked up first and only sent again if the exchange does not know it. Rejections
+/// are returned right away.
+pub async fn place_order<P, PF, L, LF>(policy: &RetryPolicy, place: P, lookup: L) -> Result<Order>
+where
+    P: Fn() -> PF,
+    PF: Future<Output = Result<Order>>,
+    L: Fn() -> LF,
+    LF: Future<Output = Result<Order>>,
+{
+    let mut attempt = 0;
+    loop {
+        let error = match place().await {
+            Ok(order) => return Ok(order),
+            Err(error) => error,
+        };
+        if is_ambiguous(&error) {
+            match lookup().await {
+                Ok(order) => return Ok(order),
+                Err(EcbtError::OrderNotFound(_)) => {}
+                // Without knowing whether the order exists it is not safe to send it again.
+                Err(_) => return Err(error),
+            }
+        } else if !is_unsent(&error) {
+            return Err(error);
+        }
+        if attempt >= policy.max_retries {
+            return Err(error);
+        }
+        tokio::time::sleep(policy.backoff(attempt)).await;
+        attempt += 1;
+    }
+}
+
+/// Whether the order may have reached the exchange despite the error.
+fn is_ambiguous(error: &EcbtError) -> bool {
+    match error {
+        EcbtError::InternalServerError(_)
+        | EcbtError::ServiceUnavailable(_)
+        | EcbtError::RequestTimeout(_)
+        | EcbtError::UnkownResponse(_)
+        | EcbtError::NotParsableResponse(_)
+        | EcbtError::JsonError(_)
+        | EcbtError::DuplicateOrder(_) => true,
+        EcbtError::ReqError(error) => !error.is_connect(),
+        _ => false,
+    }
+}
+
+/// Whether the order certainly never left the client.
+fn is_unsent(error: &EcbtError) -> bool {
+    matches!(error, EcbtError::ReqError(error) if error.is_connect())
+}
@@ -0,0 +1,18 @@
+//! This module provides the request retries shared by the exchange adapters.
+//!
+//! A [`RetryingTransport`] resends idempotent requests that hit a timeout or a server error and
+//! stops sending anything while its [`CircuitBreaker`] is open. Orders are never resent by the
+//! transport: [`place_order`] first looks an order up by its client order id after an ambiguous
+//! failure, so that an order which did reach the exchange is not placed twice.
+mod circuit_breaker;
+mod idempotent_order;
+mod retry_policy;
+mod retrying_transport;
+#[cfg(test)]
+mod tests;
+
+pub use crate::shared;
+pub use circuit_breaker::CircuitBreaker;
+pub use idempotent_order::{new_client_order_id, place_order};
+pub use retry_policy::RetryPolicy;
+pub use retrying_transport::RetryingTransport;
@@ -0,0 +1,117 @@
+use super::shared::Result;
+use super::{CircuitBreaker, RetryPolicy};
+use crate::errors::EcbtError;
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use std::sync::{Arc, Mutex};
+
+/// An [`HttpTransport`] that resends idempotent requests on timeouts and server errors, and
+/// stops sending requests while the exchange keeps failing.
+///
+/// Requests given to `send_with` are built again for every attempt, so a signed request is
+/// never replayed with a stale timestamp.
+#[derive(Debug, Clone)]
+pub struct RetryingTransport {
+    inner: Arc<dyn HttpTransport>,
+    policy: RetryPolicy,
+    circuit_breaker: Arc<CircuitBreaker>,
+}
+
+impl RetryingTransport {
+    pub fn new(inner: Arc<dyn HttpTransport>, policy: RetryPolicy) -> Self {
+        let circuit_breaker = Arc::new(CircuitBreaker::new(
+            policy.failure_threshold,
+            policy.cooldown,
+        ));
+        Self {
+            inner,
+            policy,
+            circuit_breaker,
+        }
+    }
+
+    pub fn policy(&self) -> &RetryPolicy {
+        &self.policy
+    }
+
+    pub fn circuit_breaker(&self) -> &Arc<CircuitBreaker> {
+        &self.circuit_breaker
+    }
+
+    /// Hands `build` to the wrapped transport, which builds the request when it sends it, e.g.
+    /// after a `RateLimitedTransport` released it.
+    async fn send_once(
+        &self,
+        build: &(dyn Fn() -> Result<HttpRequest> + Send + Sync),
+    ) -> Result<HttpResponse> {
+        match self.policy.request_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, self.inner.send_with(build))
+                .await
+                .map_err(|_| EcbtError::RequestTimeout(timeout))?,
+            None => self.inner.send_with(build).await,
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTransport for RetryingTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        self.send_with(&|| Ok(request.clone())).await
+    }
+
+    async fn send_with(
+        &self,
+        build: &(dyn Fn() -> Result<HttpRequest> + Send + Sync),
+    ) -> Result<HttpResponse> {
+        let request = build()?;
+        // Anything that may change the account, like placing an order, is sent only once.
+        let max_retries = match request.method.is_safe() {
+            true => self.policy.max_retries,
+            false => 0,
+        };
+        let first = Mutex::new(Some(request));
+        // The request built to read its method is the first one built by the first attempt.
+        let build = || match first.lock().ok().and_then(|mut first| first.take()) {
+            Some(request) => Ok(request),
+            None => build(),
+        };
+        let mut attempt = 0;
+        loop {
+            self.circuit_breaker.check()?;
+            let result = self.send_once(&build).await;
+            let failed = match &result {
+                Ok(response) => is_server_error(response.status),
+                Err(error) => is_transient(error),
+            };
+            if !failed {
+                self.circuit_breaker.record_success()?;
+                return result;
+            }
+            self.circuit_breaker.record_failure()?;
+            if attempt >= max_retries {
+                return result;
+            }
+            tokio::time::sleep(self.policy.backoff(attempt)).await;
+            attempt += 1;
+        }
+    }
+}
+
+fn is_server_error(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+fn is_transient(error: &EcbtError) -> bool {
+    match error {
+        EcbtError::RequestTimeout(_) => true,
+        EcbtError::ReqError(error) => error.is_timeout() || error.is_connect(),
+        _ => false,
+    }
+}
@@ -0,0 +1,215 @@
+use super::{new_client_order_id, place_order, CircuitBreaker, RetryPolicy, RetryingTransport};
+use crate::errors::{EcbtError, ExchangeErrorContext};
+use crate::model::{Order, OrderStatus, OrderType, Side};
+use crate::shared::Result;
+use crate::transport::{HttpRequest, HttpResponse, HttpTransport};
+use async_trait::async_trait;
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use rust_decimal_macros::dec;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use url::Url;
+
+/// Answers with the given statuses in turn, then with 200.
+#[derive(Debug)]
+struct ScriptedTransport {
+    statuses: Mutex<Vec<StatusCode>>,
+    sent: AtomicU32,
+}
+
+impl ScriptedTransport {
+    fn new(mut statuses: Vec<StatusCode>) -> Arc<Self> {
+        statuses.reverse();
+        Arc::new(Self {
+            statuses: Mutex::new(statuses),
+            sent: AtomicU32::new(0),
+        })
+    }
+}
+
+#[async_trait]
+impl HttpTransport for ScriptedTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        self.sent.fetch_add(1, Ordering::SeqCst);
+        Ok(HttpResponse {
+            url: request.url,
+            status: self
+                .statuses
+                .lock()
+                .unwrap()
+                .pop()
+                .unwrap_or(StatusCode::OK),
+            headers: HeaderMap::new(),
+            body: Vec::new(),
+        })
+    }
+}
+
+fn policy() -> RetryPolicy {
+    RetryPolicy::new().with_initial_backoff(Duration::from_millis(1))
+}
+
+fn request(method: Method) -> HttpRequest {
+    HttpRequest::new(
+        method,
+        Url::parse("https://api.exchange.test/order").unwrap(),
+    )
+}
+
+fn order(client_order_id: &str) -> Order {
+    Order {
+        id: "1".to_string(),
+        market_pair: "BTCUSDT".to_string(),
+        client_order_id: Some(client_order_id.to_string()),
+        created_at: None,
+        order_type: OrderType::Limit,
+        side: Side::Buy,
+        status: OrderStatus::New,
+        size: dec!(1),
+        price: Some(dec!(100)),
+        remaining: Some(dec!(1)),
+        trades: Vec::new(),
+    }
+}
+
+#[tokio::test]
+async fn only_idempotent_requests_are_retried() {
+    let inner = ScriptedTransport::new(vec![
+        StatusCode::SERVICE_UNAVAILABLE,
+        StatusCode::BAD_GATEWAY,
+    ]);
+    let transport = RetryingTransport::new(inner.clone(), policy());
+    let response = transport.send(request(Method::GET)).await.unwrap();
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(inner.sent.load(Ordering::SeqCst), 3);
+
+    let inner = ScriptedTransport::new(vec![StatusCode::SERVICE_UNAVAILABLE]);
+    let transport = RetryingTransport::new(inner.clone(), policy());
+    let response = transport.send(request(Method::POST)).await.unwrap();
+    assert_eq!(response.status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(inner.sent.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn retried_requests_are_built_again() {
+    let inner = ScriptedTransport::new(vec![
+        StatusCode::SERVICE_UNAVAILABLE,
+        StatusCode::GATEWAY_TIMEOUT,
+    ]);
+    let transport = RetryingTransport::new(inner.clone(), policy());
+    let built = AtomicU32::new(0);
+    let response = transport
+        .send_with(&|| {
+            built.fetch_add(1, Ordering::SeqCst);
+            Ok(request(Method::GET))
+        })
+        .await
+        .unwrap();
+    assert_eq!(response.status, StatusCode::OK);
+    assert_eq!(inner.sent.load(Ordering::SeqCst), 3);
+    assert_eq!(built.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn circuit_opens_after_repeated_server_errors() {
+    let inner = ScriptedTransport::new(vec![StatusCode::INTERNAL_SERVER_ERROR; 3]);
+    let transport = RetryingTransport::new(
+        inner.clone(),
+        policy()
+            .with_max_retries(0)
+            .with_circuit_breaker(2, Duration::from_millis(50)),
+    );
+
+    for _ in 0..2 {
+        transport.send(request(Method::GET)).await.unwrap();
+    }
+    assert!(matches!(
+        transport.send(request(Method::GET)).await,
+        Err(EcbtError::CircuitOpen { .. })
+    ));
+    assert_eq!(inner.sent.load(Ordering::SeqCst), 2);
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    let response = transport.send(request(Method::GET)).await.unwrap();
+    assert_eq!(response.status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(transport.circuit_breaker().is_open().unwrap());
+}
+
+#[tokio::test]
+async fn half_open_circuit_lets_a_single_probe_through() {
+    let circuit_breaker = CircuitBreaker::new(1, Duration::from_millis(50));
+    circuit_breaker.record_failure().unwrap();
+    assert!(circuit_breaker.check().is_err());
+
+    tokio::time::sleep(Duration::from_millis(60)).await;
+    circuit_breaker.check().unwrap();
+    assert!(matches!(
+        circuit_breaker.check(),
+        Err(EcbtError::CircuitOpen { .. })
+    ));
+
+    circuit_breaker.record_success().unwrap();
+    circuit_breaker.check().unwrap();
+    circuit_breaker.check().unwrap();
+}
+
+#[tokio::test]
+async fn ambiguous_order_failures_are_reconciled_before_resending() {
+    let client_order_id = new_client_order_id();
+    assert_eq!(client_order_id.len(), 36);
+
+    // The first attempt reached the exchange: the order is found and not sent again.
+    let placed = AtomicU32::new(0);
+    let placed_order = place_order(
+        &policy(),
+        || async {
+            placed.fetch_add(1, Ordering::SeqCst);
+            Err(EcbtError::ServiceUnavailable(
+                ExchangeErrorContext::new("busy").with_status(503),
+            ))
+        },
+        || async { Ok(order(&client_order_id)) },
+    )
+    .await
+    .unwrap();
+    assert_eq!(placed_order.client_order_id, Some(client_order_id.clone()));
+    assert_eq!(placed.load(Ordering::SeqCst), 1);
+
+    // The first attempt got lost: the exchange does not know the order so it is sent again.
+    let placed = AtomicU32::new(0);
+    place_order(
+        &policy(),
+        || async {
+            match placed.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(EcbtError::RequestTimeout(Duration::from_secs(10))),
+                _ => Ok(order(&client_order_id)),
+            }
+        },
+        || async {
+            Err(EcbtError::OrderNotFound(ExchangeErrorContext::new(
+                "NotFound",
+            )))
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(placed.load(Ordering::SeqCst), 2);
+
+    // Rejections are final.
+    let placed = AtomicU32::new(0);
+    let result = place_order(
+        &policy(),
+        || async {
+            placed.fetch_add(1, Ordering::SeqCst);
+            Err(EcbtError::InsufficientBalance(ExchangeErrorContext::new(
+                "Insufficient funds",
+            )))
+        },
+        || async { Ok(order(&client_order_id)) },
+    )
+    .await;
+    assert!(matches!(result, Err(EcbtError::InsufficientBalance(_))));
+    assert_eq!(placed.load(Ordering::SeqCst), 1);
+}
@@ -133,8 +133,5 @@ pub fn timestamp_mills(time: &time::OffsetDateTime) -> i64 {
 }
 
 pub fn timestamp_to_iso8601_datetime(timestamp: u64) -> Option<time::OffsetDateTime> {
-    match time::OffsetDateTime::from_unix_timestamp_nanos(timestamp as i128 * 1_000_000) {
-        Ok(time) => Some(time),
-        Err(_) => None,
-    }
+    time::OffsetDateTime::from_unix_timestamp_nanos(timestamp as i128 * 1_000_000).ok()
 }
@@ -1,7 +1,53 @@
-use std::any::Any;
-use std::fmt::Debug;
+use crate::model::websocket::WebSocketResponse;
+use crate::shared::Result;
+use crate::stream::message_buffer::MessageBuffer;
+use futures::Stream;
+use std::fmt::{self, Debug};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tokio::task::JoinHandle;
 
-#[derive(Debug)]
-pub struct CallbackHandle {
-    pub rx: Box<dyn Any + Send>,
+/// This struct represents a subscription made with `ExchangeStream::subscribe`, streaming the
+/// messages also passed to its callback.
+///
+/// Messages are buffered until read as set by the `SubscriptionOptions`, and `dropped` counts
+/// the ones lost to the overflow policy. Dropping the handle stops the task reading the
+/// connection, which closes it.
+pub struct CallbackHandle<R> {
+    buffer: Arc<MessageBuffer<R>>,
+    task: JoinHandle<()>,
+}
+
+impl<R> CallbackHandle<R> {
+    pub(crate) fn new(buffer: Arc<MessageBuffer<R>>, task: JoinHandle<()>) -> Self {
+        Self { buffer, task }
+    }
+
+    /// Number of messages dropped or replaced because the buffer was full.
+    pub fn dropped(&self) -> u64 {
+        self.buffer.dropped()
+    }
+}
+
+impl<R> Debug for CallbackHandle<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CallbackHandle")
+            .field("dropped", &self.dropped())
+            .finish()
+    }
+}
+
+impl<R> Stream for CallbackHandle<R> {
+    type Item = Result<WebSocketResponse<R>>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.buffer.poll_next(cx)
+    }
+}
+
+impl<R> Drop for CallbackHandle<R> {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
 }
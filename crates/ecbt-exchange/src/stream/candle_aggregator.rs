@@ -0,0 +1,83 @@
+use crate::model::websocket::CandleUpdate;
+use crate::model::{Candle, Interval};
+use rust_decimal::Decimal;
+use time::{Duration, OffsetDateTime, Time};
+
+/// This struct represents the candles of a market pair built from its trades, for exchanges
+/// that don't stream candles.
+///
+/// Candles start on multiples of their interval since the epoch, except monthly candles which
+/// start on the first day of each calendar month, in UTC.
+#[derive(Debug, Clone)]
+pub struct CandleAggregator {
+    market_pair: String,
+    interval: Interval,
+    candle: Option<Candle>,
+}
+
+impl CandleAggregator {
+    pub fn new(market_pair: impl Into<String>, interval: Interval) -> Self {
+        Self {
+            market_pair: market_pair.into(),
+            interval,
+            candle: None,
+        }
+    }
+
+    /// Adds a trade made at `time`, in milliseconds since the epoch, and returns the candles
+    /// it changed: the previous candle, closed, when the trade opens a new one, then the
+    /// current candle. Trades older than the current candle are ignored.
+    pub fn push(&mut self, time: u64, price: Decimal, qty: Decimal) -> Vec<CandleUpdate> {
+        let start = self.start(time);
+        let mut updates = Vec::new();
+        match &mut self.candle {
+            Some(candle) if candle.time == start => {
+                candle.high = candle.high.max(price);
+                candle.low = candle.low.min(price);
+                candle.close = price;
+                candle.volume += qty;
+            }
+            Some(candle) if candle.time > start => return updates,
+            _ => {
+                let candle = Candle {
+                    time: start,
+                    low: price,
+                    high: price,
+                    open: price,
+                    close: price,
+                    volume: qty,
+                };
+                if let Some(previous) = self.candle.replace(candle) {
+                    updates.push(self.update(previous, true));
+                }
+            }
+        }
+        if let Some(candle) = self.candle.clone() {
+            updates.push(self.update(candle, false));
+        }
+        updates
+    }
+
+    /// Returns the start of the candle a trade made at `time` belongs to.
+    fn start(&self, time: u64) -> u64 {
+        if self.interval == Interval::OneMonth {
+            let date = OffsetDateTime::UNIX_EPOCH + Duration::milliseconds(time as i64);
+            let month = date
+                .replace_day(1)
+                .expect("Every month has a first day.")
+                .replace_time(Time::MIDNIGHT);
+            return (month.unix_timestamp_nanos() / 1_000_000) as u64;
+        }
+        let length = Duration::from(self.interval).whole_milliseconds() as u64;
+        time - time % length
+    }
+
+    fn update(&self, candle: Candle, closed: bool) -> CandleUpdate {
+        CandleUpdate {
+            market_pair: self.market_pair.clone(),
+            interval: self.interval,
+            candle,
+            closed,
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::model::websocket::EcbtWebSocketMessage;
+
+/// This enum represents a change in the state of a reconnecting websocket stream.
+///
+/// Messages may have been missed between `Disconnected` and `Reconnected`, so consumers
+/// holding state built from the stream, like an order book, should resync it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    Disconnected,
+    Reconnected,
+}
+
+impl From<ConnectionEvent> for EcbtWebSocketMessage {
+    fn from(event: ConnectionEvent) -> Self {
+        match event {
+            ConnectionEvent::Disconnected => EcbtWebSocketMessage::Disconnected,
+            ConnectionEvent::Reconnected => EcbtWebSocketMessage::Reconnected,
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use super::shared::Result;
 use super::CallbackHandle;
 use super::ExchangeStream;
+use super::SubscriptionOptions;
 use super::Subscriptions;
 use crate::model::websocket::Subscription;
 use crate::model::websocket::WebSocketResponse;
@@ -30,10 +31,23 @@ impl<E: ExchangeStream> EcbtStream<E> {
         &self,
         subscription: Subscription,
         callback: F,
-    ) -> Result<CallbackHandle> {
+    ) -> Result<CallbackHandle<E::Response>> {
         self.websocket.subscribe(subscription, callback).await
     }
 
+    pub async fn subscribe_with<
+        F: Fn(&Result<WebSocketResponse<E::Response>>) + Sync + Send + 'static,
+    >(
+        &self,
+        subscription: Subscription,
+        options: SubscriptionOptions,
+        callback: F,
+    ) -> Result<CallbackHandle<E::Response>> {
+        self.websocket
+            .subscribe_with(subscription, options, callback)
+            .await
+    }
+
     pub async fn create_stream<S: Into<E::Subscription> + Clone + Send + Sync>(
         &self,
         subscriptions: &[S],
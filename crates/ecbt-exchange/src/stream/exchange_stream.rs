@@ -2,14 +2,15 @@ use crate::errors::EcbtError;
 use crate::model::websocket::Subscription;
 use crate::model::websocket::WebSocketResponse;
 use crate::shared::Result;
-use crate::stream::{CallbackHandle, Subscriptions};
+use crate::stream::message_buffer::MessageBuffer;
+use crate::stream::{CallbackHandle, SubscriptionOptions, Subscriptions};
 use async_trait::async_trait;
-use futures::channel::mpsc::channel;
 use futures::stream::BoxStream;
 use futures::StreamExt;
 use std::convert::TryInto;
 use std::fmt::Debug;
 use std::slice;
+use std::sync::Arc;
 
 #[async_trait]
 pub trait ExchangeStream: Send + Sync + Sized {
@@ -32,29 +33,59 @@ pub trait ExchangeStream: Send + Sync + Sized {
         subscriptions: Subscriptions<Self::Subscription>,
     ) -> Result<BoxStream<'static, Result<Self::Response>>>;
 
+    /// Subscribes with the default `SubscriptionOptions`, see `subscribe_with`.
     async fn subscribe<
         S: Into<Self::Subscription> + Sync + Send + Clone,
         F: FnMut(&Result<WebSocketResponse<Self::Response>>) + Send + 'static,
     >(
         &self,
         subscription: S,
+        callback: F,
+    ) -> Result<CallbackHandle<Self::Response>> {
+        self.subscribe_with(subscription, SubscriptionOptions::default(), callback)
+            .await
+    }
+
+    /// Subscribes to `subscription`, passing every message to `callback` and buffering it in
+    /// the returned handle as set by `options`. The subscription lasts as long as the handle.
+    ///
+    /// Errors of the stream are passed on as well, a stream that ends without one is followed
+    /// by `EcbtError::SocketError`.
+    async fn subscribe_with<
+        S: Into<Self::Subscription> + Sync + Send + Clone,
+        F: FnMut(&Result<WebSocketResponse<Self::Response>>) + Send + 'static,
+    >(
+        &self,
+        subscription: S,
+        options: SubscriptionOptions,
         mut callback: F,
-    ) -> Result<CallbackHandle> {
+    ) -> Result<CallbackHandle<Self::Response>> {
         let s = slice::from_ref(&subscription);
         let mut stream = self.create_stream_specific(s.into()).await?;
 
-        let (mut tx, rx) = channel(1);
-
-        tokio::spawn(async move {
-            while let Some(Ok(message)) = stream.next().await {
-                let message = message.try_into();
-                callback(&message);
-                tx.try_send(message).ok();
+        let buffer = Arc::new(MessageBuffer::new(&options));
+        let task = tokio::spawn({
+            let buffer = buffer.clone();
+            async move {
+                // Errors are forwarded as they are and the stream is read until it ends, the
+                // stream ends itself on errors it can't recover from.
+                let mut failed = false;
+                while let Some(message) = stream.next().await {
+                    let message = message.and_then(TryInto::try_into);
+                    failed = message.is_err();
+                    callback(&message);
+                    buffer.push(message).await;
+                }
+                if !failed {
+                    let end = Err(EcbtError::SocketError());
+                    callback(&end);
+                    buffer.push(end).await;
+                }
+                buffer.close();
             }
-            callback(&Err(EcbtError::SocketError()));
         });
 
-        Ok(CallbackHandle { rx: Box::new(rx) })
+        Ok(CallbackHandle::new(buffer, task))
     }
 
     async fn create_stream<S: Into<Self::Subscription> + Clone + Send + Sync>(
@@ -0,0 +1,393 @@
+use crate::errors::EcbtError;
+use crate::shared::Result;
+use crate::stream::{ProtocolMessage, SubscriptionHandle, SubscriptionProtocol};
+use futures::{future, Sink, SinkExt, Stream, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+type Consumer<M> = (u64, UnboundedSender<Result<M>>);
+type Reply<T> = oneshot::Sender<Result<T>>;
+
+pub(crate) enum Command<P: SubscriptionProtocol> {
+    Subscribe {
+        subscription: P::Subscription,
+        consumer: Consumer<P::Message>,
+        reply: Reply<()>,
+    },
+    Unsubscribe {
+        handle: u64,
+        reply: Option<Reply<()>>,
+    },
+    Subscriptions(oneshot::Sender<Vec<P::Subscription>>),
+    Close,
+}
+
+/// This struct represents a websocket connection whose subscriptions are added and removed
+/// while it is open.
+///
+/// Every `subscribe` returns a `SubscriptionHandle` streaming the messages of its
+/// subscription, and dropping the handle unsubscribes. Handles of the same subscription share
+/// it on the exchange, which is only unsubscribed when the last one goes away. The connection
+/// is closed by `close`, or once it and all of its handles are dropped.
+pub struct LiveConnection<P: SubscriptionProtocol> {
+    commands: UnboundedSender<Command<P>>,
+    handles: Arc<AtomicU64>,
+}
+
+impl<P: SubscriptionProtocol> Clone for LiveConnection<P> {
+    fn clone(&self) -> Self {
+        Self {
+            commands: self.commands.clone(),
+            handles: self.handles.clone(),
+        }
+    }
+}
+
+impl<P: SubscriptionProtocol> LiveConnection<P> {
+    /// Opens a websocket connection to `endpoint` speaking `protocol`.
+    pub async fn connect(endpoint: url::Url, protocol: P) -> Result<Self> {
+        let (ws_stream, _) = connect_async(endpoint).await?;
+        let (sink, stream) = ws_stream.split();
+        let sink = sink.with(|text| future::ok::<_, EcbtError>(Message::Text(text)));
+        // Control frames are answered by tungstenite, only the text frames are parsed.
+        let stream = stream.filter_map(|message| {
+            future::ready(match message {
+                Ok(Message::Text(text)) => Some(Ok(text)),
+                Ok(_) => None,
+                Err(err) => Some(Err(err.into())),
+            })
+        });
+        Ok(Self::new(protocol, sink, stream))
+    }
+
+    /// Drives a connection made of the text frames `sink` and `stream` with `protocol`.
+    pub fn new<T, S>(protocol: P, sink: T, stream: S) -> Self
+    where
+        T: Sink<String> + Send + 'static,
+        S: Stream<Item = Result<String>> + Send + 'static,
+    {
+        let (commands, receiver) = unbounded_channel();
+        let driver = Driver {
+            protocol,
+            requests: 0,
+            active: HashMap::new(),
+            pending: VecDeque::new(),
+        };
+        tokio::spawn(driver.run(receiver, Box::pin(sink), Box::pin(stream)));
+        Self {
+            commands,
+            handles: Default::default(),
+        }
+    }
+
+    /// Subscribes to `subscription`, returning once the exchange acknowledged it.
+    pub async fn subscribe(&self, subscription: P::Subscription) -> Result<SubscriptionHandle<P>> {
+        let handle = self.handles.fetch_add(1, Ordering::SeqCst);
+        let (sender, messages) = unbounded_channel();
+        let (reply, response) = oneshot::channel();
+        self.send(Command::Subscribe {
+            subscription: subscription.clone(),
+            consumer: (handle, sender),
+            reply,
+        })?;
+        response.await.map_err(|_| EcbtError::SocketError())??;
+        Ok(SubscriptionHandle::new(
+            handle,
+            subscription,
+            messages,
+            self.commands.clone(),
+        ))
+    }
+
+    /// Returns the subscriptions acknowledged by the exchange and still in use.
+    pub async fn subscriptions(&self) -> Result<Vec<P::Subscription>> {
+        let (reply, response) = oneshot::channel();
+        self.send(Command::Subscriptions(reply))?;
+        response.await.map_err(|_| EcbtError::SocketError())
+    }
+
+    /// Closes the connection, ending the streams of its handles.
+    pub fn close(&self) {
+        self.commands.send(Command::Close).ok();
+    }
+
+    fn send(&self, command: Command<P>) -> Result<()> {
+        self.commands
+            .send(command)
+            .map_err(|_| EcbtError::SocketError())
+    }
+}
+
+enum Request<M> {
+    Subscribe(Vec<Consumer<M>>, Vec<Reply<()>>),
+    Unsubscribe(Option<Reply<()>>),
+}
+
+struct Pending<P: SubscriptionProtocol> {
+    id: u64,
+    subscription: P::Subscription,
+    request: Request<P::Message>,
+}
+
+struct Driver<P: SubscriptionProtocol> {
+    protocol: P,
+    requests: u64,
+    active: HashMap<P::Subscription, Vec<Consumer<P::Message>>>,
+    /// Requests waiting for their acknowledgement, in the order they were sent.
+    pending: VecDeque<Pending<P>>,
+}
+
+impl<P: SubscriptionProtocol> Driver<P> {
+    async fn run<T, S>(
+        mut self,
+        mut commands: UnboundedReceiver<Command<P>>,
+        mut sink: Pin<Box<T>>,
+        mut stream: Pin<Box<S>>,
+    ) where
+        T: Sink<String> + Send + ?Sized,
+        S: Stream<Item = Result<String>> + Send + ?Sized,
+    {
+        loop {
+            let frames = tokio::select! {
+                command = commands.recv() => match command {
+                    None | Some(Command::Close) => break,
+                    Some(command) => self.command(command),
+                },
+                frame = stream.next() => match frame {
+                    Some(Ok(text)) => self.receive(&text),
+                    _ => break,
+                },
+            };
+            for frame in frames {
+                if sink.send(frame).await.is_err() {
+                    self.close();
+                    return;
+                }
+            }
+        }
+        self.close();
+        sink.close().await.ok();
+    }
+
+    /// Applies `command`, returning the frames to send.
+    fn command(&mut self, command: Command<P>) -> Vec<String> {
+        match command {
+            Command::Subscribe {
+                subscription,
+                consumer,
+                reply,
+            } => {
+                if let Some(consumers) = self.active.get_mut(&subscription) {
+                    consumers.push(consumer);
+                    reply.send(Ok(())).ok();
+                    return Vec::new();
+                }
+                let pending =
+                    self.pending
+                        .iter_mut()
+                        .find_map(|pending| match &mut pending.request {
+                            Request::Subscribe(consumers, replies)
+                                if pending.subscription == subscription =>
+                            {
+                                Some((consumers, replies))
+                            }
+                            _ => None,
+                        });
+                if let Some((consumers, replies)) = pending {
+                    consumers.push(consumer);
+                    replies.push(reply);
+                    return Vec::new();
+                }
+                match self.frame(&subscription, P::subscribe_request) {
+                    Ok((id, frame)) => {
+                        let request = Request::Subscribe(vec![consumer], vec![reply]);
+                        self.queue(id, subscription, request);
+                        vec![frame]
+                    }
+                    Err(err) => {
+                        reply.send(Err(err)).ok();
+                        Vec::new()
+                    }
+                }
+            }
+            Command::Unsubscribe { handle, reply } => {
+                let mut frames = Vec::new();
+                for pending in self.pending.iter_mut() {
+                    if let Request::Subscribe(consumers, _) = &mut pending.request {
+                        consumers.retain(|(id, _)| *id != handle);
+                    }
+                }
+                let subscription = self
+                    .active
+                    .iter_mut()
+                    .find_map(|(subscription, consumers)| {
+                        let count = consumers.len();
+                        consumers.retain(|(id, _)| *id != handle);
+                        (consumers.is_empty() && count > 0).then(|| subscription.clone())
+                    });
+                match subscription {
+                    Some(subscription) => {
+                        self.active.remove(&subscription);
+                        match self.frame(&subscription, P::unsubscribe_request) {
+                            Ok((id, frame)) => {
+                                self.queue(id, subscription, Request::Unsubscribe(reply));
+                                frames.push(frame);
+                            }
+                            Err(err) => {
+                                if let Some(reply) = reply {
+                                    reply.send(Err(err)).ok();
+                                }
+                            }
+                        }
+                    }
+                    None => {
+                        if let Some(reply) = reply {
+                            reply.send(Ok(())).ok();
+                        }
+                    }
+                }
+                frames
+            }
+            Command::Subscriptions(reply) => {
+                reply.send(self.active.keys().cloned().collect()).ok();
+                Vec::new()
+            }
+            Command::Close => Vec::new(),
+        }
+    }
+
+    /// Handles a frame received from the exchange, returning the frames to send.
+    fn receive(&mut self, text: &str) -> Vec<String> {
+        match self.protocol.parse(text) {
+            Ok(ProtocolMessage::Ack(id)) => match self.acknowledged(id) {
+                Some(Pending {
+                    subscription,
+                    request: Request::Subscribe(consumers, replies),
+                    ..
+                }) => {
+                    for reply in replies {
+                        reply.send(Ok(())).ok();
+                    }
+                    // Every handle was dropped while the request was pending.
+                    if consumers.is_empty() {
+                        return match self.frame(&subscription, P::unsubscribe_request) {
+                            Ok((id, frame)) => {
+                                self.queue(id, subscription, Request::Unsubscribe(None));
+                                vec![frame]
+                            }
+                            Err(_) => Vec::new(),
+                        };
+                    }
+                    self.active
+                        .entry(subscription)
+                        .or_default()
+                        .extend(consumers);
+                }
+                Some(Pending {
+                    request: Request::Unsubscribe(Some(reply)),
+                    ..
+                }) => {
+                    reply.send(Ok(())).ok();
+                }
+                _ => (),
+            },
+            Ok(ProtocolMessage::Rejected(id, reason)) => match self.acknowledged(id) {
+                Some(Pending {
+                    request: Request::Subscribe(_, replies),
+                    ..
+                }) => {
+                    for reply in replies {
+                        reply
+                            .send(Err(EcbtError::InvalidParameter(reason.clone())))
+                            .ok();
+                    }
+                }
+                Some(Pending {
+                    request: Request::Unsubscribe(Some(reply)),
+                    ..
+                }) => {
+                    reply.send(Err(EcbtError::InvalidParameter(reason))).ok();
+                }
+                Some(_) => (),
+                None => self.broadcast(|| EcbtError::UnkownResponse(reason.clone())),
+            },
+            Ok(ProtocolMessage::Data(topic, message)) => {
+                for (subscription, consumers) in &self.active {
+                    if self
+                        .protocol
+                        .routes(subscription, topic.as_deref(), &message)
+                    {
+                        for (_, sender) in consumers {
+                            sender.send(Ok(message.clone())).ok();
+                        }
+                    }
+                }
+            }
+            Err(err) => self.broadcast(|| EcbtError::NotParsableResponse(err.to_string())),
+        }
+        Vec::new()
+    }
+
+    /// Returns the id and the frame of a new request about `subscription`.
+    fn frame(
+        &mut self,
+        subscription: &P::Subscription,
+        frame: fn(&P, u64, &P::Subscription) -> Result<String>,
+    ) -> Result<(u64, String)> {
+        self.requests += 1;
+        let id = self.requests;
+        Ok((id, frame(&self.protocol, id, subscription)?))
+    }
+
+    fn queue(&mut self, id: u64, subscription: P::Subscription, request: Request<P::Message>) {
+        self.pending.push_back(Pending {
+            id,
+            subscription,
+            request,
+        });
+    }
+
+    /// Removes the request acknowledged by a message carrying `id`.
+    fn acknowledged(&mut self, id: Option<u64>) -> Option<Pending<P>> {
+        match id {
+            Some(id) => {
+                let index = self.pending.iter().position(|pending| pending.id == id)?;
+                self.pending.remove(index)
+            }
+            None => self.pending.pop_front(),
+        }
+    }
+
+    fn broadcast(&self, err: impl Fn() -> EcbtError) {
+        for consumers in self.active.values() {
+            for (_, sender) in consumers {
+                sender.send(Err(err())).ok();
+            }
+        }
+    }
+
+    /// Ends the streams of the handles and fails the pending requests.
+    fn close(&mut self) {
+        self.broadcast(EcbtError::SocketError);
+        self.active.clear();
+        for pending in self.pending.drain(..) {
+            match pending.request {
+                Request::Subscribe(_, replies) => {
+                    for reply in replies {
+                        reply.send(Err(EcbtError::SocketError())).ok();
+                    }
+                }
+                Request::Unsubscribe(Some(reply)) => {
+                    reply.send(Err(EcbtError::SocketError())).ok();
+                }
+                Request::Unsubscribe(None) => (),
+            }
+        }
+    }
+}
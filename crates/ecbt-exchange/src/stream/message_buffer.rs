@@ -0,0 +1,156 @@
+use crate::model::websocket::{EcbtWebSocketMessage, WebSocketResponse};
+use crate::shared::Result;
+use crate::stream::{OverflowPolicy, SubscriptionOptions};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+use std::task::{Context, Poll, Waker};
+use tokio::sync::Notify;
+
+type Message<R> = Result<WebSocketResponse<R>>;
+
+struct State<R> {
+    messages: VecDeque<(Option<String>, Message<R>)>,
+    closed: bool,
+    reader: Option<Waker>,
+}
+
+/// This struct represents the messages of a `CallbackHandle` waiting to be read, written by
+/// the task reading its connection.
+pub(crate) struct MessageBuffer<R> {
+    state: Mutex<State<R>>,
+    /// Notified when a message is read, for writers blocked on a full buffer.
+    room: Notify,
+    capacity: usize,
+    policy: OverflowPolicy,
+    dropped: AtomicU64,
+}
+
+impl<R> MessageBuffer<R> {
+    pub(crate) fn new(options: &SubscriptionOptions) -> Self {
+        Self {
+            state: Mutex::new(State {
+                messages: VecDeque::new(),
+                closed: false,
+                reader: None,
+            }),
+            room: Notify::new(),
+            capacity: options.buffer_size.max(1),
+            policy: options.overflow_policy,
+            dropped: AtomicU64::new(0),
+        }
+    }
+
+    pub(crate) fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Buffers `message` following the overflow policy.
+    pub(crate) async fn push(&self, message: Message<R>) {
+        let key = match self.policy {
+            OverflowPolicy::ConflateLatest => conflation_key(&message),
+            _ => None,
+        };
+        let mut message = Some(message);
+        loop {
+            let room = self.room.notified();
+            {
+                let mut state = self.lock();
+                let full = state.messages.len() >= self.capacity;
+                if let Some(key) = &key {
+                    let latest = state
+                        .messages
+                        .iter_mut()
+                        .find(|(buffered, _)| buffered.as_ref() == Some(key));
+                    if let Some((_, buffered)) = latest {
+                        *buffered = message.take().expect("The message is pushed once.");
+                        self.drop_one();
+                        return;
+                    }
+                }
+                match self.policy {
+                    OverflowPolicy::Block if full => (),
+                    OverflowPolicy::DropNewest if full => {
+                        self.drop_one();
+                        return;
+                    }
+                    OverflowPolicy::DropOldest | OverflowPolicy::ConflateLatest if full => {
+                        state.messages.pop_front();
+                        self.drop_one();
+                        self.append(&mut state, key, message.take());
+                        return;
+                    }
+                    _ => {
+                        self.append(&mut state, key, message.take());
+                        return;
+                    }
+                }
+            }
+            room.await;
+        }
+    }
+
+    /// Ends the stream of the handle once the buffered messages are read.
+    pub(crate) fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        if let Some(reader) = state.reader.take() {
+            reader.wake();
+        }
+    }
+
+    pub(crate) fn poll_next(&self, cx: &mut Context<'_>) -> Poll<Option<Message<R>>> {
+        let mut state = self.lock();
+        match state.messages.pop_front() {
+            Some((_, message)) => {
+                self.room.notify_one();
+                Poll::Ready(Some(message))
+            }
+            None if state.closed => Poll::Ready(None),
+            None => {
+                state.reader = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    fn append(&self, state: &mut State<R>, key: Option<String>, message: Option<Message<R>>) {
+        if let Some(message) = message {
+            state.messages.push_back((key, message));
+        }
+        if let Some(reader) = state.reader.take() {
+            reader.wake();
+        }
+    }
+
+    fn drop_one(&self) {
+        self.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<R>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Returns the key under which `message` replaces an older one when conflating: order books,
+/// tickers and book tickers of a market, and candles of a market, interval and start time.
+fn conflation_key<R>(message: &Message<R>) -> Option<String> {
+    match message {
+        Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(update))) => {
+            Some(format!("order_book:{}", update.market_pair))
+        }
+        Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::Ticker(ticker))) => {
+            Some(format!("ticker:{}", ticker.market_pair))
+        }
+        Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::BookTicker(ticker))) => {
+            Some(format!("book_ticker:{}", ticker.market_pair))
+        }
+        Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::Candle(candle))) => Some(format!(
+            "candle:{}:{}:{}",
+            candle.market_pair,
+            <&str>::from(candle.interval),
+            candle.candle.time
+        )),
+        _ => None,
+    }
+}
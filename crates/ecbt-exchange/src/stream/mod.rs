@@ -1,10 +1,31 @@
 pub mod callback_handle;
+pub mod candle_aggregator;
+pub mod connection_event;
 pub mod ecbt_stream;
 pub mod exchange_stream;
+pub mod live_connection;
+mod message_buffer;
+pub mod overflow_policy;
+pub mod reconnect_policy;
+pub mod reconnecting_stream;
+pub mod subscription_handle;
+pub mod subscription_options;
+pub mod subscription_protocol;
 pub mod subscriptions;
+#[cfg(test)]
+mod tests;
 
 pub use crate::shared;
 pub use callback_handle::CallbackHandle;
+pub use candle_aggregator::CandleAggregator;
+pub use connection_event::ConnectionEvent;
 pub use ecbt_stream::EcbtStream;
 pub use exchange_stream::ExchangeStream;
+pub use live_connection::LiveConnection;
+pub use overflow_policy::OverflowPolicy;
+pub use reconnect_policy::ReconnectPolicy;
+pub use reconnecting_stream::reconnecting_stream;
+pub use subscription_handle::SubscriptionHandle;
+pub use subscription_options::SubscriptionOptions;
+pub use subscription_protocol::{ProtocolMessage, SubscriptionProtocol};
 pub use subscriptions::Subscriptions;
@@ -0,0 +1,16 @@
+/// This enum represents what a `CallbackHandle` does with a new message when its buffer is
+/// full because the handle isn't read fast enough.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Waits for room in the buffer, which stops reading the connection and calling the
+    /// callback until the handle is read.
+    Block,
+    /// Drops the oldest buffered message.
+    #[default]
+    DropOldest,
+    /// Drops the new message.
+    DropNewest,
+    /// Replaces a buffered order book, or a buffered ticker or candle of the same market, with
+    /// the new one whether the buffer is full or not. Other messages are dropped oldest first.
+    ConflateLatest,
+}
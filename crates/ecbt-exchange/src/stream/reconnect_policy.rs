@@ -0,0 +1,78 @@
+use rand::Rng;
+use std::time::Duration;
+
+/// This struct represents how a websocket stream re-establishes a dropped connection.
+///
+/// The delay before the `n`th attempt is `initial_backoff * multiplier^n`, capped at
+/// `max_backoff` and reduced by a random fraction of at most `jitter` so that many clients
+/// dropped at once don't reconnect in lockstep.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub multiplier: f64,
+    /// Fraction of the backoff, between 0 and 1, that is randomly taken off each delay.
+    pub jitter: f64,
+    /// Consecutive failed attempts after which the stream gives up, `None` retries forever.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multiplier: 2.0,
+            jitter: 0.2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    pub fn with_max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    pub fn with_multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Delay before the reconnection attempt numbered `attempt`, starting at 0.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self.initial_backoff.as_secs_f64() * self.multiplier.powi(attempt as i32);
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+        let jitter = if self.jitter > 0.0 {
+            rand::thread_rng().gen_range(0.0..self.jitter)
+        } else {
+            0.0
+        };
+        Duration::from_secs_f64(backoff * (1.0 - jitter))
+    }
+
+    /// Whether another attempt is allowed after `attempts` consecutive failures.
+    pub fn allows(&self, attempts: u32) -> bool {
+        self.max_attempts.is_none_or(|max| attempts < max)
+    }
+}
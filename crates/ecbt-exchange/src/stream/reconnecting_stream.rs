@@ -0,0 +1,91 @@
+use crate::errors::EcbtError;
+use crate::shared::Result;
+use crate::stream::{ConnectionEvent, ReconnectPolicy};
+use futures::future::BoxFuture;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+
+type Connection<T> = BoxStream<'static, Result<T>>;
+
+struct State<T, C, D> {
+    connection: Option<Connection<T>>,
+    connect: C,
+    is_closed: D,
+    policy: ReconnectPolicy,
+    exhausted: bool,
+}
+
+/// Wraps an established websocket `connection` so that it is re-established with `connect`
+/// whenever it drops, following `policy`.
+///
+/// `connect` opens a new connection with the original subscriptions. A `Disconnected` event
+/// is yielded when the connection is lost and a `Reconnected` event once a new one is up.
+/// The stream ends when `is_closed` returns true, meaning the connection was closed on
+/// purpose, and yields the last connection error once the policy runs out of attempts.
+pub fn reconnecting_stream<T, C, D>(
+    connection: Connection<T>,
+    policy: ReconnectPolicy,
+    connect: C,
+    is_closed: D,
+) -> Connection<T>
+where
+    T: From<ConnectionEvent> + Send + 'static,
+    C: FnMut() -> BoxFuture<'static, Result<Connection<T>>> + Send + 'static,
+    D: Fn() -> bool + Send + 'static,
+{
+    let state = State {
+        connection: Some(connection),
+        connect,
+        is_closed,
+        policy,
+        exhausted: false,
+    };
+
+    stream::unfold(state, |mut state| async move {
+        if state.exhausted || (state.is_closed)() {
+            return None;
+        }
+
+        if let Some(connection) = state.connection.as_mut() {
+            match connection.next().await {
+                Some(Err(err)) if !is_connection_lost(&err) => return Some((Err(err), state)),
+                Some(Ok(message)) => return Some((Ok(message), state)),
+                _ => {
+                    state.connection = None;
+                    if (state.is_closed)() {
+                        return None;
+                    }
+                    let event = T::from(ConnectionEvent::Disconnected);
+                    return Some((Ok(event), state));
+                }
+            }
+        }
+
+        let mut attempts = 0;
+        loop {
+            tokio::time::sleep(state.policy.backoff(attempts)).await;
+            if (state.is_closed)() {
+                return None;
+            }
+            match (state.connect)().await {
+                Ok(connection) => {
+                    state.connection = Some(connection);
+                    let event = T::from(ConnectionEvent::Reconnected);
+                    return Some((Ok(event), state));
+                }
+                Err(err) => {
+                    attempts += 1;
+                    if !state.policy.allows(attempts) {
+                        state.exhausted = true;
+                        return Some((Err(err), state));
+                    }
+                }
+            }
+        }
+    })
+    .boxed()
+}
+
+fn is_connection_lost(err: &EcbtError) -> bool {
+    matches!(err, EcbtError::SocketError() | EcbtError::Tungstenite(_))
+}
@@ -0,0 +1,80 @@
+use crate::errors::EcbtError;
+use crate::shared::Result;
+use crate::stream::live_connection::Command;
+use crate::stream::SubscriptionProtocol;
+use futures::Stream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+
+/// This struct represents a subscription of a `LiveConnection`, streaming its messages.
+///
+/// Dropping the handle unsubscribes without waiting for the exchange, `unsubscribe` waits for
+/// the acknowledgement. The stream ends when the connection closes.
+pub struct SubscriptionHandle<P: SubscriptionProtocol> {
+    id: u64,
+    subscription: P::Subscription,
+    messages: UnboundedReceiver<Result<P::Message>>,
+    commands: UnboundedSender<Command<P>>,
+    subscribed: bool,
+}
+
+impl<P: SubscriptionProtocol> SubscriptionHandle<P> {
+    pub(crate) fn new(
+        id: u64,
+        subscription: P::Subscription,
+        messages: UnboundedReceiver<Result<P::Message>>,
+        commands: UnboundedSender<Command<P>>,
+    ) -> Self {
+        Self {
+            id,
+            subscription,
+            messages,
+            commands,
+            subscribed: true,
+        }
+    }
+
+    pub fn subscription(&self) -> &P::Subscription {
+        &self.subscription
+    }
+
+    /// Unsubscribes, returning once the exchange acknowledged it when this was the last handle
+    /// of the subscription.
+    pub async fn unsubscribe(mut self) -> Result<()> {
+        self.subscribed = false;
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .send(Command::Unsubscribe {
+                handle: self.id,
+                reply: Some(reply),
+            })
+            .map_err(|_| EcbtError::SocketError())?;
+        response.await.map_err(|_| EcbtError::SocketError())?
+    }
+}
+
+// The subscription is never pinned, only the receiver is polled.
+impl<P: SubscriptionProtocol> Unpin for SubscriptionHandle<P> {}
+
+impl<P: SubscriptionProtocol> Stream for SubscriptionHandle<P> {
+    type Item = Result<P::Message>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.messages.poll_recv(cx)
+    }
+}
+
+impl<P: SubscriptionProtocol> Drop for SubscriptionHandle<P> {
+    fn drop(&mut self) {
+        if self.subscribed {
+            self.commands
+                .send(Command::Unsubscribe {
+                    handle: self.id,
+                    reply: None,
+                })
+                .ok();
+        }
+    }
+}
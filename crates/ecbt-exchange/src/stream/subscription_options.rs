@@ -0,0 +1,35 @@
+use crate::stream::OverflowPolicy;
+
+/// This struct represents how a `CallbackHandle` buffers the messages of its subscription
+/// until they are read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubscriptionOptions {
+    /// Number of messages buffered before the overflow policy applies, at least 1.
+    pub buffer_size: usize,
+    pub overflow_policy: OverflowPolicy,
+}
+
+impl Default for SubscriptionOptions {
+    fn default() -> Self {
+        Self {
+            buffer_size: 1024,
+            overflow_policy: OverflowPolicy::default(),
+        }
+    }
+}
+
+impl SubscriptionOptions {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    pub fn with_overflow_policy(mut self, overflow_policy: OverflowPolicy) -> Self {
+        self.overflow_policy = overflow_policy;
+        self
+    }
+}
@@ -0,0 +1,38 @@
+use crate::shared::Result;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+/// This enum represents a text frame received on a `LiveConnection`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProtocolMessage<M> {
+    /// Acknowledges the request with this id, or the oldest pending request for exchanges
+    /// whose acknowledgements don't carry the request id.
+    Ack(Option<u64>),
+    /// Rejects the request with this id, or the oldest pending request, with a reason.
+    Rejected(Option<u64>, String),
+    /// A message published on a topic, or on no topic for exchanges routing by content.
+    Data(Option<String>, M),
+}
+
+/// This trait describes how an exchange adds and removes subscriptions on a live websocket
+/// connection, and which subscriptions receive each message.
+pub trait SubscriptionProtocol: Send + Sync + 'static {
+    type Subscription: Clone + Eq + Hash + Debug + Send + Sync + 'static;
+    type Message: Clone + Debug + Send + 'static;
+
+    /// Returns the text frame subscribing to `subscription`, sent as request `id`.
+    fn subscribe_request(&self, id: u64, subscription: &Self::Subscription) -> Result<String>;
+
+    /// Returns the text frame unsubscribing from `subscription`, sent as request `id`.
+    fn unsubscribe_request(&self, id: u64, subscription: &Self::Subscription) -> Result<String>;
+
+    fn parse(&self, text: &str) -> Result<ProtocolMessage<Self::Message>>;
+
+    /// Returns whether `subscription` receives `message`, published on `topic`.
+    fn routes(
+        &self,
+        subscription: &Self::Subscription,
+        topic: Option<&str>,
+        message: &Self::Message,
+    ) -> bool;
+}
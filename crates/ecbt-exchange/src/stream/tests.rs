@@ -0,0 +1,451 @@
+use super::message_buffer::MessageBuffer;
+use super::{
+    reconnecting_stream, CallbackHandle, CandleAggregator, ConnectionEvent, ExchangeStream,
+    LiveConnection, OverflowPolicy, ProtocolMessage, ReconnectPolicy, SubscriptionOptions,
+    SubscriptionProtocol, Subscriptions,
+};
+use crate::errors::EcbtError;
+use crate::model::currency::Currency;
+use crate::model::market_pair::MarketPair;
+use crate::model::websocket::{
+    EcbtWebSocketMessage, OrderBookUpdate, Subscription, TickerUpdate, WebSocketResponse,
+};
+use crate::model::{Interval, OrderBookResponse, Ticker};
+use crate::shared::Result;
+use async_trait::async_trait;
+use futures::channel::mpsc;
+use futures::stream::{self, BoxStream};
+use futures::{future, FutureExt, StreamExt};
+use rust_decimal_macros::dec;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+#[derive(Debug, PartialEq)]
+enum Message {
+    Data(u32),
+    Event(ConnectionEvent),
+}
+
+impl From<ConnectionEvent> for Message {
+    fn from(event: ConnectionEvent) -> Self {
+        Message::Event(event)
+    }
+}
+
+fn connection(data: Vec<u32>) -> BoxStream<'static, Result<Message>> {
+    stream::iter(data.into_iter().map(|n| Ok(Message::Data(n)))).boxed()
+}
+
+fn policy() -> ReconnectPolicy {
+    ReconnectPolicy::new()
+        .with_initial_backoff(Duration::from_millis(1))
+        .with_jitter(0.0)
+}
+
+#[test]
+fn backoff_grows_up_to_the_maximum() {
+    let policy = ReconnectPolicy::new()
+        .with_initial_backoff(Duration::from_millis(100))
+        .with_max_backoff(Duration::from_millis(500))
+        .with_jitter(0.5);
+
+    for (attempt, expected) in [(0, 100), (1, 200), (2, 400), (3, 500), (10, 500)] {
+        let backoff = policy.backoff(attempt);
+        assert!(backoff <= Duration::from_millis(expected));
+        assert!(backoff >= Duration::from_millis(expected / 2));
+    }
+}
+
+#[tokio::test]
+async fn reconnects_and_reports_connection_events() {
+    let connections = Arc::new(AtomicU32::new(0));
+    let connect = {
+        let connections = connections.clone();
+        move || {
+            let n = connections.fetch_add(1, Ordering::SeqCst);
+            async move {
+                match n {
+                    0 => Err(EcbtError::SocketError()),
+                    _ => Ok(connection(vec![2])),
+                }
+            }
+            .boxed()
+        }
+    };
+    let stream = reconnecting_stream(connection(vec![1]), policy(), connect, || false);
+
+    let messages: Vec<_> = stream
+        .take(4)
+        .map(|message| message.unwrap())
+        .collect()
+        .await;
+    assert_eq!(
+        messages,
+        vec![
+            Message::Data(1),
+            Message::Event(ConnectionEvent::Disconnected),
+            Message::Event(ConnectionEvent::Reconnected),
+            Message::Data(2),
+        ]
+    );
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let connect = || async { Err(EcbtError::SocketError()) }.boxed();
+    let stream = reconnecting_stream(
+        connection(vec![]),
+        policy().with_max_attempts(3),
+        connect,
+        || false,
+    );
+
+    let messages: Vec<_> = stream.collect().await;
+    assert_eq!(messages.len(), 2);
+    assert!(matches!(
+        messages[0],
+        Ok(Message::Event(ConnectionEvent::Disconnected))
+    ));
+    assert!(matches!(messages[1], Err(EcbtError::SocketError())));
+}
+
+#[tokio::test]
+async fn ends_when_closed_on_purpose() {
+    let connect = || async { Ok(connection(vec![2])) }.boxed();
+    let stream = reconnecting_stream(connection(vec![1]), policy(), connect, || true);
+
+    assert_eq!(stream.count().await, 0);
+}
+
+#[test]
+fn aggregates_trades_into_candles() {
+    let mut aggregator = CandleAggregator::new("BTC-USD", Interval::OneMinute);
+
+    let updates = aggregator.push(60_500, dec!(10), dec!(1));
+    assert_eq!(updates.len(), 1);
+    assert_eq!(updates[0].candle.time, 60_000);
+    assert!(!updates[0].closed);
+
+    aggregator.push(61_000, dec!(12), dec!(2));
+    let updates = aggregator.push(62_000, dec!(9), dec!(1));
+    let candle = &updates[0].candle;
+    assert_eq!(
+        (
+            candle.open,
+            candle.high,
+            candle.low,
+            candle.close,
+            candle.volume
+        ),
+        (dec!(10), dec!(12), dec!(9), dec!(9), dec!(4))
+    );
+
+    // Trades of a previous candle are ignored.
+    assert!(aggregator.push(59_000, dec!(1), dec!(1)).is_empty());
+
+    let updates = aggregator.push(120_000, dec!(11), dec!(3));
+    assert_eq!(updates.len(), 2);
+    assert!(updates[0].closed);
+    assert_eq!(updates[0].candle.close, dec!(9));
+    assert!(!updates[1].closed);
+    assert_eq!(updates[1].candle.time, 120_000);
+    assert_eq!(updates[1].candle.open, dec!(11));
+}
+
+#[test]
+fn monthly_candles_follow_calendar_months() {
+    let mut aggregator = CandleAggregator::new("BTC-USD", Interval::OneMonth);
+
+    // 2024-01-31T12:00:00Z belongs to the candle of 2024-01-01.
+    let updates = aggregator.push(1_706_702_400_000, dec!(10), dec!(1));
+    assert_eq!(updates[0].candle.time, 1_704_067_200_000);
+
+    // 2024-02-01T01:00:00Z opens the candle of 2024-02-01.
+    let updates = aggregator.push(1_706_749_200_000, dec!(11), dec!(1));
+    assert_eq!(updates.len(), 2);
+    assert!(updates[0].closed);
+    assert_eq!(updates[1].candle.time, 1_706_745_600_000);
+}
+
+/// Subscribes with "sub {id} {topic}", acknowledges with "ack {id}", rejects with
+/// "reject {id} {reason}" and publishes with "{topic} {message}".
+struct TextProtocol;
+
+impl SubscriptionProtocol for TextProtocol {
+    type Subscription = String;
+    type Message = String;
+
+    fn subscribe_request(&self, id: u64, subscription: &String) -> Result<String> {
+        Ok(format!("sub {} {}", id, subscription))
+    }
+
+    fn unsubscribe_request(&self, id: u64, subscription: &String) -> Result<String> {
+        Ok(format!("unsub {} {}", id, subscription))
+    }
+
+    fn parse(&self, text: &str) -> Result<ProtocolMessage<String>> {
+        let id = |id: &str| {
+            id.parse()
+                .map_err(|_| EcbtError::UnkownResponse(text.into()))
+        };
+        Ok(match text.split(' ').collect::<Vec<_>>().as_slice() {
+            ["ack", n] => ProtocolMessage::Ack(Some(id(n)?)),
+            ["reject", n, reason] => ProtocolMessage::Rejected(Some(id(n)?), reason.to_string()),
+            [topic, message] => ProtocolMessage::Data(Some(topic.to_string()), message.to_string()),
+            _ => return Err(EcbtError::UnkownResponse(text.into())),
+        })
+    }
+
+    fn routes(&self, subscription: &String, topic: Option<&str>, _message: &String) -> bool {
+        topic == Some(subscription.as_str())
+    }
+}
+
+#[tokio::test]
+async fn live_connection_adds_and_removes_subscriptions() {
+    let (sink, mut sent) = mpsc::unbounded::<String>();
+    let (exchange, received) = mpsc::unbounded::<Result<String>>();
+    let connection = LiveConnection::new(TextProtocol, sink, received);
+    let reply = |text: &str| exchange.unbounded_send(Ok(text.to_string())).unwrap();
+
+    let subscribing = tokio::spawn({
+        let connection = connection.clone();
+        async move { connection.subscribe("trades".to_string()).await }
+    });
+    assert_eq!(sent.next().await.unwrap(), "sub 1 trades");
+    reply("ack 1");
+    let mut trades = subscribing.await.unwrap().unwrap();
+    // The second handle shares the acknowledged subscription.
+    let mut shared = connection.subscribe("trades".to_string()).await.unwrap();
+
+    let subscribing = tokio::spawn({
+        let connection = connection.clone();
+        async move { connection.subscribe("book".to_string()).await }
+    });
+    assert_eq!(sent.next().await.unwrap(), "sub 2 book");
+    reply("reject 2 unknown");
+    assert!(matches!(
+        subscribing.await.unwrap(),
+        Err(EcbtError::InvalidParameter(reason)) if reason == "unknown"
+    ));
+
+    reply("book 1");
+    reply("trades 42");
+    assert_eq!(trades.next().await.unwrap().unwrap(), "42");
+    assert_eq!(shared.next().await.unwrap().unwrap(), "42");
+    assert_eq!(connection.subscriptions().await.unwrap(), vec!["trades"]);
+
+    // The exchange is only unsubscribed when the last handle goes away.
+    drop(trades);
+    let unsubscribing = tokio::spawn(shared.unsubscribe());
+    assert_eq!(sent.next().await.unwrap(), "unsub 3 trades");
+    reply("ack 3");
+    unsubscribing.await.unwrap().unwrap();
+    assert!(connection.subscriptions().await.unwrap().is_empty());
+
+    connection.close();
+    assert!(sent.next().await.is_none());
+}
+
+async fn read(buffer: &MessageBuffer<u32>) -> Option<u32> {
+    match future::poll_fn(|cx| buffer.poll_next(cx)).await? {
+        Ok(WebSocketResponse::Raw(n)) => Some(n),
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[tokio::test]
+async fn message_buffer_applies_the_overflow_policy() {
+    let options = SubscriptionOptions::new().with_buffer_size(2);
+
+    for (policy, expected) in [
+        (OverflowPolicy::DropOldest, [2, 3]),
+        (OverflowPolicy::DropNewest, [1, 2]),
+    ] {
+        let buffer = MessageBuffer::new(&options.clone().with_overflow_policy(policy));
+        for n in 1..=3 {
+            buffer.push(Ok(WebSocketResponse::Raw(n))).await;
+        }
+        buffer.close();
+        assert_eq!(read(&buffer).await, Some(expected[0]));
+        assert_eq!(read(&buffer).await, Some(expected[1]));
+        assert_eq!(read(&buffer).await, None);
+        assert_eq!(buffer.dropped(), 1);
+    }
+
+    // A blocked writer resumes once a message is read.
+    let options = options.with_overflow_policy(OverflowPolicy::Block);
+    let buffer = Arc::new(MessageBuffer::new(&options));
+    for n in 1..=2 {
+        buffer.push(Ok(WebSocketResponse::Raw(n))).await;
+    }
+    let mut writer = tokio::spawn({
+        let buffer = buffer.clone();
+        async move { buffer.push(Ok(WebSocketResponse::Raw(3))).await }
+    });
+    assert!(tokio::time::timeout(Duration::from_millis(20), &mut writer)
+        .await
+        .is_err());
+    assert_eq!(read(&buffer).await, Some(1));
+    writer.await.unwrap();
+    assert_eq!(read(&buffer).await, Some(2));
+    assert_eq!(read(&buffer).await, Some(3));
+    assert_eq!(buffer.dropped(), 0);
+}
+
+#[tokio::test]
+async fn message_buffer_conflates_the_latest_ticker_per_market() {
+    let ticker = |market_pair: &str, price| {
+        Ok(WebSocketResponse::<u32>::Generic(
+            EcbtWebSocketMessage::Ticker(TickerUpdate {
+                market_pair: market_pair.to_string(),
+                ticker: Ticker {
+                    price: Some(price),
+                    price_24h: None,
+                },
+                best_bid: None,
+                best_ask: None,
+                time: None,
+            }),
+        ))
+    };
+    let options = SubscriptionOptions::new().with_overflow_policy(OverflowPolicy::ConflateLatest);
+    let buffer = MessageBuffer::new(&options);
+    buffer.push(ticker("BTCUSDT", dec!(100))).await;
+    buffer.push(ticker("ETHUSDT", dec!(10))).await;
+    buffer.push(ticker("BTCUSDT", dec!(101))).await;
+
+    let mut prices = Vec::new();
+    for _ in 0..2 {
+        match future::poll_fn(|cx| buffer.poll_next(cx)).await {
+            Some(Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::Ticker(update)))) => {
+                prices.push((update.market_pair, update.ticker.price.unwrap()))
+            }
+            message => panic!("Unexpected message: {:?}", message),
+        }
+    }
+    assert_eq!(
+        prices,
+        vec![
+            ("BTCUSDT".to_string(), dec!(101)),
+            ("ETHUSDT".to_string(), dec!(10))
+        ]
+    );
+    assert_eq!(buffer.dropped(), 1);
+}
+
+#[tokio::test]
+async fn message_buffer_conflates_the_latest_order_book_per_market() {
+    let book = |market_pair: &str, update_id| {
+        Ok(WebSocketResponse::<u32>::Generic(
+            EcbtWebSocketMessage::OrderBook(OrderBookUpdate {
+                market_pair: market_pair.to_string(),
+                book: OrderBookResponse {
+                    update_id: Some(update_id),
+                    ..Default::default()
+                },
+            }),
+        ))
+    };
+    let options = SubscriptionOptions::new().with_overflow_policy(OverflowPolicy::ConflateLatest);
+    let buffer = MessageBuffer::new(&options);
+    buffer.push(book("BTCUSDT", 1)).await;
+    buffer.push(book("ETHUSDT", 2)).await;
+    buffer.push(book("BTCUSDT", 3)).await;
+
+    let mut books = Vec::new();
+    for _ in 0..2 {
+        match future::poll_fn(|cx| buffer.poll_next(cx)).await {
+            Some(Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(update)))) => {
+                books.push((update.market_pair, update.book.update_id.unwrap()))
+            }
+            message => panic!("Unexpected message: {:?}", message),
+        }
+    }
+    assert_eq!(
+        books,
+        vec![("BTCUSDT".to_string(), 3), ("ETHUSDT".to_string(), 2)]
+    );
+    assert_eq!(buffer.dropped(), 1);
+}
+
+#[derive(Debug, Clone)]
+struct Number(u32);
+
+impl TryFrom<Number> for WebSocketResponse<Number> {
+    type Error = EcbtError;
+
+    fn try_from(number: Number) -> Result<Self> {
+        Ok(WebSocketResponse::Raw(number))
+    }
+}
+
+/// Streams its messages once to every subscriber.
+struct Replay(Vec<std::result::Result<u32, String>>);
+
+#[async_trait]
+impl ExchangeStream for Replay {
+    type InitParams = Vec<std::result::Result<u32, String>>;
+    type Subscription = Subscription;
+    type Response = Number;
+
+    async fn new(messages: Self::InitParams) -> Result<Self> {
+        Ok(Self(messages))
+    }
+
+    async fn disconnect(&self) {}
+
+    async fn create_stream_specific(
+        &self,
+        _subscriptions: Subscriptions<Subscription>,
+    ) -> Result<BoxStream<'static, Result<Number>>> {
+        let messages = self
+            .0
+            .clone()
+            .into_iter()
+            .map(|message| message.map(Number).map_err(EcbtError::InvalidParameter));
+        Ok(stream::iter(messages).boxed())
+    }
+}
+
+#[tokio::test]
+async fn subscriptions_forward_stream_errors() {
+    let subscription = Subscription::Trades(MarketPair(Currency::BTC, Currency::USDT));
+    let read = |handle: CallbackHandle<Number>| {
+        handle
+            .map(|message| match message {
+                Ok(WebSocketResponse::Raw(Number(n))) => Ok(n),
+                Err(EcbtError::InvalidParameter(message)) => Err(message),
+                Err(EcbtError::SocketError()) => Err("closed".to_string()),
+                message => panic!("Unexpected message: {:?}", message),
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // The stream goes on after an error, and ends with `SocketError` when it closes.
+    let exchange = Replay::new(vec![Ok(1), Err("lagged".to_string()), Ok(2)])
+        .await
+        .unwrap();
+    let handle = exchange
+        .subscribe(subscription.clone(), |_| ())
+        .await
+        .unwrap();
+    assert_eq!(
+        read(handle).await,
+        vec![
+            Ok(1),
+            Err("lagged".to_string()),
+            Ok(2),
+            Err("closed".to_string())
+        ]
+    );
+
+    // The error a stream ends with is the last message.
+    let exchange = Replay::new(vec![Ok(1), Err("expired".to_string())])
+        .await
+        .unwrap();
+    let handle = exchange.subscribe(subscription, |_| ()).await.unwrap();
+    assert_eq!(read(handle).await, vec![Ok(1), Err("expired".to_string())]);
+}
@@ -0,0 +1,47 @@
+use super::shared::Result;
+use crate::errors::ExchangeErrorContext;
+use reqwest::header::{AsHeaderName, HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+use url::Url;
+
+/// This struct represents the raw response returned by an [`HttpTransport`](super::HttpTransport)
+#[derive(Debug, Clone)]
+pub struct HttpResponse {
+    pub url: Url,
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl HttpResponse {
+    /// Returns the value of a header if it is present and valid UTF-8.
+    pub fn header<K: AsHeaderName>(&self, name: K) -> Option<&str> {
+        self.headers.get(name).and_then(|value| value.to_str().ok())
+    }
+
+    /// Returns the body as text, replacing invalid UTF-8 sequences.
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.body).into_owned()
+    }
+
+    /// Deserializes the body as JSON.
+    pub fn json<O: DeserializeOwned>(&self) -> Result<O> {
+        Ok(serde_json::from_slice(&self.body)?)
+    }
+
+    /// Returns the `Retry-After` header in seconds as a duration.
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.header(RETRY_AFTER)
+            .and_then(|seconds| seconds.parse().ok())
+            .map(Duration::from_secs)
+    }
+
+    /// Returns the context of an exchange error carried by this response.
+    pub fn error_context(&self, message: impl Into<String>) -> ExchangeErrorContext {
+        ExchangeErrorContext::new(message)
+            .with_endpoint(self.url.path())
+            .with_status(self.status.as_u16())
+    }
+}
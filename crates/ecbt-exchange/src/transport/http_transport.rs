@@ -0,0 +1,35 @@
+use super::shared::Result;
+use super::{HttpRequest, HttpResponse, ReqwestTransport};
+use async_trait::async_trait;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+/// The networking layer used by the exchange adapters.
+///
+/// Adapters build and sign every request themselves and hand it over to the transport, so an
+/// implementation is free to route it through its own connection pool, add headers or answer
+/// it without touching the network at all.
+#[async_trait]
+pub trait HttpTransport: Debug + Send + Sync {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse>;
+
+    /// Sends the request returned by `build`. Adapters send signed requests this way, so that
+    /// a transport sending a request more than once, like `RetryingTransport`, builds it again
+    /// with a fresh timestamp and signature for every attempt.
+    async fn send_with(
+        &self,
+        build: &(dyn Fn() -> Result<HttpRequest> + Send + Sync),
+    ) -> Result<HttpResponse> {
+        self.send(build()?).await
+    }
+}
+
+/// Returns the given transport, or a [`ReqwestTransport`] when none was configured.
+pub fn http_transport_or_default(
+    transport: Option<Arc<dyn HttpTransport>>,
+) -> Result<Arc<dyn HttpTransport>> {
+    match transport {
+        Some(transport) => Ok(transport),
+        None => Ok(Arc::new(ReqwestTransport::new()?)),
+    }
+}
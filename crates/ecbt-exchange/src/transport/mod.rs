@@ -0,0 +1,11 @@
+//! This module provides the pluggable HTTP layer used by the exchange adapters.
+mod http_request;
+mod http_response;
+mod http_transport;
+mod reqwest_transport;
+
+pub use crate::shared;
+pub use http_request::HttpRequest;
+pub use http_response::HttpResponse;
+pub use http_transport::{http_transport_or_default, HttpTransport};
+pub use reqwest_transport::ReqwestTransport;
@@ -11,7 +11,7 @@ async fn main() -> Result<()> {
     dotenv().ok();
     let options = Options::from_env_us();
     let option_clone = options.clone();
-    let api = Rest::new(options);
+    let api = Rest::new(options)?;
     let market = option_clone
         .clone()
         .to_market(MarketPair(Currency::ETH, Currency::USDT));
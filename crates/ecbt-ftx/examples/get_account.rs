@@ -7,7 +7,7 @@ use ecbt_ftx::{
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    let api = Rest::new(Options::from_env());
+    let api = Rest::new(Options::from_env()).unwrap();
     println!("Account:");
     println!("{:#?}", api.request(GetAccount {}).await.unwrap());
     println!("Positions:");
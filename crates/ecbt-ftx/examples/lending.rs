@@ -13,7 +13,7 @@ use {
 #[tokio::main]
 async fn main() {
     dotenv().ok();
-    let api = Rest::new(Options::from_env());
+    let api = Rest::new(Options::from_env()).unwrap();
 
     let lending_info = api.request(GetLendingInfo {}).await.unwrap();
 
@@ -8,7 +8,7 @@ use ecbt_ftx::{
 async fn main() -> Result<()> {
     dotenv().ok();
 
-    let api = Rest::new(Options::from_env());
+    let api = Rest::new(Options::from_env())?;
 
     for market in api.request(GetMarkets {}).await? {
         println!(
@@ -16,6 +16,7 @@ async fn main() -> Result<()> {
         key: Some("obAqJG1_4_qX4deJ-UwVm6drZ89mafGhd9YkNm8l".to_string()),
         secret: Some("7dqybPY84KtZMxL_YAoKMQlQDNuFPAbbF73a8tI5".to_string()),
         subaccount: Some("saber".to_string()),
+        ..Default::default()
     };
     let option_clone = options.clone();
     let mut websocket = Ws::connect(options).await?;
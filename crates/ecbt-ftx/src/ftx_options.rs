@@ -1,14 +1,39 @@
 use ecbt_exchange::exchange::Environment;
+use ecbt_exchange::info::OrderValidation;
 use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::rate_limit::RateLimitMode;
+use ecbt_exchange::retry::RetryPolicy;
+use ecbt_exchange::stream::ReconnectPolicy;
+use ecbt_exchange::transport::HttpTransport;
 use std::env::var;
+use std::sync::Arc;
 
 #[derive(Default, Clone, Debug)]
 pub struct FtxParameters {
     pub environment: Environment,
     pub options: Options,
+    /// How orders are checked against the market limits before being sent.
+    pub order_validation: OrderValidation,
 }
 
 impl FtxParameters {
+    pub fn production() -> Self {
+        Self {
+            environment: Environment::Production,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_options(mut self, options: Options) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn with_order_validation(mut self, order_validation: OrderValidation) -> Self {
+        self.order_validation = order_validation;
+        self
+    }
+
     pub fn ws(&self) -> &'static str {
         match self.environment {
             Environment::Production => "wss://ftx.com/ws",
@@ -17,8 +42,9 @@ impl FtxParameters {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Default)]
 pub enum Endpoint {
+    #[default]
     Com,
     Us,
 }
@@ -46,18 +72,20 @@ impl Endpoint {
     }
 }
 
-impl Default for Endpoint {
-    fn default() -> Self {
-        Endpoint::Com
-    }
-}
-
 #[derive(Debug, Default, Clone)]
 pub struct Options {
     pub endpoint: Endpoint,
     pub key: Option<String>,
     pub secret: Option<String>,
     pub subaccount: Option<String>,
+    /// The HTTP transport used by `Rest`, defaults to a `ReqwestTransport`.
+    pub http_transport: Option<Arc<dyn HttpTransport>>,
+    /// Whether requests over the FTX limits wait or fail, they are not limited if unset.
+    pub rate_limit: Option<RateLimitMode>,
+    /// How failed requests are retried by `Rest`, nothing is retried if unset.
+    pub retry_policy: Option<RetryPolicy>,
+    /// How `Ws` reconnects after its connection drops, it ends with the connection if unset.
+    pub reconnect_policy: Option<ReconnectPolicy>,
 }
 
 impl Options {
@@ -103,6 +131,30 @@ impl Options {
         self
     }
 
+    #[must_use]
+    pub fn http_transport(mut self, http_transport: Arc<dyn HttpTransport>) -> Self {
+        self.http_transport = Some(http_transport);
+        self
+    }
+
+    #[must_use]
+    pub fn rate_limit(mut self, rate_limit: RateLimitMode) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    #[must_use]
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    #[must_use]
+    pub fn reconnect_policy(mut self, reconnect_policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = Some(reconnect_policy);
+        self
+    }
+
     #[must_use]
     pub fn subaccount_optional(mut self, subaccount: Option<String>) -> Self {
         self.subaccount = subaccount;
@@ -1,4 +1,681 @@
+//! This module provides functionality for communicating with the FTX API, and with the venues
+//! compatible with it.
+//! # Example
+//! ```rust,no_run
+//! use ecbt_exchange::{
+//!     model::{
+//!         market_pair::{Currency, MarketPair},
+//!         OrderBookRequest,
+//!     },
+//!     Exchange, ExchangeMarketData,
+//! };
+//! use ecbt_ftx::{Ftx, FtxParameters};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let ftx = Ftx::new(FtxParameters::production())
+//!         .await
+//!         .expect("Couldn't create ftx client");
+//!     let market_pair = MarketPair(Currency::BTC, Currency::USD);
+//!     let order_book = ftx
+//!         .order_book(&OrderBookRequest { market_pair })
+//!         .await
+//!         .expect("Couldn't get order book");
+//!     println!("{:?}", order_book);
+//! }
+//! ```
+
 pub mod ftx_options;
 pub mod rest;
+#[cfg(test)]
+mod tests;
 #[cfg(feature = "ws")]
 pub mod ws;
+
+pub use ftx_options::{Endpoint, FtxParameters, Options};
+#[cfg(feature = "ws")]
+pub use ws::FtxWebsocket;
+
+use async_trait::async_trait;
+use chrono::{DateTime, TimeZone, Utc};
+use ecbt_exchange::errors::{EcbtError, ExchangeErrorContext};
+use ecbt_exchange::info::{
+    ExchangeInfo, ExchangeInfoRetrieval, MarketPairHandle, MarketPairInfo, OrderValidation,
+};
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::{
+    AmendMethod, AmendOrderRequest, AmendedOrder, AskBid, Balance, CancelAllOrdersRequest,
+    CancelOrderRequest, Candle, ConditionalOrderRequest, EcbtOrderRequest, GetHistoricRatesRequest,
+    GetHistoricTradesRequest, GetOrderHistoryRequest, GetOrderRequest, GetPriceTickerRequest,
+    Interval, Liquidity, OpenMarketOrderRequest, Order, OrderBookRequest, OrderBookResponse,
+    OrderCanceled, OrderStatus, OrderTrigger, OrderType, Paginator, Side, Ticker, TimeInForce,
+    Trade, TradeHistoryRequest, TrailingOffset,
+};
+use ecbt_exchange::retry::{new_client_order_id, place_order, RetryPolicy};
+use ecbt_exchange::shared::Result;
+use ecbt_exchange::{Exchange, ExchangeAccount, ExchangeMarketData};
+use rest::Rest;
+use rust_decimal::Decimal;
+
+/// The main struct of the ecbt-ftx module
+#[derive(Clone)]
+pub struct Ftx {
+    pub exchange_info: ExchangeInfo,
+    pub client: Rest,
+    /// The options the client was created with, which tell the market names of the endpoint.
+    pub options: Options,
+    pub order_validation: OrderValidation,
+    /// Orders are given a client id and reconciled by it on ambiguous failures if set.
+    pub retry_policy: Option<RetryPolicy>,
+}
+
+#[async_trait]
+impl Exchange for Ftx {
+    type InitParams = FtxParameters;
+    type InnerClient = Rest;
+
+    async fn new(parameters: Self::InitParams) -> Result<Self> {
+        let ftx = Ftx {
+            exchange_info: ExchangeInfo::new(),
+            client: Rest::new(parameters.options.clone())?,
+            retry_policy: parameters.options.retry_policy.clone(),
+            options: parameters.options,
+            order_validation: parameters.order_validation,
+        };
+
+        ftx.refresh_market_info().await?;
+        Ok(ftx)
+    }
+
+    fn inner_client(&self) -> Option<&Self::InnerClient> {
+        Some(&self.client)
+    }
+}
+
+impl Ftx {
+    /// Sends a request with the REST client, converting its error.
+    async fn request<R: rest::Request + Send>(&self, req: R) -> Result<R::Response> {
+        Ok(self.client.request(req).await?)
+    }
+
+    /// Sends an order once, or with a client id it can be reconciled by when a retry policy
+    /// is set.
+    async fn place_order(&self, mut req: rest::PlaceOrder) -> Result<Order> {
+        let retry_policy = match &self.retry_policy {
+            Some(retry_policy) => retry_policy,
+            None => return self.request(req).await.map(Into::into),
+        };
+        let client_id = req.client_id.clone().unwrap_or_else(new_client_order_id);
+        req.client_id = Some(client_id.clone());
+        place_order(
+            retry_policy,
+            || async { self.request(req.clone()).await.map(Into::into) },
+            || async {
+                self.request(rest::GetOrderByClientId::new(&client_id))
+                    .await
+                    .map(Into::into)
+            },
+        )
+        .await
+    }
+
+    /// Returns the last price of a market to check price bands and market order notionals
+    /// against, when the validation of an order `needs` it.
+    async fn reference_price(&self, pair: &MarketPairInfo, needs: bool) -> Result<Option<Decimal>> {
+        if !needs || self.order_validation == OrderValidation::Off {
+            return Ok(None);
+        }
+        self.request(rest::GetMarket::new(&pair.symbol))
+            .await
+            .map(|market| Ticker::from(market).price)
+    }
+
+    async fn limit_order(&self, side: Side, req: &EcbtOrderRequest) -> Result<Order> {
+        let pair = self.get_pair(&req.market_pair).await?.read()?;
+        let reference_price = self
+            .reference_price(&pair, pair.has_price_band(&side))
+            .await?;
+        let req = pair.validate_limit_order(&side, req, self.order_validation, reference_price)?;
+        let ioc = match req.time_in_force {
+            TimeInForce::GoodTillCancelled => false,
+            TimeInForce::ImmediateOrCancelled => true,
+            time_in_force => {
+                return Err(EcbtError::InvalidParameter(format!(
+                    "{:?} is not supported by FTX",
+                    time_in_force
+                )))
+            }
+        };
+        self.place_order(rest::PlaceOrder {
+            market: pair.symbol,
+            side: side.into(),
+            price: Some(req.price),
+            r#type: rest::OrderType::Limit,
+            size: req.size,
+            ioc,
+            post_only: req.post_only,
+            client_id: req.client_order_id,
+            ..Default::default()
+        })
+        .await
+    }
+
+    async fn market_order(&self, side: Side, req: &OpenMarketOrderRequest) -> Result<Order> {
+        let pair = self.get_pair(&req.market_pair).await?.read()?;
+        let reference_price = self
+            .reference_price(&pair, pair.has_notional_limits())
+            .await?;
+        let req = pair.validate_market_order(req, self.order_validation, reference_price)?;
+        self.place_order(rest::PlaceOrder {
+            market: pair.symbol,
+            side: side.into(),
+            price: None,
+            r#type: rest::OrderType::Market,
+            size: req.size,
+            client_id: req.client_order_id,
+            ..Default::default()
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl ExchangeInfoRetrieval for Ftx {
+    async fn retrieve_pairs(&self) -> Result<Vec<MarketPairInfo>> {
+        self.request(rest::GetMarkets {})
+            .await
+            .map(|markets| markets.iter().map(MarketPairInfo::from).collect())
+    }
+
+    async fn refresh_market_info(&self) -> Result<Vec<MarketPairHandle>> {
+        self.exchange_info
+            .refresh(self as &dyn ExchangeInfoRetrieval)
+            .await
+    }
+
+    async fn get_pair(&self, market_pair: &MarketPair) -> Result<MarketPairHandle> {
+        self.exchange_info
+            .get_pair(&self.options.to_market(market_pair.clone()))
+    }
+}
+
+#[async_trait]
+impl ExchangeMarketData for Ftx {
+    async fn order_book(&self, req: &OrderBookRequest) -> Result<OrderBookResponse> {
+        let market = self.options.to_market(req.market_pair.clone());
+        self.request(rest::GetOrderBook::new(&market))
+            .await
+            .map(Into::into)
+    }
+
+    async fn get_price_ticker(&self, req: &GetPriceTickerRequest) -> Result<Ticker> {
+        let market = self.options.to_market(req.market_pair.clone());
+        self.request(rest::GetMarket::new(&market))
+            .await
+            .map(Into::into)
+    }
+
+    async fn get_historic_rates(&self, req: &GetHistoricRatesRequest) -> Result<Vec<Candle>> {
+        let paginator = req.paginator.clone().unwrap_or_default();
+        let req = rest::GetHistoricalPrices {
+            market_name: self.options.to_market(req.market_pair.clone()),
+            resolution: resolution(req.interval)?,
+            limit: paginator.limit.map(|limit| limit as u32),
+            start_time: paginator.start_time.map(datetime),
+            end_time: paginator.end_time.map(datetime),
+        };
+        self.request(req)
+            .await
+            .map(|candles| candles.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_historic_trades(&self, req: &GetHistoricTradesRequest) -> Result<Vec<Trade>> {
+        let paginator = req.paginator.clone().unwrap_or_default();
+        let market = req.market_pair.clone();
+        let req = rest::GetTrades {
+            market_name: market.clone(),
+            limit: paginator.limit.map(|limit| limit as u32),
+            start_time: paginator.start_time.map(datetime),
+            end_time: paginator.end_time.map(datetime),
+        };
+        self.request(req).await.map(|trades| {
+            trades
+                .into_iter()
+                .map(|trade| trade.into_trade(market.clone()))
+                .collect()
+        })
+    }
+}
+
+#[async_trait]
+impl ExchangeAccount for Ftx {
+    async fn limit_buy(&self, req: &EcbtOrderRequest) -> Result<Order> {
+        self.limit_order(Side::Buy, req).await
+    }
+
+    async fn limit_sell(&self, req: &EcbtOrderRequest) -> Result<Order> {
+        self.limit_order(Side::Sell, req).await
+    }
+
+    async fn market_buy(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
+        self.market_order(Side::Buy, req).await
+    }
+
+    async fn market_sell(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
+        self.market_order(Side::Sell, req).await
+    }
+
+    async fn cancel_order(&self, req: &CancelOrderRequest) -> Result<OrderCanceled> {
+        self.request(rest::CancelOrder::new(order_id(&req.id)?))
+            .await?;
+        Ok(OrderCanceled::from(req.id.clone()))
+    }
+
+    /// FTX only acknowledges the cancellation, so the orders open before it are returned.
+    async fn cancel_all_orders(&self, req: &CancelAllOrdersRequest) -> Result<Vec<OrderCanceled>> {
+        let market = req
+            .market_pair
+            .clone()
+            .map(|market_pair| self.options.to_market(market_pair));
+        let open_orders = match &market {
+            Some(market) => rest::GetOpenOrders::with_market(market),
+            None => rest::GetOpenOrders::all_market(),
+        };
+        let open_orders = self.request(open_orders).await?;
+        self.request(rest::CancelAllOrder {
+            market,
+            ..Default::default()
+        })
+        .await?;
+        Ok(open_orders
+            .into_iter()
+            .map(|order| OrderCanceled::from(order.id.to_string()))
+            .collect())
+    }
+
+    async fn get_all_open_orders(&self) -> Result<Vec<Order>> {
+        self.request(rest::GetOpenOrders::all_market())
+            .await
+            .map(|orders| orders.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_order_history(&self, req: &GetOrderHistoryRequest) -> Result<Vec<Order>> {
+        let paginator = req.paginator.clone().unwrap_or_default();
+        let history = rest::GetOrderHistory {
+            market: req
+                .market_pair
+                .clone()
+                .map(|market_pair| self.options.to_market(market_pair)),
+            limit: paginator.limit.map(|limit| limit as usize),
+            start_time: paginator.start_time.map(datetime),
+            end_time: paginator.end_time.map(datetime),
+            ..Default::default()
+        };
+        let orders = self.request(history).await?.into_iter().map(Order::from);
+        Ok(match &req.order_status {
+            Some(statuses) => orders
+                .filter(|order| statuses.contains(&order.status))
+                .collect(),
+            None => orders.collect(),
+        })
+    }
+
+    async fn get_trade_history(&self, req: &TradeHistoryRequest) -> Result<Vec<Trade>> {
+        let paginator = req.paginator.clone().unwrap_or_default();
+        let fills = rest::GetFills {
+            market_name: req
+                .market_pair
+                .clone()
+                .map(|market_pair| self.options.to_market(market_pair)),
+            start_time: paginator.start_time.map(datetime),
+            end_time: paginator.end_time.map(datetime),
+            order_id: req.order_id.as_deref().map(order_id).transpose()?,
+        };
+        self.request(fills)
+            .await
+            .map(|fills| fills.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_account_balances(&self, _paginator: Option<Paginator>) -> Result<Vec<Balance>> {
+        self.request(rest::GetWalletBalances {})
+            .await
+            .map(|balances| balances.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_order(&self, req: &GetOrderRequest) -> Result<Order> {
+        self.request(rest::GetOrder::new(order_id(&req.id)?))
+            .await
+            .map(Into::into)
+    }
+
+    /// FTX trigger orders have no client id, so they are sent once whatever the retry policy.
+    async fn place_conditional_order(&self, req: &ConditionalOrderRequest) -> Result<Order> {
+        let pair = self.get_pair(&req.market_pair).await?.read()?;
+        let req = pair.validate_conditional_order(req, self.order_validation)?;
+        let (r#type, trigger_price, trail_value) = match (req.trigger, req.limit_price) {
+            (OrderTrigger::Stop { trigger_price }, _) => {
+                (rest::OrderType::Stop, Some(trigger_price), None)
+            }
+            (OrderTrigger::TakeProfit { trigger_price }, _) => {
+                (rest::OrderType::TakeProfit, Some(trigger_price), None)
+            }
+            (OrderTrigger::TrailingStop { .. }, Some(_)) => {
+                return Err(EcbtError::UnsupportedOrderType(req.order_type()))
+            }
+            (
+                OrderTrigger::TrailingStop {
+                    offset: TrailingOffset::Price(offset),
+                },
+                None,
+            ) => {
+                // Sells trail below the price, with a negative value.
+                let trail_value = match req.side {
+                    Side::Buy => offset,
+                    Side::Sell => -offset,
+                };
+                (rest::OrderType::TrailingStop, None, Some(trail_value))
+            }
+            (OrderTrigger::TrailingStop { .. }, None) => {
+                return Err(EcbtError::InvalidParameter(
+                    "FTX trailing stops take an offset in price".to_string(),
+                ))
+            }
+        };
+        self.request(rest::PlaceTriggerOrder {
+            market: pair.symbol,
+            side: req.side.into(),
+            size: req.size,
+            r#type,
+            trigger_price,
+            order_price: req.limit_price,
+            trail_value,
+            ..Default::default()
+        })
+        .await
+        .map(Into::into)
+    }
+
+    /// FTX has no lookup of a single trigger order, so it is searched among the open trigger
+    /// orders, then among the latest ones of the history.
+    async fn get_conditional_order(&self, req: &GetOrderRequest) -> Result<Order> {
+        let id = order_id(&req.id)?;
+        let open = self
+            .request(rest::GetOpenTriggerOrders {
+                market: req.market_pair.clone(),
+                ..Default::default()
+            })
+            .await?;
+        if let Some(order) = open.into_iter().find(|order| order.id == id) {
+            return Ok(order.into());
+        }
+        let history = rest::GetTriggerOrderHistory {
+            market: req.market_pair.clone(),
+            ..Default::default()
+        };
+        self.request(history)
+            .await?
+            .into_iter()
+            .find(|order| order.id == id)
+            .map(Into::into)
+            .ok_or_else(|| {
+                EcbtError::OrderNotFound(
+                    ExchangeErrorContext::new(format!("Trigger order {} not found", id))
+                        .with_endpoint(<rest::GetTriggerOrderHistory as rest::Request>::PATH),
+                )
+            })
+    }
+
+    async fn cancel_conditional_order(&self, req: &CancelOrderRequest) -> Result<OrderCanceled> {
+        self.request(rest::CancelTriggerOrder::new(order_id(&req.id)?))
+            .await?;
+        Ok(OrderCanceled::from(req.id.clone()))
+    }
+
+    /// FTX modifies an order by canceling it and placing a replacement with a new id, which
+    /// keeps the time in force and post only flag of the amended order. The replacement is the
+    /// part of `req.size` the order didn't fill when it was fetched before the request.
+    async fn amend_order(&self, req: &AmendOrderRequest) -> Result<AmendedOrder> {
+        let pair = self.get_pair(&req.market_pair).await?.read()?;
+        let amended = self
+            .get_order(&GetOrderRequest {
+                id: req.id.clone(),
+                market_pair: None,
+            })
+            .await?;
+        let size = req.unfilled_size(&amended)?;
+        let reference_price = self
+            .reference_price(&pair, pair.has_price_band(&req.side))
+            .await?;
+        let replacement = pair.validate_limit_order(
+            &req.side,
+            &EcbtOrderRequest {
+                size,
+                ..req.replacement()
+            },
+            self.order_validation,
+            reference_price,
+        )?;
+        let client_id = replacement
+            .client_order_id
+            .unwrap_or_else(new_client_order_id);
+        let modify = rest::ModifyOrder {
+            id: order_id(&req.id)?,
+            price: Some(replacement.price),
+            size: Some(replacement.size),
+            client_id: Some(client_id.clone()),
+        };
+        let order = match &self.retry_policy {
+            Some(retry_policy) => {
+                place_order(
+                    retry_policy,
+                    || async { self.request(modify.clone()).await.map(Into::into) },
+                    || async {
+                        self.request(rest::GetOrderByClientId::new(&client_id))
+                            .await
+                            .map(Into::into)
+                    },
+                )
+                .await?
+            }
+            None => self.request(modify).await?.into(),
+        };
+        Ok(AmendedOrder {
+            order,
+            replaced_order_id: req.id.clone(),
+            replaced_client_order_id: amended.client_order_id,
+            method: AmendMethod::Native,
+        })
+    }
+}
+
+/// Parses the id of an FTX order.
+fn order_id(id: &str) -> Result<rest::Id> {
+    id.parse()
+        .map_err(|_| EcbtError::InvalidParameter(format!("{} is not an FTX order id", id)))
+}
+
+/// Converts milliseconds since the epoch to a date.
+fn datetime(millis: u64) -> DateTime<Utc> {
+    Utc.timestamp_millis_opt(millis as i64)
+        .single()
+        .unwrap_or_default()
+}
+
+/// Returns the candle resolution of `interval` in seconds, FTX only has some of them.
+fn resolution(interval: Interval) -> Result<u32> {
+    match interval {
+        Interval::OneMinute => Ok(60),
+        Interval::FiveMinutes => Ok(300),
+        Interval::FifteenMinutes => Ok(900),
+        Interval::OneHour => Ok(3600),
+        Interval::FourHours => Ok(14400),
+        Interval::OneDay => Ok(86400),
+        Interval::ThreeDays => Ok(259200),
+        Interval::OneWeek => Ok(604800),
+        _ => Err(EcbtError::InvalidParameter(format!(
+            "{:?} is not supported by FTX",
+            interval
+        ))),
+    }
+}
+
+impl From<rest::Orderbook> for OrderBookResponse {
+    fn from(book: rest::Orderbook) -> Self {
+        Self {
+            update_id: None,
+            last_update_id: None,
+            bids: book.bids.into_iter().map(ask_bid).collect(),
+            asks: book.asks.into_iter().map(ask_bid).collect(),
+        }
+    }
+}
+
+/// The levels of the book best first, as in the REST order book.
+impl From<ws::Orderbook> for OrderBookResponse {
+    fn from(book: ws::Orderbook) -> Self {
+        Self {
+            update_id: None,
+            last_update_id: None,
+            bids: book.bids.into_iter().rev().map(ask_bid).collect(),
+            asks: book.asks.into_iter().map(ask_bid).collect(),
+        }
+    }
+}
+
+pub(crate) fn ask_bid((price, qty): (Decimal, Decimal)) -> AskBid {
+    AskBid { price, qty }
+}
+
+impl From<rest::Market> for Ticker {
+    fn from(market: rest::Market) -> Self {
+        Self {
+            price: market.last.or(market.price),
+            price_24h: None,
+        }
+    }
+}
+
+impl From<rest::Candle> for Candle {
+    fn from(candle: rest::Candle) -> Self {
+        Self {
+            time: candle.start_time.timestamp_millis() as u64,
+            low: candle.low,
+            high: candle.high,
+            open: candle.open,
+            close: candle.close,
+            volume: candle.volume,
+        }
+    }
+}
+
+impl From<rest::WalletBalance> for Balance {
+    fn from(balance: rest::WalletBalance) -> Self {
+        Self {
+            asset: balance.coin,
+            total: balance.total,
+            free: balance.free,
+        }
+    }
+}
+
+impl From<rest::OrderInfo> for Order {
+    fn from(order: rest::OrderInfo) -> Self {
+        let filled_size = order.filled_size.unwrap_or_default();
+        let status = match order.status {
+            rest::OrderStatus::New => OrderStatus::New,
+            rest::OrderStatus::Open if filled_size > Decimal::ZERO => OrderStatus::PartiallyFilled,
+            rest::OrderStatus::Open => OrderStatus::Open,
+            rest::OrderStatus::Closed if filled_size >= order.size => OrderStatus::Filled,
+            rest::OrderStatus::Closed => OrderStatus::Canceled,
+        };
+        let order_type = match order.r#type {
+            rest::OrderType::Limit => OrderType::Limit,
+            rest::OrderType::Market => OrderType::Market,
+            rest::OrderType::Stop if order.order_price.is_some() => OrderType::StopLimit,
+            rest::OrderType::Stop => OrderType::StopMarket,
+            rest::OrderType::TakeProfit if order.order_price.is_some() => {
+                OrderType::TakeProfitLimit
+            }
+            rest::OrderType::TakeProfit => OrderType::TakeProfitMarket,
+            rest::OrderType::TrailingStop => OrderType::TrailingStopMarket,
+        };
+
+        Self {
+            id: order.id.to_string(),
+            market_pair: order.market,
+            client_order_id: order.client_id,
+            created_at: Some(order.created_at.timestamp_millis() as u64),
+            order_type,
+            side: order.side.into(),
+            status,
+            size: order.size,
+            price: order.price,
+            remaining: order.remaining_size,
+            trades: Vec::new(),
+        }
+    }
+}
+
+impl From<rest::TriggerOrderInfo> for Order {
+    fn from(order: rest::TriggerOrderInfo) -> Self {
+        let filled_size = order.filled_size.unwrap_or_default();
+        let status = match order.status {
+            rest::TriggerOrderStatus::Open => OrderStatus::Open,
+            rest::TriggerOrderStatus::Cancelled => OrderStatus::Canceled,
+            rest::TriggerOrderStatus::Triggered if filled_size >= order.size => OrderStatus::Filled,
+            rest::TriggerOrderStatus::Triggered if filled_size > Decimal::ZERO => {
+                OrderStatus::PartiallyFilled
+            }
+            rest::TriggerOrderStatus::Triggered => OrderStatus::New,
+        };
+        let limit = order.order_type == rest::OrderType::Limit;
+        let order_type = match order.r#type {
+            rest::OrderType::Stop if limit => OrderType::StopLimit,
+            rest::OrderType::Stop => OrderType::StopMarket,
+            rest::OrderType::TakeProfit if limit => OrderType::TakeProfitLimit,
+            rest::OrderType::TakeProfit => OrderType::TakeProfitMarket,
+            rest::OrderType::TrailingStop => OrderType::TrailingStopMarket,
+            rest::OrderType::Limit | rest::OrderType::Market => OrderType::Unknown,
+        };
+
+        Self {
+            id: order.id.to_string(),
+            market_pair: order.market,
+            client_order_id: None,
+            created_at: Some(order.created_at.timestamp_millis() as u64),
+            order_type,
+            side: order.side.into(),
+            status,
+            size: order.size,
+            price: order.order_price,
+            remaining: Some(order.size - filled_size),
+            trades: Vec::new(),
+        }
+    }
+}
+
+impl From<ws::Fill> for Trade {
+    fn from(fill: ws::Fill) -> Self {
+        let order_id = Some(fill.order_id.to_string());
+        let (buyer_order_id, seller_order_id) = match fill.side {
+            rest::Side::Buy => (order_id, None),
+            rest::Side::Sell => (None, order_id),
+        };
+
+        Self {
+            id: fill.trade_id.to_string(),
+            buyer_order_id,
+            seller_order_id,
+            market_pair: fill.market,
+            price: fill.price,
+            qty: fill.size,
+            fees: Some(fill.fee),
+            side: fill.side.into(),
+            liquidity: Some(match fill.liquidity {
+                ws::Liquidity::Maker => Liquidity::Maker,
+                ws::Liquidity::Taker => Liquidity::Taker,
+            }),
+            created_at: fill.time.timestamp_millis().to_string(),
+        }
+    }
+}
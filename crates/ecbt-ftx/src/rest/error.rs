@@ -1,3 +1,6 @@
+use ecbt_exchange::errors::{match_error_message, EcbtError, ErrorConstructor};
+use ecbt_exchange::transport::HttpResponse;
+use reqwest::StatusCode;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -5,7 +8,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Api error: {0}")]
-    Api(String),
+    Api(EcbtError),
 
     #[error("placing limit order requires price")]
     PlacingLimitOrderRequiresPrice,
@@ -21,4 +24,61 @@ pub enum Error {
 
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Url(#[from] url::ParseError),
+
+    #[error(transparent)]
+    Transport(#[from] EcbtError),
+}
+
+impl From<Error> for EcbtError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Api(error) | Error::Transport(error) => error,
+            Error::PlacingLimitOrderRequiresPrice => {
+                EcbtError::MissingParameter("Placing a limit order requires a price".to_string())
+            }
+            Error::NoSecretConfigured => EcbtError::NoApiKeySet(),
+            Error::SerdeQs(error) => EcbtError::Generic(Box::new(error)),
+            Error::Reqwest(error) => EcbtError::ReqError(error),
+            Error::Json(error) => EcbtError::JsonError(error),
+            Error::Url(error) => EcbtError::UrlParserError(error),
+        }
+    }
+}
+
+/// FTX has no error codes, so rejections are told apart by their message.
+const ERROR_MESSAGES: &[(&str, ErrorConstructor)] = &[
+    ("not enough balances", EcbtError::InsufficientBalance),
+    ("order not found", EcbtError::OrderNotFound),
+    ("order already closed", EcbtError::OrderNotFound),
+    ("duplicate client", EcbtError::DuplicateOrder),
+    ("post only", EcbtError::PostOnlyWouldTake),
+    ("post-only", EcbtError::PostOnlyWouldTake),
+    ("invalid price", EcbtError::InvalidPrice),
+    ("price too", EcbtError::InvalidPrice),
+    ("invalid size", EcbtError::InvalidQuantity),
+    ("size too", EcbtError::InvalidQuantity),
+    (
+        "request timestamp expired",
+        EcbtError::TimestampOutsideRecvWindow,
+    ),
+    ("market is closed", EcbtError::MarketClosed),
+    ("trading is disabled", EcbtError::MarketClosed),
+    ("not logged in", EcbtError::AuthenticationRejected),
+    ("invalid signature", EcbtError::AuthenticationRejected),
+    ("read-only permissions", EcbtError::AuthenticationRejected),
+];
+
+/// Maps the error message of an FTX response to a typed [`EcbtError`].
+pub(crate) fn api_error(response: &HttpResponse, message: String) -> EcbtError {
+    let context = response.error_context(message);
+    if response.status == StatusCode::TOO_MANY_REQUESTS {
+        return EcbtError::RateLimited {
+            retry_after: response.retry_after(),
+            context: Some(context),
+        };
+    }
+    match_error_message(ERROR_MESSAGES, context)
 }
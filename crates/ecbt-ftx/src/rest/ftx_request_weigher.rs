@@ -0,0 +1,40 @@
+use ecbt_exchange::rate_limit::{
+    RateLimitKind, RateLimitRule, RequestCost, RequestPriority, RequestWeigher,
+};
+use ecbt_exchange::transport::HttpRequest;
+use reqwest::Method;
+use std::time::Duration;
+
+/// The request costs of the FTX REST API, which limits the number of requests per second.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FtxRequestWeigher;
+
+impl FtxRequestWeigher {
+    pub fn rules() -> Vec<RateLimitRule> {
+        vec![RateLimitRule::new(
+            RateLimitKind::Requests,
+            Duration::from_secs(1),
+            30,
+        )]
+    }
+}
+
+impl RequestWeigher for FtxRequestWeigher {
+    fn cost(&self, request: &HttpRequest) -> RequestCost {
+        let path = request.url.path().trim_start_matches("/api");
+        let order_action = request.method != Method::GET
+            && (path.starts_with("/orders") || path.starts_with("/conditional_orders"));
+        let orders = match (&request.method, path) {
+            (&Method::POST, "/orders") | (&Method::POST, "/conditional_orders") => 1,
+            _ => 0,
+        };
+
+        RequestCost::new()
+            .with_orders(orders)
+            .with_priority(if order_action {
+                RequestPriority::High
+            } else {
+                RequestPriority::Normal
+            })
+    }
+}
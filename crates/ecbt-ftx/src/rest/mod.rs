@@ -1,23 +1,30 @@
 //! This module is used to interact with the REST API.
 
 mod error;
+mod ftx_request_weigher;
 mod model;
 #[cfg(test)]
 pub(crate) mod tests;
 
 pub use error::*;
+pub use ftx_request_weigher::*;
 pub use model::*;
 
 use crate::ftx_options::{Endpoint, Options};
 use chrono::{DateTime, Utc};
+use ecbt_exchange::rate_limit::{RateLimitedTransport, RateLimiter};
+use ecbt_exchange::retry::RetryingTransport;
+use ecbt_exchange::transport::{http_transport_or_default, HttpRequest, HttpTransport};
 use hmac_sha256::HMAC;
 use reqwest::{
     header::{HeaderMap, HeaderName, HeaderValue},
-    Client, ClientBuilder, Method,
+    Method,
 };
 use rust_decimal::prelude::*;
 use serde_json::{from_reader, to_string};
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
+use url::Url;
 
 macro_rules! deprecate_msg {
     () => {
@@ -27,7 +34,8 @@ macro_rules! deprecate_msg {
 #[derive(Debug, Clone)]
 pub struct Rest {
     secret: Option<String>,
-    client: Client,
+    http: Arc<dyn HttpTransport>,
+    headers: HeaderMap,
     subaccount: Option<String>,
     endpoint: Endpoint,
 }
@@ -39,8 +47,12 @@ impl Rest {
             key,
             secret,
             subaccount,
+            http_transport,
+            rate_limit,
+            retry_policy,
+            reconnect_policy: _,
         }: Options,
-    ) -> Self {
+    ) -> Result<Self> {
         // Set default headers.
         let mut headers = HeaderMap::new();
 
@@ -58,17 +70,25 @@ impl Rest {
             );
         }
 
-        let client = ClientBuilder::new()
-            .default_headers(headers)
-            .build()
-            .unwrap();
+        let mut http = http_transport_or_default(http_transport)?;
+        if let Some(mode) = rate_limit {
+            http = Arc::new(RateLimitedTransport::new(
+                http,
+                Arc::new(RateLimiter::new(FtxRequestWeigher::rules(), mode)),
+                Arc::new(FtxRequestWeigher),
+            ));
+        }
+        if let Some(retry_policy) = retry_policy {
+            http = Arc::new(RetryingTransport::new(http, retry_policy));
+        }
 
-        Self {
+        Ok(Self {
             secret,
-            client,
+            http,
+            headers,
             subaccount,
             endpoint,
-        }
+        })
     }
 
     pub async fn request<R>(&self, req: R) -> Result<R::Response>
@@ -89,88 +109,72 @@ impl Rest {
         }
         let url = format!("{}{}", self.endpoint.rest(), path);
 
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_millis();
+        let secret = match (R::AUTH, &self.secret) {
+            (false, _) => None,
+            (true, Some(secret)) => Some(&**secret),
+            (true, None) => return Err(Error::NoSecretConfigured),
+        };
+        let url = Url::parse(&url)?;
 
-        log::trace!("timestamp: {}", timestamp);
         log::trace!("method: {}", R::METHOD);
         log::trace!("path: {}", path);
         log::trace!("body: {}", body);
 
-        let mut headers = HeaderMap::new();
-        headers.insert(
-            reqwest::header::CONTENT_TYPE,
-            HeaderValue::from_static("application/json"),
-        );
-        headers.insert(
-            HeaderName::from_str(&format!("{}-TS", self.endpoint.header_prefix())).unwrap(),
-            HeaderValue::from_str(&format!("{}", timestamp)).unwrap(),
-        );
-
-        if R::AUTH {
-            let secret = match self.secret {
-                Some(ref secret) => &**secret,
-                None => {
-                    return Err(Error::NoSecretConfigured);
+        // The request is signed again with a fresh timestamp if it is retried.
+        let response = self
+            .http
+            .send_with(&|| {
+                let timestamp = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap()
+                    .as_millis();
+                log::trace!("timestamp: {}", timestamp);
+
+                let mut headers = self.headers.clone();
+                headers.insert(
+                    reqwest::header::CONTENT_TYPE,
+                    HeaderValue::from_static("application/json"),
+                );
+                headers.insert(
+                    HeaderName::from_str(&format!("{}-TS", self.endpoint.header_prefix())).unwrap(),
+                    HeaderValue::from_str(&format!("{}", timestamp)).unwrap(),
+                );
+
+                if let Some(secret) = secret {
+                    let sign_payload = format!("{}{}/api{}{}", timestamp, R::METHOD, path, body);
+
+                    let sign = HMAC::mac(sign_payload.as_bytes(), secret.as_bytes());
+                    let sign = hex::encode(sign);
+                    headers.insert(
+                        HeaderName::from_str(&format!("{}-SIGN", self.endpoint.header_prefix()))
+                            .unwrap(),
+                        HeaderValue::from_str(&sign).unwrap(),
+                    );
                 }
-            };
-
-            let sign_payload = format!("{}{}/api{}{}", timestamp, R::METHOD, path, body);
 
-            let sign = HMAC::mac(sign_payload.as_bytes(), secret.as_bytes());
-            let sign = hex::encode(sign);
-            headers.insert(
-                HeaderName::from_str(&format!("{}-SIGN", self.endpoint.header_prefix())).unwrap(),
-                HeaderValue::from_str(&sign).unwrap(),
-            );
-        }
-
-        if let Some(subaccount) = &self.subaccount {
-            headers.insert(
-                HeaderName::from_str(&format!("{}-SUBACCOUNT", self.endpoint.header_prefix()))
-                    .unwrap(),
-                HeaderValue::from_str(subaccount).unwrap(),
-            );
-        }
-
-        /*
-        let response: String = self
-            .client
-            .request(R::METHOD, url)
-            .query(&params)
-            .headers(headers)
-            .body(body)
-            .send()
-            .await?
-            .text()
-            .await?;
+                if let Some(subaccount) = &self.subaccount {
+                    headers.insert(
+                        HeaderName::from_str(&format!(
+                            "{}-SUBACCOUNT",
+                            self.endpoint.header_prefix()
+                        ))
+                        .unwrap(),
+                        HeaderValue::from_str(subaccount).unwrap(),
+                    );
+                }
 
-        use std::fs::File;
-        use std::io::prelude::*;
-        let mut file = File::create("response.json").unwrap();
-        file.write_all(response.as_bytes()).unwrap();
-
-        panic!("{:#?}", response);
-        */
-
-        let body = self
-            .client
-            .request(R::METHOD, url)
-            .headers(headers)
-            .body(body)
-            .send()
-            .await?
-            .bytes()
+                Ok(HttpRequest::new(R::METHOD, url.clone())
+                    .with_headers(headers)
+                    .with_body(body.clone()))
+            })
             .await?;
 
-        match from_reader(&*body) {
+        match from_reader(&*response.body) {
             Ok(SuccessResponse { result, .. }) => Ok(result),
 
             Err(e) => {
-                if let Ok(ErrorResponse { error, .. }) = from_reader(&*body) {
-                    Err(Error::Api(error))
+                if let Ok(ErrorResponse { error, .. }) = from_reader(&*response.body) {
+                    Err(Error::Api(api_error(&response, error)))
                 } else {
                     Err(e.into())
                 }
@@ -450,13 +454,13 @@ impl Rest {
         retry_until_filled: Option<bool>,
         order_price: Option<Decimal>,
         trail_value: Option<Decimal>,
-    ) -> Result<OrderInfo> {
+    ) -> Result<TriggerOrderInfo> {
         self.request(PlaceTriggerOrder {
             market: market.into(),
             side,
             size,
             r#type,
-            trigger_price,
+            trigger_price: Some(trigger_price),
             reduce_only,
             retry_until_filled,
             order_price,
@@ -5,9 +5,10 @@ pub type Id = u64;
 pub type Coin = String;
 pub type Symbol = String;
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub enum OrderType {
+    #[default]
     Market,
     Limit,
     Stop,
@@ -17,12 +18,6 @@ pub enum OrderType {
     TakeProfit,
 }
 
-impl Default for OrderType {
-    fn default() -> OrderType {
-        OrderType::Market
-    }
-}
-
 #[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 /// Represents the status of the order.
@@ -39,6 +34,7 @@ impl Default for OrderType {
 /// - Call the `get_order` REST API to see if the order status has been updated
 /// - Listen to orders over websockets to be notified of the update order status
 ///   as soon as it is available.
+///
 /// To get near-immediate feedback on the status of possibly-rejected orders,
 /// we recommend subscribing to the `Orders` channel over websockets.
 ///
@@ -62,16 +58,29 @@ pub enum OrderStatus {
     Closed,
 }
 
-#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Copy, Clone, Debug, Serialize, Deserialize, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
 pub enum Side {
+    #[default]
     Buy,
     Sell,
 }
 
-impl Default for Side {
-    fn default() -> Side {
-        Side::Buy
+impl From<Side> for ecbt_exchange::model::Side {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Buy => ecbt_exchange::model::Side::Buy,
+            Side::Sell => ecbt_exchange::model::Side::Sell,
+        }
+    }
+}
+
+impl From<ecbt_exchange::model::Side> for Side {
+    fn from(side: ecbt_exchange::model::Side) -> Self {
+        match side {
+            ecbt_exchange::model::Side::Buy => Side::Buy,
+            ecbt_exchange::model::Side::Sell => Side::Sell,
+        }
     }
 }
 
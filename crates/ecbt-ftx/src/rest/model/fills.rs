@@ -7,7 +7,9 @@ use serde::Serialize;
 #[derive(Debug, Clone, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GetFills {
-    pub market_name: String,
+    /// The fills of every market when `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market_name: Option<String>,
     #[serde(
         skip_serializing_if = "Option::is_none",
         serialize_with = "super::serialize_as_timestamp"
@@ -25,7 +27,7 @@ pub struct GetFills {
 impl GetFills {
     pub fn new(market_name: &str) -> Self {
         Self {
-            market_name: market_name.into(),
+            market_name: Some(market_name.into()),
             ..Self::default()
         }
     }
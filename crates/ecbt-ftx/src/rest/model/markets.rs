@@ -1,6 +1,7 @@
 use super::common::{Coin, Id, MarketType, Resolution, Side, Symbol};
 use super::Request;
 use chrono::{DateTime, Utc};
+use ecbt_exchange::info::{MarketPairInfo, OrderConstraints};
 use http::Method;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
@@ -33,6 +34,29 @@ pub struct Market {
     pub volume_usd24h: Decimal,
 }
 
+/// Futures have no base and quote currencies, their underlying and USD are used instead.
+impl From<&Market> for MarketPairInfo {
+    fn from(market: &Market) -> Self {
+        MarketPairInfo {
+            base: market
+                .base_currency
+                .clone()
+                .or_else(|| market.underlying.clone())
+                .unwrap_or_default(),
+            quote: market
+                .quote_currency
+                .clone()
+                .unwrap_or_else(|| "USD".to_string()),
+            symbol: market.name.clone(),
+            base_increment: market.size_increment,
+            quote_increment: market.price_increment,
+            min_base_trade_size: Some(market.size_increment),
+            min_quote_trade_size: None,
+            constraints: OrderConstraints::default(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GetMarkets {}
@@ -127,6 +151,24 @@ pub struct Trade {
     pub time: DateTime<Utc>,
 }
 
+impl Trade {
+    /// Converts the trade of `market` to a generic trade.
+    pub fn into_trade(self, market: Symbol) -> ecbt_exchange::model::Trade {
+        ecbt_exchange::model::Trade {
+            id: self.id.to_string(),
+            buyer_order_id: None,
+            seller_order_id: None,
+            market_pair: market,
+            price: self.price,
+            qty: self.size,
+            fees: None,
+            side: self.side.into(),
+            liquidity: None,
+            created_at: self.time.timestamp_millis().to_string(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Default)]
 pub struct GetTrades {
     #[serde(skip_serializing)]
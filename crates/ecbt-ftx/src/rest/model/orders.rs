@@ -271,6 +271,117 @@ impl Request for GetOrderHistory {
     type Response = Vec<OrderInfo>;
 }
 
+/// This enum represents the state of a trigger order, which places an order once triggered.
+#[derive(Copy, Clone, Debug, Deserialize, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum TriggerOrderStatus {
+    Open,
+    Cancelled,
+    Triggered,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TriggerOrderInfo {
+    pub id: Id,
+    pub market: String,
+    pub future: Option<String>,
+    /// `Stop`, `TrailingStop` or `TakeProfit`.
+    pub r#type: OrderType,
+    /// The type of the order placed once triggered, `Market` or `Limit`.
+    pub order_type: OrderType,
+    pub side: Side,
+    pub size: Decimal,
+    pub status: TriggerOrderStatus,
+    pub trigger_price: Option<Decimal>,
+    pub order_price: Option<Decimal>,
+    pub trail_value: Option<Decimal>,
+    pub trail_start: Option<Decimal>,
+    pub filled_size: Option<Decimal>,
+    pub avg_fill_price: Option<Decimal>,
+    pub reduce_only: Option<bool>,
+    pub retry_until_filled: Option<bool>,
+    pub created_at: DateTime<Utc>,
+    pub triggered_at: Option<String>,
+    pub order_id: Option<Id>,
+    pub error: Option<String>,
+    pub cancel_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GetOpenTriggerOrders {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<OrderType>,
+}
+
+impl Request for GetOpenTriggerOrders {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "/conditional_orders";
+    const AUTH: bool = true;
+
+    type Response = Vec<TriggerOrderInfo>;
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GetTriggerOrderHistory {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub market: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<Side>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r#type: Option<OrderType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_type: Option<OrderType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "super::serialize_as_timestamp"
+    )]
+    pub start_time: Option<DateTime<Utc>>,
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "super::serialize_as_timestamp"
+    )]
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+impl Request for GetTriggerOrderHistory {
+    const METHOD: Method = Method::GET;
+    const PATH: &'static str = "/conditional_orders/history";
+    const AUTH: bool = true;
+
+    type Response = Vec<TriggerOrderInfo>;
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct CancelTriggerOrder {
+    #[serde(skip_serializing)]
+    pub id: Id,
+}
+
+impl CancelTriggerOrder {
+    pub fn new(order_id: Id) -> Self {
+        Self { id: order_id }
+    }
+}
+
+impl Request for CancelTriggerOrder {
+    const METHOD: Method = Method::DELETE;
+    const PATH: &'static str = "/conditional_orders/{}";
+    const AUTH: bool = true;
+
+    type Response = String;
+
+    fn path(&self) -> Cow<'_, str> {
+        Cow::Owned(format!("/conditional_orders/{}", self.id))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct PlaceTriggerOrder {
@@ -278,7 +389,9 @@ pub struct PlaceTriggerOrder {
     pub side: Side,
     pub size: Decimal,
     pub r#type: OrderType,
-    pub trigger_price: Decimal,
+    /// Unused by trailing stops, which follow the price by `trail_value`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trigger_price: Option<Decimal>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reduce_only: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -294,7 +407,7 @@ impl Request for PlaceTriggerOrder {
     const PATH: &'static str = "/conditional_orders";
     const AUTH: bool = true;
 
-    type Response = OrderInfo;
+    type Response = TriggerOrderInfo;
 }
 
 #[derive(Debug, Clone, Serialize, Default)]
@@ -1,5 +1,6 @@
 use super::*;
 use dotenv::dotenv;
+use ecbt_exchange::errors::EcbtError;
 use rust_decimal_macros::dec;
 use std::env::var;
 
@@ -8,7 +9,7 @@ async fn init_api() -> Rest {
 
     let subaccount = var("SUBACCOUNT").ok();
 
-    let api = Rest::new(Options::from_env());
+    let api = Rest::new(Options::from_env()).expect("Couldn't create client.");
 
     // Test create subaccount only if credentials are account-wide
     if subaccount.is_none() {
@@ -19,12 +20,13 @@ async fn init_api() -> Rest {
 }
 
 async fn init_unauthenticated_api() -> Rest {
-    Rest::new(Options::default())
+    Rest::new(Options::default()).expect("Couldn't create client.")
 }
 
 fn read_only<T>(result: Result<T>) {
     match result {
-        Err(Error::Api(error)) if error == *"Not allowed with read-only permissions" => {}
+        Err(Error::Api(EcbtError::AuthenticationRejected(context)))
+            if context.message == "Not allowed with read-only permissions" => {}
         _ => panic!("Expected read-only subaccount."),
     }
 }
@@ -0,0 +1,401 @@
+use crate::rest::{self, OrderInfo};
+use crate::ws::{
+    Data, FtxSubscription, FtxWebsocketMessage, Orderbook, OrderbookAction, OrderbookData,
+};
+use crate::{Ftx, FtxParameters, Options};
+use async_trait::async_trait;
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::info::OrderValidation;
+use ecbt_exchange::model::currency::Currency;
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::websocket::{EcbtWebSocketMessage, Subscription, WebSocketResponse};
+use ecbt_exchange::model::{
+    AmendMethod, AmendOrderRequest, CancelOrderRequest, ConditionalOrderRequest, EcbtOrderRequest,
+    OrderStatus, OrderTrigger, OrderType, Side, TimeInForce, TradeHistoryRequest, TrailingOffset,
+};
+use ecbt_exchange::shared::Result;
+use ecbt_exchange::transport::{HttpRequest, HttpResponse, HttpTransport};
+use ecbt_exchange::{Exchange, ExchangeAccount};
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use rust_decimal_macros::dec;
+use std::convert::TryInto;
+use std::sync::{Arc, Mutex};
+
+const MARKETS: &str = r#"{"success":true,"result":[{"type":"spot","name":"BTC-USD",
+    "underlying":null,"baseCurrency":"BTC","quoteCurrency":"USD","enabled":true,"ask":101.0,
+    "bid":100.0,"last":100.5,"postOnly":false,"priceIncrement":0.5,"sizeIncrement":0.001,
+    "restricted":false,"minProvideSize":0.001,"price":100.5,"highLeverageFeeExempt":true,
+    "change1h":0.0,"change24h":0.0,"changeBod":0.0,"quoteVolume24h":0.0,"volumeUsd24h":0.0}]}"#;
+
+const ORDER: &str = r#"{"success":true,"result":{"id":9596912,"market":"BTC-USD",
+    "future":null,"type":"limit","side":"buy","price":100.0,"size":0.5,"reduceOnly":false,
+    "ioc":false,"postOnly":true,"status":"open","filledSize":0.1,"remainingSize":0.4,
+    "avgFillPrice":100.0,"liquidation":null,"createdAt":"2019-03-05T09:56:55.728933+00:00",
+    "clientId":"quote-1","retryUntilFilled":null,"triggerPrice":null,"orderPrice":null,
+    "triggeredAt":null,"error":null}}"#;
+
+const TRIGGER_ORDER: &str = r#"{"success":true,"result":{"id":50001,"market":"BTC-USD",
+    "future":null,"type":"trailing_stop","orderType":"market","side":"sell","size":0.5,
+    "status":"open","triggerPrice":null,"orderPrice":null,"trailValue":-50.0,"trailStart":null,
+    "filledSize":0.0,"avgFillPrice":null,"reduceOnly":false,"retryUntilFilled":true,
+    "createdAt":"2019-03-05T09:56:55.728933+00:00","triggeredAt":null,"orderId":null,
+    "error":null,"cancelReason":null}}"#;
+
+/// Answers every request with the body of the first route its path ends with.
+#[derive(Debug)]
+struct RoutedTransport {
+    requests: Mutex<Vec<HttpRequest>>,
+    routes: Vec<(&'static str, &'static str)>,
+}
+
+#[async_trait]
+impl HttpTransport for RoutedTransport {
+    async fn send(&self, request: HttpRequest) -> Result<HttpResponse> {
+        let url = request.url.clone();
+        let (_, body) = self
+            .routes
+            .iter()
+            .find(|(path, _)| url.path().ends_with(path))
+            .expect("No route for the request.");
+        self.requests.lock().unwrap().push(request);
+        Ok(HttpResponse {
+            url,
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+        })
+    }
+}
+
+#[tokio::test]
+async fn ftx_places_limit_orders_through_the_unified_api() {
+    let http = Arc::new(RoutedTransport {
+        requests: Mutex::new(Vec::new()),
+        routes: vec![("/markets", MARKETS), ("/orders", ORDER)],
+    });
+    let options = Options::default()
+        .authenticate("key".to_string(), "secret".to_string())
+        .http_transport(http.clone());
+    let ftx = Ftx::new(
+        FtxParameters::production()
+            .with_options(options)
+            .with_order_validation(OrderValidation::Normalize),
+    )
+    .await
+    .unwrap();
+
+    let order = ftx
+        .limit_buy(&EcbtOrderRequest {
+            client_order_id: Some("quote-1".to_string()),
+            market_pair: MarketPair(Currency::BTC, Currency::USD),
+            size: dec!(0.5),
+            price: dec!(100.2),
+            time_in_force: TimeInForce::GoodTillCancelled,
+            post_only: true,
+        })
+        .await
+        .unwrap();
+    assert_eq!(order.id, "9596912");
+    assert_eq!(order.client_order_id.as_deref(), Some("quote-1"));
+    assert_eq!(order.order_type, OrderType::Limit);
+    assert_eq!(order.status, OrderStatus::PartiallyFilled);
+    assert_eq!(order.remaining, Some(dec!(0.4)));
+
+    let request = http.requests.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(request.method, Method::POST);
+    let body: serde_json::Value = serde_json::from_slice(&request.body.unwrap()).unwrap();
+    assert_eq!(body["market"], "BTC-USD");
+    assert_eq!(body["side"], "buy");
+    assert_eq!(body["type"], "limit");
+    // Bids are rounded down to the price increment.
+    assert_eq!(body["price"], "100");
+    assert_eq!(body["postOnly"], true);
+    assert_eq!(body["clientId"], "quote-1");
+
+    let unsupported = ftx
+        .limit_sell(&EcbtOrderRequest {
+            client_order_id: None,
+            market_pair: MarketPair(Currency::BTC, Currency::USD),
+            size: dec!(0.5),
+            price: dec!(100),
+            time_in_force: TimeInForce::FillOrKill,
+            post_only: false,
+        })
+        .await;
+    assert!(matches!(unsupported, Err(EcbtError::InvalidParameter(_))));
+}
+
+#[tokio::test]
+async fn ftx_trade_history_of_every_market_omits_the_market() {
+    let http = Arc::new(RoutedTransport {
+        requests: Mutex::new(Vec::new()),
+        routes: vec![
+            ("/markets", MARKETS),
+            ("/fills", r#"{"success":true,"result":[]}"#),
+        ],
+    });
+    let options = Options::default()
+        .authenticate("key".to_string(), "secret".to_string())
+        .http_transport(http.clone());
+    let ftx = Ftx::new(FtxParameters::production().with_options(options))
+        .await
+        .unwrap();
+
+    let trades = ftx
+        .get_trade_history(&TradeHistoryRequest::default())
+        .await
+        .unwrap();
+    assert!(trades.is_empty());
+    let request = http.requests.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(request.url.path(), "/api/fills");
+    assert!(!request.url.as_str().contains("marketName"));
+
+    ftx.get_trade_history(&TradeHistoryRequest {
+        market_pair: Some(MarketPair(Currency::BTC, Currency::USD)),
+        ..Default::default()
+    })
+    .await
+    .unwrap();
+    let request = http.requests.lock().unwrap().last().cloned().unwrap();
+    assert!(request.url.as_str().contains("marketName=BTC"));
+}
+
+#[test]
+fn ftx_errors_and_websocket_messages_are_generic() {
+    assert!(matches!(
+        EcbtError::from(rest::Error::NoSecretConfigured),
+        EcbtError::NoApiKeySet()
+    ));
+
+    let subscription = FtxSubscription::from(Subscription::Trades(MarketPair(
+        Currency::BTC,
+        Currency::USD,
+    )));
+    assert_eq!(
+        subscription.channel(&Options::us()).unwrap(),
+        crate::ws::Channel::Trades("BTC/USD".to_string())
+    );
+
+    let trade: rest::Trade = serde_json::from_str(
+        r#"{"id":1,"liquidation":false,"price":100.5,"side":"sell","size":2.0,
+            "time":"2021-05-23T05:24:24.315884+00:00"}"#,
+    )
+    .unwrap();
+    let message = FtxWebsocketMessage {
+        market: Some("BTC-USD".to_string()),
+        data: Data::Trade(trade),
+    };
+    match message.try_into() {
+        Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::Trades(trades))) => {
+            assert_eq!(trades[0].market_pair, "BTC-USD");
+            assert_eq!(trades[0].side, Side::Sell);
+            assert_eq!(trades[0].qty, dec!(2.0));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    let order: OrderInfo = serde_json::from_str::<serde_json::Value>(ORDER)
+        .map(|order| serde_json::from_value(order["result"].clone()).unwrap())
+        .unwrap();
+    let message = FtxWebsocketMessage {
+        market: None,
+        data: Data::Order(order),
+    };
+    assert!(matches!(
+        message.try_into(),
+        Ok(WebSocketResponse::Generic(
+            EcbtWebSocketMessage::OrderUpdate(_)
+        ))
+    ));
+
+    let message = FtxWebsocketMessage {
+        market: None,
+        data: Data::Subscribed {
+            channel: Some("trades".to_string()),
+        },
+    };
+    match message.try_into() {
+        Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::SubscriptionAck {
+            request_id: None,
+            channels,
+        })) => assert_eq!(channels, vec!["trades".to_string()]),
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[test]
+fn order_book_updates_stream_the_verified_book() {
+    let update = OrderbookData {
+        action: OrderbookAction::Partial,
+        bids: vec![(dec!(100), dec!(1)), (dec!(99.5), dec!(2))],
+        asks: vec![(dec!(101), dec!(3)), (dec!(102), dec!(1))],
+        checksum: 0,
+        time: chrono::Utc::now(),
+    };
+    let mut book = Orderbook::new("BTC-USD".to_string());
+    book.update(&update);
+
+    // The changed levels aren't a book, the book they were applied to is.
+    let message = FtxWebsocketMessage {
+        market: Some("BTC-USD".to_string()),
+        data: Data::OrderbookData(update),
+    };
+    assert!(matches!(message.try_into(), Ok(WebSocketResponse::Raw(_))));
+    let message = FtxWebsocketMessage {
+        market: Some("BTC-USD".to_string()),
+        data: Data::Orderbook(book),
+    };
+    match message.try_into() {
+        Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(update))) => {
+            assert_eq!(update.market_pair, "BTC-USD");
+            assert_eq!(update.book.bids[0].price, dec!(100));
+            assert_eq!(update.book.asks[0].price, dec!(101));
+            assert_eq!((update.book.bids.len(), update.book.asks.len()), (2, 2));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    // A book that failed its checksum is emptied until the partial of its new subscription.
+    let message = FtxWebsocketMessage {
+        market: Some("BTC-USD".to_string()),
+        data: Data::OrderbookReset,
+    };
+    match message.try_into() {
+        Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(update))) => {
+            assert_eq!(update.market_pair, "BTC-USD");
+            assert!(update.book.bids.is_empty() && update.book.asks.is_empty());
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+}
+
+#[tokio::test]
+async fn ftx_places_and_cancels_trigger_orders() {
+    let http = Arc::new(RoutedTransport {
+        requests: Mutex::new(Vec::new()),
+        routes: vec![
+            ("/markets", MARKETS),
+            ("/conditional_orders", TRIGGER_ORDER),
+            (
+                "/conditional_orders/50001",
+                r#"{"success":true,"result":"Order cancelled"}"#,
+            ),
+        ],
+    });
+    let options = Options::default()
+        .authenticate("key".to_string(), "secret".to_string())
+        .http_transport(http.clone());
+    let ftx = Ftx::new(FtxParameters::production().with_options(options))
+        .await
+        .unwrap();
+
+    let req = ConditionalOrderRequest {
+        client_order_id: None,
+        market_pair: MarketPair(Currency::BTC, Currency::USD),
+        side: Side::Sell,
+        size: dec!(0.5),
+        trigger: OrderTrigger::TrailingStop {
+            offset: TrailingOffset::Price(dec!(50)),
+        },
+        limit_price: None,
+    };
+    let order = ftx.place_conditional_order(&req).await.unwrap();
+    assert_eq!(order.id, "50001");
+    assert_eq!(order.order_type, OrderType::TrailingStopMarket);
+    assert_eq!(order.status, OrderStatus::Open);
+    assert_eq!(order.remaining, Some(dec!(0.5)));
+
+    let request = http.requests.lock().unwrap().last().cloned().unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&request.body.unwrap()).unwrap();
+    assert_eq!(body["type"], "trailingStop");
+    assert_eq!(body["trailValue"], "-50");
+    assert!(body.get("triggerPrice").is_none());
+
+    let trailing_limit = ConditionalOrderRequest {
+        limit_price: Some(dec!(90)),
+        ..req
+    };
+    assert!(matches!(
+        ftx.place_conditional_order(&trailing_limit).await,
+        Err(EcbtError::UnsupportedOrderType(
+            OrderType::TrailingStopLimit
+        ))
+    ));
+
+    let canceled = ftx
+        .cancel_conditional_order(&CancelOrderRequest {
+            id: "50001".to_string(),
+            market_pair: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(canceled.id, "50001");
+    let request = http.requests.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(request.method, Method::DELETE);
+}
+
+/// The order amended by `ftx_amends_orders_with_modify`, 0.2 of which filled.
+const AMENDED_ORDER: &str = r#"{"success":true,"result":{"id":9596911,"market":"BTC-USD",
+    "future":null,"type":"limit","side":"buy","price":99.5,"size":0.6,"reduceOnly":false,
+    "ioc":false,"postOnly":true,"status":"open","filledSize":0.2,"remainingSize":0.4,
+    "avgFillPrice":99.5,"liquidation":null,"createdAt":"2019-03-05T09:56:55.728933+00:00",
+    "clientId":"quote-0","retryUntilFilled":null,"triggerPrice":null,"orderPrice":null,
+    "triggeredAt":null,"error":null}}"#;
+
+#[tokio::test]
+async fn ftx_amends_orders_with_modify() {
+    let http = Arc::new(RoutedTransport {
+        requests: Mutex::new(Vec::new()),
+        routes: vec![
+            ("/markets", MARKETS),
+            ("/orders/9596911/modify", ORDER),
+            ("/orders/9596911", AMENDED_ORDER),
+        ],
+    });
+    let options = Options::default()
+        .authenticate("key".to_string(), "secret".to_string())
+        .http_transport(http.clone());
+    let ftx = Ftx::new(
+        FtxParameters::production()
+            .with_options(options)
+            .with_order_validation(OrderValidation::Normalize),
+    )
+    .await
+    .unwrap();
+    let amend = AmendOrderRequest {
+        id: "9596911".to_string(),
+        client_order_id: Some("quote-1".to_string()),
+        market_pair: MarketPair(Currency::BTC, Currency::USD),
+        side: Side::Buy,
+        size: dec!(0.7),
+        price: dec!(100.2),
+        time_in_force: TimeInForce::GoodTillCancelled,
+        post_only: true,
+    };
+
+    let amended = ftx.amend_order(&amend).await.unwrap();
+    assert_eq!(amended.method, AmendMethod::Native);
+    assert_eq!(amended.replaced_order_id, "9596911");
+    assert_eq!(amended.replaced_client_order_id.as_deref(), Some("quote-0"));
+    assert_eq!(amended.order.id, "9596912");
+    assert_eq!(amended.order.client_order_id.as_deref(), Some("quote-1"));
+
+    let request = http.requests.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(request.method, Method::POST);
+    let body: serde_json::Value = serde_json::from_slice(&request.body.unwrap()).unwrap();
+    assert_eq!(body["price"], "100");
+    // 0.2 of the order already filled.
+    assert_eq!(body["size"], "0.5");
+    assert_eq!(body["clientId"], "quote-1");
+
+    let filled = ftx
+        .amend_order(&AmendOrderRequest {
+            size: dec!(0.2),
+            ..amend
+        })
+        .await;
+    assert!(matches!(filled, Err(EcbtError::InvalidQuantity(_))));
+    let request = http.requests.lock().unwrap().last().cloned().unwrap();
+    assert_eq!(request.method, Method::GET);
+}
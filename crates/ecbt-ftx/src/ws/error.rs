@@ -1,7 +1,15 @@
 use crate::ws::Channel;
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::model::websocket::Subscription;
 use thiserror::Error;
 use tokio_tungstenite::tungstenite;
 
+impl From<tungstenite::Error> for Error {
+    fn from(error: tungstenite::Error) -> Self {
+        Error::Tungstenite(Box::new(error))
+    }
+}
+
 pub type Result<T> = std::result::Result<T, Error>;
 
 #[derive(Debug, Error)]
@@ -15,9 +23,36 @@ pub enum Error {
     #[error("Socket is not authenticated")]
     SocketNotAuthenticated,
 
+    #[error("Subscription is not supported by FTX {0:?}")]
+    UnsupportedSubscription(Box<Subscription>),
+
+    #[error("Socket is disconnected")]
+    Disconnected,
+
     #[error(transparent)]
-    Tungstenite(#[from] tungstenite::Error),
+    Tungstenite(Box<tungstenite::Error>),
 
     #[error(transparent)]
     Serde(#[from] serde_json::Error),
 }
+
+impl From<Error> for EcbtError {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::NotSubscribedToThisChannel(channel) => {
+                EcbtError::InvalidParameter(format!("Not subscribed to {:?}", channel))
+            }
+            Error::MissingSubscriptionConfirmation => {
+                EcbtError::UnkownResponse("Missing subscription confirmation".to_string())
+            }
+            Error::SocketNotAuthenticated => EcbtError::NoApiKeySet(),
+            Error::UnsupportedSubscription(subscription) => {
+                EcbtError::InvalidParameter(format!("{:?} is not supported by FTX", subscription))
+            }
+            Error::Disconnected => EcbtError::SocketError(),
+            // FTX uses another version of tungstenite than the other adapters.
+            Error::Tungstenite(error) => EcbtError::Generic(error),
+            Error::Serde(error) => EcbtError::JsonError(error),
+        }
+    }
+}
@@ -2,21 +2,25 @@
 
 mod error;
 mod model;
+mod stream;
 #[cfg(test)]
 mod tests;
 
 pub use error::*;
 pub use model::*;
+pub use stream::FtxWebsocket;
 
 use crate::ftx_options::Options;
 use futures::{
+    future::{self, BoxFuture},
     ready,
     task::{Context, Poll},
-    Future, SinkExt, Stream, StreamExt,
+    Sink, SinkExt, Stream, StreamExt,
 };
 use hmac_sha256::HMAC;
-use serde_json::json;
-use std::collections::VecDeque;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::pin::Pin;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::net::TcpStream;
@@ -24,13 +28,38 @@ use tokio::time; // 1.3.0
 use tokio::time::Interval;
 use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
 
+type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+enum State {
+    Connected(Box<WsStream>),
+    /// Waiting for the backoff of the reconnection attempt numbered `attempt`, then connecting.
+    Reconnecting {
+        attempt: u32,
+        connection: BoxFuture<'static, Result<WsStream>>,
+    },
+    Closed,
+}
+
+/// A connection to the FTX websocket API.
+///
+/// With a `reconnect_policy` in its options, a dropped connection is re-established, logged in
+/// again and subscribed to every channel, between a `Data::Disconnected` and a
+/// `Data::Reconnected`. Without one, the stream yields the error and ends.
+///
+/// The checksum of every order book update is verified against a book kept for the market. On
+/// a mismatch the book is dropped, `Data::OrderbookReset` is yielded and the market is
+/// subscribed again, so a new partial follows.
 pub struct Ws {
+    options: Options,
     channels: Vec<Channel>,
-    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    state: State,
     buf: VecDeque<(Option<Symbol>, Data)>,
+    /// Frames waiting to be sent on the connection.
+    outgoing: VecDeque<Message>,
     ping_timer: Interval,
     /// Whether the websocket was opened authenticated with API keys or not
     is_authenticated: bool,
+    orderbooks: Orderbooks,
 }
 
 impl Ws {
@@ -38,9 +67,25 @@ impl Ws {
     pub const ENDPOINT_US: &'static str = "wss://ftx.us/ws";
 
     pub async fn connect(options: Options) -> Result<Self> {
-        println!("Connecting to {}", options.endpoint.ws());
+        log::debug!("Connecting to {}", options.endpoint.ws());
+        let stream = Self::open(options.clone(), Vec::new()).await?;
+        Ok(Self {
+            is_authenticated: options.key.is_some() && options.secret.is_some(),
+            options,
+            channels: Vec::new(),
+            state: State::Connected(Box::new(stream)),
+            buf: VecDeque::new(),
+            outgoing: VecDeque::new(),
+            ping_timer: time::interval(Duration::from_secs(15)),
+            orderbooks: Orderbooks::default(),
+        })
+    }
+
+    /// Opens a connection, logs in when the options have API keys and subscribes to `channels`
+    /// without waiting for the confirmations.
+    async fn open(options: Options, channels: Vec<Channel>) -> Result<WsStream> {
         let (mut stream, _) = connect_async(options.endpoint.ws()).await?;
-        let is_authenticated = if let (Some(key), Some(secret)) = (options.key, options.secret) {
+        if let (Some(key), Some(secret)) = (&options.key, &options.secret) {
             let timestamp = SystemTime::now()
                 .duration_since(UNIX_EPOCH)
                 .unwrap()
@@ -63,30 +108,18 @@ impl Ws {
                     .to_string(),
                 ))
                 .await?;
-            true
-        } else {
-            false
-        };
-        Ok(Self {
-            channels: Vec::new(),
-            stream,
-            buf: VecDeque::new(),
-            ping_timer: time::interval(Duration::from_secs(15)),
-            is_authenticated,
-        })
+        }
+        for channel in &channels {
+            stream.send(request("subscribe", channel)).await?;
+        }
+        Ok(stream)
     }
 
-    async fn ping(&mut self) -> Result<()> {
-        self.stream
-            .send(Message::Text(
-                json!({
-                    "op": "ping",
-                })
-                .to_string(),
-            ))
-            .await?;
-
-        Ok(())
+    fn connection(&mut self) -> Result<&mut WsStream> {
+        match &mut self.state {
+            State::Connected(stream) => Ok(stream),
+            _ => Err(Error::Disconnected),
+        }
     }
 
     /// Subscribe to specified `Channel`s
@@ -120,6 +153,11 @@ impl Ws {
 
         // Unsubscribe successful, remove specified channels from self.channels
         self.channels.retain(|c| !channels.contains(c));
+        for channel in channels {
+            if let Channel::Orderbook(symbol) = channel {
+                self.orderbooks.remove(&symbol);
+            }
+        }
 
         Ok(())
     }
@@ -145,40 +183,26 @@ impl Ws {
         };
 
         'channels: for channel in channels {
-            let (channel, symbol) = match channel {
-                Channel::Orderbook(symbol) => ("orderbook", symbol),
-                Channel::Trades(symbol) => ("trades", symbol),
-                Channel::Ticker(symbol) => ("ticker", symbol),
-                Channel::Fills => ("fills", "".to_string()),
-                Channel::Orders => ("orders", "".to_string()),
-            };
-
-            self.stream
-                .send(Message::Text(
-                    json!({
-                        "op": op,
-                        "channel": channel,
-                        "market": symbol,
-                    })
-                    .to_string(),
-                ))
-                .await?;
+            self.connection()?.send(request(op, &channel)).await?;
+            let (name, symbol) = channel.name_and_market();
 
             // Confirmation should arrive within the next 100 updates
             for _ in 0..100 {
                 let response = self.next_response().await?;
+                let confirms = response.channel.as_deref() == Some(name)
+                    && response.market.as_deref().unwrap_or_default() == symbol;
                 match response {
                     Response {
                         r#type: Type::Subscribed,
                         ..
-                    } if subscribe => {
+                    } if subscribe && confirms => {
                         // Subscribe confirmed
                         continue 'channels;
                     }
                     Response {
                         r#type: Type::Unsubscribed,
                         ..
-                    } if !subscribe => {
+                    } if !subscribe && confirms => {
                         // Unsubscribe confirmed
                         continue 'channels;
                     }
@@ -196,56 +220,169 @@ impl Ws {
     }
 
     async fn next_response(&mut self) -> Result<Response> {
+        future::poll_fn(|cx| self.poll_response(cx)).await
+    }
+
+    /// Polls the connection for the next response, sending the pending frames and a ping every
+    /// 15 seconds meanwhile. Pongs are skipped.
+    fn poll_response(&mut self, cx: &mut Context<'_>) -> Poll<Result<Response>> {
+        let mut ping = false;
+        while self.ping_timer.poll_tick(cx).is_ready() {
+            ping = true;
+        }
+        if ping {
+            self.outgoing
+                .push_back(Message::Text(json!({ "op": "ping" }).to_string()));
+        }
+
+        let stream = match &mut self.state {
+            State::Connected(stream) => stream,
+            _ => return Poll::Ready(Err(Error::Disconnected)),
+        };
+        if let Poll::Ready(Err(err)) = poll_send(stream, &mut self.outgoing, cx) {
+            return Poll::Ready(Err(err.into()));
+        }
+
         loop {
-            tokio::select! {
-                _ = self.ping_timer.tick() => {
-                    self.ping().await?;
-                },
-                Some(msg) = self.stream.next() => {
-                    let msg = msg?;
-                    if let Message::Text(text) = msg {
-                        // println!("{}", text); // Uncomment for debugging
-                        let response: Response = serde_json::from_str(&text)?;
-
-                        // Don't return Pong responses
-                        if let Response { r#type: Type::Pong, .. } = response {
-                            continue;
-                        }
+            let text = match ready!(stream.poll_next_unpin(cx)) {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(_)) => continue,
+                Some(Err(err)) => return Poll::Ready(Err(err.into())),
+                None => return Poll::Ready(Err(Error::Disconnected)),
+            };
+            // println!("{}", text); // Uncomment for debugging
+            let payload: Value = serde_json::from_str(&text)?;
+            let mut response = Response::deserialize(&payload)?;
+            response.payload = payload;
+
+            // Don't return Pong responses
+            if let Response {
+                r#type: Type::Pong, ..
+            } = response
+            {
+                continue;
+            }
 
-                        return Ok(response)
-                    }
-                },
+            return Poll::Ready(Ok(response));
+        }
+    }
+
+    /// Handles the loss of the connection, which is re-established when the options have a
+    /// reconnect policy. Returns the error to yield otherwise.
+    fn disconnected(&mut self, err: Error) -> Option<Error> {
+        self.outgoing.clear();
+        self.orderbooks = Orderbooks::default();
+        if self.options.reconnect_policy.is_none() {
+            self.state = State::Closed;
+            return Some(err);
+        }
+        self.state = self.reconnect(0);
+        self.buf.push_back((None, Data::Disconnected));
+        None
+    }
+
+    fn reconnect(&self, attempt: u32) -> State {
+        let backoff = match &self.options.reconnect_policy {
+            Some(policy) => policy.backoff(attempt),
+            None => return State::Closed,
+        };
+        let options = self.options.clone();
+        let channels = self.channels.clone();
+        State::Reconnecting {
+            attempt,
+            connection: Box::pin(async move {
+                time::sleep(backoff).await;
+                Self::open(options, channels).await
+            }),
+        }
+    }
+
+    /// Polls the reconnection in progress, returning the error to yield once the reconnect
+    /// policy runs out of attempts.
+    fn poll_reconnect(&mut self, cx: &mut Context<'_>) -> Poll<Option<Error>> {
+        let (attempt, connection) = match &mut self.state {
+            State::Reconnecting {
+                attempt,
+                connection,
+            } => (*attempt, connection),
+            _ => return Poll::Ready(None),
+        };
+        match ready!(connection.as_mut().poll(cx)) {
+            Ok(stream) => {
+                self.state = State::Connected(Box::new(stream));
+                self.ping_timer.reset();
+                self.buf.push_back((None, Data::Reconnected));
+                Poll::Ready(None)
+            }
+            Err(err) => {
+                let attempts = attempt + 1;
+                let allowed = self
+                    .options
+                    .reconnect_policy
+                    .as_ref()
+                    .is_some_and(|policy| policy.allows(attempts));
+                if allowed {
+                    self.state = self.reconnect(attempts);
+                    Poll::Ready(None)
+                } else {
+                    self.state = State::Closed;
+                    Poll::Ready(Some(err))
+                }
             }
         }
     }
 
     /// Helper function that takes a response and adds the contents to the buffer
     fn handle_response(&mut self, response: Response) {
-        if let Some(data) = response.data {
-            match data {
-                ResponseData::Trades(trades) => {
-                    // Trades channel returns an array of single trades.
-                    // Buffer so that the user receives trades one at a time
-                    for trade in trades {
-                        self.buf
-                            .push_back((response.market.clone(), Data::Trade(trade)));
-                    }
-                }
-                ResponseData::OrderbookData(orderbook) => {
-                    self.buf
-                        .push_back((response.market, Data::OrderbookData(orderbook)));
-                }
-                ResponseData::Fill(fill) => {
-                    self.buf.push_back((response.market, Data::Fill(fill)));
-                }
-                ResponseData::Ticker(ticker) => {
-                    self.buf.push_back((response.market, Data::Ticker(ticker)));
-                }
-                ResponseData::Order(order) => {
-                    self.buf.push_back((response.market, Data::Order(order)));
+        let market = response.market;
+        let message = response.msg.unwrap_or_default();
+        let data = match (response.r#type, response.data) {
+            (Type::Pong, _) => return,
+            (Type::Subscribed, _) => Data::Subscribed {
+                channel: response.channel,
+            },
+            (Type::Unsubscribed, _) => Data::Unsubscribed {
+                channel: response.channel,
+            },
+            (Type::Error, _) => Data::Error {
+                code: response.code,
+                message,
+            },
+            (Type::Info, _) => Data::Info {
+                code: response.code,
+                message,
+            },
+            (Type::Unknown, _) | (_, Some(ResponseData::Unknown(_))) => Data::Unknown {
+                stream: response.channel.unwrap_or_default(),
+                payload: response.payload,
+            },
+            (_, None) => return,
+            (_, Some(ResponseData::Trades(trades))) => {
+                // Trades channel returns an array of single trades.
+                // Buffer so that the user receives trades one at a time
+                for trade in trades {
+                    self.buf.push_back((market.clone(), Data::Trade(trade)));
                 }
+                return;
             }
-        }
+            (_, Some(ResponseData::OrderbookData(orderbook))) => match &market {
+                Some(symbol) => match self.orderbooks.apply(symbol, &orderbook) {
+                    BookCheck::Valid => Data::OrderbookData(orderbook),
+                    BookCheck::Stale => return,
+                    BookCheck::Mismatch => {
+                        let channel = Channel::Orderbook(symbol.clone());
+                        self.outgoing.push_back(request("unsubscribe", &channel));
+                        self.outgoing.push_back(request("subscribe", &channel));
+                        Data::OrderbookReset
+                    }
+                },
+                None => Data::OrderbookData(orderbook),
+            },
+            (_, Some(ResponseData::Fill(fill))) => Data::Fill(fill),
+            (_, Some(ResponseData::Ticker(ticker))) => Data::Ticker(ticker),
+            (_, Some(ResponseData::Order(order))) => Data::Order(order),
+        };
+        self.buf.push_back((market, data));
     }
 }
 
@@ -253,25 +390,29 @@ impl Stream for Ws {
     type Item = Result<(Option<Symbol>, Data)>;
 
     fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
         loop {
-            if let Some(data) = self.buf.pop_front() {
+            if let Some(data) = this.buf.pop_front() {
                 return Poll::Ready(Some(Ok(data)));
             }
-            let response = {
-                // Fetch new response if buffer is empty.
-                // safety: this is ok because the future from self.next_response() will only live in this function.
-                // It won't be moved anymore.
-                let mut next_response = self.next_response();
-                let pinned = unsafe { Pin::new_unchecked(&mut next_response) };
-                match ready!(pinned.poll(cx)) {
-                    Ok(response) => response,
-                    Err(e) => {
-                        return Poll::Ready(Some(Err(e)));
+            match this.state {
+                State::Closed => return Poll::Ready(None),
+                State::Reconnecting { .. } => {
+                    if let Some(err) = ready!(this.poll_reconnect(cx)) {
+                        return Poll::Ready(Some(Err(err)));
                     }
                 }
-            };
-            // Handle the response, possibly adding to the buffer
-            self.handle_response(response);
+                State::Connected(_) => match ready!(this.poll_response(cx)) {
+                    // Handle the response, possibly adding to the buffer
+                    Ok(response) => this.handle_response(response),
+                    Err(err @ (Error::Tungstenite(_) | Error::Disconnected)) => {
+                        if let Some(err) = this.disconnected(err) {
+                            return Poll::Ready(Some(Err(err)));
+                        }
+                    }
+                    Err(err) => return Poll::Ready(Some(Err(err))),
+                },
+            }
         }
     }
 
@@ -279,3 +420,76 @@ impl Stream for Ws {
         (self.buf.len(), None)
     }
 }
+
+/// Returns the frame of the `op` request, `subscribe` or `unsubscribe`, for `channel`.
+fn request(op: &str, channel: &Channel) -> Message {
+    let (channel, market) = channel.name_and_market();
+    Message::Text(
+        json!({
+            "op": op,
+            "channel": channel,
+            "market": market,
+        })
+        .to_string(),
+    )
+}
+
+/// Sends the `outgoing` frames on `stream` as far as it accepts them, then flushes it.
+fn poll_send(
+    stream: &mut WsStream,
+    outgoing: &mut VecDeque<Message>,
+    cx: &mut Context<'_>,
+) -> Poll<std::result::Result<(), tokio_tungstenite::tungstenite::Error>> {
+    while !outgoing.is_empty() {
+        ready!(Pin::new(&mut *stream).poll_ready(cx))?;
+        if let Some(message) = outgoing.pop_front() {
+            Pin::new(&mut *stream).start_send(message)?;
+        }
+    }
+    Pin::new(stream).poll_flush(cx)
+}
+
+/// This enum represents how an order book update fits the book kept for its market.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BookCheck {
+    /// The checksum of the updated book matches.
+    Valid,
+    /// The update belongs to a book dropped after a mismatch, whose partial has not arrived.
+    Stale,
+    /// The checksum of the updated book doesn't match, the book was dropped.
+    Mismatch,
+}
+
+/// The books of the order book channels, kept to verify the checksum of every update.
+#[derive(Default)]
+struct Orderbooks {
+    books: HashMap<Symbol, Orderbook>,
+    /// Markets waiting for a new partial after a mismatch.
+    resyncing: HashSet<Symbol>,
+}
+
+impl Orderbooks {
+    fn apply(&mut self, symbol: &Symbol, data: &OrderbookData) -> BookCheck {
+        if data.action == OrderbookAction::Partial {
+            self.resyncing.remove(symbol);
+        } else if self.resyncing.contains(symbol) {
+            return BookCheck::Stale;
+        }
+        let book = self
+            .books
+            .entry(symbol.clone())
+            .or_insert_with(|| Orderbook::new(symbol.clone()));
+        book.update(data);
+        if book.verify_checksum(data.checksum) {
+            return BookCheck::Valid;
+        }
+        self.books.remove(symbol);
+        self.resyncing.insert(symbol.clone());
+        BookCheck::Mismatch
+    }
+
+    fn remove(&mut self, symbol: &Symbol) {
+        self.books.remove(symbol);
+        self.resyncing.remove(symbol);
+    }
+}
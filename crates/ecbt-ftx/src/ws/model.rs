@@ -1,9 +1,13 @@
+use super::{Error, Result};
+use crate::ftx_options::Options;
 pub use crate::rest::{Coin, Id, MarketType, OrderInfo, Side, Symbol, Trade};
 use chrono::{DateTime, Utc};
 use crc32fast::Hasher;
+use ecbt_exchange::model::websocket::{Subscription, TickerUpdate};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use serde_with::{serde_as, TimestampSecondsWithFrac};
 use std::collections::BTreeMap;
 
@@ -17,12 +21,92 @@ pub enum Channel {
     Orders,
 }
 
+impl Channel {
+    /// Returns the channel streaming a generic `subscription`, FTX doesn't stream candles nor
+    /// balances. The fills and orders channels cover every market of the account.
+    pub fn from_subscription(subscription: Subscription, options: &Options) -> Result<Self> {
+        match subscription {
+            Subscription::Ticker(market_pair) => {
+                Ok(Channel::Ticker(options.to_market(market_pair)))
+            }
+            Subscription::OrderBookUpdates(market_pair) => {
+                Ok(Channel::Orderbook(options.to_market(market_pair)))
+            }
+            Subscription::Trades(market_pair) => {
+                Ok(Channel::Trades(options.to_market(market_pair)))
+            }
+            Subscription::AccountTrades(_) => Ok(Channel::Fills),
+            Subscription::AccountOrders(_) => Ok(Channel::Orders),
+            Subscription::Candles(..) | Subscription::AccountBalance(_) => {
+                Err(Error::UnsupportedSubscription(Box::new(subscription)))
+            }
+        }
+    }
+
+    /// Returns the name of the channel and its market, empty for the channels of the account.
+    pub fn name_and_market(&self) -> (&'static str, &str) {
+        match self {
+            Channel::Orderbook(symbol) => ("orderbook", symbol),
+            Channel::Trades(symbol) => ("trades", symbol),
+            Channel::Ticker(symbol) => ("ticker", symbol),
+            Channel::Fills => ("fills", ""),
+            Channel::Orders => ("orders", ""),
+        }
+    }
+}
+
+/// This enum represents a subscription of an `FtxWebsocket`, either generic or to a channel.
+#[derive(Clone, Debug, PartialEq)]
+pub enum FtxSubscription {
+    /// Mapped to its channel with the market names of the endpoint of the connection.
+    Generic(Subscription),
+    Channel(Channel),
+}
+
+impl From<Subscription> for FtxSubscription {
+    fn from(subscription: Subscription) -> Self {
+        FtxSubscription::Generic(subscription)
+    }
+}
+
+impl From<Channel> for FtxSubscription {
+    fn from(channel: Channel) -> Self {
+        FtxSubscription::Channel(channel)
+    }
+}
+
+impl FtxSubscription {
+    /// Returns the channel streaming the subscription, see `Channel::from_subscription`.
+    pub fn channel(self, options: &Options) -> Result<Channel> {
+        match self {
+            FtxSubscription::Generic(subscription) => {
+                Channel::from_subscription(subscription, options)
+            }
+            FtxSubscription::Channel(channel) => Ok(channel),
+        }
+    }
+}
+
+/// This struct represents a message of an `FtxWebsocket`, with the market it is about.
+#[derive(Clone, Debug, Serialize)]
+pub struct FtxWebsocketMessage {
+    pub market: Option<Symbol>,
+    pub data: Data,
+}
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Response {
     pub market: Option<Symbol>,
+    pub channel: Option<String>,
     pub r#type: Type,
     pub data: Option<ResponseData>,
+    /// Error and info responses carry a code and a message.
+    pub code: Option<i64>,
+    pub msg: Option<String>,
+    /// The response as received.
+    #[serde(skip)]
+    pub payload: Value,
 }
 
 #[derive(Copy, Clone, Debug, Deserialize, Serialize)]
@@ -35,6 +119,9 @@ pub enum Type {
     Partial,
     Pong,
     Info,
+    /// A response type this version doesn't know of.
+    #[serde(other)]
+    Unknown,
 }
 
 /// Represents the response received from FTX, and is used for
@@ -48,6 +135,8 @@ pub enum ResponseData {
     OrderbookData(OrderbookData),
     Fill(Fill),
     Order(OrderInfo),
+    /// Data of a shape this version doesn't know of.
+    Unknown(Value),
 }
 
 /// Represents the data we return to the user
@@ -55,9 +144,43 @@ pub enum ResponseData {
 pub enum Data {
     Ticker(Ticker),
     Trade(Trade),
+    /// A partial or update of the order book of the market, as received.
     OrderbookData(OrderbookData),
+    /// The order book of the market after a partial or update, streamed by `FtxWebsocket`.
+    Orderbook(Orderbook),
     Fill(Fill),
     Order(OrderInfo),
+    /// A channel was subscribed to.
+    Subscribed {
+        channel: Option<String>,
+    },
+    /// A channel was unsubscribed from.
+    Unsubscribed {
+        channel: Option<String>,
+    },
+    /// FTX rejected a request or reported an error on the connection.
+    Error {
+        code: Option<i64>,
+        message: String,
+    },
+    /// FTX reported an event of the connection, such as an upcoming restart.
+    Info {
+        code: Option<i64>,
+        message: String,
+    },
+    /// A response or data this version doesn't know of, kept as received.
+    Unknown {
+        stream: String,
+        payload: Value,
+    },
+    /// The order book of the market failed its checksum and was dropped, it is subscribed to
+    /// again and its next update is a partial. Generic streams receive an empty book.
+    OrderbookReset,
+    /// The connection dropped, nothing is received until `Reconnected`.
+    Disconnected,
+    /// The connection was re-established, logged in and subscribed to every channel again.
+    /// Order books restart with a partial.
+    Reconnected,
 }
 
 #[serde_as]
@@ -73,6 +196,22 @@ pub struct Ticker {
     pub time: DateTime<Utc>,
 }
 
+impl Ticker {
+    /// Converts the ticker of `market` to a generic ticker update.
+    pub fn into_update(self, market: Symbol) -> TickerUpdate {
+        TickerUpdate {
+            market_pair: market,
+            ticker: ecbt_exchange::model::Ticker {
+                price: Some(self.last),
+                price_24h: None,
+            },
+            best_bid: Some(self.bid),
+            best_ask: Some(self.ask),
+            time: Some(self.time.timestamp_millis() as u64),
+        }
+    }
+}
+
 /// Order book data received from FTX which is used for initializing and updating
 /// the OrderBook struct
 #[serde_as]
@@ -103,7 +242,7 @@ pub enum OrderbookAction {
 /// Represents the current state of the orderbook, guaranteed to be accurate
 /// up to the best 100 bids and best 100 asks since the latest update.
 /// Supports efficient insertions, updates, and deletions via a BTreeMap.
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct Orderbook {
     pub symbol: Symbol,
     pub bids: BTreeMap<Decimal, Decimal>,
@@ -195,7 +334,7 @@ impl Orderbook {
 
     /// Returns the price of the best bid
     pub fn bid_price(&self) -> Option<Decimal> {
-        self.bids.keys().rev().next().cloned()
+        self.bids.keys().next_back().cloned()
     }
 
     /// Returns the price of the best ask
@@ -212,7 +351,7 @@ impl Orderbook {
     /// Returns the price and quantity of the best bid
     /// (bid_price, bid_quantity)
     pub fn best_bid(&self) -> Option<(Decimal, Decimal)> {
-        let (price, quantity) = self.bids.iter().rev().next()?;
+        let (price, quantity) = self.bids.iter().next_back()?;
 
         Some((*price, *quantity))
     }
@@ -0,0 +1,182 @@
+use super::{Data, FtxSubscription, FtxWebsocketMessage, Orderbook, Symbol, Ws};
+use crate::ftx_options::FtxParameters;
+use async_trait::async_trait;
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::model::websocket::{EcbtWebSocketMessage, OrderBookUpdate, WebSocketResponse};
+use ecbt_exchange::model::OrderBookResponse;
+use ecbt_exchange::shared::Result;
+use ecbt_exchange::stream::{ExchangeStream, Subscriptions};
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+/// A websocket connection to FTX
+pub struct FtxWebsocket {
+    pub parameters: FtxParameters,
+    disconnection_senders: Arc<Mutex<Vec<UnboundedSender<()>>>>,
+}
+
+#[async_trait]
+impl ExchangeStream for FtxWebsocket {
+    type InitParams = FtxParameters;
+    type Subscription = FtxSubscription;
+    type Response = FtxWebsocketMessage;
+
+    async fn new(parameters: Self::InitParams) -> Result<Self> {
+        Ok(Self {
+            parameters,
+            disconnection_senders: Default::default(),
+        })
+    }
+
+    async fn disconnect(&self) {
+        if let Ok(mut senders) = self.disconnection_senders.lock() {
+            for sender in senders.iter() {
+                sender.send(()).ok();
+            }
+            senders.clear();
+        }
+    }
+
+    /// Opens a connection, logged in when the options have API keys, subscribed to the
+    /// channels of every subscription.
+    async fn create_stream_specific(
+        &self,
+        subscriptions: Subscriptions<Self::Subscription>,
+    ) -> Result<BoxStream<'static, Result<Self::Response>>> {
+        let options = &self.parameters.options;
+        let mut channels = Vec::new();
+        for subscription in subscriptions {
+            let channel = subscription.channel(options)?;
+            if !channels.contains(&channel) {
+                channels.push(channel);
+            }
+        }
+        if channels.is_empty() {
+            return Err(EcbtError::InvalidParameter(
+                "At least one subscription is required".to_string(),
+            ));
+        }
+
+        let mut ws = Ws::connect(options.clone()).await?;
+        ws.subscribe(channels).await?;
+
+        let (disconnection_sender, mut disconnection_receiver) = unbounded_channel();
+        if let Ok(mut senders) = self.disconnection_senders.lock() {
+            // Connections that ended dropped their receiver, their senders are closed.
+            senders.retain(|sender| !sender.is_closed());
+            senders.push(disconnection_sender);
+        }
+        let mut books = HashMap::new();
+        Ok(ws
+            .take_until(async move { disconnection_receiver.recv().await })
+            .flat_map(move |message| {
+                stream::iter(match message {
+                    Ok((market, data)) => {
+                        with_books(&mut books, FtxWebsocketMessage { market, data })
+                            .into_iter()
+                            .map(Ok)
+                            .collect()
+                    }
+                    Err(err) => vec![Err(err.into())],
+                })
+            })
+            .boxed())
+    }
+}
+
+/// Follows every order book partial or update, verified by `Ws`, with the book of its market
+/// as `Data::Orderbook`. A book is dropped when `Ws` resets it.
+pub(super) fn with_books(
+    books: &mut HashMap<Symbol, Orderbook>,
+    message: FtxWebsocketMessage,
+) -> Vec<FtxWebsocketMessage> {
+    let (symbol, data) = match (&message.market, &message.data) {
+        (Some(symbol), Data::OrderbookData(data)) => (symbol, data),
+        (Some(symbol), Data::OrderbookReset) => {
+            books.remove(symbol);
+            return vec![message];
+        }
+        (_, Data::Reconnected) => {
+            books.clear();
+            return vec![message];
+        }
+        _ => return vec![message],
+    };
+    let book = books
+        .entry(symbol.clone())
+        .or_insert_with(|| Orderbook::new(symbol.clone()));
+    book.update(data);
+    let book = FtxWebsocketMessage {
+        market: message.market.clone(),
+        data: Data::Orderbook(book.clone()),
+    };
+    vec![message, book]
+}
+
+impl TryFrom<FtxWebsocketMessage> for WebSocketResponse<FtxWebsocketMessage> {
+    type Error = EcbtError;
+
+    fn try_from(value: FtxWebsocketMessage) -> Result<Self> {
+        let market = value.market.clone().unwrap_or_default();
+        match value.data {
+            Data::Ticker(ticker) => Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::Ticker(
+                ticker.into_update(market),
+            ))),
+            Data::Trade(trade) => Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::Trades(
+                vec![trade.into_trade(market)],
+            ))),
+            // Updates only carry the changed levels, the book they were applied to follows.
+            Data::Orderbook(book) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::OrderBook(OrderBookUpdate {
+                    market_pair: market,
+                    book: book.into(),
+                }),
+            )),
+            // The book was dropped until its next partial, an empty book tells generic
+            // consumers to stop using it.
+            Data::OrderbookReset if value.market.is_some() => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::OrderBook(OrderBookUpdate {
+                    market_pair: market,
+                    book: OrderBookResponse::default(),
+                }),
+            )),
+            Data::Fill(fill) => Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::Fill(
+                fill.into(),
+            ))),
+            Data::Order(order) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::OrderUpdate(order.into()),
+            )),
+            Data::Subscribed { channel } => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::SubscriptionAck {
+                    request_id: None,
+                    channels: channel.into_iter().collect(),
+                },
+            )),
+            Data::Error { code, message } => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::SubscriptionError {
+                    request_id: None,
+                    code,
+                    message,
+                },
+            )),
+            Data::Disconnected => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Disconnected,
+            )),
+            Data::Reconnected => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Reconnected,
+            )),
+            data @ (Data::OrderbookData(_)
+            | Data::Unsubscribed { .. }
+            | Data::Info { .. }
+            | Data::Unknown { .. }
+            | Data::OrderbookReset) => Ok(WebSocketResponse::Raw(FtxWebsocketMessage {
+                market: value.market,
+                data,
+            })),
+        }
+    }
+}
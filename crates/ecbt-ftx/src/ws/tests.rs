@@ -1,6 +1,8 @@
 use super::*;
 use crate::rest::{OrderStatus, Rest};
+use chrono::Utc;
 use dotenv::dotenv;
+use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 
 async fn init_authenticated_ws() -> Ws {
@@ -20,7 +22,7 @@ async fn init_unauthenticated_ws() -> Ws {
 async fn init_api() -> Rest {
     dotenv().ok();
 
-    Rest::new(Options::from_env())
+    Rest::new(Options::from_env()).expect("Couldn't create client.")
 }
 
 #[tokio::test]
@@ -352,3 +354,107 @@ async fn orders() {
 
     ws.unsubscribe_all().await.expect("Unsubscribe failed");
 }
+
+#[test]
+fn unknown_responses_are_kept() {
+    let response: Response =
+        serde_json::from_str(r#"{"type":"auction","channel":"auctions","market":"BTC-PERP"}"#)
+            .unwrap();
+    assert!(matches!(response.r#type, Type::Unknown));
+
+    let response: Response = serde_json::from_str(
+        r#"{"type":"update","channel":"markets","data":{"action":"partial","new":true}}"#,
+    )
+    .unwrap();
+    assert!(matches!(response.data, Some(ResponseData::Unknown(_))));
+
+    let response: Response =
+        serde_json::from_str(r#"{"type":"error","code":400,"msg":"Invalid login credentials"}"#)
+            .unwrap();
+    assert_eq!(response.code, Some(400));
+    assert_eq!(response.msg.as_deref(), Some("Invalid login credentials"));
+}
+
+#[test]
+fn order_book_checksum_mismatch_resets_the_book() {
+    let symbol: Symbol = String::from("BTC-PERP");
+    let data = |action, bids: Vec<(Decimal, Decimal)>, asks, checksum| OrderbookData {
+        action,
+        bids,
+        asks,
+        checksum,
+        time: Utc::now(),
+    };
+    let mut orderbooks = Orderbooks::default();
+
+    let partial = crc32fast::hash(b"100.0:1.0:101.0:2.0");
+    let partial = data(
+        OrderbookAction::Partial,
+        vec![(dec!(100), dec!(1))],
+        vec![(dec!(101), dec!(2))],
+        partial,
+    );
+    assert_eq!(orderbooks.apply(&symbol, &partial), BookCheck::Valid);
+
+    let update = crc32fast::hash(b"100.0:3.0:101.0:2.0");
+    let update = data(
+        OrderbookAction::Update,
+        vec![(dec!(100), dec!(3))],
+        vec![],
+        update,
+    );
+    assert_eq!(orderbooks.apply(&symbol, &update), BookCheck::Valid);
+
+    let corrupted = data(
+        OrderbookAction::Update,
+        vec![(dec!(99), dec!(1))],
+        vec![],
+        0,
+    );
+    assert_eq!(orderbooks.apply(&symbol, &corrupted), BookCheck::Mismatch);
+    // Updates are dropped until the partial of the new subscription.
+    assert_eq!(orderbooks.apply(&symbol, &update), BookCheck::Stale);
+    assert_eq!(orderbooks.apply(&symbol, &partial), BookCheck::Valid);
+    assert_eq!(orderbooks.apply(&symbol, &update), BookCheck::Valid);
+}
+
+#[test]
+fn websocket_streams_follow_order_book_updates_with_their_book() {
+    let symbol = "BTC-PERP".to_string();
+    let message = |data| FtxWebsocketMessage {
+        market: Some(symbol.clone()),
+        data,
+    };
+    let data = |action, bids| {
+        Data::OrderbookData(OrderbookData {
+            action,
+            bids,
+            asks: vec![(dec!(101), dec!(2))],
+            checksum: 0,
+            time: Utc::now(),
+        })
+    };
+    let book = |messages: Vec<FtxWebsocketMessage>| match messages.as_slice() {
+        [FtxWebsocketMessage {
+            data: Data::OrderbookData(_),
+            ..
+        }, FtxWebsocketMessage {
+            data: Data::Orderbook(book),
+            ..
+        }] => book.clone(),
+        messages => panic!("Unexpected messages: {:?}", messages),
+    };
+    let mut books = HashMap::new();
+
+    let partial = data(OrderbookAction::Partial, vec![(dec!(100), dec!(1))]);
+    let partial = book(stream::with_books(&mut books, message(partial)));
+    assert_eq!(partial.bids.len(), 1);
+    let update = data(OrderbookAction::Update, vec![(dec!(99), dec!(4))]);
+    let update = book(stream::with_books(&mut books, message(update)));
+    assert_eq!(update.bids.get(&dec!(100)), Some(&dec!(1)));
+    assert_eq!(update.bids.get(&dec!(99)), Some(&dec!(4)));
+
+    let reset = stream::with_books(&mut books, message(Data::OrderbookReset));
+    assert_eq!(reset.len(), 1);
+    assert!(books.is_empty());
+}
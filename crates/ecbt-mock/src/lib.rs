@@ -0,0 +1,210 @@
+//! This module provides an in-memory exchange for testing strategies without a network.
+//!
+//! `MockExchange` runs a price-time-priority matching engine per configured market and
+//! settles fills against a simulated account. Scripted orders play the rest of the market.
+//!
+//! `PaperExchange` runs the same simulated account against the market data of a live exchange.
+
+pub use ecbt_exchange::shared;
+
+use async_trait::async_trait;
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::info::{ExchangeInfo, ExchangeInfoRetrieval, MarketPairHandle, MarketPairInfo};
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::{
+    Balance, CancelAllOrdersRequest, CancelOrderRequest, Candle, EcbtOrderRequest,
+    GetHistoricRatesRequest, GetHistoricTradesRequest, GetOrderHistoryRequest, GetOrderRequest,
+    GetPriceTickerRequest, OpenMarketOrderRequest, Order, OrderBookRequest, OrderBookResponse,
+    OrderCanceled, Paginator, Side, Ticker, Trade, TradeHistoryRequest,
+};
+use ecbt_exchange::{Exchange, ExchangeAccount, ExchangeMarketData};
+use std::sync::{Arc, Mutex, MutexGuard};
+use tokio::sync::broadcast;
+
+use crate::shared::Result;
+
+mod matching_engine;
+mod mock_parameters;
+mod mock_state;
+mod mock_websocket_message;
+mod paper_exchange;
+mod paper_parameters;
+mod scripted_order;
+mod stream;
+#[cfg(test)]
+mod tests;
+
+pub use matching_engine::*;
+pub use mock_parameters::*;
+pub use mock_websocket_message::*;
+pub use paper_exchange::*;
+pub use paper_parameters::*;
+pub use scripted_order::*;
+
+use mock_state::MockState;
+
+const EVENT_CAPACITY: usize = 1024;
+
+/// The main struct of the ecbt-mock module.
+///
+/// Clones share the same books and account, so one clone can be handed to the strategy
+/// while another drives the scripted market or listens to its streams.
+#[derive(Clone)]
+pub struct MockExchange {
+    pub exchange_info: ExchangeInfo,
+    state: Arc<Mutex<MockState>>,
+    events: broadcast::Sender<MockWebsocketMessage>,
+    disconnection: broadcast::Sender<()>,
+}
+
+impl MockExchange {
+    /// Submit an order on behalf of a scripted market participant.
+    pub fn submit(&self, order: ScriptedOrder) -> Result<Order> {
+        self.update(|state| state.submit(order))
+    }
+
+    /// Move the exchange clock forward, which timestamps new orders, trades and candles.
+    pub fn advance_time(&self, millis: u64) -> Result<()> {
+        self.update(|state| {
+            state.advance_time(millis);
+            Ok(())
+        })
+    }
+
+    /// Current time of the exchange clock in milliseconds.
+    pub fn now(&self) -> Result<u64> {
+        Ok(self.state()?.now())
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, MockState>> {
+        self.state.lock().map_err(|_| EcbtError::PoisonError())
+    }
+
+    /// Runs `f` on the state and publishes the resulting events before releasing the lock,
+    /// so subscribers observe them in the order they happened.
+    fn update<T>(&self, f: impl FnOnce(&mut MockState) -> Result<T>) -> Result<T> {
+        let mut state = self.state()?;
+        let result = f(&mut state);
+        for event in state.drain_events() {
+            self.events.send(event).ok();
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl Exchange for MockExchange {
+    type InitParams = MockParameters;
+    type InnerClient = ();
+
+    async fn new(parameters: Self::InitParams) -> Result<Self> {
+        let (events, _) = broadcast::channel(EVENT_CAPACITY);
+        let (disconnection, _) = broadcast::channel(1);
+        let mock = MockExchange {
+            exchange_info: ExchangeInfo::new(),
+            state: Arc::new(Mutex::new(MockState::new(parameters)?)),
+            events,
+            disconnection,
+        };
+
+        mock.refresh_market_info().await?;
+        Ok(mock)
+    }
+
+    fn inner_client(&self) -> Option<&Self::InnerClient> {
+        None
+    }
+}
+
+#[async_trait]
+impl ExchangeInfoRetrieval for MockExchange {
+    async fn retrieve_pairs(&self) -> Result<Vec<MarketPairInfo>> {
+        Ok(self.state()?.markets().to_vec())
+    }
+
+    async fn refresh_market_info(&self) -> Result<Vec<MarketPairHandle>> {
+        self.exchange_info
+            .refresh(self as &dyn ExchangeInfoRetrieval)
+            .await
+    }
+
+    async fn get_pair(&self, market_pair: &MarketPair) -> Result<MarketPairHandle> {
+        let symbol = self.state()?.symbol(market_pair)?;
+        self.exchange_info.get_pair(&symbol)
+    }
+}
+
+#[async_trait]
+impl ExchangeMarketData for MockExchange {
+    async fn order_book(&self, req: &OrderBookRequest) -> Result<OrderBookResponse> {
+        let state = self.state()?;
+        state.order_book(&state.symbol(&req.market_pair)?)
+    }
+
+    async fn get_price_ticker(&self, req: &GetPriceTickerRequest) -> Result<Ticker> {
+        let state = self.state()?;
+        let symbol = state.symbol(&req.market_pair)?;
+        Ok(Ticker {
+            price: state.last_price(&symbol),
+            price_24h: None,
+        })
+    }
+
+    async fn get_historic_rates(&self, req: &GetHistoricRatesRequest) -> Result<Vec<Candle>> {
+        let state = self.state()?;
+        let symbol = state.symbol(&req.market_pair)?;
+        let interval = req.interval.to_duration().whole_milliseconds() as u64;
+        Ok(state.candles(&symbol, interval))
+    }
+
+    async fn get_historic_trades(&self, req: &GetHistoricTradesRequest) -> Result<Vec<Trade>> {
+        Ok(self.state()?.market_trades(&req.market_pair))
+    }
+}
+
+#[async_trait]
+impl ExchangeAccount for MockExchange {
+    async fn limit_buy(&self, req: &EcbtOrderRequest) -> Result<Order> {
+        self.update(|state| state.place_limit(Side::Buy, req))
+    }
+
+    async fn limit_sell(&self, req: &EcbtOrderRequest) -> Result<Order> {
+        self.update(|state| state.place_limit(Side::Sell, req))
+    }
+
+    async fn market_buy(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
+        self.update(|state| state.place_market(Side::Buy, req))
+    }
+
+    async fn market_sell(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
+        self.update(|state| state.place_market(Side::Sell, req))
+    }
+
+    async fn cancel_order(&self, req: &CancelOrderRequest) -> Result<OrderCanceled> {
+        self.update(|state| state.cancel(&req.id))
+    }
+
+    async fn cancel_all_orders(&self, req: &CancelAllOrdersRequest) -> Result<Vec<OrderCanceled>> {
+        self.update(|state| state.cancel_all_orders(req))
+    }
+
+    async fn get_all_open_orders(&self) -> Result<Vec<Order>> {
+        Ok(self.state()?.open_orders())
+    }
+
+    async fn get_order_history(&self, req: &GetOrderHistoryRequest) -> Result<Vec<Order>> {
+        self.state()?.order_history(req)
+    }
+
+    async fn get_trade_history(&self, req: &TradeHistoryRequest) -> Result<Vec<Trade>> {
+        self.state()?.trade_history(req)
+    }
+
+    async fn get_account_balances(&self, _paginator: Option<Paginator>) -> Result<Vec<Balance>> {
+        Ok(self.state()?.balances())
+    }
+
+    async fn get_order(&self, req: &GetOrderRequest) -> Result<Order> {
+        self.state()?.order(&req.id)
+    }
+}
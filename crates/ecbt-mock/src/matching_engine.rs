@@ -0,0 +1,191 @@
+use ecbt_exchange::model::{AskBid, Side};
+use rust_decimal::prelude::Decimal;
+use std::collections::{BTreeMap, VecDeque};
+
+/// This enum represents who owns an order resting in the matching engine
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Owner {
+    /// The simulated account, whose balances are settled on every fill.
+    Account,
+    /// Scripted market participants, which have no balances.
+    Script,
+}
+
+/// This struct represents an order resting in the matching engine
+#[derive(Clone, Debug, PartialEq)]
+pub struct RestingOrder {
+    pub id: String,
+    pub owner: Owner,
+    pub side: Side,
+    pub price: Decimal,
+    pub remaining: Decimal,
+}
+
+/// This struct represents a match between an incoming order and a resting order
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fill {
+    pub maker_order_id: String,
+    pub maker_owner: Owner,
+    pub price: Decimal,
+    pub qty: Decimal,
+}
+
+/// This struct represents a price-time-priority matching engine for a single market.
+///
+/// Incoming orders are matched against the best opposite price level first and, within a
+/// level, against the oldest resting order first. Fills always happen at the maker's price.
+#[derive(Clone, Debug, Default)]
+pub struct MatchingEngine {
+    bids: BTreeMap<Decimal, VecDeque<RestingOrder>>,
+    asks: BTreeMap<Decimal, VecDeque<RestingOrder>>,
+}
+
+impl MatchingEngine {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Returns whether an order on `side` at `price` would take liquidity.
+    pub fn crosses(&self, side: &Side, price: Decimal) -> bool {
+        self.best_opposite(side)
+            .map(|best| Self::is_marketable(side, best, Some(price)))
+            .unwrap_or(false)
+    }
+
+    /// Computes the `(price, qty)` fills an order would receive without touching the book.
+    pub fn preview(
+        &self,
+        side: &Side,
+        limit: Option<Decimal>,
+        size: Decimal,
+    ) -> Vec<(Decimal, Decimal)> {
+        let levels: Box<dyn Iterator<Item = (&Decimal, &VecDeque<RestingOrder>)>> = match side {
+            Side::Buy => Box::new(self.asks.iter()),
+            Side::Sell => Box::new(self.bids.iter().rev()),
+        };
+
+        let mut fills = Vec::new();
+        let mut left = size;
+        for (price, orders) in levels {
+            if left.is_zero() || !Self::is_marketable(side, *price, limit) {
+                break;
+            }
+            let qty = orders
+                .iter()
+                .map(|order| order.remaining)
+                .sum::<Decimal>()
+                .min(left);
+            left -= qty;
+            fills.push((*price, qty));
+        }
+        fills
+    }
+
+    /// Matches an incoming order against the book, removing the liquidity it takes.
+    pub fn execute(&mut self, side: &Side, limit: Option<Decimal>, size: Decimal) -> Vec<Fill> {
+        let mut fills = Vec::new();
+        let mut left = size;
+
+        while !left.is_zero() {
+            let price = match self.best_opposite(side) {
+                Some(price) if Self::is_marketable(side, price, limit) => price,
+                _ => break,
+            };
+            let book = match side {
+                Side::Buy => &mut self.asks,
+                Side::Sell => &mut self.bids,
+            };
+            let level = book.get_mut(&price).expect("Best level must exist.");
+
+            while let Some(maker) = level.front_mut() {
+                if left.is_zero() {
+                    break;
+                }
+                let qty = maker.remaining.min(left);
+                maker.remaining -= qty;
+                left -= qty;
+                fills.push(Fill {
+                    maker_order_id: maker.id.clone(),
+                    maker_owner: maker.owner,
+                    price,
+                    qty,
+                });
+                if maker.remaining.is_zero() {
+                    level.pop_front();
+                }
+            }
+
+            if level.is_empty() {
+                book.remove(&price);
+            }
+        }
+        fills
+    }
+
+    /// Places an order at the back of its price level.
+    pub fn insert(&mut self, order: RestingOrder) {
+        let book = match order.side {
+            Side::Buy => &mut self.bids,
+            Side::Sell => &mut self.asks,
+        };
+        book.entry(order.price).or_default().push_back(order);
+    }
+
+    /// Removes a resting order from the book.
+    pub fn cancel(&mut self, id: &str) -> Option<RestingOrder> {
+        for book in [&mut self.bids, &mut self.asks] {
+            let found = book.iter_mut().find_map(|(price, level)| {
+                level
+                    .iter()
+                    .position(|order| order.id == id)
+                    .map(|index| (*price, index))
+            });
+            if let Some((price, index)) = found {
+                let level = book.get_mut(&price).expect("Level must exist.");
+                let order = level.remove(index);
+                if level.is_empty() {
+                    book.remove(&price);
+                }
+                return order;
+            }
+        }
+        None
+    }
+
+    /// Removes every resting order of `owner`.
+    pub fn clear(&mut self, owner: Owner) {
+        for book in [&mut self.bids, &mut self.asks] {
+            book.retain(|_, level| {
+                level.retain(|order| order.owner != owner);
+                !level.is_empty()
+            });
+        }
+    }
+
+    /// Returns the aggregated bids (best first) and asks (best first).
+    pub fn levels(&self) -> (Vec<AskBid>, Vec<AskBid>) {
+        let aggregate = |(price, orders): (&Decimal, &VecDeque<RestingOrder>)| AskBid {
+            price: *price,
+            qty: orders.iter().map(|order| order.remaining).sum(),
+        };
+        (
+            self.bids.iter().rev().map(aggregate).collect(),
+            self.asks.iter().map(aggregate).collect(),
+        )
+    }
+
+    fn best_opposite(&self, side: &Side) -> Option<Decimal> {
+        match side {
+            Side::Buy => self.asks.keys().next().copied(),
+            Side::Sell => self.bids.keys().next_back().copied(),
+        }
+    }
+
+    fn is_marketable(side: &Side, price: Decimal, limit: Option<Decimal>) -> bool {
+        match (side, limit) {
+            (_, None) => true,
+            (Side::Buy, Some(limit)) => price <= limit,
+            (Side::Sell, Some(limit)) => price >= limit,
+        }
+    }
+}
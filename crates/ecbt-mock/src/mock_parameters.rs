@@ -0,0 +1,62 @@
+use super::ScriptedOrder;
+use ecbt_exchange::info::{MarketPairInfo, OrderValidation};
+use rust_decimal::prelude::Decimal;
+
+/// This struct represents the markets, balances and order flow a `MockExchange` starts with.
+#[derive(Default, Clone, Debug)]
+pub struct MockParameters {
+    pub markets: Vec<MarketPairInfo>,
+    /// Free balance per asset, e.g. `("USDT", 1000)`.
+    pub balances: Vec<(String, Decimal)>,
+    /// Orders submitted in sequence when the exchange is created.
+    pub orders: Vec<ScriptedOrder>,
+    pub maker_fee: Decimal,
+    pub taker_fee: Decimal,
+    /// Initial value in milliseconds of the exchange clock.
+    pub start_time: u64,
+    /// How account orders are checked against the market constraints, scripted orders are not.
+    pub order_validation: OrderValidation,
+}
+
+impl MockParameters {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Add a tradable market.
+    pub fn with_market(mut self, market: MarketPairInfo) -> Self {
+        self.markets.push(market);
+        self
+    }
+
+    /// Seed the free balance of an asset.
+    pub fn with_balance(mut self, asset: &str, free: Decimal) -> Self {
+        self.balances.push((asset.to_string(), free));
+        self
+    }
+
+    /// Append a scripted order to the initial order flow.
+    pub fn with_order(mut self, order: ScriptedOrder) -> Self {
+        self.orders.push(order);
+        self
+    }
+
+    /// Set the maker and taker fee rates, charged in the received asset.
+    pub fn with_fees(mut self, maker_fee: Decimal, taker_fee: Decimal) -> Self {
+        self.maker_fee = maker_fee;
+        self.taker_fee = taker_fee;
+        self
+    }
+
+    /// Set the initial time in milliseconds.
+    pub fn with_start_time(mut self, start_time: u64) -> Self {
+        self.start_time = start_time;
+        self
+    }
+
+    /// Set how account orders are checked against the market constraints.
+    pub fn with_order_validation(mut self, order_validation: OrderValidation) -> Self {
+        self.order_validation = order_validation;
+        self
+    }
+}
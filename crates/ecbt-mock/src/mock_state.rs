@@ -0,0 +1,665 @@
+use super::matching_engine::{Fill, MatchingEngine, Owner, RestingOrder};
+use super::{MockParameters, MockWebsocketMessage, ScriptedOrder};
+use ecbt_exchange::errors::{EcbtError, ExchangeErrorContext};
+use ecbt_exchange::info::{MarketPairInfo, OrderValidation};
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::{
+    Balance, CancelAllOrdersRequest, Candle, EcbtOrderRequest, GetOrderHistoryRequest, Liquidity,
+    OpenMarketOrderRequest, Order, OrderBookResponse, OrderCanceled, OrderStatus, OrderType, Side,
+    TimeInForce, Trade, TradeHistoryRequest,
+};
+use ecbt_exchange::shared::Result;
+use rust_decimal::prelude::Decimal;
+use std::collections::{BTreeMap, HashMap};
+
+/// This struct represents the free and locked amounts of an asset
+#[derive(Clone, Copy, Debug, Default)]
+struct Holding {
+    free: Decimal,
+    locked: Decimal,
+}
+
+/// This struct represents an order entering the matching engine
+#[derive(Clone, Debug)]
+pub struct NewOrder {
+    pub owner: Owner,
+    pub symbol: String,
+    pub client_order_id: Option<String>,
+    pub order_type: OrderType,
+    pub side: Side,
+    pub price: Option<Decimal>,
+    pub size: Decimal,
+    pub time_in_force: TimeInForce,
+    pub post_only: bool,
+}
+
+/// This struct represents the whole simulated exchange: books, account and market history.
+#[derive(Debug)]
+pub struct MockState {
+    markets: Vec<MarketPairInfo>,
+    books: HashMap<String, MatchingEngine>,
+    holdings: BTreeMap<String, Holding>,
+    orders: Vec<Order>,
+    account_trades: Vec<Trade>,
+    market_trades: HashMap<String, Vec<(u64, Trade)>>,
+    maker_fee: Decimal,
+    taker_fee: Decimal,
+    order_validation: OrderValidation,
+    now: u64,
+    last_id: u64,
+    update_id: u64,
+    events: Vec<MockWebsocketMessage>,
+}
+
+impl MockState {
+    pub fn new(parameters: MockParameters) -> Result<Self> {
+        let mut state = Self {
+            books: parameters
+                .markets
+                .iter()
+                .map(|market| (market.symbol.clone(), MatchingEngine::new()))
+                .collect(),
+            markets: parameters.markets,
+            holdings: BTreeMap::new(),
+            orders: Vec::new(),
+            account_trades: Vec::new(),
+            market_trades: HashMap::new(),
+            maker_fee: parameters.maker_fee,
+            taker_fee: parameters.taker_fee,
+            order_validation: parameters.order_validation,
+            now: parameters.start_time,
+            last_id: 0,
+            update_id: 0,
+            events: Vec::new(),
+        };
+
+        for (asset, free) in parameters.balances {
+            state.holdings.entry(asset).or_default().free += free;
+        }
+        for order in parameters.orders {
+            state.submit(order)?;
+        }
+        state.events.clear();
+
+        Ok(state)
+    }
+
+    pub fn markets(&self) -> &[MarketPairInfo] {
+        &self.markets
+    }
+
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    pub fn advance_time(&mut self, millis: u64) {
+        self.now += millis;
+    }
+
+    pub fn set_time(&mut self, now: u64) {
+        self.now = now;
+    }
+
+    /// Resolves a market pair to the symbol of a configured market.
+    pub fn symbol(&self, market_pair: &MarketPair) -> Result<String> {
+        let base = market_pair.0.to_string();
+        let quote = market_pair.1.to_string();
+        self.markets
+            .iter()
+            .find(|market| {
+                market.base.eq_ignore_ascii_case(&base) && market.quote.eq_ignore_ascii_case(&quote)
+            })
+            .map(|market| market.symbol.clone())
+            .ok_or(EcbtError::SymbolNotFound())
+    }
+
+    fn optional_symbol(&self, market_pair: Option<&MarketPair>) -> Result<Option<String>> {
+        market_pair
+            .map(|market_pair| self.symbol(market_pair))
+            .transpose()
+    }
+
+    /// Takes the websocket messages produced since the last call.
+    pub fn drain_events(&mut self) -> Vec<MockWebsocketMessage> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn submit(&mut self, order: ScriptedOrder) -> Result<Order> {
+        let symbol = self.symbol(&order.market_pair)?;
+        self.place(NewOrder {
+            owner: Owner::Script,
+            symbol,
+            client_order_id: None,
+            order_type: match order.price {
+                Some(_) => OrderType::Limit,
+                None => OrderType::Market,
+            },
+            side: order.side,
+            price: order.price,
+            size: order.size,
+            time_in_force: TimeInForce::GoodTillCancelled,
+            post_only: false,
+        })
+    }
+
+    pub fn place_limit(&mut self, side: Side, req: &EcbtOrderRequest) -> Result<Order> {
+        let symbol = self.symbol(&req.market_pair)?;
+        let req = &self.market(&symbol)?.validate_limit_order(
+            &side,
+            req,
+            self.order_validation,
+            self.last_price(&symbol),
+        )?;
+        self.place(NewOrder {
+            owner: Owner::Account,
+            symbol,
+            client_order_id: req.client_order_id.clone(),
+            order_type: OrderType::Limit,
+            side,
+            price: Some(req.price),
+            size: req.size,
+            time_in_force: req.time_in_force,
+            post_only: req.post_only,
+        })
+    }
+
+    pub fn place_market(&mut self, side: Side, req: &OpenMarketOrderRequest) -> Result<Order> {
+        let symbol = self.symbol(&req.market_pair)?;
+        let req = &self.market(&symbol)?.validate_market_order(
+            req,
+            self.order_validation,
+            self.last_price(&symbol),
+        )?;
+        self.place(NewOrder {
+            owner: Owner::Account,
+            symbol,
+            client_order_id: req.client_order_id.clone(),
+            order_type: OrderType::Market,
+            side,
+            price: None,
+            size: req.size,
+            time_in_force: TimeInForce::ImmediateOrCancelled,
+            post_only: false,
+        })
+    }
+
+    /// Replaces the scripted liquidity of a market with `order_book`.
+    ///
+    /// Levels crossing resting account orders fill them at the account's price.
+    pub fn replace_liquidity(
+        &mut self,
+        symbol: &str,
+        order_book: &OrderBookResponse,
+    ) -> Result<()> {
+        self.books
+            .get_mut(symbol)
+            .ok_or(EcbtError::SymbolNotFound())?
+            .clear(Owner::Script);
+        let levels = order_book
+            .asks
+            .iter()
+            .map(|level| (Side::Sell, level))
+            .chain(order_book.bids.iter().map(|level| (Side::Buy, level)));
+        for (side, level) in levels.filter(|(_, level)| level.qty > Decimal::ZERO) {
+            self.place(NewOrder {
+                owner: Owner::Script,
+                symbol: symbol.to_string(),
+                client_order_id: None,
+                order_type: OrderType::Limit,
+                side,
+                price: Some(level.price),
+                size: level.qty,
+                time_in_force: TimeInForce::GoodTillCancelled,
+                post_only: false,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Replays a trade that happened elsewhere against the resting account orders.
+    ///
+    /// The scripted liquidity of the market is dropped first, as the trade makes it stale.
+    pub fn apply_trade(&mut self, trade: &Trade) -> Result<()> {
+        self.books
+            .get_mut(&trade.market_pair)
+            .ok_or(EcbtError::SymbolNotFound())?
+            .clear(Owner::Script);
+        self.place(NewOrder {
+            owner: Owner::Script,
+            symbol: trade.market_pair.clone(),
+            client_order_id: None,
+            order_type: OrderType::Limit,
+            side: trade.side.clone(),
+            price: Some(trade.price),
+            size: trade.qty,
+            time_in_force: TimeInForce::ImmediateOrCancelled,
+            post_only: false,
+        })
+        .map(|_| ())
+    }
+
+    /// Matches an order against the book, settles the account and rests any remainder.
+    ///
+    /// `GoodTillTime` orders rest like `GoodTillCancelled` ones; they never expire.
+    pub fn place(&mut self, new: NewOrder) -> Result<Order> {
+        let market = self.market(&new.symbol)?;
+        if new.size <= Decimal::ZERO {
+            return Err(EcbtError::InvalidParameter(
+                "order size must be positive".to_string(),
+            ));
+        }
+        if matches!(new.price, Some(price) if price <= Decimal::ZERO) {
+            return Err(EcbtError::InvalidParameter(
+                "order price must be positive".to_string(),
+            ));
+        }
+
+        let book = &self.books[&new.symbol];
+        if new.post_only && new.price.is_none_or(|price| book.crosses(&new.side, price)) {
+            return Err(EcbtError::PostOnlyWouldTake(ExchangeErrorContext::new(
+                "post-only order would take liquidity",
+            )));
+        }
+        let preview = book.preview(&new.side, new.price, new.size);
+
+        let mut order = Order {
+            id: self.next_id(),
+            market_pair: new.symbol.clone(),
+            client_order_id: new.client_order_id.clone(),
+            created_at: Some(self.now),
+            order_type: new.order_type.clone(),
+            side: new.side.clone(),
+            status: OrderStatus::New,
+            size: new.size,
+            price: new.price,
+            remaining: Some(new.size),
+            trades: Vec::new(),
+        };
+
+        let available: Decimal = preview.iter().map(|(_, qty)| *qty).sum();
+        if new.time_in_force == TimeInForce::FillOrKill && available < new.size {
+            order.status = OrderStatus::Expired;
+            if new.owner == Owner::Account {
+                self.orders.push(order.clone());
+            }
+            return Ok(order);
+        }
+
+        if new.owner == Owner::Account {
+            match new.side {
+                Side::Buy => {
+                    let cost = match new.price {
+                        Some(price) => price * new.size,
+                        None => preview.iter().map(|(price, qty)| price * qty).sum(),
+                    };
+                    self.lock(&market.quote, cost)?;
+                }
+                Side::Sell => self.lock(&market.base, new.size)?,
+            }
+        }
+
+        let fills = self
+            .books
+            .get_mut(&new.symbol)
+            .expect("Book must exist.")
+            .execute(&new.side, new.price, new.size);
+        let mut trades = Vec::with_capacity(fills.len());
+        for fill in fills {
+            trades.push(self.settle(&market, &mut order, new.owner, fill));
+        }
+
+        let remaining = order.remaining.unwrap_or_default();
+        let rests = new.order_type == OrderType::Limit
+            && matches!(
+                new.time_in_force,
+                TimeInForce::GoodTillCancelled | TimeInForce::GoodTillTime(_)
+            );
+        order.status = if remaining.is_zero() {
+            OrderStatus::Filled
+        } else if rests {
+            self.books
+                .get_mut(&new.symbol)
+                .expect("Book must exist.")
+                .insert(RestingOrder {
+                    id: order.id.clone(),
+                    owner: new.owner,
+                    side: new.side.clone(),
+                    price: new.price.unwrap_or_default(),
+                    remaining,
+                });
+            if trades.is_empty() {
+                OrderStatus::New
+            } else {
+                OrderStatus::PartiallyFilled
+            }
+        } else {
+            if new.owner == Owner::Account {
+                self.release_remaining(&market, &order);
+            }
+            OrderStatus::Expired
+        };
+
+        if !trades.is_empty() {
+            self.events.push(MockWebsocketMessage::Trades(trades));
+        }
+        self.publish_book(&new.symbol);
+
+        if new.owner == Owner::Account {
+            self.orders.push(order.clone());
+        }
+        Ok(order)
+    }
+
+    pub fn cancel(&mut self, id: &str) -> Result<OrderCanceled> {
+        let index = self
+            .orders
+            .iter()
+            .position(|order| order.id == id && Self::is_open(order))
+            .ok_or_else(|| {
+                EcbtError::OrderNotFound(ExchangeErrorContext::new(format!(
+                    "order {} is not open",
+                    id
+                )))
+            })?;
+        let symbol = self.orders[index].market_pair.clone();
+        let market = self.market(&symbol)?;
+
+        self.books
+            .get_mut(&symbol)
+            .expect("Book must exist.")
+            .cancel(id);
+        let order = self.orders[index].clone();
+        self.release_remaining(&market, &order);
+        self.orders[index].status = OrderStatus::Canceled;
+        self.publish_book(&symbol);
+
+        Ok(OrderCanceled::from(id.to_string()))
+    }
+
+    pub fn cancel_all_orders(
+        &mut self,
+        req: &CancelAllOrdersRequest,
+    ) -> Result<Vec<OrderCanceled>> {
+        let symbol = self.optional_symbol(req.market_pair.as_ref())?;
+        let ids: Vec<String> = self
+            .open_orders()
+            .into_iter()
+            .filter(|order| {
+                symbol
+                    .as_ref()
+                    .is_none_or(|symbol| order.market_pair == *symbol)
+            })
+            .map(|order| order.id)
+            .collect();
+        ids.iter().map(|id| self.cancel(id)).collect()
+    }
+
+    pub fn order(&self, id: &str) -> Result<Order> {
+        self.orders
+            .iter()
+            .find(|order| order.id == id)
+            .cloned()
+            .ok_or_else(|| {
+                EcbtError::OrderNotFound(ExchangeErrorContext::new(format!(
+                    "order {} not found",
+                    id
+                )))
+            })
+    }
+
+    pub fn order_history(&self, req: &GetOrderHistoryRequest) -> Result<Vec<Order>> {
+        let symbol = self.optional_symbol(req.market_pair.as_ref())?;
+        Ok(self
+            .orders
+            .iter()
+            .filter(|order| symbol.as_ref().is_none_or(|s| *s == order.market_pair))
+            .filter(|order| {
+                req.order_status
+                    .as_ref()
+                    .is_none_or(|statuses| statuses.contains(&order.status))
+            })
+            .cloned()
+            .collect())
+    }
+
+    pub fn open_orders(&self) -> Vec<Order> {
+        self.orders
+            .iter()
+            .filter(|order| Self::is_open(order))
+            .cloned()
+            .collect()
+    }
+
+    pub fn trade_history(&self, req: &TradeHistoryRequest) -> Result<Vec<Trade>> {
+        let symbol = self.optional_symbol(req.market_pair.as_ref())?;
+        Ok(self
+            .account_trades
+            .iter()
+            .filter(|trade| symbol.as_ref().is_none_or(|s| *s == trade.market_pair))
+            .filter(|trade| {
+                req.order_id.as_ref().is_none_or(|id| {
+                    trade.buyer_order_id.as_ref() == Some(id)
+                        || trade.seller_order_id.as_ref() == Some(id)
+                })
+            })
+            .cloned()
+            .collect())
+    }
+
+    pub fn market_trades(&self, symbol: &str) -> Vec<Trade> {
+        self.market_trades
+            .get(symbol)
+            .map(|trades| trades.iter().map(|(_, trade)| trade.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn last_price(&self, symbol: &str) -> Option<Decimal> {
+        self.market_trades
+            .get(symbol)
+            .and_then(|trades| trades.last())
+            .map(|(_, trade)| trade.price)
+    }
+
+    /// Buckets the market's trades into candles of `interval` milliseconds.
+    pub fn candles(&self, symbol: &str, interval: u64) -> Vec<Candle> {
+        let mut candles: Vec<Candle> = Vec::new();
+        for (time, trade) in self.market_trades.get(symbol).into_iter().flatten() {
+            let bucket = time - time % interval.max(1);
+            match candles.last_mut() {
+                Some(candle) if candle.time == bucket => {
+                    candle.low = candle.low.min(trade.price);
+                    candle.high = candle.high.max(trade.price);
+                    candle.close = trade.price;
+                    candle.volume += trade.qty;
+                }
+                _ => candles.push(Candle {
+                    time: bucket,
+                    low: trade.price,
+                    high: trade.price,
+                    open: trade.price,
+                    close: trade.price,
+                    volume: trade.qty,
+                }),
+            }
+        }
+        candles
+    }
+
+    pub fn order_book(&self, symbol: &str) -> Result<OrderBookResponse> {
+        let book = self.books.get(symbol).ok_or(EcbtError::SymbolNotFound())?;
+        let (bids, asks) = book.levels();
+        Ok(OrderBookResponse {
+            update_id: Some(self.update_id),
+            last_update_id: None,
+            bids,
+            asks,
+        })
+    }
+
+    pub fn balances(&self) -> Vec<Balance> {
+        self.holdings
+            .iter()
+            .map(|(asset, holding)| Balance {
+                asset: asset.clone(),
+                total: holding.free + holding.locked,
+                free: holding.free,
+            })
+            .collect()
+    }
+
+    fn settle(
+        &mut self,
+        market: &MarketPairInfo,
+        taker: &mut Order,
+        owner: Owner,
+        fill: Fill,
+    ) -> Trade {
+        let (buyer_order_id, seller_order_id) = match taker.side {
+            Side::Buy => (taker.id.clone(), fill.maker_order_id.clone()),
+            Side::Sell => (fill.maker_order_id.clone(), taker.id.clone()),
+        };
+        let trade = Trade {
+            id: self.next_id(),
+            buyer_order_id: Some(buyer_order_id),
+            seller_order_id: Some(seller_order_id),
+            market_pair: market.symbol.clone(),
+            price: fill.price,
+            qty: fill.qty,
+            fees: None,
+            side: taker.side.clone(),
+            liquidity: None,
+            created_at: self.now.to_string(),
+        };
+        self.market_trades
+            .entry(market.symbol.clone())
+            .or_default()
+            .push((self.now, trade.clone()));
+
+        if fill.maker_owner == Owner::Account {
+            let maker_side = match taker.side {
+                Side::Buy => Side::Sell,
+                Side::Sell => Side::Buy,
+            };
+            let fees = self.settle_account(market, &maker_side, fill.price, &fill, self.maker_fee);
+            let account_trade = Trade {
+                fees: Some(fees),
+                side: maker_side,
+                liquidity: Some(Liquidity::Maker),
+                ..trade.clone()
+            };
+            if let Some(maker) = self
+                .orders
+                .iter_mut()
+                .find(|order| order.id == fill.maker_order_id)
+            {
+                let remaining = maker.remaining.unwrap_or_default() - fill.qty;
+                maker.remaining = Some(remaining);
+                maker.status = if remaining.is_zero() {
+                    OrderStatus::Filled
+                } else {
+                    OrderStatus::PartiallyFilled
+                };
+                maker.trades.push(account_trade.clone());
+            }
+            self.account_trades.push(account_trade);
+        }
+
+        taker.remaining = Some(taker.remaining.unwrap_or_default() - fill.qty);
+        if owner == Owner::Account {
+            let locked_price = taker.price.unwrap_or(fill.price);
+            let fees =
+                self.settle_account(market, &taker.side, locked_price, &fill, self.taker_fee);
+            let account_trade = Trade {
+                fees: Some(fees),
+                liquidity: Some(Liquidity::Taker),
+                ..trade.clone()
+            };
+            taker.trades.push(account_trade.clone());
+            self.account_trades.push(account_trade);
+        }
+
+        trade
+    }
+
+    /// Moves the locked funds of a fill to the counter asset and returns the fee charged.
+    fn settle_account(
+        &mut self,
+        market: &MarketPairInfo,
+        side: &Side,
+        locked_price: Decimal,
+        fill: &Fill,
+        fee_rate: Decimal,
+    ) -> Decimal {
+        match side {
+            Side::Buy => {
+                let quote = self.holdings.entry(market.quote.clone()).or_default();
+                quote.locked -= locked_price * fill.qty;
+                quote.free += (locked_price - fill.price) * fill.qty;
+                let fees = fill.qty * fee_rate;
+                self.holdings.entry(market.base.clone()).or_default().free += fill.qty - fees;
+                fees
+            }
+            Side::Sell => {
+                self.holdings.entry(market.base.clone()).or_default().locked -= fill.qty;
+                let received = fill.price * fill.qty;
+                let fees = received * fee_rate;
+                self.holdings.entry(market.quote.clone()).or_default().free += received - fees;
+                fees
+            }
+        }
+    }
+
+    fn lock(&mut self, asset: &str, amount: Decimal) -> Result<()> {
+        let holding = self.holdings.entry(asset.to_string()).or_default();
+        if holding.free < amount {
+            return Err(EcbtError::InsufficientBalance(ExchangeErrorContext::new(
+                format!("insufficient {} balance", asset),
+            )));
+        }
+        holding.free -= amount;
+        holding.locked += amount;
+        Ok(())
+    }
+
+    fn release_remaining(&mut self, market: &MarketPairInfo, order: &Order) {
+        let remaining = order.remaining.unwrap_or_default();
+        let (asset, amount) = match (&order.side, order.price) {
+            (Side::Buy, Some(price)) => (&market.quote, price * remaining),
+            (Side::Buy, None) => return,
+            (Side::Sell, _) => (&market.base, remaining),
+        };
+        let holding = self.holdings.entry(asset.clone()).or_default();
+        holding.locked -= amount;
+        holding.free += amount;
+    }
+
+    fn publish_book(&mut self, symbol: &str) {
+        self.update_id += 1;
+        if let Ok(order_book) = self.order_book(symbol) {
+            self.events.push(MockWebsocketMessage::OrderBook {
+                market_pair: symbol.to_string(),
+                order_book,
+            });
+        }
+    }
+
+    fn market(&self, symbol: &str) -> Result<MarketPairInfo> {
+        self.markets
+            .iter()
+            .find(|market| market.symbol == symbol)
+            .cloned()
+            .ok_or(EcbtError::SymbolNotFound())
+    }
+
+    fn next_id(&mut self) -> String {
+        self.last_id += 1;
+        self.last_id.to_string()
+    }
+
+    fn is_open(order: &Order) -> bool {
+        matches!(
+            order.status,
+            OrderStatus::New | OrderStatus::PartiallyFilled
+        )
+    }
+}
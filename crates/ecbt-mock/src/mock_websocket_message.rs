@@ -0,0 +1,36 @@
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::model::websocket::{EcbtWebSocketMessage, OrderBookUpdate, WebSocketResponse};
+use ecbt_exchange::model::{OrderBookResponse, Trade};
+use std::convert::TryFrom;
+
+/// This enum represents a websocket message of the mock exchange
+#[derive(Debug, Clone)]
+pub enum MockWebsocketMessage {
+    /// Full snapshot of a market's book, sent on subscription and after every change.
+    OrderBook {
+        market_pair: String,
+        order_book: OrderBookResponse,
+    },
+    Trades(Vec<Trade>),
+}
+
+impl TryFrom<MockWebsocketMessage> for WebSocketResponse<MockWebsocketMessage> {
+    type Error = EcbtError;
+
+    fn try_from(value: MockWebsocketMessage) -> Result<Self, Self::Error> {
+        match value {
+            MockWebsocketMessage::OrderBook {
+                market_pair,
+                order_book,
+            } => Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(
+                OrderBookUpdate {
+                    market_pair,
+                    book: order_book,
+                },
+            ))),
+            MockWebsocketMessage::Trades(trades) => Ok(WebSocketResponse::Generic(
+                EcbtWebSocketMessage::Trades(trades),
+            )),
+        }
+    }
+}
@@ -0,0 +1,227 @@
+use super::mock_state::MockState;
+use super::{MockParameters, PaperParameters};
+use crate::shared::Result;
+use async_trait::async_trait;
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::info::{ExchangeInfoRetrieval, MarketPairHandle, MarketPairInfo};
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::websocket::{EcbtWebSocketMessage, Subscription, WebSocketResponse};
+use ecbt_exchange::model::{
+    Balance, CancelAllOrdersRequest, CancelOrderRequest, Candle, EcbtOrderRequest,
+    GetHistoricRatesRequest, GetHistoricTradesRequest, GetOrderHistoryRequest, GetOrderRequest,
+    GetPriceTickerRequest, OpenMarketOrderRequest, Order, OrderBookRequest, OrderBookResponse,
+    OrderCanceled, Paginator, Side, Ticker, Trade, TradeHistoryRequest,
+};
+use ecbt_exchange::stream::ExchangeStream;
+use ecbt_exchange::{Exchange, ExchangeAccount, ExchangeMarketData};
+use futures::StreamExt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::task::JoinHandle;
+
+/// This struct represents an exchange that reads market data from a live exchange but
+/// trades against a simulated account.
+///
+/// Orders are matched against a fresh order book snapshot of the wrapped exchange when they
+/// are placed, without consuming any real liquidity. Whatever rests is filled at its own price
+/// when a live trade goes through it, see `PaperExchange::follow`, or when a later snapshot
+/// crosses it.
+pub struct PaperExchange<E> {
+    inner: Arc<E>,
+    state: Arc<Mutex<MockState>>,
+}
+
+impl<E> Clone for PaperExchange<E> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            state: self.state.clone(),
+        }
+    }
+}
+
+impl<E: Exchange + Send + Sync> PaperExchange<E> {
+    /// Fetch the order book of `market_pair` from the wrapped exchange and match the resting
+    /// paper orders against it.
+    pub async fn sync(&self, market_pair: &MarketPair) -> Result<()> {
+        let symbol = self.state()?.symbol(market_pair)?;
+        let order_book = self
+            .inner
+            .order_book(&OrderBookRequest {
+                market_pair: market_pair.clone(),
+            })
+            .await?;
+        update(&self.state, |state| {
+            state.replace_liquidity(&symbol, &order_book)
+        })
+    }
+
+    /// Subscribe to the trades of `market_pairs` on `stream` and fill the resting paper orders
+    /// they go through. The returned task runs until the stream ends or is aborted.
+    pub async fn follow<S: ExchangeStream>(
+        &self,
+        stream: &S,
+        market_pairs: &[MarketPair],
+    ) -> Result<JoinHandle<()>> {
+        let subscriptions: Vec<Subscription> = market_pairs
+            .iter()
+            .cloned()
+            .map(Subscription::Trades)
+            .collect();
+        let mut stream = stream.create_stream(&subscriptions).await?;
+        let state = self.state.clone();
+
+        Ok(tokio::spawn(async move {
+            while let Some(Ok(message)) = stream.next().await {
+                if let WebSocketResponse::Generic(EcbtWebSocketMessage::Trades(trades)) = message {
+                    for trade in trades {
+                        update(&state, |state| state.apply_trade(&trade)).ok();
+                    }
+                }
+            }
+        }))
+    }
+
+    fn state(&self) -> Result<MutexGuard<'_, MockState>> {
+        self.state.lock().map_err(|_| EcbtError::PoisonError())
+    }
+}
+
+/// Runs `f` on the state at the current wall-clock time, discarding the produced events.
+fn update<T>(state: &Mutex<MockState>, f: impl FnOnce(&mut MockState) -> Result<T>) -> Result<T> {
+    let mut state = state.lock().map_err(|_| EcbtError::PoisonError())?;
+    state.set_time(now());
+    let result = f(&mut state);
+    state.drain_events();
+    result
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[async_trait]
+impl<E> Exchange for PaperExchange<E>
+where
+    E: Exchange + Send + Sync,
+    E::InitParams: Send,
+{
+    type InitParams = PaperParameters<E::InitParams>;
+    type InnerClient = E;
+
+    async fn new(parameters: Self::InitParams) -> Result<Self> {
+        let inner = E::new(parameters.inner).await?;
+        let markets = inner
+            .refresh_market_info()
+            .await?
+            .iter()
+            .map(MarketPairHandle::read)
+            .collect::<Result<Vec<_>>>()?;
+        let state = MockState::new(MockParameters {
+            markets,
+            balances: parameters.balances,
+            orders: Vec::new(),
+            maker_fee: parameters.maker_fee,
+            taker_fee: parameters.taker_fee,
+            start_time: now(),
+            order_validation: parameters.order_validation,
+        })?;
+
+        Ok(Self {
+            inner: Arc::new(inner),
+            state: Arc::new(Mutex::new(state)),
+        })
+    }
+
+    fn inner_client(&self) -> Option<&Self::InnerClient> {
+        Some(&self.inner)
+    }
+}
+
+#[async_trait]
+impl<E: Exchange + Send + Sync> ExchangeInfoRetrieval for PaperExchange<E> {
+    async fn retrieve_pairs(&self) -> Result<Vec<MarketPairInfo>> {
+        self.inner.retrieve_pairs().await
+    }
+
+    async fn refresh_market_info(&self) -> Result<Vec<MarketPairHandle>> {
+        self.inner.refresh_market_info().await
+    }
+
+    async fn get_pair(&self, market_pair: &MarketPair) -> Result<MarketPairHandle> {
+        self.inner.get_pair(market_pair).await
+    }
+}
+
+#[async_trait]
+impl<E: Exchange + Send + Sync> ExchangeMarketData for PaperExchange<E> {
+    async fn order_book(&self, req: &OrderBookRequest) -> Result<OrderBookResponse> {
+        self.inner.order_book(req).await
+    }
+
+    async fn get_price_ticker(&self, req: &GetPriceTickerRequest) -> Result<Ticker> {
+        self.inner.get_price_ticker(req).await
+    }
+
+    async fn get_historic_rates(&self, req: &GetHistoricRatesRequest) -> Result<Vec<Candle>> {
+        self.inner.get_historic_rates(req).await
+    }
+
+    async fn get_historic_trades(&self, req: &GetHistoricTradesRequest) -> Result<Vec<Trade>> {
+        self.inner.get_historic_trades(req).await
+    }
+}
+
+#[async_trait]
+impl<E: Exchange + Send + Sync> ExchangeAccount for PaperExchange<E> {
+    async fn limit_buy(&self, req: &EcbtOrderRequest) -> Result<Order> {
+        self.sync(&req.market_pair).await?;
+        update(&self.state, |state| state.place_limit(Side::Buy, req))
+    }
+
+    async fn limit_sell(&self, req: &EcbtOrderRequest) -> Result<Order> {
+        self.sync(&req.market_pair).await?;
+        update(&self.state, |state| state.place_limit(Side::Sell, req))
+    }
+
+    async fn market_buy(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
+        self.sync(&req.market_pair).await?;
+        update(&self.state, |state| state.place_market(Side::Buy, req))
+    }
+
+    async fn market_sell(&self, req: &OpenMarketOrderRequest) -> Result<Order> {
+        self.sync(&req.market_pair).await?;
+        update(&self.state, |state| state.place_market(Side::Sell, req))
+    }
+
+    async fn cancel_order(&self, req: &CancelOrderRequest) -> Result<OrderCanceled> {
+        update(&self.state, |state| state.cancel(&req.id))
+    }
+
+    async fn cancel_all_orders(&self, req: &CancelAllOrdersRequest) -> Result<Vec<OrderCanceled>> {
+        update(&self.state, |state| state.cancel_all_orders(req))
+    }
+
+    async fn get_all_open_orders(&self) -> Result<Vec<Order>> {
+        Ok(self.state()?.open_orders())
+    }
+
+    async fn get_order_history(&self, req: &GetOrderHistoryRequest) -> Result<Vec<Order>> {
+        self.state()?.order_history(req)
+    }
+
+    async fn get_trade_history(&self, req: &TradeHistoryRequest) -> Result<Vec<Trade>> {
+        self.state()?.trade_history(req)
+    }
+
+    async fn get_account_balances(&self, _paginator: Option<Paginator>) -> Result<Vec<Balance>> {
+        Ok(self.state()?.balances())
+    }
+
+    async fn get_order(&self, req: &GetOrderRequest) -> Result<Order> {
+        self.state()?.order(&req.id)
+    }
+}
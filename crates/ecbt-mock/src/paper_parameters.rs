@@ -0,0 +1,45 @@
+use ecbt_exchange::info::OrderValidation;
+use rust_decimal::prelude::Decimal;
+
+/// This struct represents the parameters of a `PaperExchange`: those of the wrapped exchange
+/// and the simulated account that trades against it.
+#[derive(Default, Clone, Debug)]
+pub struct PaperParameters<P> {
+    pub inner: P,
+    /// Free balance per asset, e.g. `("USDT", 1000)`.
+    pub balances: Vec<(String, Decimal)>,
+    pub maker_fee: Decimal,
+    pub taker_fee: Decimal,
+    pub order_validation: OrderValidation,
+}
+
+impl<P> PaperParameters<P> {
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            balances: Vec::new(),
+            maker_fee: Decimal::ZERO,
+            taker_fee: Decimal::ZERO,
+            order_validation: OrderValidation::default(),
+        }
+    }
+
+    /// Seed the free balance of an asset.
+    pub fn with_balance(mut self, asset: &str, free: Decimal) -> Self {
+        self.balances.push((asset.to_string(), free));
+        self
+    }
+
+    /// Set the maker and taker fee rates, charged in the received asset.
+    pub fn with_fees(mut self, maker_fee: Decimal, taker_fee: Decimal) -> Self {
+        self.maker_fee = maker_fee;
+        self.taker_fee = taker_fee;
+        self
+    }
+
+    /// Set how orders are checked against the constraints of the wrapped exchange.
+    pub fn with_order_validation(mut self, order_validation: OrderValidation) -> Self {
+        self.order_validation = order_validation;
+        self
+    }
+}
@@ -0,0 +1,37 @@
+use ecbt_exchange::model::market_pair::MarketPair;
+use ecbt_exchange::model::Side;
+use rust_decimal::prelude::Decimal;
+
+/// This struct represents an order placed by a scripted market participant.
+///
+/// Scripted orders provide liquidity and trade against the simulated account without
+/// affecting its balances. A `price` of `None` submits a market order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScriptedOrder {
+    pub market_pair: MarketPair,
+    pub side: Side,
+    pub price: Option<Decimal>,
+    pub size: Decimal,
+}
+
+impl ScriptedOrder {
+    /// Limit order resting at `price`
+    pub fn limit(market_pair: MarketPair, side: Side, price: Decimal, size: Decimal) -> Self {
+        Self {
+            market_pair,
+            side,
+            price: Some(price),
+            size,
+        }
+    }
+
+    /// Market order taking liquidity from the book
+    pub fn market(market_pair: MarketPair, side: Side, size: Decimal) -> Self {
+        Self {
+            market_pair,
+            side,
+            price: None,
+            size,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use super::{MockExchange, MockParameters, MockWebsocketMessage};
+use crate::shared::Result;
+use async_trait::async_trait;
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::model::websocket::Subscription;
+use ecbt_exchange::stream::{ExchangeStream, Subscriptions};
+use ecbt_exchange::Exchange;
+use futures::stream::{self, BoxStream};
+use futures::StreamExt;
+use std::collections::HashSet;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Symbols a stream forwards messages for
+#[derive(Default)]
+struct StreamFilter {
+    order_books: HashSet<String>,
+    trades: HashSet<String>,
+}
+
+impl StreamFilter {
+    fn accepts(&self, message: &MockWebsocketMessage) -> Option<MockWebsocketMessage> {
+        match message {
+            MockWebsocketMessage::OrderBook { market_pair, .. } => self
+                .order_books
+                .contains(market_pair)
+                .then(|| message.clone()),
+            MockWebsocketMessage::Trades(trades) => {
+                let trades: Vec<_> = trades
+                    .iter()
+                    .filter(|trade| self.trades.contains(&trade.market_pair))
+                    .cloned()
+                    .collect();
+                (!trades.is_empty()).then_some(MockWebsocketMessage::Trades(trades))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ExchangeStream for MockExchange {
+    type InitParams = MockParameters;
+    type Subscription = Subscription;
+    type Response = MockWebsocketMessage;
+
+    /// Creates an independent exchange; subscribe on a clone of an existing `MockExchange`
+    /// to observe the orders placed through it.
+    async fn new(parameters: Self::InitParams) -> Result<Self> {
+        <Self as Exchange>::new(parameters).await
+    }
+
+    async fn disconnect(&self) {
+        self.disconnection.send(()).ok();
+    }
+
+    async fn create_stream_specific(
+        &self,
+        subscriptions: Subscriptions<Self::Subscription>,
+    ) -> Result<BoxStream<'static, Result<Self::Response>>> {
+        let mut filter = StreamFilter::default();
+        let mut snapshots = Vec::new();
+
+        let state = self.state()?;
+        for subscription in subscriptions.into_iter() {
+            match subscription {
+                Subscription::OrderBookUpdates(market_pair) => {
+                    let symbol = state.symbol(&market_pair)?;
+                    snapshots.push(Ok(MockWebsocketMessage::OrderBook {
+                        market_pair: symbol.clone(),
+                        order_book: state.order_book(&symbol)?,
+                    }));
+                    filter.order_books.insert(symbol);
+                }
+                Subscription::Trades(market_pair) => {
+                    filter.trades.insert(state.symbol(&market_pair)?);
+                }
+                Subscription::Ticker(_)
+                | Subscription::Candles(..)
+                | Subscription::AccountTrades(_)
+                | Subscription::AccountBalance(_)
+                | Subscription::AccountOrders(_) => {
+                    return Err(EcbtError::InvalidParameter(format!(
+                        "{subscription:?} is not supported by the mock exchange"
+                    )))
+                }
+            }
+        }
+        // Subscribing while the state is locked guarantees no update is missed between the
+        // snapshots and the live messages.
+        let events = self.events.subscribe();
+        drop(state);
+
+        let mut disconnection = self.disconnection.subscribe();
+        let live = stream::unfold((events, filter), |(mut events, filter)| async move {
+            loop {
+                match events.recv().await {
+                    Ok(message) => {
+                        if let Some(message) = filter.accepts(&message) {
+                            return Some((Ok(message), (events, filter)));
+                        }
+                    }
+                    Err(RecvError::Lagged(_)) => {
+                        return Some((Err(EcbtError::SocketError()), (events, filter)))
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+
+        Ok(stream::iter(snapshots)
+            .chain(live)
+            .take_until(async move { disconnection.recv().await.ok() })
+            .boxed())
+    }
+}
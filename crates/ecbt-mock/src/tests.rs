@@ -0,0 +1,504 @@
+use crate::{
+    MockExchange, MockParameters, MockWebsocketMessage, PaperExchange, PaperParameters,
+    ScriptedOrder,
+};
+use ecbt_exchange::errors::EcbtError;
+use ecbt_exchange::info::MarketPairInfo;
+use ecbt_exchange::model::market_pair::{Currency, MarketPair};
+use ecbt_exchange::model::websocket::{EcbtWebSocketMessage, Subscription, WebSocketResponse};
+use ecbt_exchange::model::{
+    AmendMethod, AmendOrderRequest, AskBid, Balance, CancelAllOrdersRequest, EcbtOrderRequest,
+    OpenMarketOrderRequest, OrderBookRequest, OrderFilter, OrderStatus, Side, TimeInForce,
+    TradeHistoryRequest,
+};
+use ecbt_exchange::stream::{ExchangeStream, OverflowPolicy, SubscriptionOptions};
+use ecbt_exchange::{Exchange, ExchangeAccount, ExchangeMarketData};
+use futures::StreamExt;
+use rust_decimal::prelude::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+fn btc_usdt() -> MarketPair {
+    MarketPair(Currency::BTC, Currency::USDT)
+}
+
+fn eth_usdt() -> MarketPair {
+    MarketPair(Currency::ETH, Currency::USDT)
+}
+
+fn market(base: &str, quote: &str) -> MarketPairInfo {
+    MarketPairInfo {
+        base: base.to_string(),
+        quote: quote.to_string(),
+        symbol: format!("{}{}", base, quote),
+        base_increment: dec!(0.0001),
+        quote_increment: dec!(0.01),
+        min_base_trade_size: None,
+        min_quote_trade_size: None,
+        constraints: Default::default(),
+    }
+}
+
+fn limit(market_pair: MarketPair, size: Decimal, price: Decimal) -> EcbtOrderRequest {
+    EcbtOrderRequest {
+        client_order_id: None,
+        market_pair,
+        size,
+        price,
+        time_in_force: TimeInForce::GoodTillCancelled,
+        post_only: false,
+    }
+}
+
+fn parameters() -> MockParameters {
+    MockParameters::new()
+        .with_market(market("BTC", "USDT"))
+        .with_market(market("ETH", "USDT"))
+        .with_balance("USDT", dec!(10000))
+        .with_balance("BTC", dec!(1))
+        .with_order(ScriptedOrder::limit(
+            btc_usdt(),
+            Side::Sell,
+            dec!(101),
+            dec!(1),
+        ))
+        .with_order(ScriptedOrder::limit(
+            btc_usdt(),
+            Side::Sell,
+            dec!(100),
+            dec!(1),
+        ))
+        .with_order(ScriptedOrder::limit(
+            btc_usdt(),
+            Side::Buy,
+            dec!(90),
+            dec!(2),
+        ))
+}
+
+async fn exchange() -> MockExchange {
+    <MockExchange as Exchange>::new(parameters()).await.unwrap()
+}
+
+async fn paper() -> PaperExchange<MockExchange> {
+    let parameters = PaperParameters::new(parameters()).with_balance("USDT", dec!(1000));
+    PaperExchange::new(parameters).await.unwrap()
+}
+
+fn balance(balances: &[Balance], asset: &str) -> Balance {
+    balances
+        .iter()
+        .find(|balance| balance.asset == asset)
+        .cloned()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn limit_buy_rests_and_locks_quote() {
+    let exchange = exchange().await;
+
+    let order = exchange
+        .limit_buy(&limit(btc_usdt(), dec!(2), dec!(95)))
+        .await
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::New);
+    assert_eq!(order.market_pair, "BTCUSDT");
+
+    let book = exchange
+        .order_book(&OrderBookRequest {
+            market_pair: btc_usdt(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(
+        book.bids[0],
+        AskBid {
+            price: dec!(95),
+            qty: dec!(2)
+        }
+    );
+
+    let usdt = balance(&exchange.get_account_balances(None).await.unwrap(), "USDT");
+    assert_eq!(usdt.total, dec!(10000));
+    assert_eq!(usdt.free, dec!(9810));
+}
+
+#[tokio::test]
+async fn limit_buy_matches_best_price_first_at_maker_price() {
+    let exchange = exchange().await;
+
+    let order = exchange
+        .limit_buy(&limit(btc_usdt(), dec!(1.5), dec!(105)))
+        .await
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Filled);
+    let fills: Vec<_> = order.trades.iter().map(|t| (t.price, t.qty)).collect();
+    assert_eq!(fills, vec![(dec!(100), dec!(1)), (dec!(101), dec!(0.5))]);
+
+    let balances = exchange.get_account_balances(None).await.unwrap();
+    assert_eq!(balance(&balances, "BTC").free, dec!(2.5));
+    assert_eq!(balance(&balances, "USDT").free, dec!(9849.5));
+    assert_eq!(balance(&balances, "USDT").total, dec!(9849.5));
+}
+
+#[tokio::test]
+async fn scripted_flow_fills_resting_account_order() {
+    let exchange = exchange().await;
+
+    let order = exchange
+        .limit_sell(&limit(btc_usdt(), dec!(1), dec!(99)))
+        .await
+        .unwrap();
+    exchange
+        .submit(ScriptedOrder::market(btc_usdt(), Side::Buy, dec!(0.4)))
+        .unwrap();
+
+    let open = exchange.get_all_open_orders().await.unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].id, order.id);
+    assert_eq!(open[0].status, OrderStatus::PartiallyFilled);
+    assert_eq!(open[0].remaining, Some(dec!(0.6)));
+
+    let trades = exchange
+        .get_trade_history(&TradeHistoryRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(trades.len(), 1);
+    assert_eq!(trades[0].price, dec!(99));
+    assert_eq!(trades[0].side, Side::Sell);
+}
+
+#[tokio::test]
+async fn insufficient_balance_is_rejected() {
+    let exchange = exchange().await;
+
+    let result = exchange
+        .limit_buy(&limit(btc_usdt(), dec!(200), dec!(95)))
+        .await;
+    assert!(matches!(result, Err(EcbtError::InsufficientBalance(_))));
+    assert!(exchange.get_all_open_orders().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn post_only_and_fill_or_kill_do_not_take_partial_liquidity() {
+    let exchange = exchange().await;
+
+    let mut post_only = limit(btc_usdt(), dec!(1), dec!(100));
+    post_only.post_only = true;
+    assert!(matches!(
+        exchange.limit_buy(&post_only).await,
+        Err(EcbtError::PostOnlyWouldTake(_))
+    ));
+
+    let mut fill_or_kill = limit(btc_usdt(), dec!(3), dec!(101));
+    fill_or_kill.time_in_force = TimeInForce::FillOrKill;
+    let order = exchange.limit_buy(&fill_or_kill).await.unwrap();
+    assert_eq!(order.status, OrderStatus::Expired);
+    assert!(order.trades.is_empty());
+}
+
+#[tokio::test]
+async fn market_buy_walks_the_book() {
+    let exchange = exchange().await;
+
+    let order = exchange
+        .market_buy(&OpenMarketOrderRequest {
+            client_order_id: None,
+            market_pair: btc_usdt(),
+            size: dec!(3),
+        })
+        .await
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Expired);
+    assert_eq!(order.remaining, Some(dec!(1)));
+
+    let usdt = balance(&exchange.get_account_balances(None).await.unwrap(), "USDT");
+    assert_eq!(usdt.free, dec!(9799));
+    assert_eq!(usdt.total, dec!(9799));
+}
+
+#[tokio::test]
+async fn cancel_all_orders_only_cancels_the_requested_market() {
+    let exchange = exchange().await;
+
+    exchange
+        .limit_buy(&limit(btc_usdt(), dec!(1), dec!(80)))
+        .await
+        .unwrap();
+    exchange
+        .limit_buy(&limit(eth_usdt(), dec!(1), dec!(10)))
+        .await
+        .unwrap();
+
+    let canceled = exchange
+        .cancel_all_orders(&CancelAllOrdersRequest {
+            market_pair: Some(btc_usdt()),
+        })
+        .await
+        .unwrap();
+    assert_eq!(canceled.len(), 1);
+
+    let open = exchange.get_all_open_orders().await.unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].market_pair, "ETHUSDT");
+
+    let usdt = balance(&exchange.get_account_balances(None).await.unwrap(), "USDT");
+    assert_eq!(usdt.free, dec!(9990));
+}
+
+#[tokio::test]
+async fn amend_order_cancels_then_places_the_replacement() {
+    let exchange = exchange().await;
+
+    let order = exchange
+        .limit_buy(&limit(btc_usdt(), dec!(1), dec!(80)))
+        .await
+        .unwrap();
+    let req = AmendOrderRequest {
+        id: order.id.clone(),
+        client_order_id: None,
+        market_pair: btc_usdt(),
+        side: Side::Buy,
+        size: dec!(2),
+        price: dec!(85),
+        time_in_force: TimeInForce::GoodTillCancelled,
+        post_only: false,
+    };
+    let amended = exchange.amend_order(&req).await.unwrap();
+    assert_eq!(amended.method, AmendMethod::CancelThenPlace);
+    assert_eq!(amended.replaced_order_id, order.id);
+    assert_ne!(amended.order.id, order.id);
+    assert!(amended.order.client_order_id.is_some());
+
+    let open = exchange.get_all_open_orders().await.unwrap();
+    assert_eq!(open.len(), 1);
+    assert_eq!(open[0].id, amended.order.id);
+    assert_eq!(open[0].price, Some(dec!(85)));
+    assert_eq!(open[0].size, dec!(2));
+
+    // The order is no longer open, so no replacement is placed.
+    assert!(matches!(
+        exchange.amend_order(&req).await,
+        Err(EcbtError::OrderNotFound(_))
+    ));
+    assert_eq!(exchange.get_all_open_orders().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn amend_order_places_what_the_canceled_order_did_not_fill() {
+    let exchange = exchange().await;
+
+    let order = exchange
+        .limit_buy(&EcbtOrderRequest {
+            client_order_id: Some("quote-1".to_string()),
+            ..limit(btc_usdt(), dec!(1), dec!(95))
+        })
+        .await
+        .unwrap();
+    exchange
+        .submit(ScriptedOrder::market(btc_usdt(), Side::Sell, dec!(0.4)))
+        .unwrap();
+    let req = AmendOrderRequest {
+        id: order.id.clone(),
+        client_order_id: None,
+        market_pair: btc_usdt(),
+        side: Side::Buy,
+        size: dec!(2),
+        price: dec!(96),
+        time_in_force: TimeInForce::GoodTillCancelled,
+        post_only: false,
+    };
+    let amended = exchange.amend_order(&req).await.unwrap();
+    assert_eq!(amended.order.size, dec!(1.6));
+    assert_eq!(amended.replaced_client_order_id.as_deref(), Some("quote-1"));
+
+    // Nothing is placed when the order already filled the amended size.
+    exchange
+        .submit(ScriptedOrder::market(btc_usdt(), Side::Sell, dec!(0.5)))
+        .unwrap();
+    let req = AmendOrderRequest {
+        id: amended.order.id,
+        size: dec!(0.5),
+        ..req
+    };
+    assert!(matches!(
+        exchange.amend_order(&req).await,
+        Err(EcbtError::InvalidQuantity(_))
+    ));
+    assert!(exchange.get_all_open_orders().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn get_open_orders_applies_filter() {
+    let exchange = exchange().await;
+
+    exchange
+        .limit_buy(&limit(btc_usdt(), dec!(1), dec!(80)))
+        .await
+        .unwrap();
+    exchange
+        .limit_buy(&limit(btc_usdt(), dec!(1), dec!(85)))
+        .await
+        .unwrap();
+    exchange
+        .limit_sell(&limit(btc_usdt(), dec!(0.5), dec!(120)))
+        .await
+        .unwrap();
+    exchange
+        .limit_buy(&limit(eth_usdt(), dec!(1), dec!(10)))
+        .await
+        .unwrap();
+
+    let filter = OrderFilter::new()
+        .with_market_pair(Some("BTCUSDT".to_string()))
+        .with_side(Some(Side::Buy))
+        .with_price(Some(dec!(82)..dec!(90)));
+    let orders = exchange.get_open_orders(&filter).await.unwrap();
+    assert_eq!(orders.len(), 1);
+    assert_eq!(orders[0].price, Some(dec!(85)));
+}
+
+#[tokio::test]
+async fn order_book_updates_stream_snapshot_then_changes() {
+    let exchange = exchange().await;
+
+    let mut stream = exchange
+        .create_stream_specific(
+            [Subscription::OrderBookUpdates(btc_usdt())]
+                .as_slice()
+                .into(),
+        )
+        .await
+        .unwrap();
+
+    match stream.next().await.unwrap().unwrap() {
+        MockWebsocketMessage::OrderBook { order_book, .. } => {
+            assert_eq!(order_book.asks[0].price, dec!(100));
+            assert_eq!(order_book.bids[0].price, dec!(90));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    exchange
+        .limit_buy(&limit(btc_usdt(), dec!(1), dec!(95)))
+        .await
+        .unwrap();
+
+    match stream.next().await.unwrap().unwrap() {
+        MockWebsocketMessage::OrderBook { order_book, .. } => {
+            assert_eq!(order_book.bids[0].price, dec!(95));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+
+    exchange.disconnect().await;
+    assert!(stream.next().await.is_none());
+}
+
+#[tokio::test]
+async fn subscription_handle_conflates_unread_order_books() {
+    let exchange = exchange().await;
+    let received = Arc::new(AtomicUsize::new(0));
+    let options = SubscriptionOptions::new()
+        .with_buffer_size(1)
+        .with_overflow_policy(OverflowPolicy::ConflateLatest);
+    let mut handle = exchange
+        .subscribe_with(Subscription::OrderBookUpdates(btc_usdt()), options, {
+            let received = received.clone();
+            move |_| {
+                received.fetch_add(1, Ordering::SeqCst);
+            }
+        })
+        .await
+        .unwrap();
+
+    for price in [dec!(95), dec!(96)] {
+        exchange
+            .limit_buy(&limit(btc_usdt(), dec!(1), price))
+            .await
+            .unwrap();
+    }
+    // The callback sees every book, the handle only the latest one.
+    while received.load(Ordering::SeqCst) < 3 {
+        tokio::task::yield_now().await;
+    }
+    match handle.next().await.unwrap().unwrap() {
+        WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(order_book)) => {
+            assert_eq!(order_book.book.bids[0].price, dec!(96));
+        }
+        message => panic!("Unexpected message: {:?}", message),
+    }
+    assert_eq!(handle.dropped(), 2);
+
+    exchange.disconnect().await;
+    assert!(matches!(
+        handle.next().await,
+        Some(Err(EcbtError::SocketError()))
+    ));
+    assert!(handle.next().await.is_none());
+}
+
+#[tokio::test]
+async fn paper_orders_fill_from_live_book_without_consuming_it() {
+    let paper = paper().await;
+
+    let order = paper
+        .limit_buy(&limit(btc_usdt(), dec!(1.5), dec!(105)))
+        .await
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::Filled);
+    let fills: Vec<_> = order.trades.iter().map(|t| (t.price, t.qty)).collect();
+    assert_eq!(fills, vec![(dec!(100), dec!(1)), (dec!(101), dec!(0.5))]);
+
+    let balances = paper.get_account_balances(None).await.unwrap();
+    assert_eq!(balance(&balances, "BTC").free, dec!(1.5));
+    assert_eq!(balance(&balances, "USDT").free, dec!(849.5));
+
+    let live = paper.inner_client().unwrap();
+    let book = live
+        .order_book(&OrderBookRequest {
+            market_pair: btc_usdt(),
+        })
+        .await
+        .unwrap();
+    assert_eq!(book.asks[0].qty, dec!(1));
+    assert!(live.get_all_open_orders().await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn paper_resting_order_fills_when_live_trade_goes_through() {
+    let paper = paper().await;
+    let live = paper.inner_client().unwrap().clone();
+    let follower = paper.follow(&live, &[btc_usdt()]).await.unwrap();
+
+    let order = paper
+        .limit_buy(&limit(btc_usdt(), dec!(1), dec!(95)))
+        .await
+        .unwrap();
+    assert_eq!(order.status, OrderStatus::New);
+
+    live.submit(ScriptedOrder::market(btc_usdt(), Side::Sell, dec!(2)))
+        .unwrap();
+
+    let filled = tokio::time::timeout(std::time::Duration::from_secs(5), async {
+        loop {
+            if paper.get_all_open_orders().await.unwrap().is_empty() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+    })
+    .await;
+    follower.abort();
+    assert!(filled.is_ok());
+
+    let trades = paper
+        .get_trade_history(&TradeHistoryRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(trades.last().unwrap().price, dec!(95));
+    let balances = paper.get_account_balances(None).await.unwrap();
+    assert_eq!(balance(&balances, "BTC").free, dec!(1));
+    assert_eq!(balance(&balances, "USDT").total, dec!(905));
+}
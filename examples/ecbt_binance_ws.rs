@@ -16,17 +16,19 @@ async fn main() {
         .unwrap();
     let market = MarketPair(Currency::ETH, Currency::BTC);
 
-    ecbt.subscribe(Subscription::OrderBookUpdates(market), move |m| {
-        let r = m.as_ref();
+    // The subscription lasts as long as its handle.
+    let _handle = ecbt
+        .subscribe(Subscription::OrderBookUpdates(market), move |m| {
+            let r = m.as_ref();
 
-        if let Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(order_book))) = r {
-            println!("{:?}", order_book)
-        } else if let Err(err) = r {
-            println!("{:#?}", err);
-        }
-    })
-    .await
-    .expect("Failed to subscribe to orderbook on Binance");
+            if let Ok(WebSocketResponse::Generic(EcbtWebSocketMessage::OrderBook(order_book))) = r {
+                println!("{:?}", order_book)
+            } else if let Err(err) = r {
+                println!("{:#?}", err);
+            }
+        })
+        .await
+        .expect("Failed to subscribe to orderbook on Binance");
 
     std::thread::sleep(std::time::Duration::from_millis(5000));
 }
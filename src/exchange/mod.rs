@@ -1,4 +1,5 @@
 pub use ecbt_binance as binance;
+pub use ecbt_ftx as ftx;
 
 pub use ecbt_exchange::errors;
 pub use ecbt_exchange::info;